@@ -0,0 +1,56 @@
+/*
+ * DomFuzz C API
+ *
+ * Link against libdomfuzz (cdylib or staticlib built by `cargo build --release`).
+ * All strings are NUL-terminated UTF-8. Negative return values are DOMFUZZ_ERR_* codes.
+ */
+#ifndef DOMFUZZ_H
+#define DOMFUZZ_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define DOMFUZZ_ERR_NULL_ARGUMENT (-1)
+#define DOMFUZZ_ERR_INVALID_UTF8 (-2)
+#define DOMFUZZ_ERR_PANIC (-3)
+#define DOMFUZZ_ERR_INVALID_DOMAIN (-4)
+#define DOMFUZZ_ERR_UNKNOWN_TRANSFORMATION (-5)
+
+/* Called once per variation. Return 0 to continue, non-zero to stop early.
+ * The string pointers are only valid for the duration of the call. */
+typedef int (*domfuzz_variation_cb)(const char *domain,
+                                    const char *transformation,
+                                    double score,
+                                    void *user_data);
+
+/* Generate variations of `domain`. `transformations` is a comma-separated list of
+ * transformation or bundle names, or NULL for the default `lookalike` bundle.
+ * Returns the number of variations delivered to `callback`. */
+int64_t domfuzz_generate(const char *domain,
+                         const char *transformations,
+                         domfuzz_variation_cb callback,
+                         void *user_data);
+
+/* Write newline-separated variations into `buf` with snprintf semantics.
+ * Returns the full output length excluding the terminating NUL; pass a NULL
+ * buffer to query the required size. */
+int64_t domfuzz_generate_into(const char *domain,
+                              const char *transformations,
+                              char *buf,
+                              size_t buf_len);
+
+/* Combined similarity of `variant` to `original` in [0.0, 1.0]. */
+double domfuzz_similarity(const char *original, const char *variant);
+
+/* Library version string; owned by the library, do not free. */
+const char *domfuzz_version(void);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* DOMFUZZ_H */
@@ -0,0 +1,308 @@
+//! Abuse report drafts about registered variations (`domfuzz report-abuse`).
+//!
+//! A takedown request is an email to the registrar's abuse contact, copying
+//! the hosting networks', which lays out why the domain imitates the brand and
+//! what was seen on it. The draft is a MIME message (`.eml`) built with
+//! mail-builder, which encodes non-ASCII names and attachments; `X-Unsent`
+//! makes mail clients open it as a draft to review and send.
+
+use std::path::Path;
+
+use chrono::Utc;
+use mail_builder::headers::address::Address;
+use mail_builder::headers::raw::Raw;
+use mail_builder::MessageBuilder;
+
+use crate::domain::Domain;
+use crate::error::DomFuzzError;
+use crate::page::PageAnalysis;
+use crate::registration::Registration;
+use crate::similarity::compare;
+use crate::tls::Certificate;
+
+/// Hosting networks whose abuse contacts `report-abuse` looks up, at most
+pub const MAX_ABUSE_HOSTS: usize = 2;
+
+/// Evidence about a registered variation, gathered for an abuse report
+#[derive(Debug, Clone, Default)]
+pub struct AbuseEvidence {
+    pub registration: Registration,
+    pub addresses: Vec<std::net::IpAddr>,
+    /// Abuse contacts of the networks hosting the domain
+    pub host_contacts: Vec<String>,
+    pub certificate: Option<Certificate>,
+    pub page: Option<PageAnalysis>,
+}
+
+/// An abuse report about `domain` as a MIME message (`.eml`): addressed to the
+/// registrar's abuse contact, copying the hosts', from `from` (`ADDRESS` or
+/// `NAME <ADDRESS>`), with the evidence in the body and the `(file name,
+/// contents)` screenshots attached
+pub fn abuse_report(
+    domain: &Domain,
+    against: &Domain,
+    evidence: &AbuseEvidence,
+    from: Option<&str>,
+    screenshots: &[(String, Vec<u8>)],
+) -> Result<Vec<u8>, DomFuzzError> {
+    let mut message = MessageBuilder::new()
+        .subject(format!(
+            "Abuse report: {} impersonates {}",
+            domain.to_ascii(),
+            against.to_ascii()
+        ))
+        .date(Utc::now().timestamp())
+        .header("X-Unsent", Raw::new("1"))
+        .text_body(report_body(domain, against, evidence, from, screenshots));
+    if let Some(to) = &evidence.registration.abuse_email {
+        message = message.to(to.as_str());
+    }
+    if !evidence.host_contacts.is_empty() {
+        message = message.cc(evidence
+            .host_contacts
+            .iter()
+            .map(String::as_str)
+            .collect::<Vec<_>>());
+    }
+    if let Some(from) = from.filter(|from| !from.trim().is_empty()) {
+        message = message.from(sender(from));
+    }
+    for (name, data) in screenshots {
+        message = message.attachment(content_type(name), name.as_str(), data.as_slice());
+    }
+    Ok(message.write_to_vec()?)
+}
+
+/// The text of the report
+fn report_body(
+    domain: &Domain,
+    against: &Domain,
+    evidence: &AbuseEvidence,
+    from: Option<&str>,
+    screenshots: &[(String, Vec<u8>)],
+) -> String {
+    let comparison = compare(against, domain);
+    let (name, brand) = (domain.to_unicode(), against.to_unicode());
+    let shown = |domain: &Domain| {
+        if domain.is_idn() {
+            format!("{} ({})", domain.to_unicode(), domain.to_ascii())
+        } else {
+            domain.to_string()
+        }
+    };
+    let percent = |score: f64| format!("{:.2}%", score * 100.0);
+
+    let mut reasons = Vec::new();
+    if comparison.skeleton_match() {
+        reasons.push(format!("reads the same as {} at a glance", brand));
+    } else if comparison.containment.is_some() {
+        reasons.push(format!("contains the name {}", brand));
+    }
+    if let Some(page) = evidence.page.as_ref() {
+        if page.is_credential_harvest_suspect() {
+            reasons.push("its landing page asks for a password".to_string());
+        }
+    }
+    let registrar = evidence
+        .registration
+        .registrar
+        .as_deref()
+        .map_or(String::new(), |registrar| {
+            format!(", registered through {},", registrar)
+        });
+
+    let mut body = format!(
+        "Hello,\n\nWe are writing on behalf of {brand} to report the domain {domain}{registrar} \
+         which imitates {brand}{reasons}. We believe it is used, or held, to deceive {brand}'s \
+         users, and ask that you review it under your abuse policy and suspend it.\n\n",
+        brand = brand,
+        domain = name,
+        registrar = registrar,
+        reasons = if reasons.is_empty() {
+            String::new()
+        } else {
+            format!(": it {}", reasons.join(", and "))
+        },
+    );
+    body.push_str("Evidence\n--------\n");
+    let mut field = |label: &str, value: String| {
+        body.push_str(&format!("{:<16}{}\n", format!("{}:", label), value));
+    };
+    field("Domain", shown(domain));
+    field("Imitates", shown(against));
+    field(
+        "Similarity",
+        format!(
+            "{} (visual {}, cognitive {}, {} edit{})",
+            percent(comparison.combined),
+            percent(comparison.visual),
+            percent(comparison.cognitive),
+            comparison.damerau_levenshtein,
+            if comparison.damerau_levenshtein == 1 {
+                ""
+            } else {
+                "s"
+            }
+        ),
+    );
+    let registration = &evidence.registration;
+    if let Some(registrar) = &registration.registrar {
+        field("Registrar", registrar.clone());
+    }
+    if !registration.nameservers.is_empty() {
+        field("Name servers", registration.nameservers.join(", "));
+    }
+    if !registration.status_codes.is_empty() {
+        field("Status", registration.status_codes.join(", "));
+    }
+    if !evidence.addresses.is_empty() {
+        field(
+            "Addresses",
+            evidence
+                .addresses
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", "),
+        );
+    }
+    if let Some(certificate) = &evidence.certificate {
+        field("Certificate", format!("SHA-256 {}", certificate.sha256));
+        if let Some(issuer) = &certificate.issuer {
+            field("Issued by", issuer.clone());
+        }
+        if !certificate.sans.is_empty() {
+            field("Issued for", certificate.sans.join(", "));
+        }
+    }
+    if let Some(page) = &evidence.page {
+        field("Landing page", page.url.clone());
+        if page.password_inputs > 0 {
+            field("Password fields", page.password_inputs.to_string());
+        }
+        if !page.cross_origin_forms.is_empty() {
+            field("Forms post to", page.cross_origin_forms.join(", "));
+        }
+        if !page.brand_keywords.is_empty() {
+            field("Mentions", page.brand_keywords.join(", "));
+        }
+    }
+    if !screenshots.is_empty() {
+        field(
+            "Screenshots",
+            format!(
+                "attached ({})",
+                screenshots
+                    .iter()
+                    .map(|(name, _)| name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        );
+    }
+    body.push_str(&format!(
+        "\nRegards,\n{}\n",
+        from.unwrap_or("[Your name and contact details]")
+    ));
+    body
+}
+
+/// `from` as an address, with its display name if given as `NAME <ADDRESS>`
+fn sender(from: &str) -> Address<'_> {
+    match from
+        .trim()
+        .strip_suffix('>')
+        .and_then(|from| from.rsplit_once('<'))
+    {
+        Some((name, address)) => {
+            let name = name.trim().trim_matches('"').trim();
+            Address::new_address((!name.is_empty()).then_some(name), address.trim())
+        }
+        None => Address::new_address(None::<&str>, from.trim()),
+    }
+}
+
+/// MIME type of a screenshot, by its file name's extension
+fn content_type(name: &str) -> &'static str {
+    match Path::new(name)
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase())
+        .as_deref()
+    {
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("webp") => "image/webp",
+        _ => "application/octet-stream",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn draft(from: Option<&str>, screenshots: &[(String, Vec<u8>)]) -> String {
+        let evidence = AbuseEvidence {
+            registration: Registration {
+                abuse_email: Some("abuse@registrar.example".to_string()),
+                ..Registration::default()
+            },
+            host_contacts: vec!["abuse@host.example".to_string()],
+            ..AbuseEvidence::default()
+        };
+        let domain = Domain::parse("examp1e.com").unwrap();
+        let against = Domain::parse("example.com").unwrap();
+        let message = abuse_report(&domain, &against, &evidence, from, screenshots).unwrap();
+        String::from_utf8(message).unwrap()
+    }
+
+    #[test]
+    fn drafts_are_addressed_and_unsent() {
+        let message = draft(Some("Brand Team <brand@example.com>"), &[]);
+        assert!(
+            message.contains("To: <abuse@registrar.example>\r\n"),
+            "{}",
+            message
+        );
+        assert!(
+            message.contains("Cc: <abuse@host.example>\r\n"),
+            "{}",
+            message
+        );
+        assert!(
+            message.contains("From: \"Brand Team\" <brand@example.com>\r\n"),
+            "{}",
+            message
+        );
+        assert!(message.contains("Subject: Abuse report: examp1e.com impersonates example.com\r\n"));
+        assert!(message.contains("X-Unsent: 1\r\n"));
+    }
+
+    #[test]
+    fn non_ascii_names_are_encoded() {
+        let message = draft(Some("Zoë Müller <zoe@example.com>"), &[]);
+        let from = message
+            .lines()
+            .find(|line| line.starts_with("From: "))
+            .unwrap();
+        assert!(from.is_ascii(), "{}", from);
+        assert!(from.contains("=?utf-8?"), "{}", from);
+        assert!(from.ends_with("<zoe@example.com>"), "{}", from);
+    }
+
+    #[test]
+    fn screenshots_are_attached_in_base64() {
+        let message = draft(
+            None,
+            &[("page.png".to_string(), vec![0x89, b'P', b'N', b'G', 0, 255])],
+        );
+        assert!(message.contains("Content-Type: image/png"), "{}", message);
+        assert!(message.contains("filename=\"page.png\""), "{}", message);
+        assert!(
+            message.contains("Content-Transfer-Encoding: base64"),
+            "{}",
+            message
+        );
+        assert!(message.contains("iVBORwD/"), "{}", message);
+        assert!(!message.contains("\r\nFrom: "), "{}", message);
+    }
+}
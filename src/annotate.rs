@@ -0,0 +1,216 @@
+//! Analyst triage kept with the findings (`domfuzz annotate`, `--tag`).
+//!
+//! Findings live in JSON Lines results files and monitor event streams, and
+//! triage tracked anywhere else drifts out of step with them. An annotation (a
+//! disposition, a note, or both) is written into the file itself: a results
+//! record gains it in its `annotations` list, with the latest disposition in
+//! `disposition`; an event stream gains an `annotated` event, which a monitor
+//! appending to the same stream reads back on start, so that variations marked
+//! `false-positive` raise no more alerts. Tags (`--tag engagement=acme-q3`)
+//! label every record a run writes with the engagement it belongs to.
+
+use std::collections::HashMap;
+
+use chrono::{SecondsFormat, Utc};
+
+use crate::domain::Domain;
+use crate::error::DomFuzzError;
+
+/// What an analyst decided about a variation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Disposition {
+    /// Not a threat: owned by the brand, unrelated, or benign
+    FalsePositive,
+    /// Handed to incident response
+    Escalated,
+    /// Reported to the registrar or host for takedown
+    TakedownRequested,
+}
+
+impl Disposition {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Disposition::FalsePositive => "false-positive",
+            Disposition::Escalated => "escalated",
+            Disposition::TakedownRequested => "takedown-requested",
+        }
+    }
+
+    pub fn parse(text: &str) -> Option<Disposition> {
+        [
+            Disposition::FalsePositive,
+            Disposition::Escalated,
+            Disposition::TakedownRequested,
+        ]
+        .into_iter()
+        .find(|disposition| disposition.as_str() == text)
+    }
+}
+
+/// An analyst's note or disposition on one variation
+#[derive(Debug, Clone, PartialEq)]
+pub struct Annotation {
+    pub disposition: Option<Disposition>,
+    pub note: Option<String>,
+    pub analyst: Option<String>,
+    /// RFC 3339, UTC
+    pub timestamp: String,
+}
+
+impl Annotation {
+    /// An annotation made now
+    pub fn new(
+        disposition: Option<Disposition>,
+        note: Option<String>,
+        analyst: Option<String>,
+    ) -> Self {
+        Annotation {
+            disposition,
+            note,
+            analyst,
+            timestamp: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        let mut json = serde_json::json!({ "timestamp": self.timestamp });
+        if let Some(disposition) = self.disposition {
+            json["disposition"] = disposition.as_str().into();
+        }
+        if let Some(note) = &self.note {
+            json["note"] = note.clone().into();
+        }
+        if let Some(analyst) = &self.analyst {
+            json["analyst"] = analyst.clone().into();
+        }
+        json
+    }
+}
+
+/// Parse a `--tag` written as `KEY=VALUE`
+pub fn parse_tag(tag: &str) -> Result<(String, String), DomFuzzError> {
+    let (key, value) = tag
+        .split_once('=')
+        .ok_or_else(|| DomFuzzError::Parse(format!("expected `KEY=VALUE`, got {:?}", tag)))?;
+    let key = key.trim();
+    if key.is_empty() {
+        return Err(DomFuzzError::Parse(format!("missing tag key in {:?}", tag)));
+    }
+    Ok((key.to_string(), value.trim().to_string()))
+}
+
+/// Tags as the JSON object written in records' `tags` field, later tags
+/// replacing earlier ones with the same key
+pub fn tags_json(tags: &[(String, String)]) -> serde_json::Map<String, serde_json::Value> {
+    tags.iter()
+        .map(|(key, value)| (key.clone(), value.clone().into()))
+        .collect()
+}
+
+/// What an annotated file holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileKind {
+    /// JSON Lines result records
+    Results,
+    /// Monitor events
+    Events,
+}
+
+/// Whether `contents` is a monitor event stream or results: events have an
+/// `event` field
+pub fn file_kind(contents: &str) -> FileKind {
+    let is_event = records(contents).any(|record| record.get("event").is_some());
+    if is_event {
+        FileKind::Events
+    } else {
+        FileKind::Results
+    }
+}
+
+/// `contents`, a JSON Lines results file, with `annotation` added to every
+/// record of `domain`; `None` if there is none. Other lines are kept as they
+/// are.
+pub fn annotate_results(contents: &str, domain: &str, annotation: &Annotation) -> Option<String> {
+    let wanted = domain_key(domain);
+    let mut annotated = 0;
+    let mut output = String::with_capacity(contents.len());
+    for line in contents.lines() {
+        let record = serde_json::from_str::<serde_json::Value>(line)
+            .ok()
+            .filter(|record| record_domain(record).as_deref() == Some(wanted.as_str()));
+        match record {
+            Some(mut record) => {
+                annotate_record(&mut record, annotation);
+                output.push_str(&record.to_string());
+                annotated += 1;
+            }
+            None => output.push_str(line),
+        }
+        output.push('\n');
+    }
+    (annotated > 0).then_some(output)
+}
+
+/// Add `annotation` to a result record's `annotations`, and make its
+/// disposition the record's
+fn annotate_record(record: &mut serde_json::Value, annotation: &Annotation) {
+    let Some(fields) = record.as_object_mut() else {
+        return;
+    };
+    if let Some(disposition) = annotation.disposition {
+        fields.insert("disposition".to_string(), disposition.as_str().into());
+    }
+    let annotations = fields
+        .entry("annotations")
+        .or_insert_with(|| serde_json::Value::Array(Vec::new()));
+    if let Some(annotations) = annotations.as_array_mut() {
+        annotations.push(annotation.to_json());
+    }
+}
+
+/// The last cycle of a monitor event stream that mentions `domain`, to date an
+/// annotation of it by; `None` if no event does
+pub fn last_cycle(contents: &str, domain: &str) -> Option<u64> {
+    let wanted = domain_key(domain);
+    let mut mentioned = false;
+    let mut cycle = 0;
+    for record in records(contents) {
+        mentioned |= record_domain(&record).as_deref() == Some(wanted.as_str());
+        cycle = cycle.max(record.get("cycle").and_then(|c| c.as_u64()).unwrap_or(0));
+    }
+    mentioned.then_some(cycle)
+}
+
+/// The latest disposition of each variation annotated in a monitor event
+/// stream, keyed by domain in ASCII form
+pub fn dispositions(contents: &str) -> HashMap<String, Disposition> {
+    let mut dispositions = HashMap::new();
+    for record in records(contents) {
+        if record.get("event").and_then(|e| e.as_str()) != Some("annotated") {
+            continue;
+        }
+        let disposition = record
+            .get("disposition")
+            .and_then(|d| d.as_str())
+            .and_then(Disposition::parse);
+        if let (Some(domain), Some(disposition)) = (record_domain(&record), disposition) {
+            dispositions.insert(domain, disposition);
+        }
+    }
+    dispositions
+}
+
+fn records(contents: &str) -> impl Iterator<Item = serde_json::Value> + '_ {
+    contents
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+}
+
+/// A record's `domain`, as compared: ASCII, lowercase
+fn record_domain(record: &serde_json::Value) -> Option<String> {
+    record.get("domain")?.as_str().map(domain_key)
+}
+
+fn domain_key(domain: &str) -> String {
+    Domain::parse(domain).map_or_else(|_| domain.trim().to_lowercase(), |d| d.to_ascii())
+}
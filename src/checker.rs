@@ -0,0 +1,1013 @@
+//! Domain status checking over RDAP, WHOIS, DNS, and HTTP.
+//!
+//! Statuses are plain strings: `available`, `registered`, `parked`, `timeout`, or
+//! `error` when every lookup method failed. Registered domains resolving only to
+//! null routes, loopback, or reserved addresses are `sinkholed` or `placeholder`
+//! instead (see [`crate::sinkhole`]). Individual lookups return
+//! [`DomFuzzError`] so callers can tell which protocol failed and why. With
+//! consensus resolvers configured (see [`configure`]), `inconsistent` marks
+//! domains whose availability the resolvers disagree on.
+
+use futures::stream::{self, Stream, StreamExt};
+use hickory_resolver::{
+    config::{LookupIpStrategy, NameServerConfigGroup, ResolverConfig, ResolverOpts},
+    error::ResolveErrorKind,
+    proto::op::ResponseCode,
+    TokioAsyncResolver,
+};
+use std::net::IpAddr;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    time::timeout,
+};
+
+use crate::error::DomFuzzError;
+use crate::generators::extract_registrable_domain;
+use crate::metrics;
+use crate::net;
+use crate::polite::{self, Politeness};
+use crate::public::{self, PublicMode};
+use crate::sinkhole::AddressRules;
+use crate::throttle;
+use crate::whois::WhoisRules;
+
+// Constants for timeout values
+pub const RDAP_TIMEOUT_SECS: u64 = 5;
+pub const DNS_TIMEOUT_SECS: u64 = 5;
+pub const HTTP_TIMEOUT_SECS: u64 = 10;
+pub const HTTP_CONTENT_TIMEOUT_SECS: u64 = 5;
+pub const WHOIS_CONNECT_TIMEOUT_SECS: u64 = 10;
+pub const WHOIS_WRITE_TIMEOUT_SECS: u64 = 5;
+pub const WHOIS_READ_TIMEOUT_SECS: u64 = 10;
+pub const RETRY_DELAY_MS: u64 = 500;
+pub const WHOIS_PORT: u16 = 43;
+/// Referrals followed after the first WHOIS response (IANA, registry, registrar)
+pub const MAX_WHOIS_REFERRALS: usize = 2;
+/// Bytes of a page read for the parked-page check; parking pages say so near
+/// the top, and hostile pages can run to tens of megabytes
+pub const MAX_PARKED_PAGE_BYTES: usize = 256 * 1024;
+
+/// Phrases of parking and for-sale pages, lowercase
+const PARKING_MARKERS: &[&str] = &[
+    "parked",
+    "parking",
+    "sedo",
+    "domain for sale",
+    "this domain may be for sale",
+    "under construction",
+    "coming soon",
+];
+
+/// Result of a single protocol lookup
+pub type DomainCheckResult<T> = Result<T, DomFuzzError>;
+
+/// Settings that apply to every status check in the process
+#[derive(Debug, Clone, Default)]
+pub struct CheckOptions {
+    /// Independent resolvers that must all report a domain as nonexistent before
+    /// it is reported `available`; any disagreement yields `inconsistent`.
+    /// Empty disables the consensus check.
+    pub consensus_resolvers: Vec<IpAddr>,
+    /// User-Agent sent with RDAP and HTTP requests instead of [`DEFAULT_USER_AGENT`]
+    pub user_agent: Option<String>,
+    /// Extra headers sent with every RDAP and HTTP request, as (name, value)
+    pub headers: Vec<(String, String)>,
+    /// Timeouts, retries, and concurrency limits
+    pub network: NetworkSettings,
+    /// WHOIS rules tried before the built-in ones
+    pub whois_rules: WhoisRules,
+    /// Sinkhole and placeholder addresses tried before the built-in ones
+    pub sinkholes: AddressRules,
+    /// Pacing and limits of requests to the domains' own web servers; `None`
+    /// sends them as fast as lookups complete
+    pub polite: Option<Politeness>,
+    /// Registry etiquette for shared infrastructure; `None` lets lookups go as
+    /// fast as the endpoints tolerate
+    pub public: Option<PublicMode>,
+}
+
+/// Per-protocol network behavior, e.g. from a config file profile
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NetworkSettings {
+    pub rdap_timeout: Duration,
+    /// Applies to connecting to and reading from a WHOIS server, separately
+    pub whois_timeout: Duration,
+    pub dns_timeout: Duration,
+    pub http_timeout: Duration,
+    /// Retries of an RDAP lookup answered with HTTP 429
+    pub retries: u32,
+    /// Wait before each retry
+    pub retry_delay: Duration,
+    /// Most lookups in flight per endpoint, however well it responds
+    pub max_concurrency: usize,
+}
+
+impl Default for NetworkSettings {
+    fn default() -> Self {
+        NetworkSettings {
+            rdap_timeout: Duration::from_secs(RDAP_TIMEOUT_SECS),
+            whois_timeout: Duration::from_secs(WHOIS_READ_TIMEOUT_SECS),
+            dns_timeout: Duration::from_secs(DNS_TIMEOUT_SECS),
+            http_timeout: Duration::from_secs(HTTP_TIMEOUT_SECS),
+            retries: 1,
+            retry_delay: Duration::from_millis(RETRY_DELAY_MS),
+            max_concurrency: throttle::MAX_CONCURRENCY,
+        }
+    }
+}
+
+/// User-Agent sent with RDAP and HTTP requests unless configured otherwise
+pub const DEFAULT_USER_AGENT: &str = "Mozilla/5.0 (compatible; DomFuzz/0.1)";
+
+static OPTIONS: OnceLock<CheckOptions> = OnceLock::new();
+static CONSENSUS_RESOLVERS: OnceLock<Vec<TokioAsyncResolver>> = OnceLock::new();
+static WHOIS_RULES: OnceLock<WhoisRules> = OnceLock::new();
+static SINKHOLES: OnceLock<AddressRules> = OnceLock::new();
+
+/// Set process-wide check options. Call once, before the first check.
+pub fn configure(options: CheckOptions) -> Result<(), DomFuzzError> {
+    if options.consensus_resolvers.len() == 1 {
+        return Err(DomFuzzError::Config(
+            "consensus requires at least two resolvers".to_string(),
+        ));
+    }
+    if let Some(user_agent) = &options.user_agent {
+        reqwest::header::HeaderValue::from_str(user_agent)
+            .map_err(|_| DomFuzzError::Config(format!("invalid user agent: {:?}", user_agent)))?;
+    }
+    default_headers(&options.headers)?;
+    if let Some(polite) = &options.polite {
+        if !(polite.rate > 0.0 && polite.rate.is_finite()) {
+            return Err(DomFuzzError::Config(
+                "the polite rate must be a positive number of requests per second".to_string(),
+            ));
+        }
+        if polite.max_body_bytes == 0 {
+            return Err(DomFuzzError::Config(
+                "the maximum body size must be at least 1 byte".to_string(),
+            ));
+        }
+    }
+    if let Some(public) = &options.public {
+        if !(0.0..=1.0).contains(&public.jitter) {
+            return Err(DomFuzzError::Config(
+                "the jitter must be from 0.0 to 1.0".to_string(),
+            ));
+        }
+    }
+    if options.network.max_concurrency == 0 {
+        return Err(DomFuzzError::Config(
+            "max_concurrency must be at least 1".to_string(),
+        ));
+    }
+    OPTIONS
+        .set(options)
+        .map_err(|_| DomFuzzError::Config("check options are already configured".to_string()))
+}
+
+fn options() -> &'static CheckOptions {
+    OPTIONS.get_or_init(CheckOptions::default)
+}
+
+/// Configured WHOIS rules followed by the built-in ones
+fn whois_rules() -> &'static WhoisRules {
+    WHOIS_RULES.get_or_init(|| options().whois_rules.clone().then(WhoisRules::builtin()))
+}
+
+/// Configured sinkhole addresses followed by the built-in ones
+fn sinkholes() -> &'static AddressRules {
+    SINKHOLES.get_or_init(|| options().sinkholes.clone().then(AddressRules::builtin()))
+}
+
+/// The configured network settings, or the defaults
+pub fn network() -> &'static NetworkSettings {
+    &options().network
+}
+
+/// The configured pacing of requests to sites, if polite mode is on
+pub fn politeness() -> Option<&'static Politeness> {
+    options().polite.as_ref()
+}
+
+/// The configured registry etiquette, if public mode is on
+pub fn public_mode() -> Option<&'static PublicMode> {
+    options().public.as_ref()
+}
+
+/// Parse configured (name, value) pairs into a header map
+fn default_headers(
+    headers: &[(String, String)],
+) -> Result<reqwest::header::HeaderMap, DomFuzzError> {
+    let mut map = reqwest::header::HeaderMap::new();
+    for (name, value) in headers {
+        let invalid = || DomFuzzError::Config(format!("invalid header: {}: {}", name, value));
+        map.append(
+            reqwest::header::HeaderName::from_bytes(name.as_bytes()).map_err(|_| invalid())?,
+            reqwest::header::HeaderValue::from_str(value).map_err(|_| invalid())?,
+        );
+    }
+    Ok(map)
+}
+
+/// Answers (including NXDOMAIN) kept by the shared resolver's cache
+pub const DNS_CACHE_SIZE: usize = 4096;
+
+/// How long idle connections are kept for the next burst of lookups
+pub const HTTP_POOL_IDLE_TIMEOUT_SECS: u64 = 90;
+/// Interval of HTTP/2 pings that keep idle multiplexed connections alive
+pub const HTTP2_KEEPALIVE_SECS: u64 = 20;
+
+lazy_static::lazy_static! {
+    /// Shared HTTP client for connection reuse across RDAP and HTTP probes,
+    /// sending the User-Agent and headers set with [`configure`].
+    ///
+    /// HTTP/2 is negotiated via ALPN where the server supports it, which most
+    /// RDAP servers do: a whole run's lookups then share one multiplexed
+    /// connection per registry instead of paying a TLS handshake per burst.
+    /// HTTP/1.1 servers keep up to one idle connection per concurrent lookup
+    /// the endpoint is allowed (see [`throttle`]).
+    pub static ref HTTP_CLIENT: reqwest::Client = {
+        let options = options();
+        reqwest::Client::builder()
+            // RDAP requests set their own, usually shorter, timeout
+            .timeout(options.network.rdap_timeout.max(options.network.http_timeout))
+            .user_agent(options.user_agent.as_deref().unwrap_or(DEFAULT_USER_AGENT))
+            .default_headers(default_headers(&options.headers).expect("validated by configure"))
+            .pool_max_idle_per_host(throttle::MAX_CONCURRENCY)
+            .pool_idle_timeout(Duration::from_secs(HTTP_POOL_IDLE_TIMEOUT_SECS))
+            .tcp_nodelay(true)
+            .tcp_keepalive(Duration::from_secs(HTTP_POOL_IDLE_TIMEOUT_SECS))
+            .http2_adaptive_window(true)
+            .http2_keep_alive_interval(Duration::from_secs(HTTP2_KEEPALIVE_SECS))
+            .http2_keep_alive_timeout(options.network.rdap_timeout)
+            .http2_keep_alive_while_idle(true)
+            .build()
+            .expect("Failed to create HTTP client")
+    };
+
+    /// Shared DNS resolver, so every lookup in the process benefits from one
+    /// positive and negative answer cache instead of starting cold. Address
+    /// lookups return both A and AAAA records, so IPv6-only hosts are not missed.
+    pub static ref DNS_RESOLVER: TokioAsyncResolver = {
+        let mut options = ResolverOpts::default();
+        options.cache_size = DNS_CACHE_SIZE;
+        options.timeout = network().dns_timeout;
+        options.ip_strategy = LookupIpStrategy::Ipv4AndIpv6;
+        TokioAsyncResolver::tokio(ResolverConfig::default(), options)
+    };
+}
+
+/// Fast domain status checking using RDAP (Registration Data Access Protocol) first,
+/// with WHOIS/DNS fallback for unknown TLDs. This provides significant performance
+/// improvements over the original WHOIS-first approach:
+///
+/// Performance improvements:
+/// - RDAP uses HTTP/HTTPS with structured JSON responses (vs TCP WHOIS text parsing)
+/// - HTTP 404 = available, 200 = registered (simple status determination)
+/// - Built-in endpoint mapping for 30+ major TLDs avoids discovery overhead
+/// - Typical speedup: 3-5x faster for supported TLDs
+/// - Concurrent processing: 5-10x speedup with parallel requests
+///
+/// Lookup failures are reported as the `timeout` or `error` status, or
+/// `unknown` when an endpoint's circuit breaker is open; use
+/// [`try_check_domain_status`] to get the underlying [`DomFuzzError`].
+pub async fn check_domain_status(domain: &str) -> String {
+    // Public mode answers from its status cache when it can
+    let registrable_domain = extract_registrable_domain(domain);
+    if let Some(status) = public::cached_status(&registrable_domain) {
+        metrics::record_check(&status);
+        return status;
+    }
+    let status = match try_check_domain_status(domain).await {
+        Ok(status) => status,
+        Err(e) => e.status().to_string(),
+    };
+    public::cache_status(&registrable_domain, &status);
+    metrics::record_check(&status);
+    status
+}
+
+/// Like [`check_domain_status`], but returns the error from the last lookup
+/// method tried when none of them could determine a status.
+pub async fn try_check_domain_status(domain: &str) -> DomainCheckResult<String> {
+    // Extract the registrable domain
+    let registrable_domain = extract_registrable_domain(domain);
+
+    // Try fast RDAP check first (modern protocol, HTTP-based)
+    let status = match check_domain_rdap(&registrable_domain).await {
+        Ok(status) => status,
+        // Fallback to the original implementation for unknown TLDs
+        Err(_) => try_check_domain_status_legacy(&registrable_domain).await?,
+    };
+
+    if status == "available" && !options().consensus_resolvers.is_empty() {
+        return Ok(resolver_consensus(&registrable_domain).await.to_string());
+    }
+    if status == "registered" {
+        if let Some(status) = address_status(&registrable_domain).await {
+            return Ok(status.to_string());
+        }
+    }
+    Ok(status)
+}
+
+/// `sinkholed` or `placeholder` if every address `domain` resolves to is a
+/// sinkhole or placeholder; `None` if any is not, or it does not resolve
+async fn address_status(domain: &str) -> Option<&'static str> {
+    metrics::record_request("dns", metrics::RESOLVER);
+    let start = Instant::now();
+    let lookup = timeout(network().dns_timeout, DNS_RESOLVER.lookup_ip(domain)).await;
+    metrics::observe_lookup("dns", start.elapsed());
+    let addresses: Vec<IpAddr> = lookup.ok()?.ok()?.iter().collect();
+    sinkholes().classify(&addresses)
+}
+
+/// What one resolver says about a domain's delegation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Delegation {
+    Exists,
+    Nonexistent,
+    Unknown,
+}
+
+/// Ask every consensus resolver for the domain's NS records. The domain is
+/// `available` only if all of them answer NXDOMAIN; otherwise `inconsistent`.
+async fn resolver_consensus(domain: &str) -> &'static str {
+    let resolvers = CONSENSUS_RESOLVERS.get_or_init(|| {
+        options()
+            .consensus_resolvers
+            .iter()
+            .map(|ip| {
+                let servers = NameServerConfigGroup::from_ips_clear(&[*ip], 53, true);
+                TokioAsyncResolver::tokio(
+                    ResolverConfig::from_parts(None, vec![], servers),
+                    ResolverOpts::default(),
+                )
+            })
+            .collect()
+    });
+
+    let resolver_ips = options().consensus_resolvers.iter();
+    let answers = futures::future::join_all(resolvers.iter().zip(resolver_ips).map(
+        |(resolver, ip)| async move {
+            metrics::record_request("dns", &ip.to_string());
+            let start = Instant::now();
+            let result = timeout(network().dns_timeout, resolver.ns_lookup(domain)).await;
+            metrics::observe_lookup("dns", start.elapsed());
+            match result {
+                Ok(Ok(_)) => Delegation::Exists,
+                Ok(Err(e)) => match e.kind() {
+                    ResolveErrorKind::NoRecordsFound { response_code, .. } => {
+                        if *response_code == ResponseCode::NXDomain {
+                            Delegation::Nonexistent
+                        } else {
+                            // The name exists, just without NS records at this level
+                            Delegation::Exists
+                        }
+                    }
+                    _ => Delegation::Unknown,
+                },
+                Err(_) => Delegation::Unknown,
+            }
+        },
+    ))
+    .await;
+
+    if answers.iter().all(|a| *a == Delegation::Nonexistent) {
+        "available"
+    } else {
+        "inconsistent"
+    }
+}
+
+/// True for statuses that show the domain is taken (`registered`, `parked`,
+/// `sinkholed`, or `placeholder`)
+pub fn is_registered_status(status: &str) -> bool {
+    matches!(
+        status,
+        "registered" | "parked" | "for-sale" | "sinkholed" | "placeholder"
+    )
+}
+
+/// True for statuses reported when no lookup method produced an answer
+pub fn is_failed_status(status: &str) -> bool {
+    matches!(status, "timeout" | "error" | "unknown")
+}
+
+/// Fast RDAP-based domain checking using built-in registry mapping
+pub async fn check_domain_rdap(domain: &str) -> DomainCheckResult<String> {
+    Ok(match fetch_rdap(domain).await? {
+        // Domain exists (registered), check if it might be parked
+        Some(json) if is_domain_parked_rdap(&json) => "parked",
+        Some(_) => "registered",
+        None => "available",
+    }
+    .to_string())
+}
+
+/// `domain`'s RDAP record, or `None` if the registry reports no such domain;
+/// a record that is not valid JSON is returned as `Value::Null`
+pub async fn fetch_rdap(domain: &str) -> DomainCheckResult<Option<serde_json::Value>> {
+    let tld = extract_tld(domain)?;
+
+    // Get RDAP endpoint for this TLD
+    let endpoint = get_rdap_endpoint(&tld)?;
+
+    // Build RDAP URL
+    let rdap_url = format!("{}{}", endpoint, domain);
+
+    // Use shared HTTP client for connection reuse
+
+    // Make RDAP request using shared client, within the registry's tuned concurrency
+    let host = endpoint_host(endpoint);
+    let Some(permit) = throttle::acquire(host).await else {
+        return Err(DomFuzzError::CircuitOpen {
+            endpoint: host.to_string(),
+            domain: domain.to_string(),
+        });
+    };
+    metrics::record_request("rdap", host);
+    let start = Instant::now();
+    let response = HTTP_CLIENT
+        .get(&rdap_url)
+        .timeout(network().rdap_timeout)
+        .send()
+        .await
+        .map_err(|e| rdap_error(domain, e));
+    metrics::observe_lookup("rdap", start.elapsed());
+    let response = match response {
+        Ok(response) => response,
+        Err(e @ DomFuzzError::Timeout { .. }) => {
+            permit.time_out();
+            return Err(e);
+        }
+        Err(e) => {
+            permit.finish(false);
+            return Err(e);
+        }
+    };
+    let status = response.status();
+    permit.finish(matches!(
+        status,
+        reqwest::StatusCode::OK | reqwest::StatusCode::NOT_FOUND
+    ));
+
+    match status {
+        reqwest::StatusCode::OK => Ok(Some(rdap_json(response).await)),
+        reqwest::StatusCode::NOT_FOUND => Ok(None),
+        reqwest::StatusCode::TOO_MANY_REQUESTS => {
+            // Rate limited, wait and retry as often as configured
+            metrics::record_rate_limit("rdap");
+            let network = network();
+            for _ in 0..network.retries {
+                tokio::time::sleep(network.retry_delay).await;
+                metrics::record_request("rdap", host);
+                let retry_response = HTTP_CLIENT
+                    .get(&rdap_url)
+                    .timeout(network.rdap_timeout)
+                    .send()
+                    .await
+                    .map_err(|e| rdap_error(domain, e))?;
+                match retry_response.status() {
+                    reqwest::StatusCode::OK => return Ok(Some(rdap_json(retry_response).await)),
+                    reqwest::StatusCode::NOT_FOUND => return Ok(None),
+                    reqwest::StatusCode::TOO_MANY_REQUESTS => metrics::record_rate_limit("rdap"),
+                    status => {
+                        return Err(DomFuzzError::Rdap {
+                            domain: domain.to_string(),
+                            message: format!("server returned status {} after retry", status),
+                        })
+                    }
+                }
+            }
+            Err(DomFuzzError::Rdap {
+                domain: domain.to_string(),
+                message: format!("rate limited after {} retries", network.retries),
+            })
+        }
+        status => Err(DomFuzzError::Rdap {
+            domain: domain.to_string(),
+            message: format!("server returned status {}", status),
+        }),
+    }
+}
+
+/// Body of a successful RDAP response, or `Value::Null` if it is not JSON
+async fn rdap_json(response: reqwest::Response) -> serde_json::Value {
+    response
+        .json::<serde_json::Value>()
+        .await
+        .unwrap_or(serde_json::Value::Null)
+}
+
+/// Host part of an RDAP endpoint URL, which keys its concurrency limit
+fn endpoint_host(endpoint: &str) -> &str {
+    let rest = endpoint
+        .split_once("://")
+        .map_or(endpoint, |(_, rest)| rest);
+    rest.split('/').next().unwrap_or(rest)
+}
+
+/// Map a transport error from an RDAP request, keeping timeouts distinct
+fn rdap_error(domain: &str, e: reqwest::Error) -> DomFuzzError {
+    if e.is_timeout() {
+        DomFuzzError::Timeout {
+            protocol: "RDAP",
+            domain: domain.to_string(),
+        }
+    } else {
+        DomFuzzError::Rdap {
+            domain: domain.to_string(),
+            message: e.to_string(),
+        }
+    }
+}
+
+/// Check if domain appears to be parked based on RDAP data
+pub fn is_domain_parked_rdap(json: &serde_json::Value) -> bool {
+    // Check status codes for parking indicators
+    if let Some(statuses) = json.get("status").and_then(|s| s.as_array()) {
+        for status in statuses {
+            if let Some(status_str) = status.as_str() {
+                let status_lower = status_str.to_lowercase();
+                if status_lower.contains("client hold")
+                    || status_lower.contains("redemption")
+                    || status_lower.contains("pending delete")
+                {
+                    return true;
+                }
+            }
+        }
+    }
+
+    // Check entities for parking service registrars
+    if let Some(entities) = json.get("entities").and_then(|e| e.as_array()) {
+        for entity in entities {
+            if let Some(roles) = entity.get("roles").and_then(|r| r.as_array()) {
+                if roles.iter().any(|role| role.as_str() == Some("registrar")) {
+                    if let Some(name) = extract_registrar_name(entity) {
+                        let name_lower = name.to_lowercase();
+                        if name_lower.contains("sedo")
+                            || name_lower.contains("parking")
+                            || name_lower.contains("bodis")
+                            || name_lower.contains("hugedomains")
+                        {
+                            return true;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    false
+}
+
+/// Extract registrar name from RDAP entity
+pub(crate) fn extract_registrar_name(entity: &serde_json::Value) -> Option<String> {
+    // Try vcardArray first
+    if let Some(name) = extract_vcard_name(entity) {
+        return Some(name);
+    }
+
+    // Fallback to publicIds or handle
+    if let Some(public_ids) = entity.get("publicIds").and_then(|p| p.as_array()) {
+        if let Some(id) = public_ids
+            .first()
+            .and_then(|id| id.get("identifier"))
+            .and_then(|i| i.as_str())
+        {
+            return Some(id.to_string());
+        }
+    }
+
+    entity
+        .get("handle")
+        .or_else(|| entity.get("name"))
+        .and_then(|n| n.as_str())
+        .map(String::from)
+}
+
+/// Extract organization name from vCard format
+fn extract_vcard_name(entity: &serde_json::Value) -> Option<String> {
+    entity
+        .get("vcardArray")
+        .and_then(|v| v.as_array())
+        .and_then(|a| a.get(1))
+        .and_then(|a| a.as_array())
+        .and_then(|items| {
+            for item in items {
+                if let Some(item_array) = item.as_array() {
+                    if item_array.len() >= 4 {
+                        if let Some(first) = item_array.first().and_then(|f| f.as_str()) {
+                            if first == "fn" {
+                                return item_array
+                                    .get(3)
+                                    .and_then(|n| n.as_str())
+                                    .map(String::from);
+                            }
+                        }
+                    }
+                }
+            }
+            None
+        })
+}
+
+/// Get RDAP endpoint for a TLD
+pub fn get_rdap_endpoint(tld: &str) -> DomainCheckResult<&'static str> {
+    let endpoint = match tld.to_lowercase().as_str() {
+        // Major gTLDs
+        "com" => "https://rdap.verisign.com/com/v1/domain/",
+        "net" => "https://rdap.verisign.com/net/v1/domain/",
+        "org" => "https://rdap.publicinterestregistry.org/rdap/domain/",
+        "info" => "https://rdap.identitydigital.services/rdap/domain/",
+        "biz" => "https://rdap.nic.biz/domain/",
+        // Google TLDs
+        "app" => "https://rdap.nic.google/domain/",
+        "dev" => "https://rdap.nic.google/domain/",
+        "page" => "https://rdap.nic.google/domain/",
+        // Other popular TLDs
+        "xyz" => "https://rdap.nic.xyz/domain/",
+        "tech" => "https://rdap.nic.tech/domain/",
+        "online" => "https://rdap.nic.online/domain/",
+        "site" => "https://rdap.nic.site/domain/",
+        // ccTLDs
+        "io" => "https://rdap.identitydigital.services/rdap/domain/",
+        "ai" => "https://rdap.nic.ai/domain/",
+        "co" => "https://rdap.nic.co/domain/",
+        "me" => "https://rdap.nic.me/domain/",
+        "us" => "https://rdap.nic.us/domain/",
+        "uk" => "https://rdap.nominet.uk/domain/",
+        "eu" => "https://rdap.eu.org/domain/",
+        "de" => "https://rdap.denic.de/domain/",
+        "ca" => "https://rdap.cira.ca/domain/",
+        "au" => "https://rdap.auda.org.au/domain/",
+        "fr" => "https://rdap.nic.fr/domain/",
+        "jp" => "https://rdap.jprs.jp/domain/",
+        "br" => "https://rdap.registro.br/domain/",
+        "in" => "https://rdap.registry.in/domain/",
+        "cn" => "https://rdap.cnnic.cn/domain/",
+        "tv" => "https://rdap.verisign.com/tv/v1/domain/",
+        "cc" => "https://rdap.verisign.com/cc/v1/domain/",
+        _ => {
+            return Err(DomFuzzError::Config(format!(
+                "no RDAP endpoint known for TLD: {}",
+                tld
+            )))
+        }
+    };
+
+    Ok(endpoint)
+}
+
+/// Extract TLD from domain
+pub fn extract_tld(domain: &str) -> DomainCheckResult<String> {
+    let parts: Vec<&str> = domain.split('.').collect();
+    if parts.len() < 2 {
+        return Err(DomFuzzError::Parse(format!(
+            "invalid domain format: {}",
+            domain
+        )));
+    }
+    Ok(parts
+        .last()
+        .expect("Domain must have at least one part after split")
+        .to_lowercase())
+}
+
+/// Legacy domain checking (fallback for unknown TLDs)
+pub async fn check_domain_status_legacy(domain: &str) -> String {
+    match try_check_domain_status_legacy(domain).await {
+        Ok(status) => status,
+        Err(e) => e.status().to_string(),
+    }
+}
+
+/// WHOIS, then DNS + HTTP. A name that resolves to no records is `available`;
+/// any other resolver failure is returned as [`DomFuzzError::Dns`].
+pub async fn try_check_domain_status_legacy(domain: &str) -> DomainCheckResult<String> {
+    // First check WHOIS for the most accurate information
+    let start = Instant::now();
+    let whois_result = check_whois(domain).await;
+    metrics::observe_lookup("whois", start.elapsed());
+    match whois_result {
+        Ok(status) => return Ok(status),
+        // The registry keeps timing out; leave the domain unknown rather than
+        // guess from DNS
+        Err(e @ DomFuzzError::CircuitOpen { .. }) => return Err(e),
+        Err(_) => {}
+    }
+
+    // Fallback to DNS + HTTP checking
+    metrics::record_request("dns", metrics::RESOLVER);
+    let start = Instant::now();
+    let dns_result = timeout(network().dns_timeout, DNS_RESOLVER.lookup_ip(domain)).await;
+    metrics::observe_lookup("dns", start.elapsed());
+
+    match dns_result {
+        Ok(Ok(lookup)) => {
+            if lookup.iter().count() == 0 {
+                return Ok("available".to_string());
+            }
+
+            // Domain has DNS records, check if it's parked or active
+            // Use shared HTTP client for better performance
+
+            // Try HTTP first, then HTTPS
+            for protocol in ["http", "https"] {
+                let url = format!("{}://{}", protocol, domain);
+                // A site whose page is not worth reading answered the HEAD
+                if polite::admit(&url).await.is_err() {
+                    return Ok("registered".to_string());
+                }
+                metrics::record_request("http", metrics::SITES);
+                if let Ok(Ok(resp)) =
+                    timeout(network().http_timeout, HTTP_CLIENT.get(&url).send()).await
+                {
+                    if resp.status().is_success() {
+                        let limit = polite::body_limit(MAX_PARKED_PAGE_BYTES);
+                        if let Ok(Ok(true)) = timeout(
+                            Duration::from_secs(HTTP_CONTENT_TIMEOUT_SECS),
+                            has_parking_marker(resp, limit),
+                        )
+                        .await
+                        {
+                            return Ok("parked".to_string());
+                        }
+                        return Ok("registered".to_string());
+                    }
+                }
+            }
+
+            Ok("registered".to_string())
+        }
+        Ok(Err(e)) => match e.kind() {
+            ResolveErrorKind::NoRecordsFound { .. } => Ok("available".to_string()),
+            _ => Err(DomFuzzError::Dns {
+                domain: domain.to_string(),
+                message: e.to_string(),
+            }),
+        },
+        Err(_) => Err(DomFuzzError::Timeout {
+            protocol: "DNS",
+            domain: domain.to_string(),
+        }),
+    }
+}
+
+/// Whether a page has a parking marker, looked for in each chunk as it arrives;
+/// reading stops at the first marker or after `limit` bytes
+async fn has_parking_marker(
+    mut response: reqwest::Response,
+    limit: usize,
+) -> Result<bool, reqwest::Error> {
+    // The end of the text seen so far is kept for markers split across chunks
+    let overlap = PARKING_MARKERS
+        .iter()
+        .map(|marker| marker.len())
+        .max()
+        .unwrap_or(1)
+        - 1;
+    let mut window: Vec<u8> = Vec::new();
+    let mut read = 0;
+    while read < limit {
+        let Some(chunk) = response.chunk().await? else {
+            break;
+        };
+        let chunk = &chunk[..chunk.len().min(limit - read)];
+        read += chunk.len();
+        window.drain(..window.len().saturating_sub(overlap));
+        window.extend(chunk.iter().map(u8::to_ascii_lowercase));
+        if PARKING_MARKERS.iter().any(|marker| {
+            window
+                .windows(marker.len())
+                .any(|text| text == marker.as_bytes())
+        }) {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+pub async fn check_whois(domain: &str) -> DomainCheckResult<String> {
+    let tld = domain.split('.').next_back().unwrap_or("");
+    let responses = whois_responses(domain).await?;
+
+    // Analyze each hop with the TLD's rules, then the generic ones. A
+    // registrar that has lost track of a domain answers "not found" even
+    // though the registry lists it, so any registered verdict wins
+    let verdicts: Vec<&str> = responses
+        .iter()
+        .filter_map(|response| whois_rules().classify(tld, &response.to_lowercase()))
+        .collect();
+    verdicts
+        .iter()
+        .find(|verdict| **verdict != "available")
+        .or_else(|| verdicts.first())
+        .map(|verdict| verdict.to_string())
+        .ok_or_else(|| DomFuzzError::Whois {
+            domain: domain.to_string(),
+            message: "unable to determine status from response".to_string(),
+        })
+}
+
+/// The WHOIS response for `domain` from its TLD's server, with the responses of
+/// the servers it refers to appended
+pub async fn whois_response(domain: &str) -> DomainCheckResult<String> {
+    Ok(whois_responses(domain).await?.join("\n"))
+}
+
+/// The WHOIS responses for `domain`, one per hop: its TLD's server first, then
+/// each server it refers to
+async fn whois_responses(domain: &str) -> DomainCheckResult<Vec<String>> {
+    let tld = domain.split('.').next_back().unwrap_or("");
+    let whois_server = get_whois_server(tld);
+
+    let mut responses = vec![throttled_whois(domain, &whois_server).await?];
+
+    // Thin registries (.com, .net) and IANA answer with little more than a
+    // referral; follow the chain, each hop from the latest response
+    let mut queried = vec![whois_server];
+    while queried.len() <= MAX_WHOIS_REFERRALS {
+        let latest = responses.last().map(String::as_str).unwrap_or_default();
+        let Some(mut referral) = whois_referral(latest) else {
+            break;
+        };
+        if !referral.contains(':') {
+            referral = format!("{}:{}", referral, WHOIS_PORT);
+        }
+        if queried.contains(&referral) {
+            break;
+        }
+        match throttled_whois(domain, &referral).await {
+            Ok(referred) => responses.push(referred),
+            // The thin response is still better than nothing
+            Err(_) => break,
+        }
+        queried.push(referral);
+    }
+    Ok(responses)
+}
+
+/// Query `whois_server` within its endpoint's concurrency limit
+async fn throttled_whois(domain: &str, whois_server: &str) -> DomainCheckResult<String> {
+    let endpoint = format!("whois:{}", whois_server);
+    let Some(permit) = throttle::acquire(&endpoint).await else {
+        return Err(DomFuzzError::CircuitOpen {
+            endpoint,
+            domain: domain.to_string(),
+        });
+    };
+    metrics::record_request("whois", whois_server);
+    let response = query_whois(domain, whois_server).await;
+    match &response {
+        Err(DomFuzzError::Timeout { .. }) => permit.time_out(),
+        _ => permit.finish(response.is_ok()),
+    }
+    Ok(String::from_utf8_lossy(&response?).into_owned())
+}
+
+/// The server a WHOIS response refers to, from `Registrar WHOIS Server:`
+/// (thin registries) or `refer:` (IANA), as `host` or `host:port`
+pub fn whois_referral(response: &str) -> Option<String> {
+    response.lines().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        let key = key.trim().to_lowercase();
+        if key != "registrar whois server" && key != "refer" {
+            return None;
+        }
+        let value = value.trim();
+        let value = value
+            .split_once("://")
+            .map_or(value, |(_, rest)| rest)
+            .split('/')
+            .next()?
+            .trim();
+        (!value.is_empty()).then(|| value.to_lowercase())
+    })
+}
+
+/// Send a WHOIS query for `domain` to `whois_server` and read the raw response
+async fn query_whois(domain: &str, whois_server: &str) -> DomainCheckResult<Vec<u8>> {
+    // Connect to WHOIS server over whichever address family answers first
+    let (host, port) = whois_server
+        .rsplit_once(':')
+        .and_then(|(host, port)| Some((host, port.parse().ok()?)))
+        .unwrap_or((whois_server, WHOIS_PORT));
+    let mut stream = timeout(network().whois_timeout, net::connect(host, port))
+        .await
+        .map_err(|_| whois_timeout(domain))?
+        .map_err(|e| whois_error(domain, e))?;
+
+    // Send WHOIS query
+    let query = format!(
+        "{}
+",
+        domain
+    );
+    timeout(
+        Duration::from_secs(WHOIS_WRITE_TIMEOUT_SECS),
+        stream.write_all(query.as_bytes()),
+    )
+    .await
+    .map_err(|_| whois_timeout(domain))?
+    .map_err(|e| whois_error(domain, e))?;
+
+    // Read response
+    let mut response = Vec::new();
+    timeout(network().whois_timeout, stream.read_to_end(&mut response))
+        .await
+        .map_err(|_| whois_timeout(domain))?
+        .map_err(|e| whois_error(domain, e))?;
+    Ok(response)
+}
+
+fn whois_timeout(domain: &str) -> DomFuzzError {
+    DomFuzzError::Timeout {
+        protocol: "WHOIS",
+        domain: domain.to_string(),
+    }
+}
+
+fn whois_error(domain: &str, e: std::io::Error) -> DomFuzzError {
+    DomFuzzError::Whois {
+        domain: domain.to_string(),
+        message: e.to_string(),
+    }
+}
+
+pub fn get_whois_server(tld: &str) -> String {
+    match tld {
+        "com" | "net" => "whois.verisign-grs.com:43".to_string(),
+        "org" => "whois.pir.org:43".to_string(),
+        "info" => "whois.afilias.net:43".to_string(),
+        "biz" => "whois.neulevel.biz:43".to_string(),
+        "us" => "whois.nic.us:43".to_string(),
+        "co" => "whois.nic.co:43".to_string(),
+        "io" => "whois.nic.io:43".to_string(),
+        "me" => "whois.nic.me:43".to_string(),
+        "uk" => "whois.nic.uk:43".to_string(),
+        "ca" => "whois.cira.ca:43".to_string(),
+        "de" => "whois.denic.de:43".to_string(),
+        "fr" => "whois.afnic.fr:43".to_string(),
+        "ru" => "whois.tcinet.ru:43".to_string(),
+        "cn" => "whois.cnnic.net.cn:43".to_string(),
+        "jp" => "whois.jprs.jp:43".to_string(),
+        "au" => "whois.auda.org.au:43".to_string(),
+        "br" => "whois.registro.br:43".to_string(),
+        "tk" => "whois.dot.tk:43".to_string(),
+        "ml" => "whois.dot.ml:43".to_string(),
+        "ga" => "whois.dot.ga:43".to_string(),
+        "cf" => "whois.dot.cf:43".to_string(),
+        "app" => "whois.nic.google:43".to_string(),
+        "dev" => "whois.nic.google:43".to_string(),
+        "tech" => "whois.nic.tech:43".to_string(),
+        _ => "whois.iana.org:43".to_string(), // Fallback to IANA
+    }
+}
+
+/// Status of a single checked domain
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckedDomain {
+    pub domain: String,
+    pub status: String,
+}
+
+/// Check domains as they arrive, yielding each result as soon as it completes.
+///
+/// At most `concurrency` lookups are in flight; the input stream is only polled
+/// as slots free up, so a slow consumer applies backpressure all the way to the
+/// source. Results are yielded in completion order, not input order.
+pub fn check_stream<S>(domains: S, concurrency: usize) -> impl Stream<Item = CheckedDomain>
+where
+    S: Stream<Item = String>,
+{
+    domains
+        .map(|domain| async move {
+            let status = check_domain_status(&domain).await;
+            CheckedDomain { domain, status }
+        })
+        .buffer_unordered(concurrency.max(1))
+}
+
+/// Check many domains concurrently, calling `on_checked` as each result arrives.
+/// Results are returned in completion order, not input order.
+pub async fn check_domains<F>(
+    domains: Vec<String>,
+    concurrency: usize,
+    on_checked: F,
+) -> Vec<(String, String)>
+where
+    F: Fn(&str, &str),
+{
+    check_stream(stream::iter(domains), concurrency)
+        .map(|checked| {
+            on_checked(&checked.domain, &checked.status);
+            (checked.domain, checked.status)
+        })
+        .collect()
+        .await
+}
@@ -0,0 +1,420 @@
+//! Combo mode: chaining several transformations on the same domain.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::num::NonZeroUsize;
+use std::rc::Rc;
+
+use lru::LruCache;
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::domain::Domain;
+use crate::generators::Generator;
+
+/// One applied step of a combo chain
+#[derive(Debug, Clone)]
+pub struct ComboStep {
+    pub transformation: &'static str,
+    /// Domain before this step was applied
+    pub from: String,
+    /// Domain produced by this step
+    pub to: String,
+}
+
+/// A chain of transformations applied to the original domain
+#[derive(Debug, Clone)]
+pub struct ComboChain {
+    /// Final domain after all steps
+    pub domain: String,
+    pub steps: Vec<ComboStep>,
+}
+
+/// Default number of transformation outputs a [`ComboMemo`] keeps
+pub const DEFAULT_MEMO_CAPACITY: usize = 4096;
+
+/// (transformation, domain, TLD)
+type MemoKey = (&'static str, String, String);
+
+/// Least-recently-used cache of transformation outputs, keyed by
+/// (transformation, domain, TLD). Random chains start from the same original
+/// and keep passing through the same few intermediates, so without it a long
+/// run regenerates identical output on almost every step.
+pub struct ComboMemo {
+    outputs: LruCache<MemoKey, Rc<Vec<String>>>,
+    hits: u64,
+    misses: u64,
+}
+
+impl ComboMemo {
+    /// A cache of at most `capacity` outputs (at least one)
+    pub fn new(capacity: usize) -> Self {
+        let capacity = NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN);
+        ComboMemo {
+            outputs: LruCache::new(capacity),
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// `generator`'s output for `domain`, generated only on a miss
+    pub fn generate(
+        &mut self,
+        name: &'static str,
+        generator: &Generator,
+        domain: &Domain,
+    ) -> Rc<Vec<String>> {
+        let key = (name, domain.name(), domain.suffix().to_string());
+        if let Some(output) = self.outputs.get(&key) {
+            self.hits += 1;
+            return Rc::clone(output);
+        }
+        self.misses += 1;
+        let output = Rc::new(generator(domain));
+        self.outputs.put(key, Rc::clone(&output));
+        output
+    }
+
+    /// Lookups answered from the cache
+    pub fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    /// Lookups that had to run the transformation
+    pub fn misses(&self) -> u64 {
+        self.misses
+    }
+}
+
+impl Default for ComboMemo {
+    fn default() -> Self {
+        ComboMemo::new(DEFAULT_MEMO_CAPACITY)
+    }
+}
+
+/// Apply a random sequence of 2-5 transformations (repeats allowed), picking one
+/// random result at each step. Returns `None` when no step produced anything.
+pub fn random_combo_chain<R: Rng + ?Sized>(
+    rng: &mut R,
+    domain: &Domain,
+    generators: &[(&'static str, Generator)],
+) -> Option<ComboChain> {
+    random_combo_chain_pruned(rng, domain, generators, |_| true)
+}
+
+/// Like [`random_combo_chain`], but abandons the chain as soon as an
+/// intermediate domain fails `keep`, typically a similarity floor. Steps rarely
+/// restore similarity lost earlier, so finishing such a chain is wasted work.
+pub fn random_combo_chain_pruned<R, F>(
+    rng: &mut R,
+    domain: &Domain,
+    generators: &[(&'static str, Generator)],
+    keep: F,
+) -> Option<ComboChain>
+where
+    R: Rng + ?Sized,
+    F: FnMut(&str) -> bool,
+{
+    random_combo_chain_memoized(rng, domain, generators, &mut ComboMemo::new(1), keep)
+}
+
+/// Like [`random_combo_chain_pruned`], but takes transformation outputs from
+/// `memo` when an earlier chain already generated them. Keep one memo for a
+/// whole run.
+pub fn random_combo_chain_memoized<R, F>(
+    rng: &mut R,
+    domain: &Domain,
+    generators: &[(&'static str, Generator)],
+    memo: &mut ComboMemo,
+    keep: F,
+) -> Option<ComboChain>
+where
+    R: Rng + ?Sized,
+    F: FnMut(&str) -> bool,
+{
+    combo_chain(
+        rng,
+        domain,
+        generators,
+        memo,
+        None::<&mut SimilarityWeights<fn(&str) -> f64>>,
+        keep,
+    )
+}
+
+/// Like [`random_combo_chain_memoized`], but picks each step's transformation
+/// with probability proportional to the share of similarity its outputs have
+/// kept so far, as recorded in `weights`. Unlimited runs drift toward
+/// convincing chains without rejecting more of them. Keep one set of weights
+/// for a whole run.
+pub fn weighted_combo_chain_memoized<R, S, F>(
+    rng: &mut R,
+    domain: &Domain,
+    generators: &[(&'static str, Generator)],
+    memo: &mut ComboMemo,
+    weights: &mut SimilarityWeights<S>,
+    keep: F,
+) -> Option<ComboChain>
+where
+    R: Rng + ?Sized,
+    S: FnMut(&str) -> f64,
+    F: FnMut(&str) -> bool,
+{
+    combo_chain(rng, domain, generators, memo, Some(weights), keep)
+}
+
+/// A random chain; transformations are picked uniformly, or by `weighting`
+/// when given
+fn combo_chain<R, S, F>(
+    rng: &mut R,
+    domain: &Domain,
+    generators: &[(&'static str, Generator)],
+    memo: &mut ComboMemo,
+    mut weighting: Option<&mut SimilarityWeights<S>>,
+    mut keep: F,
+) -> Option<ComboChain>
+where
+    R: Rng + ?Sized,
+    S: FnMut(&str) -> f64,
+    F: FnMut(&str) -> bool,
+{
+    let mut current = domain.clone();
+    let mut steps = Vec::new();
+    // The original is perfectly similar to itself
+    let mut current_similarity = 1.0;
+
+    let num_steps = rng.gen_range(2..=5);
+    for _ in 0..num_steps {
+        let chosen = match &weighting {
+            Some(weights) => weights.choose(rng, generators),
+            None => generators.choose(rng),
+        };
+        let Some((name, generator)) = chosen else {
+            break;
+        };
+        // Apply the transformation and randomly select one result
+        let results = memo.generate(name, generator, &current);
+        if let Some(selected) = results.choose(rng) {
+            // Nothing can be generated from a result that is not a valid domain
+            let Ok(next) = Domain::parse(selected) else {
+                continue;
+            };
+            if let Some(weights) = &mut weighting {
+                let next_similarity = (weights.similarity)(selected);
+                weights.observe(name, current_similarity, next_similarity);
+                current_similarity = next_similarity;
+            }
+            if !keep(selected) {
+                return None;
+            }
+            steps.push(ComboStep {
+                transformation: name,
+                from: current.to_string(),
+                to: selected.clone(),
+            });
+            current = next;
+        }
+    }
+
+    if steps.is_empty() {
+        None
+    } else {
+        Some(ComboChain {
+            domain: current.to_string(),
+            steps,
+        })
+    }
+}
+
+/// Share of similarity assumed kept by a transformation not yet observed, and
+/// how many observations that assumption counts as
+const PRIOR_RETAINED: f64 = 0.8;
+const PRIOR_OBSERVATIONS: f64 = 5.0;
+/// Lowest weight a transformation keeps, so none is ruled out for good
+const MIN_WEIGHT: f64 = 0.01;
+
+/// How much similarity each transformation's outputs keep, learned from the
+/// steps of [`weighted_combo_chain_memoized`]
+pub struct SimilarityWeights<S> {
+    /// Similarity of a domain to the original
+    similarity: S,
+    /// Sum of the shares kept, and the number of steps observed
+    retained: HashMap<&'static str, (f64, u64)>,
+}
+
+impl<S: FnMut(&str) -> f64> SimilarityWeights<S> {
+    /// Weights learned from domains scored by `similarity`
+    pub fn new(similarity: S) -> Self {
+        SimilarityWeights {
+            similarity,
+            retained: HashMap::new(),
+        }
+    }
+
+    /// Record a step of `transformation` from a domain scoring `before` to one
+    /// scoring `after`
+    pub fn observe(&mut self, transformation: &'static str, before: f64, after: f64) {
+        let share = if before > 0.0 {
+            (after / before).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        let (sum, count) = self.retained.entry(transformation).or_default();
+        *sum += share;
+        *count += 1;
+    }
+
+    /// Average share of similarity `transformation` keeps per step, starting
+    /// from [`PRIOR_RETAINED`] before it has been observed
+    pub fn retained(&self, transformation: &str) -> f64 {
+        let (sum, count) = self
+            .retained
+            .get(transformation)
+            .copied()
+            .unwrap_or_default();
+        (sum + PRIOR_RETAINED * PRIOR_OBSERVATIONS) / (count as f64 + PRIOR_OBSERVATIONS)
+    }
+
+    /// Steps observed for `transformation`
+    pub fn observations(&self, transformation: &str) -> u64 {
+        self.retained
+            .get(transformation)
+            .map_or(0, |&(_, count)| count)
+    }
+
+    fn choose<'a, 'g, R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        generators: &'a [(&'static str, Generator<'g>)],
+    ) -> Option<&'a (&'static str, Generator<'g>)> {
+        let weights = generators
+            .iter()
+            .map(|(name, _)| self.retained(name).max(MIN_WEIGHT));
+        let index = WeightedIndex::new(weights).ok()?;
+        generators.get(index.sample(rng))
+    }
+}
+
+/// Deterministically enumerates every distinct chain of 2 to `max_depth`
+/// transformations, breadth first, so shorter and therefore more similar chains
+/// come first and two runs cover exactly the same ground.
+///
+/// Each domain is reached once, by its shortest chain; domains reachable in a
+/// single step are expanded but not yielded, since they are not combinations.
+/// Domains rejected by `keep` are neither yielded nor expanded.
+pub struct ExhaustiveCombos<'a, F> {
+    generators: &'a [(&'static str, Generator<'a>)],
+    max_depth: usize,
+    keep: F,
+    nodes: Vec<ChainNode>,
+    seen: HashSet<String>,
+    /// Nodes still to be expanded
+    queue: VecDeque<usize>,
+    /// Nodes to yield before expanding further
+    ready: VecDeque<usize>,
+}
+
+struct ChainNode {
+    domain: String,
+    transformation: &'static str,
+    parent: Option<usize>,
+    depth: usize,
+}
+
+impl<'a, F: FnMut(&str) -> bool> ExhaustiveCombos<'a, F> {
+    pub fn new(
+        domain: &Domain,
+        generators: &'a [(&'static str, Generator<'a>)],
+        max_depth: usize,
+        keep: F,
+    ) -> Self {
+        let original = domain.to_string();
+        ExhaustiveCombos {
+            generators,
+            max_depth,
+            keep,
+            seen: HashSet::from([original.clone()]),
+            nodes: vec![ChainNode {
+                domain: original,
+                transformation: "",
+                parent: None,
+                depth: 0,
+            }],
+            queue: VecDeque::from([0]),
+            ready: VecDeque::new(),
+        }
+    }
+
+    /// Number of distinct domains reached so far, including the original
+    pub fn reached(&self) -> usize {
+        self.nodes.len()
+    }
+
+    fn expand(&mut self, index: usize) {
+        let depth = self.nodes[index].depth + 1;
+        // Nothing can be generated from a result that is not a valid domain
+        let Ok(domain) = Domain::parse(&self.nodes[index].domain) else {
+            return;
+        };
+        for (name, generator) in self.generators {
+            let mut results = generator(&domain);
+            // Generators backed by hash sets return results in arbitrary order
+            results.sort();
+            results.dedup();
+            for result in results {
+                if self.seen.contains(&result) {
+                    continue;
+                }
+                self.seen.insert(result.clone());
+                if !(self.keep)(&result) {
+                    continue;
+                }
+                let child = self.nodes.len();
+                self.nodes.push(ChainNode {
+                    domain: result,
+                    transformation: name,
+                    parent: Some(index),
+                    depth,
+                });
+                if depth < self.max_depth {
+                    self.queue.push_back(child);
+                }
+                if depth >= 2 {
+                    self.ready.push_back(child);
+                }
+            }
+        }
+    }
+
+    fn chain_to(&self, index: usize) -> ComboChain {
+        let mut steps = Vec::new();
+        let mut current = index;
+        while let Some(parent) = self.nodes[current].parent {
+            steps.push(ComboStep {
+                transformation: self.nodes[current].transformation,
+                from: self.nodes[parent].domain.clone(),
+                to: self.nodes[current].domain.clone(),
+            });
+            current = parent;
+        }
+        steps.reverse();
+        ComboChain {
+            domain: self.nodes[index].domain.clone(),
+            steps,
+        }
+    }
+}
+
+impl<F: FnMut(&str) -> bool> Iterator for ExhaustiveCombos<'_, F> {
+    type Item = ComboChain;
+
+    fn next(&mut self) -> Option<ComboChain> {
+        loop {
+            if let Some(index) = self.ready.pop_front() {
+                return Some(self.chain_to(index));
+            }
+            let index = self.queue.pop_front()?;
+            self.expand(index);
+        }
+    }
+}
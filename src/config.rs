@@ -0,0 +1,340 @@
+//! The `domfuzz` configuration file.
+//!
+//! Settings that are secret or rarely change, such as provider API keys, live in
+//! a TOML file instead of on the command line. The file is read from `--config`,
+//! or else from `$XDG_CONFIG_HOME/domfuzz/config.toml` (falling back to
+//! `~/.config/domfuzz/config.toml`) when that exists.
+//!
+//! ```toml
+//! [reputation.abuseipdb]
+//! api_key = "..."
+//!
+//! [reputation.spamhaus]
+//! # Data Query Service key; omit to query the public zen.spamhaus.org mirror
+//! dqs_key = "..."
+//!
+//! # Enables --safe-browsing; `service = "web-risk"` uses the Web Risk API instead
+//! [safe_browsing]
+//! api_key = "..."
+//!
+//! # Network behavior selected with `--profile strict-registries`
+//! [profile.strict-registries]
+//! rdap_timeout_secs = 15
+//! whois_timeout_secs = 30
+//! retries = 3
+//! retry_delay_ms = 2000
+//! max_concurrency = 4
+//!
+//! # Transitions that raise alerts in monitor mode
+//! [alerts]
+//! webhook = "https://hooks.example.com/domfuzz"
+//!
+//! [[alerts.rule]]
+//! transition = "available->registered"
+//! severity = "high"
+//!
+//! # Transformations selected together with `-t phishkit`
+//! [bundle.phishkit]
+//! transforms = ["lookalike", "combosquatting", "brand-confusion"]
+//! ```
+
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::checker::NetworkSettings;
+use crate::enrich::{ReputationProvider, ThreatList};
+use crate::error::DomFuzzError;
+use crate::generators::{
+    check_transformation_names, is_builtin_transformation, parse_transformations_with,
+};
+use crate::monitor::AlertRule;
+
+/// Parsed configuration file; every section is optional
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    #[serde(default)]
+    pub reputation: ReputationConfig,
+    pub safe_browsing: Option<SafeBrowsingConfig>,
+    /// Named network profiles, `[profile.<name>]`
+    #[serde(default, rename = "profile")]
+    pub profiles: BTreeMap<String, NetworkProfile>,
+    #[serde(default)]
+    pub alerts: AlertsConfig,
+    /// User-defined transformation bundles, `[bundle.<name>]`
+    #[serde(default, rename = "bundle")]
+    pub bundles: BTreeMap<String, Bundle>,
+}
+
+/// Transformations selected together by the bundle's name in `-t`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Bundle {
+    /// Transformations, built-in bundles, and other user-defined bundles
+    pub transforms: Vec<String>,
+}
+
+/// Monitor-mode alerting: which transitions raise alerts, and where they go
+/// besides the event stream
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AlertsConfig {
+    /// URL each alert is POSTed to as JSON
+    pub webhook: Option<String>,
+    #[serde(default, rename = "rule")]
+    pub rules: Vec<AlertRule>,
+}
+
+/// Overrides of the default network behavior; unset fields keep their defaults
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NetworkProfile {
+    pub rdap_timeout_secs: Option<u64>,
+    pub whois_timeout_secs: Option<u64>,
+    pub dns_timeout_secs: Option<u64>,
+    pub http_timeout_secs: Option<u64>,
+    /// Retries of a rate-limited RDAP lookup
+    pub retries: Option<u32>,
+    pub retry_delay_ms: Option<u64>,
+    /// Most lookups in flight per RDAP or WHOIS endpoint
+    pub max_concurrency: Option<usize>,
+}
+
+impl NetworkProfile {
+    /// Every timeout set is at least a second; a zero timeout would fail every
+    /// lookup
+    fn check(&self) -> Result<(), String> {
+        for (field, value) in [
+            ("rdap_timeout_secs", self.rdap_timeout_secs),
+            ("whois_timeout_secs", self.whois_timeout_secs),
+            ("dns_timeout_secs", self.dns_timeout_secs),
+            ("http_timeout_secs", self.http_timeout_secs),
+        ] {
+            if value == Some(0) {
+                return Err(format!("{} must be at least 1", field));
+            }
+        }
+        Ok(())
+    }
+
+    /// Apply the overrides to `settings`
+    pub fn apply(&self, settings: &mut NetworkSettings) {
+        let secs = |value: Option<u64>, setting: &mut Duration| {
+            if let Some(value) = value {
+                *setting = Duration::from_secs(value);
+            }
+        };
+        secs(self.rdap_timeout_secs, &mut settings.rdap_timeout);
+        secs(self.whois_timeout_secs, &mut settings.whois_timeout);
+        secs(self.dns_timeout_secs, &mut settings.dns_timeout);
+        secs(self.http_timeout_secs, &mut settings.http_timeout);
+        if let Some(retries) = self.retries {
+            settings.retries = retries;
+        }
+        if let Some(delay) = self.retry_delay_ms {
+            settings.retry_delay = Duration::from_millis(delay);
+        }
+        if let Some(max) = self.max_concurrency {
+            settings.max_concurrency = max;
+        }
+    }
+}
+
+/// IP reputation providers; a provider is enabled by its section being present
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ReputationConfig {
+    pub abuseipdb: Option<AbuseIpDbConfig>,
+    pub spamhaus: Option<SpamhausConfig>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AbuseIpDbConfig {
+    pub api_key: String,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SpamhausConfig {
+    pub dqs_key: Option<String>,
+}
+
+/// Google threat list looked up by `--safe-browsing`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SafeBrowsingConfig {
+    pub api_key: String,
+    #[serde(default)]
+    pub service: ThreatService,
+}
+
+/// Which Google API `[safe_browsing]` uses
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ThreatService {
+    /// Safe Browsing v4, free for non-commercial use
+    #[default]
+    SafeBrowsing,
+    /// Web Risk, for commercial use
+    WebRisk,
+}
+
+impl Config {
+    /// Load `path`, or the default location if `path` is `None`. A missing
+    /// default file yields the default configuration; a missing explicit file is
+    /// an error.
+    pub fn load(path: Option<&Path>) -> Result<Config, DomFuzzError> {
+        let path = match path {
+            Some(path) => path.to_path_buf(),
+            None => match Config::default_path() {
+                Some(path) if path.is_file() => path,
+                _ => return Ok(Config::default()),
+            },
+        };
+        let contents = std::fs::read_to_string(&path).map_err(|e| {
+            DomFuzzError::Config(format!("cannot read config {}: {}", path.display(), e))
+        })?;
+        let invalid = |message: String| {
+            DomFuzzError::Config(format!("invalid config {}: {}", path.display(), message))
+        };
+        let config: Config = toml::from_str(&contents).map_err(|e| invalid(e.to_string()))?;
+        config.check_bundles().map_err(invalid)?;
+        for (name, profile) in &config.profiles {
+            profile
+                .check()
+                .map_err(|message| invalid(format!("profile {:?}: {}", name, message)))?;
+        }
+        Ok(config)
+    }
+
+    /// Every bundle has a lowercase name of its own, lists only names `-t`
+    /// knows, does not include itself, and expands to some transformation
+    fn check_bundles(&self) -> Result<(), String> {
+        for (name, bundle) in &self.bundles {
+            if *name != name.to_lowercase() {
+                return Err(format!("bundle name {:?} must be lowercase", name));
+            }
+            if is_builtin_transformation(name) {
+                return Err(format!(
+                    "bundle {:?} would hide the built-in {:?}",
+                    name, name
+                ));
+            }
+            if bundle.transforms.is_empty() {
+                return Err(format!("bundle {:?} lists no transforms", name));
+            }
+            if let Some(unknown) = bundle.transforms.iter().find(|member| {
+                !is_builtin_transformation(member)
+                    && !self.bundles.contains_key(&member.to_lowercase())
+            }) {
+                return Err(format!(
+                    "bundle {:?} lists unknown transformation {:?}",
+                    name, unknown
+                ));
+            }
+        }
+        for name in self.bundles.keys() {
+            self.check_bundle_cycle(&mut vec![name.as_str()])?;
+            let expanded = parse_transformations_with(std::slice::from_ref(name), |name| {
+                self.bundles
+                    .get(name)
+                    .map(|bundle| bundle.transforms.as_slice())
+            });
+            if expanded.is_empty() {
+                return Err(format!("bundle {:?} expands to no transformations", name));
+            }
+        }
+        Ok(())
+    }
+
+    /// Reject a member of the last bundle on `path` that leads back to a
+    /// bundle on it
+    fn check_bundle_cycle<'a>(&'a self, path: &mut Vec<&'a str>) -> Result<(), String> {
+        let bundle = &self.bundles[*path.last().expect("path starts at a bundle")];
+        for member in &bundle.transforms {
+            let Some((member, _)) = self.bundles.get_key_value(&member.to_lowercase()) else {
+                continue;
+            };
+            if path.contains(&member.as_str()) {
+                path.push(member);
+                return Err(format!("bundles include themselves: {}", path.join(" -> ")));
+            }
+            path.push(member);
+            self.check_bundle_cycle(path)?;
+            path.pop();
+        }
+        Ok(())
+    }
+
+    /// The transformations `-t` enables given `requested`, with the bundles of
+    /// `[bundle.<name>]` expanded; an error names the first unknown name
+    pub fn transformations(&self, requested: &[String]) -> Result<HashSet<String>, DomFuzzError> {
+        let bundles: Vec<&str> = self.bundles.keys().map(String::as_str).collect();
+        check_transformation_names(requested, &bundles)?;
+        Ok(parse_transformations_with(requested, |name| {
+            self.bundles
+                .get(name)
+                .map(|bundle| bundle.transforms.as_slice())
+        }))
+    }
+
+    /// `$XDG_CONFIG_HOME/domfuzz/config.toml`, or `~/.config/domfuzz/config.toml`
+    pub fn default_path() -> Option<PathBuf> {
+        let base = std::env::var_os("XDG_CONFIG_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+        Some(base.join("domfuzz").join("config.toml"))
+    }
+
+    /// Default network settings with the overrides of `profile`, if given
+    pub fn network_settings(&self, profile: Option<&str>) -> Result<NetworkSettings, DomFuzzError> {
+        let mut settings = NetworkSettings::default();
+        if let Some(name) = profile {
+            let profile = self.profiles.get(name).ok_or_else(|| {
+                let known: Vec<&str> = self.profiles.keys().map(String::as_str).collect();
+                DomFuzzError::Config(if known.is_empty() {
+                    format!("unknown profile {:?}: the config defines no profiles", name)
+                } else {
+                    format!(
+                        "unknown profile {:?}; defined profiles: {}",
+                        name,
+                        known.join(", ")
+                    )
+                })
+            })?;
+            profile.apply(&mut settings);
+        }
+        Ok(settings)
+    }
+
+    /// The reputation providers configured in `[reputation]`
+    pub fn reputation_providers(&self) -> Vec<ReputationProvider> {
+        let mut providers = Vec::new();
+        if let Some(abuseipdb) = &self.reputation.abuseipdb {
+            providers.push(ReputationProvider::AbuseIpDb {
+                api_key: abuseipdb.api_key.clone(),
+            });
+        }
+        if let Some(spamhaus) = &self.reputation.spamhaus {
+            providers.push(ReputationProvider::Spamhaus {
+                dqs_key: spamhaus.dqs_key.clone(),
+            });
+        }
+        providers
+    }
+
+    /// The threat list configured in `[safe_browsing]`, if any
+    pub fn threat_list(&self) -> Option<ThreatList> {
+        let config = self.safe_browsing.as_ref()?;
+        let api_key = config.api_key.clone();
+        Some(match config.service {
+            ThreatService::SafeBrowsing => ThreatList::SafeBrowsing { api_key },
+            ThreatService::WebRisk => ThreatList::WebRisk { api_key },
+        })
+    }
+}
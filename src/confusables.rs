@@ -0,0 +1,325 @@
+//! Confusable analysis of a suspicious domain (`domfuzz analyze`).
+//!
+//! Generation goes from a brand to its lookalikes; analysis goes the other way,
+//! taking a domain seen in the wild apart character by character. Each
+//! character is reduced to the ASCII it passes for: NFKC normalization and
+//! lowercasing first (fullwidth `ｅ` is `e`), then decomposition with combining
+//! marks dropped (`é` is `e`), then the homoglyphs in
+//! `data/confusables.txt` (Cyrillic `а` is `a`, `rn` is `m`). The results
+//! joined make the domain's skeleton, in the sense of UTS #39: two domains
+//! with the same skeleton read the same at a glance.
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use icu_normalizer::{ComposingNormalizerBorrowed, DecomposingNormalizerBorrowed};
+use icu_properties::props::{GeneralCategory, GeneralCategoryGroup, Script};
+use icu_properties::{CodePointMapData, PropertyNamesLong};
+
+use crate::domain::Domain;
+use crate::error::DomFuzzError;
+
+/// What a run of characters in a suspicious domain is read as
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum SegmentKind {
+    /// ASCII, read as itself
+    Plain,
+    /// Read as different ASCII: a homoglyph, a compatibility form, or a letter
+    /// with a diacritic
+    Confusable,
+    /// Not ASCII and not like any ASCII character
+    Unmapped,
+}
+
+/// One character of a suspicious domain, or a few read together (`rn`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Segment {
+    pub text: String,
+    /// What `text` reads as; its contribution to the skeleton
+    pub prototype: String,
+    /// Unicode script of the first character, such as `Latin` or `Cyrillic`
+    pub script: &'static str,
+    pub kind: SegmentKind,
+}
+
+/// Scripts the letters of one label are written in
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct LabelScripts {
+    pub label: String,
+    /// In order of first use; characters common to all scripts, such as digits
+    /// and hyphens, are left out
+    pub scripts: Vec<&'static str>,
+}
+
+impl LabelScripts {
+    /// Whether the label mixes scripts, which no honest name needs
+    pub fn is_mixed(&self) -> bool {
+        self.scripts.len() > 1
+    }
+}
+
+/// How the skeletons of a suspicious domain and the brand compare
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum SkeletonMatch {
+    /// The whole domains read the same
+    Exact,
+    /// The names read the same under different suffixes
+    Name,
+    None,
+}
+
+/// A suspicious domain taken apart against a brand
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Analysis {
+    /// The suspicious domain in Unicode form
+    pub domain: String,
+    /// The suspicious domain as sent to DNS, in punycode where not ASCII
+    pub ascii: String,
+    pub against: String,
+    /// The suspicious domain, left to right
+    pub segments: Vec<Segment>,
+    pub label_scripts: Vec<LabelScripts>,
+    pub skeleton: String,
+    pub against_skeleton: String,
+    pub skeleton_match: SkeletonMatch,
+}
+
+impl Analysis {
+    /// Whether any label mixes scripts
+    pub fn is_mixed_script(&self) -> bool {
+        self.label_scripts.iter().any(LabelScripts::is_mixed)
+    }
+
+    /// Segments that read as something they are not
+    pub fn confusables(&self) -> impl Iterator<Item = &Segment> {
+        self.segments
+            .iter()
+            .filter(|segment| segment.kind == SegmentKind::Confusable)
+    }
+}
+
+/// Take `domain` apart against `against`; either may be in Unicode or
+/// punycode form
+pub fn analyze(domain: &str, against: &str) -> Result<Analysis, DomFuzzError> {
+    let (suspicious, brand) = (parse(domain)?, parse(against)?);
+    let unicode = suspicious.to_unicode();
+    let segments = segments(&unicode);
+    let skeleton: String = segments.iter().map(|s| s.prototype.as_str()).collect();
+    let against_skeleton = self::skeleton(&brand.to_unicode());
+    let skeleton_match = if skeleton == against_skeleton {
+        SkeletonMatch::Exact
+    } else if self::skeleton(&suspicious.name()) == self::skeleton(&brand.name()) {
+        SkeletonMatch::Name
+    } else {
+        SkeletonMatch::None
+    };
+    let label_scripts = unicode
+        .split('.')
+        .map(|label| {
+            let mut scripts = Vec::new();
+            for c in label.chars() {
+                let script = script_of(c);
+                if script != Script::Common && script != Script::Inherited {
+                    let name = script_name(script);
+                    if !scripts.contains(&name) {
+                        scripts.push(name);
+                    }
+                }
+            }
+            LabelScripts {
+                label: label.to_string(),
+                scripts,
+            }
+        })
+        .collect();
+
+    Ok(Analysis {
+        domain: unicode,
+        ascii: suspicious.to_ascii(),
+        against: brand.to_unicode(),
+        segments,
+        label_scripts,
+        skeleton,
+        against_skeleton,
+        skeleton_match,
+    })
+}
+
+/// What `domain` reads as, in ASCII where its characters pass for ASCII
+pub fn skeleton(domain: &str) -> String {
+    segments(&domain.to_lowercase())
+        .into_iter()
+        .map(|segment| segment.prototype)
+        .collect()
+}
+
+/// Non-ASCII characters the bundled table reads as the ASCII letter or digit
+/// `prototype`, in file order
+pub fn homoglyphs(prototype: char) -> &'static [char] {
+    confusables()
+        .homoglyphs
+        .get(&prototype)
+        .map_or(&[], Vec::as_slice)
+}
+
+/// A domain as written, with the full stops that IDNA takes as dots (`。`)
+/// read as dots
+fn parse(input: &str) -> Result<Domain, DomFuzzError> {
+    Domain::parse(&input.replace(['。', '．', '｡'], "."))
+}
+
+fn segments(domain: &str) -> Vec<Segment> {
+    let table = confusables();
+    let chars: Vec<char> = domain.chars().collect();
+    let mut segments = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let rest: String = chars[i..].iter().collect();
+        if let Some((sequence, prototype)) = table
+            .sequences
+            .iter()
+            .find(|(sequence, _)| rest.starts_with(sequence.as_str()))
+        {
+            segments.push(Segment {
+                text: sequence.clone(),
+                prototype: prototype.clone(),
+                script: script_name(script_of(chars[i])),
+                kind: SegmentKind::Confusable,
+            });
+            i += sequence.chars().count();
+            continue;
+        }
+
+        let c = chars[i];
+        let prototype = prototype_of(c, table);
+        let kind = if prototype == c.to_string() {
+            if c.is_ascii() {
+                SegmentKind::Plain
+            } else {
+                SegmentKind::Unmapped
+            }
+        } else if prototype.is_ascii() {
+            SegmentKind::Confusable
+        } else {
+            SegmentKind::Unmapped
+        };
+        segments.push(Segment {
+            text: c.to_string(),
+            prototype,
+            script: script_name(script_of(c)),
+            kind,
+        });
+        i += 1;
+    }
+    segments
+}
+
+/// `c` normalized, lowercased, stripped of combining marks, and mapped
+/// through the confusables table
+fn prototype_of(c: char, table: &Confusables) -> String {
+    let categories = CodePointMapData::<GeneralCategory>::new();
+    let compatible = ComposingNormalizerBorrowed::new_nfkc()
+        .normalize(&c.to_string())
+        .to_lowercase();
+    DecomposingNormalizerBorrowed::new_nfd()
+        .normalize(&compatible)
+        .chars()
+        .filter(|&c| !GeneralCategoryGroup::Mark.contains(categories.get(c)))
+        .map(|c| {
+            table
+                .chars
+                .get(&c)
+                .cloned()
+                .unwrap_or_else(|| c.to_string())
+        })
+        .collect()
+}
+
+fn script_of(c: char) -> Script {
+    CodePointMapData::<Script>::new().get(c)
+}
+
+fn script_name(script: Script) -> &'static str {
+    PropertyNamesLong::<Script>::new()
+        .get(script)
+        .unwrap_or("Unknown")
+}
+
+/// The bundled homoglyph table
+struct Confusables {
+    /// Single characters and what they read as
+    chars: HashMap<char, String>,
+    /// Runs of several ASCII characters read as one (`rn`), longest first
+    sequences: Vec<(String, String)>,
+    /// Single ASCII characters and the non-ASCII characters read as each
+    homoglyphs: HashMap<char, Vec<char>>,
+}
+
+fn confusables() -> &'static Confusables {
+    static CONFUSABLES: OnceLock<Confusables> = OnceLock::new();
+    CONFUSABLES.get_or_init(|| {
+        parse_confusables(include_str!("../data/confusables.txt"))
+            .unwrap_or_else(|e| panic!("built-in confusables data is invalid: {}", e))
+    })
+}
+
+fn parse_confusables(text: &str) -> Result<Confusables, DomFuzzError> {
+    let mut confusables = Confusables {
+        chars: HashMap::new(),
+        sequences: Vec::new(),
+        homoglyphs: HashMap::new(),
+    };
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let invalid =
+            |reason: String| DomFuzzError::Parse(format!("line {}: {}", number + 1, reason));
+        let mut words = line.split_whitespace().map(str::to_lowercase);
+        let prototype = words.next().expect("line is not blank");
+        if !prototype.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(invalid(format!("prototype {:?} is not ASCII", prototype)));
+        }
+        for confusable in words {
+            let mut chars = confusable.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => {
+                    confusables.chars.insert(c, prototype.clone());
+                    let mut prototype_chars = prototype.chars();
+                    if let (Some(p), None, false) =
+                        (prototype_chars.next(), prototype_chars.next(), c.is_ascii())
+                    {
+                        confusables.homoglyphs.entry(p).or_default().push(c);
+                    }
+                }
+                _ if confusable.is_ascii() => {
+                    confusables.sequences.push((confusable, prototype.clone()))
+                }
+                _ => {
+                    return Err(invalid(format!(
+                        "confusable {:?} is neither one character nor ASCII",
+                        confusable
+                    )))
+                }
+            }
+        }
+    }
+    confusables
+        .sequences
+        .sort_by_key(|(sequence, _)| std::cmp::Reverse(sequence.len()));
+    Ok(confusables)
+}
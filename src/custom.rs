@@ -0,0 +1,236 @@
+//! User-defined transformation rules (`--rules FILE`).
+//!
+//! Engagement-specific patterns, such as a brand's habitual abbreviations or a
+//! campaign's favourite suffix, are declared in a small rules file instead of
+//! a fork of the generators. Each rule rewrites the domain name (without the
+//! TLD); together they form the `custom` transformation.
+
+use regex::Regex;
+
+use crate::error::DomFuzzError;
+
+/// Where a rule applies when its pattern occurs, or where text is inserted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Position {
+    /// One variation per occurrence (per gap, for `insert`)
+    Each,
+    /// One variation with every occurrence rewritten
+    All,
+    /// The first occurrence only (the start of the name, for `insert`)
+    First,
+    /// The last occurrence only (the end of the name, for `insert`)
+    Last,
+}
+
+impl Position {
+    fn parse(position: &str) -> Option<Self> {
+        match position {
+            "each" => Some(Position::Each),
+            "all" => Some(Position::All),
+            "first" | "start" => Some(Position::First),
+            "last" | "end" => Some(Position::Last),
+            _ => None,
+        }
+    }
+}
+
+/// One rewrite of the domain name
+#[derive(Debug, Clone)]
+pub enum CustomRule {
+    /// Replace a literal substring
+    Replace {
+        find: String,
+        replace: String,
+        position: Position,
+    },
+    /// Replace regex matches; the replacement may use `$1` or `${name}`
+    Regex {
+        pattern: Regex,
+        replace: String,
+        position: Position,
+    },
+    /// Insert text at the start, the end, or between every two characters
+    Insert { text: String, position: Position },
+}
+
+/// The rules of one file, applied in order
+#[derive(Debug, Clone, Default)]
+pub struct CustomRules {
+    rules: Vec<CustomRule>,
+}
+
+impl CustomRules {
+    /// Parse rules, one per line, with whitespace-separated fields:
+    ///
+    /// ```text
+    /// replace FIND REPLACE [each|all|first|last]
+    /// regex PATTERN REPLACE [each|all|first|last]
+    /// insert TEXT [each|start|end]
+    /// ```
+    ///
+    /// The position defaults to `each`. Blank lines and lines starting with
+    /// `#` are ignored; a replacement of `""` deletes the match.
+    pub fn parse(text: &str) -> Result<Self, DomFuzzError> {
+        let mut rules = Vec::new();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid =
+                |reason: String| DomFuzzError::Parse(format!("line {}: {}", number + 1, reason));
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let (kind, arguments) = (fields[0], &fields[1..]);
+            let expected = match kind {
+                "replace" | "regex" => 2,
+                "insert" => 1,
+                _ => {
+                    return Err(invalid(format!(
+                        "expected replace, regex, or insert, got {:?}",
+                        kind
+                    )))
+                }
+            };
+            if arguments.len() < expected || arguments.len() > expected + 1 {
+                return Err(invalid(format!(
+                    "{} takes {} arguments and an optional position",
+                    kind, expected
+                )));
+            }
+            let position = match arguments.get(expected) {
+                Some(position) => Position::parse(position).ok_or_else(|| {
+                    invalid(format!(
+                        "expected each, all, first, or last, got {:?}",
+                        position
+                    ))
+                })?,
+                None => Position::Each,
+            };
+            let unquote = |field: &str| if field == "\"\"" { "" } else { field }.to_string();
+            rules.push(match kind {
+                "replace" => CustomRule::Replace {
+                    find: arguments[0].to_lowercase(),
+                    replace: unquote(arguments[1]),
+                    position,
+                },
+                "regex" => CustomRule::Regex {
+                    pattern: Regex::new(arguments[0])
+                        .map_err(|e| invalid(format!("invalid regex: {}", e)))?,
+                    replace: unquote(arguments[1]),
+                    position,
+                },
+                _ => {
+                    if position == Position::All {
+                        return Err(invalid("insert takes each, start, or end".to_string()));
+                    }
+                    CustomRule::Insert {
+                        text: arguments[0].to_string(),
+                        position,
+                    }
+                }
+            });
+        }
+        Ok(CustomRules { rules })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Apply every rule to `domain`, returning candidate domains under `tld`
+    pub fn generate(&self, domain: &str, tld: &str) -> Vec<String> {
+        let domain = domain.to_lowercase();
+        let mut names = Vec::new();
+        for rule in &self.rules {
+            match rule {
+                CustomRule::Replace {
+                    find,
+                    replace,
+                    position,
+                } => {
+                    let matches: Vec<(usize, usize)> = domain
+                        .match_indices(find.as_str())
+                        .map(|(start, found)| (start, start + found.len()))
+                        .collect();
+                    rewrite(
+                        &domain,
+                        &matches,
+                        |_| replace.clone(),
+                        *position,
+                        &mut names,
+                    );
+                }
+                CustomRule::Regex {
+                    pattern,
+                    replace,
+                    position,
+                } => {
+                    let captures: Vec<regex::Captures> = pattern.captures_iter(&domain).collect();
+                    let matches: Vec<(usize, usize)> = captures
+                        .iter()
+                        .map(|c| c.get(0).map_or((0, 0), |m| (m.start(), m.end())))
+                        .collect();
+                    rewrite(
+                        &domain,
+                        &matches,
+                        |i| {
+                            let mut expanded = String::new();
+                            captures[i].expand(replace, &mut expanded);
+                            expanded
+                        },
+                        *position,
+                        &mut names,
+                    );
+                }
+                CustomRule::Insert { text, position } => {
+                    let gaps: Vec<usize> = match position {
+                        Position::First => vec![0],
+                        Position::Last => vec![domain.len()],
+                        _ => domain.char_indices().skip(1).map(|(i, _)| i).collect(),
+                    };
+                    for gap in gaps {
+                        names.push(format!("{}{}{}", &domain[..gap], text, &domain[gap..]));
+                    }
+                }
+            }
+        }
+        names
+            .into_iter()
+            .map(|name| format!("{}.{}", name, tld))
+            .collect()
+    }
+}
+
+/// Replace the byte ranges in `matches` of `domain` with `replacement(index)`,
+/// pushing one name per rewrite that `position` asks for
+fn rewrite<F>(
+    domain: &str,
+    matches: &[(usize, usize)],
+    replacement: F,
+    position: Position,
+    names: &mut Vec<String>,
+) where
+    F: Fn(usize) -> String,
+{
+    let replace_one = |i: usize| {
+        let (start, end) = matches[i];
+        format!("{}{}{}", &domain[..start], replacement(i), &domain[end..])
+    };
+    match position {
+        _ if matches.is_empty() => {}
+        Position::Each => names.extend((0..matches.len()).map(replace_one)),
+        Position::First => names.push(replace_one(0)),
+        Position::Last => names.push(replace_one(matches.len() - 1)),
+        Position::All => {
+            let mut name = String::new();
+            let mut last = 0;
+            for (i, (start, end)) in matches.iter().enumerate() {
+                name.push_str(&domain[last..*start]);
+                name.push_str(&replacement(i));
+                last = *end;
+            }
+            name.push_str(&domain[last..]);
+            names.push(name);
+        }
+    }
+}
@@ -0,0 +1,172 @@
+//! Sets of already-seen domains for de-duplicating long generation runs.
+//!
+//! [`DomainSet::Exact`] stores every domain and never errs. For unlimited combo
+//! runs that can emit hundreds of millions of candidates,
+//! [`DomainSet::Probabilistic`] keeps a scalable Bloom filter instead: memory
+//! grows by a few bits per domain, at the cost of occasionally treating a new
+//! domain as already seen (never the reverse).
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+
+/// Default false-positive rate of probabilistic de-duplication
+pub const DEFAULT_FALSE_POSITIVE_RATE: f64 = 0.001;
+
+/// Domains held by the first Bloom filter; each later filter holds twice as many
+const INITIAL_CAPACITY: usize = 1 << 16;
+/// Each later filter's false-positive rate is this fraction of the previous one's,
+/// keeping the compound rate below the target however large the set grows
+const TIGHTENING_RATIO: f64 = 0.5;
+
+/// Seen-domain set used to skip duplicates
+#[derive(Debug, Clone)]
+pub enum DomainSet {
+    Exact(HashSet<String>),
+    Probabilistic(ScalableBloomFilter),
+}
+
+impl DomainSet {
+    /// An exact set holding `domains`
+    pub fn exact(domains: HashSet<String>) -> Self {
+        DomainSet::Exact(domains)
+    }
+
+    /// A Bloom-filter set holding `domains`, with the given false-positive rate
+    pub fn probabilistic<'a>(
+        domains: impl IntoIterator<Item = &'a String>,
+        false_positive_rate: f64,
+    ) -> Self {
+        let mut filter = ScalableBloomFilter::new(false_positive_rate);
+        for domain in domains {
+            filter.insert(domain);
+        }
+        DomainSet::Probabilistic(filter)
+    }
+
+    /// Whether `domain` has (probably, for the probabilistic set) been seen
+    pub fn contains(&self, domain: &str) -> bool {
+        match self {
+            DomainSet::Exact(set) => set.contains(domain),
+            DomainSet::Probabilistic(filter) => filter.contains(domain),
+        }
+    }
+
+    /// Record `domain`; returns `false` if it was (probably) already present
+    pub fn insert(&mut self, domain: &str) -> bool {
+        match self {
+            DomainSet::Exact(set) => set.insert(domain.to_string()),
+            DomainSet::Probabilistic(filter) => filter.insert(domain),
+        }
+    }
+}
+
+/// A Bloom filter that adds larger, stricter filters as it fills up, so no
+/// capacity has to be known in advance
+#[derive(Debug, Clone)]
+pub struct ScalableBloomFilter {
+    filters: Vec<BloomFilter>,
+    false_positive_rate: f64,
+}
+
+impl ScalableBloomFilter {
+    pub fn new(false_positive_rate: f64) -> Self {
+        ScalableBloomFilter {
+            filters: Vec::new(),
+            false_positive_rate,
+        }
+    }
+
+    pub fn contains(&self, item: &str) -> bool {
+        let hashes = item_hashes(item);
+        self.filters.iter().any(|filter| filter.contains(hashes))
+    }
+
+    /// Add `item`; returns `false` if it was probably already present
+    pub fn insert(&mut self, item: &str) -> bool {
+        let hashes = item_hashes(item);
+        if self.filters.iter().any(|filter| filter.contains(hashes)) {
+            return false;
+        }
+        if self.filters.last().is_none_or(BloomFilter::is_full) {
+            let index = self.filters.len() as i32;
+            let rate =
+                self.false_positive_rate * (1.0 - TIGHTENING_RATIO) * TIGHTENING_RATIO.powi(index);
+            self.filters
+                .push(BloomFilter::new(INITIAL_CAPACITY << index, rate));
+        }
+        self.filters
+            .last_mut()
+            .expect("a filter was just ensured")
+            .insert(hashes);
+        true
+    }
+
+    /// Approximate memory held by the filter bits
+    pub fn size_bytes(&self) -> usize {
+        self.filters.iter().map(|f| f.bits.len() * 8).sum()
+    }
+}
+
+#[derive(Debug, Clone)]
+struct BloomFilter {
+    bits: Vec<u64>,
+    bit_count: u64,
+    hash_count: u32,
+    capacity: usize,
+    len: usize,
+}
+
+impl BloomFilter {
+    fn new(capacity: usize, false_positive_rate: f64) -> Self {
+        let ln2 = std::f64::consts::LN_2;
+        let bit_count = (-(capacity as f64) * false_positive_rate.ln() / (ln2 * ln2)).ceil() as u64;
+        let bit_count = bit_count.max(64);
+        let hash_count = ((bit_count as f64 / capacity as f64) * ln2)
+            .round()
+            .max(1.0) as u32;
+        BloomFilter {
+            bits: vec![0; bit_count.div_ceil(64) as usize],
+            bit_count,
+            hash_count,
+            capacity,
+            len: 0,
+        }
+    }
+
+    fn is_full(&self) -> bool {
+        self.len >= self.capacity
+    }
+
+    /// The `i`th bit position by double hashing: h1 + i * h2
+    fn position(&self, (h1, h2): (u64, u64), i: u64) -> u64 {
+        h1.wrapping_add(i.wrapping_mul(h2)) % self.bit_count
+    }
+
+    fn contains(&self, hashes: (u64, u64)) -> bool {
+        (0..self.hash_count as u64).all(|i| {
+            let bit = self.position(hashes, i);
+            self.bits[(bit / 64) as usize] & (1 << (bit % 64)) != 0
+        })
+    }
+
+    fn insert(&mut self, hashes: (u64, u64)) {
+        for i in 0..self.hash_count as u64 {
+            let bit = self.position(hashes, i);
+            self.bits[(bit / 64) as usize] |= 1 << (bit % 64);
+        }
+        self.len += 1;
+    }
+}
+
+/// Two independent 64-bit hashes of `item`; the second is odd so double
+/// hashing visits distinct positions
+fn item_hashes(item: &str) -> (u64, u64) {
+    let hash = |seed: u8| {
+        let mut hasher = DefaultHasher::new();
+        seed.hash(&mut hasher);
+        item.hash(&mut hasher);
+        hasher.finish()
+    };
+    (hash(0), hash(1) | 1)
+}
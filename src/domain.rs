@@ -0,0 +1,222 @@
+//! Domain names as labels under a public suffix.
+//!
+//! `mail.example.co.uk` is the labels `mail` and `example` under the suffix
+//! `co.uk`. Generators transform the labels and keep or replace the suffix, and
+//! lookups go to the registrable domain, the last label plus the suffix. Suffixes
+//! are a TLD, or a ccTLD with one of its second-level labels from the Public
+//! Suffix List (`co.uk`, `com.au`).
+//!
+//! Internationalized labels are kept as written, lowercased; [`Domain::to_ascii`]
+//! and [`Domain::to_unicode`] convert between Unicode and punycode (`xn--`)
+//! forms. Mapping beyond lowercasing (UTS #46) is not applied to labels, but
+//! [`normalizes_to_original`] applies it to tell which variations a browser
+//! would take back to the original.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::DomFuzzError;
+use crate::generators::cctld_slds;
+
+/// Suffix assumed for a bare name such as `example`
+pub const DEFAULT_SUFFIX: &str = "com";
+/// Longest domain, in ASCII form, without the trailing dot
+pub const MAX_DOMAIN_LEN: usize = 253;
+/// Longest label, in ASCII form
+pub const MAX_LABEL_LEN: usize = 63;
+
+/// Status recorded, instead of a lookup, for variations that UTS #46 maps back
+/// to the original domain
+pub const NORMALIZES_TO_ORIGINAL: &str = "normalizes-to-original";
+
+/// A validated domain name
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Domain {
+    /// Labels below the suffix, leftmost first; never empty
+    labels: Vec<String>,
+    suffix: String,
+}
+
+impl Domain {
+    /// Parse and validate `input`, lowercased and without a trailing dot. A
+    /// name without dots is taken to be under [`DEFAULT_SUFFIX`].
+    pub fn parse(input: &str) -> Result<Self, DomFuzzError> {
+        let invalid = |message: String| {
+            DomFuzzError::Parse(format!("invalid domain {:?}: {}", input, message))
+        };
+        let normalized = input.trim().trim_end_matches('.').to_lowercase();
+        if normalized.is_empty() {
+            return Err(invalid("empty".to_string()));
+        }
+        let mut labels: Vec<String> = normalized.split('.').map(str::to_string).collect();
+        if labels.len() == 1 {
+            labels.push(DEFAULT_SUFFIX.to_string());
+        }
+
+        let mut ascii_len = labels.len() - 1;
+        for label in &labels {
+            ascii_len += validate_label(label).map_err(invalid)?;
+        }
+        if ascii_len > MAX_DOMAIN_LEN {
+            return Err(invalid(format!(
+                "longer than {} characters in ASCII form",
+                MAX_DOMAIN_LEN
+            )));
+        }
+
+        let tld = labels.pop().expect("at least two labels");
+        let second_level = labels.len() >= 2
+            && cctld_slds()
+                .get(tld.as_str())
+                .is_some_and(|slds| slds.contains(&labels[labels.len() - 1].as_str()));
+        let suffix = if second_level {
+            format!("{}.{}", labels.pop().expect("checked above"), tld)
+        } else {
+            tld
+        };
+        Ok(Domain { labels, suffix })
+    }
+
+    /// Labels below the suffix, leftmost first
+    pub fn labels(&self) -> &[String] {
+        &self.labels
+    }
+
+    /// The public suffix, such as `com` or `co.uk`
+    pub fn suffix(&self) -> &str {
+        &self.suffix
+    }
+
+    /// The top-level domain, the suffix's last label
+    pub fn tld(&self) -> &str {
+        self.suffix.rsplit('.').next().unwrap_or(&self.suffix)
+    }
+
+    /// Everything below the suffix, such as `mail.example`; what generators
+    /// transform
+    pub fn name(&self) -> String {
+        self.labels.join(".")
+    }
+
+    /// The label registered under the suffix, such as `example`
+    pub fn registered_label(&self) -> &str {
+        self.labels.last().expect("labels are never empty")
+    }
+
+    /// The domain a registry holds, such as `example.co.uk`
+    pub fn registrable(&self) -> String {
+        format!("{}.{}", self.registered_label(), self.suffix)
+    }
+
+    /// Labels left of the registrable domain, such as `mail`
+    pub fn subdomain(&self) -> Option<String> {
+        let subdomain = &self.labels[..self.labels.len() - 1];
+        (!subdomain.is_empty()).then(|| subdomain.join("."))
+    }
+
+    /// The whole name with every Unicode label in punycode, as sent to DNS
+    pub fn to_ascii(&self) -> String {
+        self.all_labels()
+            .map(|label| label_to_ascii(label).unwrap_or_else(|| label.to_string()))
+            .collect::<Vec<_>>()
+            .join(".")
+    }
+
+    /// The whole name with every punycode label decoded, as shown to people
+    pub fn to_unicode(&self) -> String {
+        self.all_labels()
+            .map(|label| label_to_unicode(label).unwrap_or_else(|| label.to_string()))
+            .collect::<Vec<_>>()
+            .join(".")
+    }
+
+    /// Whether any label is non-ASCII or in punycode
+    pub fn is_idn(&self) -> bool {
+        self.all_labels()
+            .any(|label| !label.is_ascii() || label.starts_with("xn--"))
+    }
+
+    fn all_labels(&self) -> impl Iterator<Item = &str> {
+        self.labels
+            .iter()
+            .map(String::as_str)
+            .chain(self.suffix.split('.'))
+    }
+}
+
+impl fmt::Display for Domain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.name(), self.suffix)
+    }
+}
+
+impl FromStr for Domain {
+    type Err = DomFuzzError;
+
+    fn from_str(input: &str) -> Result<Self, DomFuzzError> {
+        Domain::parse(input)
+    }
+}
+
+/// Whether `variant` is the same domain as `original` once both go through UTS
+/// #46 processing (NFKC and case folding), as browsers and resolvers do: a
+/// fullwidth `ｅxample.com` leads to `example.com` itself, so it is no
+/// lookalike anyone could register
+pub fn normalizes_to_original(original: &str, variant: &str) -> bool {
+    let ascii = |domain: &str| idna::domain_to_ascii(domain.trim().trim_end_matches('.')).ok();
+    match (ascii(original), ascii(variant)) {
+        (Some(original), Some(variant)) => original == variant,
+        _ => false,
+    }
+}
+
+/// Check one label and return its length in ASCII form
+fn validate_label(label: &str) -> Result<usize, String> {
+    if label.is_empty() {
+        return Err("empty label".to_string());
+    }
+    if label.starts_with('-') || label.ends_with('-') {
+        return Err(format!("label {:?} starts or ends with a hyphen", label));
+    }
+    if let Some(c) = label
+        .chars()
+        .find(|&c| !(c.is_alphanumeric() || c == '-' || c == '_'))
+    {
+        return Err(format!("label {:?} contains {:?}", label, c));
+    }
+    if label.starts_with("xn--") && label_to_unicode(label).is_none() {
+        return Err(format!("label {:?} is not valid punycode", label));
+    }
+    // `ab--` is reserved for IDNA encodings (RFC 5891, section 4.2.3.1), and
+    // registries refuse it for anything but `xn--`
+    if label.chars().skip(2).take(2).eq("--".chars()) && !label.starts_with("xn--") {
+        return Err(format!(
+            "label {:?} has hyphens in the third and fourth positions",
+            label
+        ));
+    }
+    let ascii =
+        label_to_ascii(label).ok_or_else(|| format!("label {:?} cannot be encoded", label))?;
+    if ascii.len() > MAX_LABEL_LEN {
+        return Err(format!(
+            "label {:?} is longer than {} characters in ASCII form",
+            label, MAX_LABEL_LEN
+        ));
+    }
+    Ok(ascii.len())
+}
+
+/// `label` in ASCII form: unchanged if it is ASCII, `xn--` and its punycode
+/// otherwise
+pub fn label_to_ascii(label: &str) -> Option<String> {
+    if label.is_ascii() {
+        return Some(label.to_string());
+    }
+    Some(format!("xn--{}", idna::punycode::encode_str(label)?))
+}
+
+/// `label` decoded from punycode if it starts with `xn--`; `None` otherwise or
+/// if the encoding is invalid
+pub fn label_to_unicode(label: &str) -> Option<String> {
+    idna::punycode::decode_to_string(label.strip_prefix("xn--")?)
+}
@@ -0,0 +1,719 @@
+//! Enrichment of registered variations with hosting intelligence.
+//!
+//! Status checking answers "is this squat registered?"; enrichment answers
+//! "what is it doing?". Each lookup is opt-in through [`EnrichOptions`] and
+//! failures are recorded on the [`Enrichment`] rather than aborting the run.
+
+use std::collections::BTreeSet;
+use std::net::{IpAddr, Ipv4Addr};
+
+use hickory_resolver::error::ResolveErrorKind;
+use tokio::time::timeout;
+
+use crate::checker::{network, DNS_RESOLVER, HTTP_CLIENT};
+use crate::error::DomFuzzError;
+use crate::fingerprint::{fingerprint, Hosting};
+use crate::generators::extract_registrable_domain;
+use crate::metrics;
+use crate::net::{reachability, Reachability};
+use crate::page::{
+    analyze_html, detect_sale_listing, fetch_page, fetch_robots_txt, fetch_security_txt,
+    PageAnalysis, RobotsTxt, SaleListing, SecurityTxt, CREDENTIAL_HARVEST_SUSPECT,
+    DEFENSIVE_REGISTRATION_LIKELY,
+};
+#[cfg(feature = "screenshots")]
+use crate::screenshot::{capture_and_hash, Screenshot, ScreenshotOptions, PROBABLE_CLONE};
+use crate::smtp::{probe_mail, MailProbe};
+use crate::wayback::{web_history, WebHistory, NO_WEB_HISTORY};
+
+/// Reverse-IP service queried as `REVERSE_IP_ENDPOINT?q=<ip>`; it answers with
+/// one co-hosted domain per line
+pub const REVERSE_IP_ENDPOINT: &str = "https://api.hackertarget.com/reverseiplookup/";
+
+/// Addresses per domain sent to the reverse-IP service, which is rate limited
+pub const MAX_REVERSE_IP_ADDRESSES: usize = 2;
+
+/// AbuseIPDB endpoint for single-address checks
+pub const ABUSEIPDB_ENDPOINT: &str = "https://api.abuseipdb.com/api/v2/check";
+
+/// AbuseIPDB confidence score (0-100) at or above which an address counts as listed
+pub const ABUSEIPDB_LISTED_SCORE: u8 = 50;
+
+/// Google Safe Browsing v4 endpoint for URL lookups
+pub const SAFE_BROWSING_ENDPOINT: &str =
+    "https://safebrowsing.googleapis.com/v4/threatMatches:find";
+
+/// Google Web Risk endpoint for URL lookups
+pub const WEB_RISK_ENDPOINT: &str = "https://webrisk.googleapis.com/v1/uris:search";
+
+/// Header carrying the Google API key for Safe Browsing and Web Risk
+const API_KEY_HEADER: &str = "X-Goog-Api-Key";
+
+/// Threat types asked about; Web Risk knows all but the last
+const THREAT_TYPES: &[&str] = &[
+    "MALWARE",
+    "SOCIAL_ENGINEERING",
+    "UNWANTED_SOFTWARE",
+    "POTENTIALLY_HARMFUL_APPLICATION",
+];
+
+/// Flag recorded for variations Google already lists as a threat
+pub const SAFE_BROWSING_LISTED: &str = "safe-browsing-listed";
+
+/// Flag recorded for variations hosted on an address a reputation provider lists
+pub const KNOWN_BAD_HOSTING: &str = "known-bad-hosting";
+
+/// Which enrichment lookups to perform
+#[derive(Debug, Clone, Default)]
+pub struct EnrichOptions {
+    /// List other domains hosted on the same IPv4 addresses
+    pub reverse_ip: bool,
+    /// Reputation providers to query for each resolved address
+    pub reputation: Vec<ReputationProvider>,
+    /// Google threat list to look the domain up in
+    pub threat_list: Option<ThreatList>,
+    /// Attribute the domain to a hosting, CDN, or parking provider
+    pub fingerprint: bool,
+    /// Fetch the landing page and look for credential-harvesting forms
+    pub credential_forms: bool,
+    /// Words identifying the impersonated brand, searched for in fetched pages
+    pub brand_keywords: Vec<String>,
+    /// Look for a marketplace listing or sale notice and its asking price on
+    /// the landing page
+    pub for_sale: bool,
+    /// Retrieve security.txt and robots.txt
+    pub policy_files: bool,
+    /// The original domain, whose own security contacts mark defensive registrations
+    pub original_domain: String,
+    /// Connect to the preferred MX host and record its SMTP banner
+    pub smtp_probe: bool,
+    /// Test whether the domain accepts connections over IPv4, IPv6, or both
+    pub reachability: bool,
+    /// Ask the Wayback Machine when the homepage served content
+    pub wayback: bool,
+    /// Capture screenshots and compare them with the original site's
+    #[cfg(feature = "screenshots")]
+    pub screenshots: Option<ScreenshotOptions>,
+}
+
+impl EnrichOptions {
+    /// Whether any lookup is enabled
+    pub fn is_enabled(&self) -> bool {
+        #[cfg(feature = "screenshots")]
+        if self.screenshots.is_some() {
+            return true;
+        }
+        self.reverse_ip
+            || !self.reputation.is_empty()
+            || self.threat_list.is_some()
+            || self.fingerprint
+            || self.credential_forms
+            || self.for_sale
+            || self.policy_files
+            || self.smtp_probe
+            || self.reachability
+            || self.wayback
+    }
+}
+
+/// A source of IP reputation data
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReputationProvider {
+    /// AbuseIPDB's crowd-sourced abuse confidence score
+    AbuseIpDb { api_key: String },
+    /// Spamhaus ZEN blocklists over DNS; without a Data Query Service key the
+    /// public mirror is used, which refuses queries from large public resolvers
+    Spamhaus { dqs_key: Option<String> },
+}
+
+impl ReputationProvider {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ReputationProvider::AbuseIpDb { .. } => "abuseipdb",
+            ReputationProvider::Spamhaus { .. } => "spamhaus",
+        }
+    }
+}
+
+/// A Google threat list API; both take a Google Cloud API key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ThreatList {
+    /// Safe Browsing v4 Lookup API, free for non-commercial use
+    SafeBrowsing { api_key: String },
+    /// Web Risk Lookup API, its commercial counterpart
+    WebRisk { api_key: String },
+}
+
+impl ThreatList {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ThreatList::SafeBrowsing { .. } => "safe-browsing",
+            ThreatList::WebRisk { .. } => "web-risk",
+        }
+    }
+}
+
+/// Threat types a Google threat list reports for a domain
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ThreatMatch {
+    /// `safe-browsing` or `web-risk`
+    pub service: &'static str,
+    /// Such as `SOCIAL_ENGINEERING`, sorted; empty if the domain is not listed
+    pub threat_types: Vec<String>,
+}
+
+impl ThreatMatch {
+    fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "service": self.service,
+            "threat_types": self.threat_types,
+        })
+    }
+}
+
+/// What one provider reports about one address
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReputationReport {
+    pub ip: IpAddr,
+    pub provider: &'static str,
+    /// Whether the provider considers the address malicious
+    pub listed: bool,
+    /// Provider-specific score, e.g. AbuseIPDB's 0-100 abuse confidence
+    pub score: Option<u8>,
+    /// Lists or categories the address appears on, e.g. `SBL` or `XBL`
+    pub categories: Vec<String>,
+}
+
+impl ReputationReport {
+    fn to_json(&self) -> serde_json::Value {
+        let mut json = serde_json::json!({
+            "ip": self.ip.to_string(),
+            "provider": self.provider,
+            "listed": self.listed,
+        });
+        if let Some(score) = self.score {
+            json["score"] = score.into();
+        }
+        if !self.categories.is_empty() {
+            json["categories"] = self.categories.clone().into();
+        }
+        json
+    }
+}
+
+/// Hosting intelligence gathered for one domain
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Enrichment {
+    /// Addresses the domain resolves to
+    pub addresses: Vec<IpAddr>,
+    /// Other domains sharing those addresses, if the reverse-IP lookup ran and succeeded
+    pub cohosted_domains: Option<Vec<String>>,
+    /// Reputation of each address from each configured provider
+    pub reputation: Vec<ReputationReport>,
+    /// Google's verdict on the domain, if looked up
+    pub threats: Option<ThreatMatch>,
+    /// Hosting provider, if fingerprinting ran and a provider was recognized
+    pub hosting: Option<Hosting>,
+    /// Login markers on the landing page, if it was fetched
+    pub page: Option<PageAnalysis>,
+    /// Sale listing on the landing page, if it was looked for and found
+    pub listing: Option<SaleListing>,
+    pub security_txt: Option<SecurityTxt>,
+    pub robots_txt: Option<RobotsTxt>,
+    /// MX records and SMTP banner, if probed and the domain has MX records
+    pub mail: Option<MailProbe>,
+    /// Address families accepting connections, if tested
+    pub reachability: Option<Reachability>,
+    /// Wayback Machine captures of the homepage, if looked up
+    pub web_history: Option<WebHistory>,
+    /// Screenshot of the landing page, if one was captured
+    #[cfg(feature = "screenshots")]
+    pub screenshot: Option<Screenshot>,
+    /// Triage flags such as [`CREDENTIAL_HARVEST_SUSPECT`]
+    pub flags: Vec<&'static str>,
+    /// Lookups that failed, as human-readable messages
+    pub errors: Vec<String>,
+}
+
+impl Enrichment {
+    /// JSON object for the `enrichment` field of JSON Lines output
+    pub fn to_json(&self) -> serde_json::Value {
+        let mut json = serde_json::json!({
+            "addresses": self.addresses.iter().map(ToString::to_string).collect::<Vec<_>>(),
+        });
+        if let Some(cohosted) = &self.cohosted_domains {
+            json["cohosted_domains"] = cohosted.clone().into();
+        }
+        if !self.reputation.is_empty() {
+            json["reputation"] = self
+                .reputation
+                .iter()
+                .map(ReputationReport::to_json)
+                .collect();
+        }
+        if let Some(threats) = &self.threats {
+            json["threats"] = threats.to_json();
+        }
+        if let Some(hosting) = &self.hosting {
+            json["hosting"] = hosting.to_json();
+        }
+        if let Some(page) = &self.page {
+            json["page"] = page.to_json();
+        }
+        if let Some(listing) = &self.listing {
+            json["listing"] = listing.to_json();
+        }
+        if let Some(security_txt) = &self.security_txt {
+            json["security_txt"] = security_txt.to_json();
+        }
+        if let Some(robots_txt) = &self.robots_txt {
+            json["robots_txt"] = robots_txt.to_json();
+        }
+        if let Some(mail) = &self.mail {
+            json["mail"] = mail.to_json();
+        }
+        if let Some(reachability) = &self.reachability {
+            json["reachability"] = reachability.to_json();
+        }
+        if let Some(web_history) = &self.web_history {
+            json["web_history"] = web_history.to_json();
+        }
+        #[cfg(feature = "screenshots")]
+        if let Some(screenshot) = &self.screenshot {
+            json["screenshot"] = screenshot.to_json();
+        }
+        if !self.flags.is_empty() {
+            json["flags"] = self.flags.clone().into();
+        }
+        if !self.errors.is_empty() {
+            json["errors"] = self.errors.clone().into();
+        }
+        json
+    }
+
+    /// Whether any provider lists one of the domain's addresses as malicious
+    pub fn known_bad_hosting(&self) -> bool {
+        self.reputation.iter().any(|report| report.listed)
+    }
+}
+
+/// Run the lookups enabled in `options` for `domain`
+pub async fn enrich(domain: &str, options: &EnrichOptions) -> Enrichment {
+    let mut enrichment = Enrichment::default();
+    match resolve_addresses(domain).await {
+        Ok(addresses) => enrichment.addresses = addresses,
+        Err(e) => {
+            enrichment.errors.push(e.to_string());
+            return enrichment;
+        }
+    }
+
+    if options.reachability {
+        enrichment.reachability = Some(reachability(&enrichment.addresses).await);
+    }
+
+    if options.reverse_ip {
+        match cohosted_domains(domain, &enrichment.addresses).await {
+            Ok(domains) => enrichment.cohosted_domains = Some(domains),
+            Err(e) => enrichment.errors.push(e.to_string()),
+        }
+    }
+
+    if options.fingerprint {
+        enrichment.hosting = fingerprint(domain, &enrichment.addresses).await;
+    }
+
+    // Both look at the landing page, which is fetched once
+    if options.credential_forms || options.for_sale {
+        match fetch_page(domain).await {
+            Ok((url, html)) => {
+                if options.credential_forms {
+                    let page = analyze_html(&url, &html, &options.brand_keywords);
+                    if page.is_credential_harvest_suspect() {
+                        enrichment.flags.push(CREDENTIAL_HARVEST_SUSPECT);
+                    }
+                    enrichment.page = Some(page);
+                }
+                if options.for_sale {
+                    enrichment.listing = detect_sale_listing(&url, &html);
+                }
+            }
+            Err(e) => enrichment.errors.push(e.to_string()),
+        }
+    }
+
+    if options.policy_files {
+        match fetch_security_txt(domain).await {
+            Ok(security_txt) => {
+                let original = extract_registrable_domain(&options.original_domain);
+                if extract_registrable_domain(domain) != original
+                    && security_txt
+                        .as_ref()
+                        .is_some_and(|s| s.points_to(&original))
+                {
+                    enrichment.flags.push(DEFENSIVE_REGISTRATION_LIKELY);
+                }
+                enrichment.security_txt = security_txt;
+            }
+            Err(e) => enrichment.errors.push(e.to_string()),
+        }
+        match fetch_robots_txt(domain).await {
+            Ok(robots_txt) => enrichment.robots_txt = robots_txt,
+            Err(e) => enrichment.errors.push(e.to_string()),
+        }
+    }
+
+    if options.smtp_probe {
+        match probe_mail(domain).await {
+            Ok(mail) => enrichment.mail = mail,
+            Err(e) => enrichment.errors.push(e.to_string()),
+        }
+    }
+
+    if options.wayback {
+        match web_history(domain).await {
+            Ok(history) => {
+                if !history.is_archived() {
+                    enrichment.flags.push(NO_WEB_HISTORY);
+                }
+                enrichment.web_history = Some(history);
+            }
+            Err(e) => enrichment.errors.push(e.to_string()),
+        }
+    }
+
+    #[cfg(feature = "screenshots")]
+    if let Some(screenshots) = &options.screenshots {
+        match capture_and_hash(domain, screenshots).await {
+            Ok(screenshot) => {
+                if screenshot
+                    .distance
+                    .is_some_and(|distance| distance <= screenshots.max_distance)
+                {
+                    enrichment.flags.push(PROBABLE_CLONE);
+                }
+                if let Some(error) = &screenshots.reference_error {
+                    enrichment.errors.push(error.clone());
+                }
+                enrichment.screenshot = Some(screenshot);
+            }
+            Err(e) => enrichment.errors.push(e.to_string()),
+        }
+    }
+
+    if let Some(list) = &options.threat_list {
+        match lookup_threats(list, domain).await {
+            Ok(threats) => {
+                if !threats.threat_types.is_empty() {
+                    enrichment.flags.push(SAFE_BROWSING_LISTED);
+                }
+                enrichment.threats = Some(threats);
+            }
+            Err(e) => enrichment.errors.push(e.to_string()),
+        }
+    }
+
+    for provider in &options.reputation {
+        for ip in &enrichment.addresses {
+            match check_reputation(provider, *ip).await {
+                Ok(Some(report)) => enrichment.reputation.push(report),
+                Ok(None) => {}
+                Err(e) => enrichment.errors.push(e.to_string()),
+            }
+        }
+    }
+    if enrichment.known_bad_hosting() {
+        enrichment.flags.push(KNOWN_BAD_HOSTING);
+    }
+
+    enrichment
+}
+
+async fn resolve_addresses(domain: &str) -> Result<Vec<IpAddr>, DomFuzzError> {
+    let resolver = &*DNS_RESOLVER;
+    metrics::record_request("dns", metrics::RESOLVER);
+    match timeout(network().dns_timeout, resolver.lookup_ip(domain)).await {
+        Ok(Ok(lookup)) => Ok(lookup.iter().collect()),
+        Ok(Err(e)) => Err(DomFuzzError::Dns {
+            domain: domain.to_string(),
+            message: e.to_string(),
+        }),
+        Err(_) => Err(DomFuzzError::Timeout {
+            protocol: "DNS",
+            domain: domain.to_string(),
+        }),
+    }
+}
+
+/// Domains co-hosted with `domain` on its IPv4 addresses, sorted and deduplicated
+async fn cohosted_domains(domain: &str, addresses: &[IpAddr]) -> Result<Vec<String>, DomFuzzError> {
+    let mut domains = BTreeSet::new();
+    for ip in addresses
+        .iter()
+        .filter(|ip| ip.is_ipv4())
+        .take(MAX_REVERSE_IP_ADDRESSES)
+    {
+        domains.extend(reverse_ip(*ip).await?);
+    }
+    domains.remove(&domain.to_lowercase());
+    Ok(domains.into_iter().collect())
+}
+
+async fn reverse_ip(ip: IpAddr) -> Result<Vec<String>, DomFuzzError> {
+    let url = format!("{}?q={}", REVERSE_IP_ENDPOINT, ip);
+    let http_error = |message: String| DomFuzzError::Http {
+        url: url.clone(),
+        message,
+    };
+
+    metrics::record_request("http", metrics::url_host(REVERSE_IP_ENDPOINT));
+    let response = timeout(network().http_timeout, HTTP_CLIENT.get(&url).send())
+        .await
+        .map_err(|_| DomFuzzError::Timeout {
+            protocol: "reverse-IP",
+            domain: ip.to_string(),
+        })?
+        .map_err(|e| http_error(e.to_string()))?;
+    if !response.status().is_success() {
+        return Err(http_error(format!("HTTP {}", response.status())));
+    }
+    let body = response
+        .text()
+        .await
+        .map_err(|e| http_error(e.to_string()))?;
+
+    // Errors and quota messages come back as a single line of prose with HTTP 200
+    let body = body.trim();
+    if body.starts_with("error") || body.starts_with("API count exceeded") {
+        return Err(http_error(body.to_string()));
+    }
+    Ok(body
+        .lines()
+        .map(|line| line.trim().to_lowercase())
+        .filter(|line| !line.is_empty() && !line.starts_with("no dns a records"))
+        .collect())
+}
+
+/// Query one provider about one address; `None` if the provider does not cover it
+async fn check_reputation(
+    provider: &ReputationProvider,
+    ip: IpAddr,
+) -> Result<Option<ReputationReport>, DomFuzzError> {
+    match provider {
+        ReputationProvider::AbuseIpDb { api_key } => abuseipdb(api_key, ip).await.map(Some),
+        ReputationProvider::Spamhaus { dqs_key } => match ip {
+            IpAddr::V4(ip) => spamhaus(dqs_key.as_deref(), ip).await.map(Some),
+            IpAddr::V6(_) => Ok(None),
+        },
+    }
+}
+
+/// Look `domain`'s homepage up, over HTTP and HTTPS, in a Google threat list
+async fn lookup_threats(list: &ThreatList, domain: &str) -> Result<ThreatMatch, DomFuzzError> {
+    let urls = [
+        format!("http://{}/", domain),
+        format!("https://{}/", domain),
+    ];
+    // The key goes in a header rather than the query string, so it never shows
+    // up in the URL that reqwest errors carry
+    let (endpoint, request) = match list {
+        ThreatList::SafeBrowsing { api_key } => {
+            let entries: Vec<serde_json::Value> = urls
+                .iter()
+                .map(|url| serde_json::json!({ "url": url }))
+                .collect();
+            let body = serde_json::json!({
+                "client": {
+                    "clientId": "domfuzz",
+                    "clientVersion": env!("CARGO_PKG_VERSION"),
+                },
+                "threatInfo": {
+                    "threatTypes": THREAT_TYPES,
+                    "platformTypes": ["ANY_PLATFORM"],
+                    "threatEntryTypes": ["URL"],
+                    "threatEntries": entries,
+                },
+            });
+            let request = HTTP_CLIENT
+                .post(SAFE_BROWSING_ENDPOINT)
+                .header(API_KEY_HEADER, api_key)
+                .json(&body);
+            (SAFE_BROWSING_ENDPOINT, request)
+        }
+        ThreatList::WebRisk { api_key } => {
+            // Web Risk takes one URI per request; its entries cover the whole host
+            let mut query: Vec<(&str, &str)> = THREAT_TYPES[..3]
+                .iter()
+                .map(|threat_type| ("threatTypes", *threat_type))
+                .collect();
+            query.push(("uri", &urls[0]));
+            (
+                WEB_RISK_ENDPOINT,
+                HTTP_CLIENT
+                    .get(WEB_RISK_ENDPOINT)
+                    .header(API_KEY_HEADER, api_key)
+                    .query(&query),
+            )
+        }
+    };
+    let http_error = |message: String| DomFuzzError::Http {
+        url: endpoint.to_string(),
+        message,
+    };
+
+    metrics::record_request("http", metrics::url_host(endpoint));
+    let response = timeout(network().http_timeout, request.send())
+        .await
+        .map_err(|_| DomFuzzError::Timeout {
+            protocol: match list {
+                ThreatList::SafeBrowsing { .. } => "Safe Browsing",
+                ThreatList::WebRisk { .. } => "Web Risk",
+            },
+            domain: domain.to_string(),
+        })?
+        .map_err(|e| http_error(e.without_url().to_string()))?;
+    if !response.status().is_success() {
+        return Err(http_error(format!("HTTP {}", response.status())));
+    }
+    let json: serde_json::Value = response
+        .json()
+        .await
+        .map_err(|e| http_error(e.without_url().to_string()))?;
+
+    // Both answer `{}` for a URL on no list
+    let (items, field) = match list {
+        ThreatList::SafeBrowsing { .. } => (&json["matches"], "threatType"),
+        ThreatList::WebRisk { .. } => (&json["threat"]["threatTypes"], ""),
+    };
+    let mut threat_types: Vec<String> = items
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|item| {
+            let value = if field.is_empty() { item } else { &item[field] };
+            value.as_str().map(str::to_string)
+        })
+        .collect();
+    threat_types.sort();
+    threat_types.dedup();
+    Ok(ThreatMatch {
+        service: list.name(),
+        threat_types,
+    })
+}
+
+async fn abuseipdb(api_key: &str, ip: IpAddr) -> Result<ReputationReport, DomFuzzError> {
+    let url = format!("{}?ipAddress={}&maxAgeInDays=90", ABUSEIPDB_ENDPOINT, ip);
+    let http_error = |message: String| DomFuzzError::Http {
+        url: url.clone(),
+        message,
+    };
+
+    metrics::record_request("http", metrics::url_host(ABUSEIPDB_ENDPOINT));
+    let response = timeout(
+        network().http_timeout,
+        HTTP_CLIENT
+            .get(&url)
+            .header("Key", api_key)
+            .header("Accept", "application/json")
+            .send(),
+    )
+    .await
+    .map_err(|_| DomFuzzError::Timeout {
+        protocol: "AbuseIPDB",
+        domain: ip.to_string(),
+    })?
+    .map_err(|e| http_error(e.to_string()))?;
+    if !response.status().is_success() {
+        return Err(http_error(format!("HTTP {}", response.status())));
+    }
+    let json: serde_json::Value = response
+        .json()
+        .await
+        .map_err(|e| http_error(e.to_string()))?;
+
+    let score = json["data"]["abuseConfidenceScore"]
+        .as_u64()
+        .and_then(|score| u8::try_from(score).ok())
+        .ok_or_else(|| http_error("response has no abuseConfidenceScore".to_string()))?;
+    let categories = json["data"]["usageType"]
+        .as_str()
+        .map(|usage| vec![usage.to_string()])
+        .unwrap_or_default();
+    Ok(ReputationReport {
+        ip,
+        provider: "abuseipdb",
+        listed: score >= ABUSEIPDB_LISTED_SCORE,
+        score: Some(score),
+        categories,
+    })
+}
+
+async fn spamhaus(dqs_key: Option<&str>, ip: Ipv4Addr) -> Result<ReputationReport, DomFuzzError> {
+    let [a, b, c, d] = ip.octets();
+    let zone = match dqs_key {
+        Some(key) => format!("{}.zen.dq.spamhaus.net", key),
+        None => "zen.spamhaus.org".to_string(),
+    };
+    let query = format!("{}.{}.{}.{}.{}.", d, c, b, a, zone);
+    // Counted under the zone without the DQS key, which is a secret
+    metrics::record_request(
+        "dns",
+        if dqs_key.is_some() {
+            "zen.dq.spamhaus.net"
+        } else {
+            "zen.spamhaus.org"
+        },
+    );
+
+    let resolver = &*DNS_RESOLVER;
+    let answers = match timeout(network().dns_timeout, resolver.ipv4_lookup(query.as_str())).await {
+        Ok(Ok(lookup)) => lookup.iter().map(|a| a.0).collect::<Vec<_>>(),
+        Ok(Err(e)) if matches!(e.kind(), ResolveErrorKind::NoRecordsFound { .. }) => Vec::new(),
+        Ok(Err(e)) => {
+            return Err(DomFuzzError::Dns {
+                domain: query,
+                message: e.to_string(),
+            })
+        }
+        Err(_) => {
+            return Err(DomFuzzError::Timeout {
+                protocol: "Spamhaus",
+                domain: ip.to_string(),
+            })
+        }
+    };
+
+    // 127.255.255.x answers are errors (refused resolver, bad key), not listings
+    if let Some(refusal) = answers.iter().find(|a| a.octets()[..3] == [127, 255, 255]) {
+        return Err(DomFuzzError::Dns {
+            domain: query,
+            message: format!("Spamhaus refused the query ({})", refusal),
+        });
+    }
+    let mut categories: Vec<String> = answers
+        .iter()
+        .filter_map(|a| spamhaus_list(a.octets()[3]))
+        .map(str::to_string)
+        .collect();
+    categories.sort();
+    categories.dedup();
+    Ok(ReputationReport {
+        ip: IpAddr::V4(ip),
+        provider: "spamhaus",
+        // The PBL lists end-user address ranges by policy, not for abuse
+        listed: categories.iter().any(|list| list != "PBL"),
+        score: None,
+        categories,
+    })
+}
+
+/// ZEN return code (last octet of the 127.0.0.x answer) to list name
+fn spamhaus_list(code: u8) -> Option<&'static str> {
+    match code {
+        2 | 3 | 9 => Some("SBL"),
+        4..=7 => Some("XBL"),
+        10 | 11 => Some("PBL"),
+        _ => None,
+    }
+}
@@ -0,0 +1,82 @@
+//! Error type shared by the library API and the CLI.
+
+use thiserror::Error;
+
+/// Everything that can go wrong while configuring a run or checking a domain.
+///
+/// Lookup failures (`Dns`, `Rdap`, `Whois`, `Http`, `Timeout`, `CircuitOpen`) describe a single
+/// domain and surface as a status field; `Parse`, `Config`, and `Io` are fatal
+/// and map to a process exit code.
+#[derive(Debug, Error)]
+pub enum DomFuzzError {
+    #[error("DNS lookup for {domain} failed: {message}")]
+    Dns { domain: String, message: String },
+
+    #[error("RDAP lookup for {domain} failed: {message}")]
+    Rdap { domain: String, message: String },
+
+    #[error("WHOIS lookup for {domain} failed: {message}")]
+    Whois { domain: String, message: String },
+
+    #[error("HTTP request to {url} failed: {message}")]
+    Http { url: String, message: String },
+
+    #[error("{protocol} lookup for {domain} timed out")]
+    Timeout {
+        protocol: &'static str,
+        domain: String,
+    },
+
+    /// The endpoint timed out repeatedly and its circuit breaker turned the
+    /// lookup away
+    #[error("lookup for {domain} skipped: {endpoint} keeps timing out")]
+    CircuitOpen { endpoint: String, domain: String },
+
+    #[error("parse error: {0}")]
+    Parse(String),
+
+    #[error("configuration error: {0}")]
+    Config(String),
+
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+/// Exit code for a run whose status lookups all failed.
+pub const EXIT_LOOKUP_FAILED: i32 = 3;
+
+/// Result alias used throughout the crate.
+pub type Result<T> = std::result::Result<T, DomFuzzError>;
+
+impl DomFuzzError {
+    /// Status string reported for a domain whose lookup ended in this error.
+    pub fn status(&self) -> &'static str {
+        match self {
+            DomFuzzError::Timeout { .. } => "timeout",
+            DomFuzzError::CircuitOpen { .. } => "unknown",
+            _ => "error",
+        }
+    }
+
+    /// Process exit code the CLI uses when this error aborts a run.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            DomFuzzError::Parse(_) | DomFuzzError::Config(_) => 2,
+            DomFuzzError::Io(_) => 1,
+            _ => EXIT_LOOKUP_FAILED,
+        }
+    }
+
+    /// True for per-domain lookup failures as opposed to fatal setup errors.
+    pub fn is_lookup(&self) -> bool {
+        matches!(
+            self,
+            DomFuzzError::Dns { .. }
+                | DomFuzzError::Rdap { .. }
+                | DomFuzzError::Whois { .. }
+                | DomFuzzError::Http { .. }
+                | DomFuzzError::Timeout { .. }
+                | DomFuzzError::CircuitOpen { .. }
+        )
+    }
+}
@@ -0,0 +1,200 @@
+//! C ABI for embedding DomFuzz generators in non-Rust security products.
+//!
+//! All strings crossing the boundary are NUL-terminated UTF-8. Functions that
+//! return a count use negative values for errors (`DOMFUZZ_ERR_*`). No memory
+//! allocated by the library is ever handed to the caller, so there is nothing
+//! to free.
+
+use std::collections::HashSet;
+use std::ffi::{c_char, c_int, c_void, CStr, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use crate::domain::Domain;
+use crate::generators::{
+    check_transformation_names, generate_variations, parse_transformations,
+    split_transformation_list, GeneratorOptions, Variation,
+};
+use crate::similarity::{calculate_similarity, score_domain};
+
+/// A required pointer argument was NULL
+pub const DOMFUZZ_ERR_NULL_ARGUMENT: i64 = -1;
+/// A string argument was not valid UTF-8
+pub const DOMFUZZ_ERR_INVALID_UTF8: i64 = -2;
+/// The library panicked; no results were produced
+pub const DOMFUZZ_ERR_PANIC: i64 = -3;
+/// The domain argument is not a valid domain name
+pub const DOMFUZZ_ERR_INVALID_DOMAIN: i64 = -4;
+/// The transformation list names an unknown transformation or bundle
+pub const DOMFUZZ_ERR_UNKNOWN_TRANSFORMATION: i64 = -5;
+
+/// Called once per variation. Return 0 to continue, non-zero to stop early.
+pub type DomfuzzVariationCallback = Option<
+    unsafe extern "C" fn(
+        domain: *const c_char,
+        transformation: *const c_char,
+        score: f64,
+        user_data: *mut c_void,
+    ) -> c_int,
+>;
+
+/// Borrow a C string as UTF-8, mapping failures to error codes
+unsafe fn read_str<'a>(ptr: *const c_char) -> Result<&'a str, i64> {
+    if ptr.is_null() {
+        return Err(DOMFUZZ_ERR_NULL_ARGUMENT);
+    }
+    CStr::from_ptr(ptr)
+        .to_str()
+        .map_err(|_| DOMFUZZ_ERR_INVALID_UTF8)
+}
+
+/// Generate variations for `domain` using a comma-separated transformation list,
+/// or the default `lookalike` bundle when `transformations` is NULL
+unsafe fn generate(
+    domain: *const c_char,
+    transformations: *const c_char,
+) -> Result<Vec<Variation>, i64> {
+    let domain = read_str(domain)?;
+    let requested: Vec<String> = if transformations.is_null() {
+        Vec::new()
+    } else {
+        split_transformation_list(read_str(transformations)?)
+    };
+
+    check_transformation_names(&requested, &[]).map_err(|_| DOMFUZZ_ERR_UNKNOWN_TRANSFORMATION)?;
+    let enabled: HashSet<String> = parse_transformations(&requested);
+    let domain = Domain::parse(domain).map_err(|_| DOMFUZZ_ERR_INVALID_DOMAIN)?;
+    Ok(generate_variations(
+        &domain,
+        &enabled,
+        &GeneratorOptions::default(),
+    ))
+}
+
+/// Generate variations and pass each one to `callback` along with its
+/// transformation name and combined similarity score.
+///
+/// Returns the number of variations delivered, or a negative `DOMFUZZ_ERR_*` code.
+///
+/// # Safety
+///
+/// `domain` must be a valid NUL-terminated string. `transformations` must be
+/// NULL or a valid NUL-terminated string. `callback` must be safe to call with
+/// `user_data`; the string pointers it receives are only valid for the
+/// duration of the call.
+#[no_mangle]
+pub unsafe extern "C" fn domfuzz_generate(
+    domain: *const c_char,
+    transformations: *const c_char,
+    callback: DomfuzzVariationCallback,
+    user_data: *mut c_void,
+) -> i64 {
+    let Some(callback) = callback else {
+        return DOMFUZZ_ERR_NULL_ARGUMENT;
+    };
+
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        let original = read_str(domain)?.to_string();
+        let variations = generate(domain, transformations)?;
+        let mut delivered = 0;
+        for variation in variations {
+            let score =
+                calculate_similarity(&original, &variation.domain, variation.transformation);
+            // Generated domains never contain NUL bytes, but skip rather than panic if one does
+            let (Ok(c_domain), Ok(c_transformation)) = (
+                CString::new(variation.domain),
+                CString::new(variation.transformation),
+            ) else {
+                continue;
+            };
+            delivered += 1;
+            if callback(
+                c_domain.as_ptr(),
+                c_transformation.as_ptr(),
+                score.combined_score,
+                user_data,
+            ) != 0
+            {
+                break;
+            }
+        }
+        Ok(delivered)
+    }));
+
+    match result {
+        Ok(Ok(count)) => count,
+        Ok(Err(code)) => code,
+        Err(_) => DOMFUZZ_ERR_PANIC,
+    }
+}
+
+/// Generate variations into a caller-provided buffer as newline-separated,
+/// NUL-terminated text, with `snprintf` semantics: at most `buf_len - 1` bytes
+/// are written, and the return value is the full length the output needs
+/// (excluding the NUL). Pass a NULL buffer to query the required size.
+///
+/// Returns a negative `DOMFUZZ_ERR_*` code on failure.
+///
+/// # Safety
+///
+/// `domain` must be a valid NUL-terminated string. `transformations` must be
+/// NULL or a valid NUL-terminated string. `buf` must be NULL or point to at
+/// least `buf_len` writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn domfuzz_generate_into(
+    domain: *const c_char,
+    transformations: *const c_char,
+    buf: *mut c_char,
+    buf_len: usize,
+) -> i64 {
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        let variations = generate(domain, transformations)?;
+        let mut text = String::new();
+        for variation in &variations {
+            text.push_str(&variation.domain);
+            text.push('\n');
+        }
+
+        if !buf.is_null() && buf_len > 0 {
+            let copied = text.len().min(buf_len - 1);
+            std::ptr::copy_nonoverlapping(text.as_ptr(), buf as *mut u8, copied);
+            *buf.add(copied) = 0;
+        }
+        Ok(text.len() as i64)
+    }));
+
+    match result {
+        Ok(Ok(len)) => len,
+        Ok(Err(code)) => code,
+        Err(_) => DOMFUZZ_ERR_PANIC,
+    }
+}
+
+/// Combined similarity of `variant` to `original` in 0.0..=1.0, or a negative
+/// `DOMFUZZ_ERR_*` code converted to `double` on failure.
+///
+/// # Safety
+///
+/// Both arguments must be valid NUL-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn domfuzz_similarity(
+    original: *const c_char,
+    variant: *const c_char,
+) -> f64 {
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        let original = read_str(original)?;
+        let variant = read_str(variant)?;
+        Ok::<f64, i64>(score_domain(original, variant).combined_score)
+    }));
+
+    match result {
+        Ok(Ok(score)) => score,
+        Ok(Err(code)) => code as f64,
+        Err(_) => DOMFUZZ_ERR_PANIC as f64,
+    }
+}
+
+/// Library version as a static NUL-terminated string
+#[no_mangle]
+pub extern "C" fn domfuzz_version() -> *const c_char {
+    concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr() as *const c_char
+}
@@ -0,0 +1,229 @@
+//! Hosting and CDN provider fingerprinting.
+//!
+//! Three independent signals are combined: the origin ASN of each address (via
+//! Team Cymru's DNS service), the domain's name servers, and the response
+//! headers of its web server. A parking or bulletproof signal from any source
+//! wins over CDN and cloud signals, since it says more about intent.
+
+use std::net::IpAddr;
+
+use hickory_resolver::TokioAsyncResolver;
+use tokio::time::timeout;
+
+use crate::checker::{network, politeness, DNS_RESOLVER, HTTP_CLIENT};
+use crate::generators::extract_registrable_domain;
+use crate::metrics;
+use crate::polite;
+
+/// What kind of infrastructure a provider offers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum HostingKind {
+    /// Ordered so that the most telling kind sorts first
+    Parking,
+    Bulletproof,
+    Cdn,
+    Cloud,
+    Hosting,
+}
+
+impl HostingKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            HostingKind::Parking => "parking",
+            HostingKind::Bulletproof => "bulletproof",
+            HostingKind::Cdn => "cdn",
+            HostingKind::Cloud => "cloud",
+            HostingKind::Hosting => "hosting",
+        }
+    }
+}
+
+/// The provider a domain was attributed to, and the signals that matched
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hosting {
+    pub provider: &'static str,
+    pub kind: HostingKind,
+    /// Matching signals, e.g. `asn:13335`, `ns:ns1.sedoparking.com`, `header:cf-ray`
+    pub evidence: Vec<String>,
+}
+
+impl Hosting {
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "provider": self.provider,
+            "kind": self.kind.as_str(),
+            "evidence": self.evidence,
+        })
+    }
+}
+
+/// Origin ASNs of well-known providers
+const ASN_PROVIDERS: &[(u32, &str, HostingKind)] = &[
+    (13335, "Cloudflare", HostingKind::Cdn),
+    (54113, "Fastly", HostingKind::Cdn),
+    (20940, "Akamai", HostingKind::Cdn),
+    (16625, "Akamai", HostingKind::Cdn),
+    (16509, "AWS", HostingKind::Cloud),
+    (14618, "AWS", HostingKind::Cloud),
+    (15169, "Google Cloud", HostingKind::Cloud),
+    (396982, "Google Cloud", HostingKind::Cloud),
+    (8075, "Microsoft Azure", HostingKind::Cloud),
+    (14061, "DigitalOcean", HostingKind::Cloud),
+    (63949, "Linode", HostingKind::Cloud),
+    (20473, "Vultr", HostingKind::Cloud),
+    (24940, "Hetzner", HostingKind::Hosting),
+    (16276, "OVH", HostingKind::Hosting),
+    (26496, "GoDaddy", HostingKind::Hosting),
+    (47846, "Sedo", HostingKind::Parking),
+    (44477, "Stark Industries", HostingKind::Bulletproof),
+    (200593, "Prospero", HostingKind::Bulletproof),
+    (202425, "IP Volume", HostingKind::Bulletproof),
+    (210644, "Aeza", HostingKind::Bulletproof),
+];
+
+/// Name server suffixes of well-known providers
+const NS_PROVIDERS: &[(&str, &str, HostingKind)] = &[
+    (".ns.cloudflare.com", "Cloudflare", HostingKind::Cdn),
+    (".awsdns-", "AWS", HostingKind::Cloud),
+    (".googledomains.com", "Google Cloud", HostingKind::Cloud),
+    (".azure-dns.", "Microsoft Azure", HostingKind::Cloud),
+    (".digitalocean.com", "DigitalOcean", HostingKind::Cloud),
+    (".sedoparking.com", "Sedo", HostingKind::Parking),
+    (".parkingcrew.net", "ParkingCrew", HostingKind::Parking),
+    (".bodis.com", "Bodis", HostingKind::Parking),
+    (".above.com", "Above.com", HostingKind::Parking),
+    (".afternic.com", "Afternic", HostingKind::Parking),
+    (".dan.com", "Dan.com", HostingKind::Parking),
+    (".parklogic.com", "ParkLogic", HostingKind::Parking),
+    (".domaincontrol.com", "GoDaddy", HostingKind::Hosting),
+];
+
+/// Response headers (name, and a value substring or "" for any value) of
+/// well-known providers
+const HEADER_PROVIDERS: &[(&str, &str, &str, HostingKind)] = &[
+    ("cf-ray", "", "Cloudflare", HostingKind::Cdn),
+    ("server", "cloudflare", "Cloudflare", HostingKind::Cdn),
+    ("x-amz-cf-id", "", "AWS", HostingKind::Cdn),
+    ("server", "amazons3", "AWS", HostingKind::Cloud),
+    ("x-fastly-request-id", "", "Fastly", HostingKind::Cdn),
+    ("server", "akamaighost", "Akamai", HostingKind::Cdn),
+    ("x-azure-ref", "", "Microsoft Azure", HostingKind::Cdn),
+    ("via", "google", "Google Cloud", HostingKind::Cloud),
+    ("x-vercel-id", "", "Vercel", HostingKind::Cloud),
+    ("x-nf-request-id", "", "Netlify", HostingKind::Cloud),
+    ("server", "parking", "Parking", HostingKind::Parking),
+];
+
+/// Attribute `domain`, which resolves to `addresses`, to a hosting provider.
+/// Signals that cannot be collected are skipped; `None` means nothing matched.
+pub async fn fingerprint(domain: &str, addresses: &[IpAddr]) -> Option<Hosting> {
+    let resolver = &*DNS_RESOLVER;
+    let mut matches: Vec<(&'static str, HostingKind, String)> = Vec::new();
+
+    for ip in addresses {
+        if let Some(asn) = origin_asn(resolver, *ip).await {
+            if let Some((_, provider, kind)) = ASN_PROVIDERS.iter().find(|(a, ..)| *a == asn) {
+                matches.push((provider, *kind, format!("asn:{}", asn)));
+            }
+        }
+    }
+
+    let registrable = extract_registrable_domain(domain);
+    metrics::record_request("dns", metrics::RESOLVER);
+    if let Ok(Ok(lookup)) = timeout(
+        network().dns_timeout,
+        resolver.ns_lookup(registrable.as_str()),
+    )
+    .await
+    {
+        for ns in lookup.iter() {
+            let name = ns.to_string().to_lowercase();
+            let name = name.trim_end_matches('.');
+            if let Some((_, provider, kind)) = NS_PROVIDERS
+                .iter()
+                .find(|(pattern, ..)| name.contains(pattern))
+            {
+                matches.push((provider, *kind, format!("ns:{}", name)));
+            }
+        }
+    }
+
+    for url in [format!("https://{}", domain), format!("http://{}", domain)] {
+        // Only the headers are read; polite mode does not ask for the body
+        polite::pace(&url).await;
+        let request = if politeness().is_some() {
+            HTTP_CLIENT.head(&url)
+        } else {
+            HTTP_CLIENT.get(&url)
+        };
+        metrics::record_request("http", metrics::SITES);
+        if let Ok(Ok(response)) = timeout(network().http_timeout, request.send()).await {
+            let headers = response.headers();
+            for (name, pattern, provider, kind) in HEADER_PROVIDERS {
+                let Some(value) = headers.get(*name) else {
+                    continue;
+                };
+                let value = value.to_str().unwrap_or_default().to_lowercase();
+                if value.contains(pattern) {
+                    matches.push((provider, *kind, format!("header:{}", name)));
+                }
+            }
+            break;
+        }
+    }
+
+    // The most telling kind wins; ties go to the provider with the most evidence
+    let (provider, kind) = matches
+        .iter()
+        .map(|(provider, kind, _)| (*provider, *kind))
+        .min_by_key(|(provider, kind)| {
+            let support = matches.iter().filter(|(p, ..)| p == provider).count();
+            (*kind, std::cmp::Reverse(support))
+        })?;
+    let mut evidence: Vec<String> = matches
+        .into_iter()
+        .filter(|(p, ..)| *p == provider)
+        .map(|(_, _, evidence)| evidence)
+        .collect();
+    evidence.dedup();
+    Some(Hosting {
+        provider,
+        kind,
+        evidence,
+    })
+}
+
+/// Origin ASN of `ip` from `origin.asn.cymru.com` (IPv4) or
+/// `origin6.asn.cymru.com` (IPv6), whose TXT answers look like
+/// `13335 | 104.16.0.0/12 | US | arin | 2014-03-28`
+async fn origin_asn(resolver: &TokioAsyncResolver, ip: IpAddr) -> Option<u32> {
+    let query = match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, c, d] = ip.octets();
+            format!("{}.{}.{}.{}.origin.asn.cymru.com.", d, c, b, a)
+        }
+        IpAddr::V6(ip) => {
+            let nibbles: Vec<String> = ip
+                .octets()
+                .iter()
+                .rev()
+                .flat_map(|byte| [byte & 0xf, byte >> 4])
+                .map(|nibble| format!("{:x}", nibble))
+                .collect();
+            format!("{}.origin6.asn.cymru.com.", nibbles.join("."))
+        }
+    };
+    metrics::record_request("dns", "asn.cymru.com");
+    let lookup = timeout(network().dns_timeout, resolver.txt_lookup(query))
+        .await
+        .ok()?
+        .ok()?;
+    let txt = lookup.iter().next()?.to_string();
+    // Multi-origin prefixes list several ASNs separated by spaces; take the first
+    txt.split('|')
+        .next()?
+        .split_whitespace()
+        .next()?
+        .parse()
+        .ok()
+}
@@ -0,0 +1,471 @@
+//! Builder-style programmatic API.
+//!
+//! ```
+//! use domfuzz::FuzzerBuilder;
+//!
+//! let fuzzer = FuzzerBuilder::new("example.com")
+//!     .transformations(["lookalike", "tld-variations"])
+//!     .min_similarity(0.7)
+//!     .max_variations(50)
+//!     .build()
+//!     .unwrap();
+//! for variation in fuzzer.generate() {
+//!     println!("{} {:.2}", variation.domain, variation.similarity.combined_score);
+//! }
+//! ```
+
+use std::collections::HashSet;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::combo::{random_combo_chain_memoized, ComboMemo};
+use crate::custom::CustomRules;
+use crate::domain::Domain;
+use crate::error::DomFuzzError;
+use crate::generators::{
+    check_transformation_names, default_dictionary, generate_variations, is_valid_domain,
+    parse_transformations, transformation_generators, GeneratorOptions, Realism, POPULAR_TLDS,
+};
+use crate::idn_policy::IdnPolicies;
+use crate::language::Language;
+#[cfg(feature = "scripting")]
+use crate::script::Script;
+use crate::similarity::{calculate_similarity_with_mode, ScoringMode, SimilarityScore};
+
+/// A generated variation with its similarity to the original domain
+#[derive(Debug, Clone)]
+pub struct ScoredVariation {
+    pub domain: String,
+    /// Transformation that produced the variation, or `combo` for chained transformations
+    pub transformation: &'static str,
+    pub similarity: SimilarityScore,
+}
+
+/// A variation together with its registration status
+#[cfg(feature = "network")]
+#[derive(Debug, Clone)]
+pub struct CheckedVariation {
+    pub variation: ScoredVariation,
+    /// One of `available`, `registered`, `parked`, `timeout`, or `error`
+    pub status: String,
+}
+
+/// Configures a [`Fuzzer`]
+#[derive(Debug, Clone)]
+pub struct FuzzerBuilder {
+    domain: String,
+    transformations: Vec<String>,
+    dictionary: Option<Vec<String>>,
+    site_keywords: Vec<String>,
+    tlds: Option<Vec<String>>,
+    custom_rules: CustomRules,
+    #[cfg(feature = "scripting")]
+    script: Option<Script>,
+    max_variations: Option<usize>,
+    min_similarity: Option<f64>,
+    max_similarity: Option<f64>,
+    scoring: ScoringMode,
+    realism: Realism,
+    languages: Vec<Language>,
+    respect_idn_policies: bool,
+    seed: Option<u64>,
+    combo: bool,
+    concurrency: usize,
+}
+
+impl FuzzerBuilder {
+    pub fn new(domain: impl Into<String>) -> Self {
+        FuzzerBuilder {
+            domain: domain.into(),
+            transformations: Vec::new(),
+            dictionary: None,
+            site_keywords: Vec::new(),
+            tlds: None,
+            custom_rules: CustomRules::default(),
+            #[cfg(feature = "scripting")]
+            script: None,
+            max_variations: None,
+            min_similarity: None,
+            max_similarity: None,
+            scoring: ScoringMode::Standard,
+            realism: Realism::Standard,
+            languages: vec![Language::English],
+            respect_idn_policies: false,
+            seed: None,
+            combo: false,
+            concurrency: 15,
+        }
+    }
+
+    /// Transformation or bundle names, as accepted by `-t`. Defaults to the `lookalike` bundle;
+    /// an unknown name makes [`build`](Self::build) fail.
+    pub fn transformations<I, S>(mut self, names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.transformations = names.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Words used by combosquatting instead of the default dictionary
+    pub fn dictionary(mut self, words: Vec<String>) -> Self {
+        self.dictionary = Some(words);
+        self
+    }
+
+    /// The brand's product and service names, joined to the domain by
+    /// combosquatting and standing in for its name in cognitive; with the
+    /// `network` feature, `page::fetch_site_keywords` takes them from its site
+    pub fn site_keywords(mut self, keywords: Vec<String>) -> Self {
+        self.site_keywords = keywords;
+        self
+    }
+
+    /// TLDs tried by tld-variations instead of the popular set, e.g. from
+    /// [`load_tld_set`](crate::generators::load_tld_set)
+    pub fn tlds(mut self, tlds: Vec<String>) -> Self {
+        self.tlds = Some(tlds);
+        self
+    }
+
+    /// User-defined rules, run as the `custom` transformation (which this enables)
+    pub fn custom_rules(mut self, rules: CustomRules) -> Self {
+        self.custom_rules = rules;
+        self
+    }
+
+    /// Rhai script run as the `script` transformation (which this enables)
+    #[cfg(feature = "scripting")]
+    pub fn script(mut self, script: Script) -> Self {
+        self.script = Some(script);
+        self
+    }
+
+    /// Maximum number of variations to return; required in combo mode
+    pub fn max_variations(mut self, max: usize) -> Self {
+        self.max_variations = Some(max);
+        self
+    }
+
+    /// Drop variations whose combined similarity is below `threshold` (0.0-1.0)
+    pub fn min_similarity(mut self, threshold: f64) -> Self {
+        self.min_similarity = Some(threshold);
+        self
+    }
+
+    /// Drop variations whose combined similarity is above `threshold` (0.0-1.0)
+    pub fn max_similarity(mut self, threshold: f64) -> Self {
+        self.max_similarity = Some(threshold);
+        self
+    }
+
+    /// How variations are scored; [`ScoringMode::Containment`] ranks combosquats
+    /// and prefixed or suffixed names by how much of them the domain fills
+    pub fn scoring(mut self, mode: ScoringMode) -> Self {
+        self.scoring = mode;
+        self
+    }
+
+    /// How closely keyboard transformations follow real typing errors;
+    /// [`Realism::High`] keeps only the likeliest fat-finger errors per position
+    pub fn realism(mut self, realism: Realism) -> Self {
+        self.realism = realism;
+        self
+    }
+
+    /// Languages whose words the cognitive, homophones, and singular-plural
+    /// transformations replace. Defaults to English.
+    pub fn languages<I>(mut self, languages: I) -> Self
+    where
+        I: IntoIterator<Item = Language>,
+    {
+        self.languages = languages.into_iter().collect();
+        self
+    }
+
+    /// Drop variations whose registered label the TLD's registry would reject
+    /// under its IDN policy (see [`crate::idn_policy`])
+    pub fn respect_idn_policies(mut self, respect: bool) -> Self {
+        self.respect_idn_policies = respect;
+        self
+    }
+
+    /// Seed for combo mode's random chains, making runs reproducible
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Chain several transformations per variation instead of applying each once
+    pub fn combo(mut self, enabled: bool) -> Self {
+        self.combo = enabled;
+        self
+    }
+
+    /// Number of concurrent status checks performed by [`Fuzzer::check`]
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency;
+        self
+    }
+
+    pub fn build(self) -> Result<Fuzzer, DomFuzzError> {
+        if !is_valid_domain(&self.domain) {
+            return Err(DomFuzzError::Parse(format!(
+                "invalid domain: {}",
+                self.domain
+            )));
+        }
+        for threshold in [self.min_similarity, self.max_similarity]
+            .into_iter()
+            .flatten()
+        {
+            if !(0.0..=1.0).contains(&threshold) {
+                return Err(DomFuzzError::Config(format!(
+                    "similarity threshold must be between 0.0 and 1.0, got: {}",
+                    threshold
+                )));
+            }
+        }
+        if let (Some(min), Some(max)) = (self.min_similarity, self.max_similarity) {
+            if min > max {
+                return Err(DomFuzzError::Config(format!(
+                    "min_similarity {} is above max_similarity {}",
+                    min, max
+                )));
+            }
+        }
+        if self.combo && self.max_variations.is_none() {
+            return Err(DomFuzzError::Config(
+                "combo mode requires max_variations".to_string(),
+            ));
+        }
+        if self.concurrency == 0 {
+            return Err(DomFuzzError::Config(
+                "concurrency must be at least 1".to_string(),
+            ));
+        }
+
+        let domain = Domain::parse(&self.domain)?;
+        check_transformation_names(&self.transformations, &[])?;
+        let mut enabled_transformations = parse_transformations(&self.transformations);
+        if !self.custom_rules.is_empty() {
+            enabled_transformations.insert("custom".to_string());
+        }
+        #[cfg(feature = "scripting")]
+        if self.script.is_some() {
+            enabled_transformations.insert("script".to_string());
+        }
+        Ok(Fuzzer {
+            enabled_transformations,
+            generator_options: GeneratorOptions {
+                dict_words: self.dictionary.unwrap_or_else(default_dictionary),
+                site_keywords: self.site_keywords,
+                tlds: self
+                    .tlds
+                    .unwrap_or_else(|| POPULAR_TLDS.iter().map(|t| t.to_string()).collect()),
+                custom_rules: self.custom_rules,
+                #[cfg(feature = "scripting")]
+                script: self.script,
+                realism: self.realism,
+                languages: self.languages,
+                respect_idn_policies: self.respect_idn_policies,
+            },
+            original: domain.to_string(),
+            domain,
+            max_variations: self.max_variations,
+            min_similarity: self.min_similarity,
+            max_similarity: self.max_similarity,
+            scoring: self.scoring,
+            seed: self.seed,
+            combo: self.combo,
+            concurrency: self.concurrency,
+        })
+    }
+}
+
+/// Generates, scores, and optionally checks variations of one domain
+#[derive(Debug, Clone)]
+pub struct Fuzzer {
+    original: String,
+    domain: Domain,
+    enabled_transformations: HashSet<String>,
+    generator_options: GeneratorOptions,
+    max_variations: Option<usize>,
+    min_similarity: Option<f64>,
+    max_similarity: Option<f64>,
+    scoring: ScoringMode,
+    seed: Option<u64>,
+    combo: bool,
+    #[cfg_attr(not(feature = "network"), allow(dead_code))]
+    concurrency: usize,
+}
+
+impl Fuzzer {
+    /// Generate variations, highest similarity first
+    pub fn generate(&self) -> Vec<ScoredVariation> {
+        let mut variations = if self.combo {
+            self.generate_combo()
+        } else {
+            generate_variations(
+                &self.domain,
+                &self.enabled_transformations,
+                &self.generator_options,
+            )
+            .into_iter()
+            .map(|variation| self.score(variation.domain, variation.transformation))
+            .filter(|variation| self.meets_threshold(variation))
+            .collect()
+        };
+
+        variations.sort_by(|a, b| {
+            b.similarity
+                .combined_score
+                .partial_cmp(&a.similarity.combined_score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.domain.cmp(&b.domain))
+        });
+        if let Some(max) = self.max_variations {
+            variations.truncate(max);
+        }
+        variations
+    }
+
+    /// Generate variations and check the registration status of each
+    #[cfg(feature = "network")]
+    pub async fn check(&self) -> Vec<CheckedVariation> {
+        use futures::StreamExt;
+
+        self.check_stream().collect().await
+    }
+
+    /// Like [`Fuzzer::check`], but yields each variation as soon as its status is
+    /// known. Lookups start with the most similar variations; variations that
+    /// normalize back to the original are not looked up but come first, with
+    /// the status [`NORMALIZES_TO_ORIGINAL`](crate::domain::NORMALIZES_TO_ORIGINAL).
+    #[cfg(feature = "network")]
+    pub fn check_stream(&self) -> impl futures::Stream<Item = CheckedVariation> + Send + '_ {
+        self.check_generated_stream(self.generate())
+    }
+
+    /// Like [`Fuzzer::check_stream`], for `variations` already produced by
+    /// [`Fuzzer::generate`]; generation is CPU-bound, so async callers can run
+    /// it on a blocking thread first
+    #[cfg(feature = "network")]
+    pub fn check_generated_stream(
+        &self,
+        variations: Vec<ScoredVariation>,
+    ) -> impl futures::Stream<Item = CheckedVariation> + Send + '_ {
+        use crate::domain::{normalizes_to_original, NORMALIZES_TO_ORIGINAL};
+        use crate::generators::extract_registrable_domain;
+        use futures::StreamExt;
+        use std::collections::HashMap;
+
+        let original_registrable = extract_registrable_domain(&self.original);
+        // Variations that normalize back to the original are flagged, not looked up
+        let (normalized, variations): (Vec<ScoredVariation>, Vec<ScoredVariation>) = variations
+            .into_iter()
+            .filter(|v| extract_registrable_domain(&v.domain) != original_registrable)
+            .partition(|v| normalizes_to_original(&self.original, &v.domain));
+        let flagged = normalized.into_iter().map(|variation| CheckedVariation {
+            variation,
+            status: NORMALIZES_TO_ORIGINAL.to_string(),
+        });
+        let domains: Vec<String> = variations.iter().map(|v| v.domain.clone()).collect();
+        let mut pending: HashMap<String, ScoredVariation> = variations
+            .into_iter()
+            .map(|v| (v.domain.clone(), v))
+            .collect();
+
+        let checked =
+            crate::checker::check_stream(futures::stream::iter(domains), self.concurrency)
+                .filter_map(move |checked| {
+                    let result =
+                        pending
+                            .remove(&checked.domain)
+                            .map(|variation| CheckedVariation {
+                                variation,
+                                status: checked.status,
+                            });
+                    std::future::ready(result)
+                });
+        futures::stream::iter(flagged).chain(checked)
+    }
+
+    fn score(&self, domain: String, transformation: &'static str) -> ScoredVariation {
+        let similarity =
+            calculate_similarity_with_mode(&self.original, &domain, transformation, self.scoring);
+        ScoredVariation {
+            domain,
+            transformation,
+            similarity,
+        }
+    }
+
+    fn meets_threshold(&self, variation: &ScoredVariation) -> bool {
+        let score = variation.similarity.combined_score;
+        self.min_similarity.is_none_or(|min| score >= min)
+            && self.max_similarity.is_none_or(|max| score <= max)
+    }
+
+    fn generate_combo(&self) -> Vec<ScoredVariation> {
+        let target = self.max_variations.unwrap_or_default();
+        let generators: Vec<_> = transformation_generators(&self.generator_options)
+            .into_iter()
+            .filter(|(name, _)| self.enabled_transformations.contains(*name))
+            .collect();
+        let seed = self.seed.unwrap_or_else(|| {
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map_or(0, |d| d.as_nanos() as u64)
+        });
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        let original_lower = self.original.to_lowercase();
+        let mut seen = HashSet::new();
+        let mut variations = Vec::new();
+        let max_attempts = target.saturating_mul(10);
+        let mut attempts = 0;
+        let mut memo = ComboMemo::default();
+
+        while variations.len() < target && attempts < max_attempts {
+            attempts += 1;
+            let Some(chain) = random_combo_chain_memoized(
+                &mut rng,
+                &self.domain,
+                &generators,
+                &mut memo,
+                |candidate| {
+                    self.min_similarity.is_none_or(|min| {
+                        calculate_similarity_with_mode(
+                            &self.original,
+                            candidate,
+                            "combo",
+                            self.scoring,
+                        )
+                        .combined_score
+                            >= min
+                    })
+                },
+            ) else {
+                continue;
+            };
+            let domain = chain.domain.to_lowercase();
+            if domain == original_lower
+                || !is_valid_domain(&domain)
+                || (self.generator_options.respect_idn_policies
+                    && !IdnPolicies::builtin().allows(&domain))
+                || !seen.insert(domain.clone())
+            {
+                continue;
+            }
+            let variation = self.score(domain, "combo");
+            if self.meets_threshold(&variation) {
+                variations.push(variation);
+            }
+        }
+
+        variations
+    }
+}
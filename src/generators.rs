@@ -0,0 +1,2654 @@
+//! Domain variation generators.
+//!
+//! Every transformation is a function taking the domain label(s) without the
+//! TLD and the TLD itself, and returning candidate domains; [`by_parts`] makes
+//! one a [`Generator`] over a [`Domain`]. Results are not
+//! validated; run them through [`filter_valid_domains`] before use.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::OnceLock;
+
+use crate::confusables;
+use crate::custom::CustomRules;
+use crate::domain::Domain;
+use crate::error::DomFuzzError;
+use crate::idn_policy::IdnPolicies;
+use crate::language::{Language, WordData};
+use crate::phonetic::homophones;
+#[cfg(feature = "scripting")]
+use crate::script::Script;
+use crate::similarity::{damerau_levenshtein_distance, tld_similarity};
+use crate::transliteration::transliterations;
+use crate::typo;
+
+/// Signature shared by all transformation generators: domain -> variations
+pub type Generator<'a> = Box<dyn Fn(&Domain) -> Vec<String> + 'a>;
+
+/// A [`Generator`] from a transformation taking the labels below the suffix
+/// and the suffix, as the `generate_*` functions do
+pub fn by_parts<'a, F>(transformation: F) -> Generator<'a>
+where
+    F: Fn(&str, &str) -> Vec<String> + 'a,
+{
+    Box::new(move |domain: &Domain| transformation(&domain.name(), domain.suffix()))
+}
+
+/// A generated variation and the transformation that first produced it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Variation {
+    pub domain: String,
+    pub transformation: &'static str,
+}
+
+/// Inputs that shape what the generators produce
+#[derive(Debug, Clone)]
+pub struct GeneratorOptions {
+    /// Words joined to the domain by combosquatting
+    pub dict_words: Vec<String>,
+    /// The brand's own product and service names, as taken from its site
+    /// (`--site-keywords`): joined to the domain by combosquatting and standing
+    /// in for its name in cognitive
+    pub site_keywords: Vec<String>,
+    /// Replacement TLDs tried by tld-variations, without the dot
+    pub tlds: Vec<String>,
+    /// User-defined rules run as the `custom` transformation
+    pub custom_rules: CustomRules,
+    /// Script run as the `script` transformation
+    #[cfg(feature = "scripting")]
+    pub script: Option<Script>,
+    /// How closely keyboard transformations follow how people actually mistype
+    pub realism: Realism,
+    /// Languages whose words cognitive, homophones, and singular-plural
+    /// replace; English by default
+    pub languages: Vec<Language>,
+    /// Drop variations whose registered label the TLD's IDN policy rejects
+    /// (see [`crate::idn_policy`])
+    pub respect_idn_policies: bool,
+}
+
+/// How closely keyboard transformations follow how people actually mistype
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum Realism {
+    /// Every adjacent-key error at every position
+    #[default]
+    Standard,
+    /// Only the most probable errors at each position, ranked by the typing
+    /// model of [`crate::typo`] (same-finger and same-hand slips first)
+    High,
+}
+
+/// Errors kept per position by fat-finger under [`Realism::High`]
+pub const HIGH_REALISM_ERRORS_PER_POSITION: usize = 3;
+
+impl Default for GeneratorOptions {
+    /// The default dictionary and the popular TLD set
+    fn default() -> Self {
+        GeneratorOptions {
+            dict_words: default_dictionary(),
+            site_keywords: Vec::new(),
+            tlds: POPULAR_TLDS.iter().map(|t| t.to_string()).collect(),
+            custom_rules: CustomRules::default(),
+            #[cfg(feature = "scripting")]
+            script: None,
+            realism: Realism::Standard,
+            languages: vec![Language::English],
+            respect_idn_policies: false,
+        }
+    }
+}
+
+/// All transformations with their generator functions, keyed by CLI name.
+/// Order matters: when several transformations produce the same domain, the
+/// earliest one in this list is credited with it.
+pub fn transformation_generators(options: &GeneratorOptions) -> Vec<(&'static str, Generator<'_>)> {
+    #[cfg_attr(not(feature = "scripting"), allow(unused_mut))]
+    let mut generators: Vec<(&'static str, Generator<'_>)> = vec![
+        (
+            "custom",
+            by_parts(|d: &str, t: &str| options.custom_rules.generate(d, t)),
+        ),
+        ("1337speak", by_parts(generate_1337speak)),
+        ("misspelling", by_parts(generate_misspelling)),
+        ("mixed-encodings", by_parts(generate_mixed_encodings)),
+        ("transliteration", by_parts(generate_transliteration)),
+        (
+            "tld-variations",
+            by_parts(|d: &str, t: &str| generate_tld_variations_from(d, t, &options.tlds)),
+        ),
+        ("word-swap", by_parts(generate_word_swaps)),
+        ("bitsquatting", by_parts(generate_bitsquatting)),
+        (
+            "fat-finger",
+            by_parts(|d: &str, t: &str| generate_fat_finger_with_realism(d, t, options.realism)),
+        ),
+        ("hyphenation", by_parts(generate_hyphenation)),
+        ("subdomain", by_parts(generate_subdomain_injection)),
+        (
+            "combosquatting",
+            by_parts(move |d: &str, t: &str| {
+                let mut variations = generate_combosquatting(d, t, &options.dict_words);
+                variations.extend(generate_combosquatting(d, t, &options.site_keywords));
+                variations
+            }),
+        ),
+        ("brand-confusion", by_parts(generate_brand_confusion)),
+        ("idn-tld", by_parts(generate_idn_tld)),
+        ("fake-tld", by_parts(generate_fake_tld)),
+        (
+            "cognitive",
+            by_parts(|d: &str, t: &str| {
+                let mut variations = generate_cognitive_in(d, t, &options.languages);
+                variations.extend(generate_product_names(d, t, &options.site_keywords));
+                variations
+            }),
+        ),
+        ("dot-insertion", by_parts(generate_dot_insertion)),
+        ("dot-omission", by_parts(generate_dot_omission)),
+        ("dot-hyphen-sub", by_parts(generate_dot_hyphen_substitution)),
+        (
+            "cardinal-substitution",
+            by_parts(generate_cardinal_substitution),
+        ),
+        (
+            "ordinal-substitution",
+            by_parts(generate_ordinal_substitution),
+        ),
+        (
+            "homophones",
+            by_parts(|d: &str, t: &str| generate_homophones_in(d, t, &options.languages)),
+        ),
+        (
+            "singular-plural",
+            by_parts(|d: &str, t: &str| generate_singular_plural_in(d, t, &options.languages)),
+        ),
+        ("wrong-sld", by_parts(generate_wrong_sld)),
+        ("wrong-sld-local", by_parts(generate_wrong_sld_local)),
+        ("domain-prefix", by_parts(generate_domain_prefix)),
+        ("domain-suffix", by_parts(generate_domain_suffix)),
+    ];
+    #[cfg(feature = "scripting")]
+    if let Some(script) = &options.script {
+        generators.insert(
+            1,
+            (
+                "script",
+                by_parts(|d: &str, t: &str| script.generate_or_record(d, t)),
+            ),
+        );
+    }
+    generators
+}
+
+/// Apply each enabled transformation once to the original domain.
+///
+/// Returns valid, lowercased, de-duplicated variations in registry order, each
+/// credited to the first transformation that produced it. The original domain
+/// is never included.
+pub fn generate_variations(
+    domain: &Domain,
+    enabled_transformations: &HashSet<String>,
+    options: &GeneratorOptions,
+) -> Vec<Variation> {
+    generate_variations_with_progress(domain, enabled_transformations, options, |_, _| {})
+}
+
+/// Like [`generate_variations`], calling `on_transformation(name, added)` after
+/// each enabled transformation with the number of new variations it contributed.
+pub fn generate_variations_with_progress<F>(
+    domain: &Domain,
+    enabled_transformations: &HashSet<String>,
+    options: &GeneratorOptions,
+    mut on_transformation: F,
+) -> Vec<Variation>
+where
+    F: FnMut(&'static str, usize),
+{
+    let original = domain.to_string();
+    let mut seen = HashSet::new();
+    let mut variations = Vec::new();
+
+    for (name, generator) in transformation_generators(options) {
+        if !enabled_transformations.contains(name) {
+            continue;
+        }
+        let before = variations.len();
+        for result in filter_valid_domains(generator(domain)) {
+            if options.respect_idn_policies && !IdnPolicies::builtin().allows(&result) {
+                continue;
+            }
+            if result != original && seen.insert(result.clone()) {
+                variations.push(Variation {
+                    domain: result,
+                    transformation: name,
+                });
+            }
+        }
+        on_transformation(name, variations.len() - before);
+    }
+
+    variations
+}
+
+/// Built-in bundles `-t` accepts, each standing for several transformations
+pub const BUNDLES: &[&str] = &["lookalike", "system-fault", "all"];
+
+/// Names of every transformation, in registry order
+pub fn transformation_names() -> Vec<&'static str> {
+    #[cfg_attr(not(feature = "scripting"), allow(unused_mut))]
+    let mut names: Vec<&'static str> = transformation_generators(&GeneratorOptions::default())
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect();
+    #[cfg(feature = "scripting")]
+    names.insert(1, "script");
+    names
+}
+
+/// Whether `-t` knows `name`: a transformation, a built-in bundle, or a legacy
+/// name of a merged transformation
+pub fn is_builtin_transformation(name: &str) -> bool {
+    let name = name.to_lowercase();
+    transformation_names().contains(&name.as_str())
+        || parse_transformations(std::slice::from_ref(&name)) != HashSet::from([name])
+}
+
+/// Check that `-t` knows every requested name: a transformation, a built-in
+/// bundle, or one of `bundles`. An unknown name would otherwise enable nothing
+/// and the run would quietly produce fewer variations, or none; the error
+/// suggests the closest known name.
+pub fn check_transformation_names(
+    requested: &[String],
+    bundles: &[&str],
+) -> Result<(), DomFuzzError> {
+    for name in requested {
+        let lowered = name.to_lowercase();
+        if lowered.is_empty()
+            || is_builtin_transformation(&lowered)
+            || bundles.contains(&lowered.as_str())
+        {
+            continue;
+        }
+        let closest = transformation_names()
+            .into_iter()
+            .chain(BUNDLES.iter().copied())
+            .chain(bundles.iter().copied())
+            .map(|known| (damerau_levenshtein_distance(&lowered, known), known))
+            .min()
+            .filter(|&(distance, _)| distance <= (lowered.chars().count() / 3).max(2));
+        return Err(DomFuzzError::Config(match closest {
+            Some((_, known)) => format!(
+                "unknown transformation {:?}; did you mean {:?}? (`domfuzz transformations` lists them all)",
+                name, known
+            ),
+            None => format!(
+                "unknown transformation {:?} (`domfuzz transformations` lists them all)",
+                name
+            ),
+        }));
+    }
+    Ok(())
+}
+
+/// Split a comma-separated transformation list, as accepted by `-t`
+pub fn split_transformation_list(list: &str) -> Vec<String> {
+    list.split(',')
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .collect()
+}
+
+/// Expand requested transformation names and bundles into the set of enabled transformations
+pub fn parse_transformations(transformations: &[String]) -> HashSet<String> {
+    let mut enabled = HashSet::new();
+
+    // If no transformations specified, use lookalike bundle by default
+    if transformations.is_empty() {
+        enabled.insert("lookalike".to_string());
+    } else {
+        for transformation in transformations {
+            enabled.insert(transformation.to_lowercase());
+        }
+    }
+
+    // Handle transformation bundles
+    if enabled.contains("lookalike") {
+        enabled.remove("lookalike");
+        // Lookalike bundle: character-level transformations that create visually similar domains
+        enabled.insert("1337speak".to_string());
+        enabled.insert("1337speak".to_string());
+        enabled.insert("misspelling".to_string());
+        enabled.insert("fat-finger".to_string());
+        enabled.insert("mixed-encodings".to_string());
+
+        enabled.insert("fat-finger".to_string());
+        // Character-level additions
+    }
+
+    // Legacy names merged into consolidated transformations
+    if enabled.remove("keyboard") {
+        enabled.insert("misspelling".to_string());
+    }
+    if enabled.remove("cyrillic-comprehensive") {
+        enabled.insert("mixed-encodings".to_string());
+    }
+    if enabled.remove("intl-tld") {
+        enabled.insert("idn-tld".to_string());
+        enabled.insert("fake-tld".to_string());
+    }
+
+    // Handle system-fault bundle
+    if enabled.contains("system-fault") {
+        enabled.remove("system-fault");
+        // System-fault bundle: errors caused by hardware/system failures
+        enabled.insert("bitsquatting".to_string());
+    }
+
+    // If "all" is specified, add all transformation names
+    if enabled.contains("all") {
+        enabled.clear();
+        // Basic Typos
+        enabled.insert("1337speak".to_string());
+        enabled.insert("misspelling".to_string());
+
+        enabled.insert("fat-finger".to_string());
+
+        // Character Manipulation
+        enabled.insert("bitsquatting".to_string());
+
+        enabled.insert("fat-finger".to_string());
+
+        // Unicode/Script
+        enabled.insert("mixed-encodings".to_string());
+        enabled.insert("transliteration".to_string());
+
+        // Phonetic/Semantic
+        enabled.insert("homophones".to_string());
+
+        enabled.insert("cognitive".to_string());
+        enabled.insert("singular-plural".to_string());
+
+        // Number/Word Substitution
+        enabled.insert("cardinal-substitution".to_string());
+        enabled.insert("ordinal-substitution".to_string());
+
+        // Structure Manipulation
+        enabled.insert("word-swap".to_string());
+        enabled.insert("hyphenation".to_string());
+
+        enabled.insert("subdomain".to_string());
+        enabled.insert("dot-insertion".to_string());
+        enabled.insert("dot-omission".to_string());
+        enabled.insert("dot-hyphen-sub".to_string());
+
+        // Domain Extensions
+        enabled.insert("tld-variations".to_string());
+        enabled.insert("idn-tld".to_string());
+        enabled.insert("fake-tld".to_string());
+        enabled.insert("wrong-sld".to_string());
+        enabled.insert("combosquatting".to_string());
+        enabled.insert("brand-confusion".to_string());
+        enabled.insert("domain-prefix".to_string());
+        enabled.insert("domain-suffix".to_string());
+        enabled.insert("custom".to_string());
+        enabled.insert("script".to_string());
+    }
+
+    enabled
+}
+
+/// Like [`parse_transformations`], first expanding user-defined bundles:
+/// `bundle(name)` gives the names a bundle lists, which may be transformations,
+/// built-in bundles, or other user-defined bundles. A bundle reached twice is
+/// expanded once.
+pub fn parse_transformations_with<'a>(
+    transformations: &[String],
+    bundle: impl Fn(&str) -> Option<&'a [String]>,
+) -> HashSet<String> {
+    let mut expanded = Vec::new();
+    let mut expanded_bundles = HashSet::new();
+    let mut pending: Vec<String> = transformations.iter().map(|t| t.to_lowercase()).collect();
+    while let Some(name) = pending.pop() {
+        match bundle(&name) {
+            Some(members) => {
+                if expanded_bundles.insert(name) {
+                    pending.extend(members.iter().map(|member| member.to_lowercase()));
+                }
+            }
+            None => expanded.push(name),
+        }
+    }
+    parse_transformations(&expanded)
+}
+
+/// Split `input` into everything below its public suffix and the suffix, as
+/// generators take them: `mail.example.co.uk` becomes `("mail.example",
+/// "co.uk")`. Input that is not a valid [`Domain`] is split at its last dot.
+pub fn parse_domain(input: &str) -> (String, String) {
+    if let Ok(domain) = Domain::parse(input) {
+        return (domain.name(), domain.suffix().to_string());
+    }
+    if let Some(dot_pos) = input.rfind('.') {
+        let (domain_part, tld_part) = input.split_at(dot_pos);
+        let domain = domain_part.to_string();
+        let tld = tld_part.trim_start_matches('.').to_string();
+        (domain, tld)
+    } else {
+        (input.to_string(), "com".to_string())
+    }
+}
+
+/// The domain a registry holds: `cordiumm.com` for `con.cordiumm.com`, and
+/// `example.co.uk` for `mail.example.co.uk`
+pub fn extract_registrable_domain(input: &str) -> String {
+    if let Ok(domain) = Domain::parse(input) {
+        return domain.registrable();
+    }
+    let parts: Vec<&str> = input.split('.').collect();
+
+    if parts.len() >= 2 {
+        // Take the last two parts (domain + TLD)
+        let domain = parts[parts.len() - 2];
+        let tld = parts[parts.len() - 1];
+        format!("{}.{}", domain, tld)
+    } else {
+        // If somehow there's no dot, return as-is
+        input.to_string()
+    }
+}
+
+pub fn generate_1337speak(domain: &str, tld: &str) -> Vec<String> {
+    let mut variations = Vec::new();
+
+    // Work with lowercase for consistent matching
+    let domain_lower = domain.to_lowercase();
+    let chars: Vec<char> = domain_lower.chars().collect();
+
+    if chars.is_empty() {
+        return variations;
+    }
+
+    let substitutions = [
+        // Classic numeric leet substitutions
+        ('o', '0'),
+        ('0', 'o'),
+        ('l', '1'),
+        ('1', 'l'),
+        ('i', '1'),
+        ('1', 'i'),
+        ('e', '3'),
+        ('3', 'e'),
+        ('a', '4'),
+        ('4', 'a'),
+        ('s', '5'),
+        ('5', 's'),
+        ('g', '9'),
+        ('9', 'g'),
+        ('b', '6'),
+        ('6', 'b'),
+        ('t', '7'),
+        ('7', 't'),
+        ('z', '2'),
+        ('2', 'z'),
+        // Advanced symbol-based leet substitutions (will be filtered by is_valid_domain)
+        ('a', '@'),
+        ('@', 'a'),
+        ('i', '!'),
+        ('!', 'i'),
+        ('s', '$'),
+        ('$', 's'),
+        ('h', '#'),
+        ('#', 'h'),
+        ('c', '('),
+        ('(', 'c'),
+        ('d', ')'),
+        (')', 'd'),
+        ('p', '%'),
+        ('%', 'p'),
+        ('r', '®'),
+        ('®', 'r'),
+        ('t', '+'),
+        ('+', 't'),
+        ('x', '*'),
+        ('*', 'x'),
+        ('n', '^'),
+        ('^', 'n'),
+        ('l', '|'),
+        ('|', 'l'),
+        ('i', '|'),
+        ('e', '€'),
+        ('€', 'e'),
+        // Letter confusion substitutions
+        ('i', 'l'),
+        ('l', 'i'),
+        ('o', 'q'),
+        ('q', 'o'),
+        ('p', 'q'),
+        ('q', 'p'),
+        ('d', 'b'),
+        ('b', 'd'),
+        ('u', 'v'),
+        ('v', 'u'),
+        ('m', 'n'),
+        ('n', 'm'),
+        ('r', 'n'),
+        ('h', 'n'),
+    ];
+
+    // Build character errors for each position
+    let mut character_errors = Vec::new();
+    for (pos, &ch) in chars.iter().enumerate() {
+        let mut pos_errors = Vec::new();
+
+        // Find all possible 1337speak substitutions for this character
+        for &(from, to) in &substitutions {
+            if from == ch {
+                pos_errors.push((pos, "substitute", to));
+            }
+        }
+
+        if !pos_errors.is_empty() {
+            character_errors.push(pos_errors);
+        }
+    }
+
+    if character_errors.is_empty() {
+        return variations;
+    }
+
+    // Apply realistic constraints similar to fat-finger
+    let max_errors = ((chars.len() as f32 * 0.4).ceil() as usize).clamp(1, 3);
+    let max_length = (domain_lower.len() as f32 * 1.2) as usize; // 1337speak doesn't typically increase length much
+
+    generate_realistic_combinations(
+        &chars,
+        &character_errors,
+        max_errors,
+        max_length,
+        tld,
+        &mut variations,
+    );
+
+    variations
+}
+
+pub fn generate_cognitive(domain: &str, tld: &str) -> Vec<String> {
+    let mut variations = Vec::new();
+
+    // Cognitive/semantic word confusion transformations
+    // Based on lexical similarity, phonetic similarity, and common business terminology confusion
+
+    // Dictionary of common word confusions for business/tech domains
+    let word_confusions = [
+        // Spelling variations and common misspellings
+        ("amazon", vec!["amazom", "amazone", "amazn"]),
+        ("google", vec!["gogle", "googel", "googlle"]),
+        ("microsoft", vec!["mircosoft", "microsooft", "microsft"]),
+        ("facebook", vec!["facbook", "facebok", "faceboook"]),
+        ("paypal", vec!["payball", "paypall", "paypaul"]),
+        ("apple", vec!["aple", "applle", "aplle"]),
+        ("twitter", vec!["twiter", "twittr", "twittter"]),
+        ("linkedin", vec!["linkdin", "linkin", "linkedinn"]),
+        // Phonetic and semantic confusion
+        ("secure", vec!["secur", "securee", "secuure"]),
+        ("support", vec!["suport", "supportt", "supp0rt"]),
+        ("service", vec!["servic", "servicee", "servise"]),
+        ("account", vec!["acount", "accont", "accountt"]),
+        ("login", vec!["loginn", "log1n", "l0gin"]),
+        ("portal", vec!["portall", "p0rtal", "porttal"]),
+        ("center", vec!["centre", "centr", "centerr"]),
+        ("office", vec!["offic", "officee", "0ffice"]),
+        // Business terminology confusion
+        ("corp", vec!["corporate", "company", "inc"]),
+        ("inc", vec!["incorporated", "corp", "company"]),
+        ("company", vec!["corp", "inc", "co"]),
+        ("group", vec!["grp", "groupe", "groupp"]),
+        ("tech", vec!["technology", "tec", "techno"]),
+        ("solutions", vec!["solution", "solve", "solutionz"]),
+        ("systems", vec!["system", "sys", "systemz"]),
+        ("services", vec!["service", "servs", "servicez"]),
+        // Common domain confusions (sophisticated transformations like concordium->consordium)
+        ("concordium", vec!["consordium", "consortium", "concardium"]),
+        ("consortium", vec!["consordium", "concordium", "consortum"]),
+        ("foundation", vec!["fundation", "foundtion", "foundaton"]),
+        ("enterprise", vec!["enterprize", "enterpise", "enterpris"]),
+        (
+            "international",
+            vec!["internacional", "internation", "intl"],
+        ),
+        ("development", vec!["developement", "developmnt", "develop"]),
+        ("management", vec!["managment", "managem", "manage"]),
+        ("consulting", vec!["consultng", "consult", "consultancy"]),
+        ("financial", vec!["finance", "finacial", "financ"]),
+        ("research", vec!["reserch", "researh", "resarch"]),
+        ("laboratory", vec!["lab", "laborat", "laboratry"]),
+        ("institute", vec!["institut", "institu", "instit"]),
+        ("university", vec!["univrsity", "univ", "universty"]),
+        ("college", vec!["colege", "coleg", "collegee"]),
+        ("academy", vec!["acadmy", "academ", "academie"]),
+        ("network", vec!["netwrk", "net", "nework"]),
+        ("security", vec!["securty", "sec", "securit"]),
+        ("technology", vec!["technlogy", "tech", "tecnology"]),
+        ("innovation", vec!["inovation", "innov", "innovaton"]),
+        ("intelligence", vec!["inteligence", "intel", "intelligenc"]),
+        ("analytics", vec!["analytic", "anlytics", "analytix"]),
+        (
+            "communications",
+            vec!["communication", "comm", "comunications"],
+        ),
+    ];
+
+    // Apply word confusion transformations
+    for &(original_word, ref confusions) in &word_confusions {
+        if domain.to_lowercase().contains(original_word) {
+            for confusion in confusions {
+                let confused_domain = domain.to_lowercase().replace(original_word, confusion);
+                if confused_domain != domain.to_lowercase() {
+                    variations.push(format!("{}.{}", confused_domain, tld));
+                }
+            }
+        }
+    }
+
+    // Reverse lookup - check if domain contains any confusion words
+    for &(original_word, ref confusions) in &word_confusions {
+        for confusion in confusions {
+            if domain.to_lowercase().contains(confusion) {
+                let corrected_domain = domain.to_lowercase().replace(confusion, original_word);
+                if corrected_domain != domain.to_lowercase() {
+                    variations.push(format!("{}.{}", corrected_domain, tld));
+                }
+            }
+        }
+    }
+
+    // Phonetic similarity transformations (sounds-like transformations)
+    let phonetic_substitutions = [
+        ("ph", "f"),
+        ("f", "ph"),
+        ("ck", "k"),
+        ("k", "ck"),
+        ("c", "k"),
+        ("k", "c"),
+        ("s", "z"),
+        ("z", "s"),
+        ("i", "y"),
+        ("y", "i"),
+        ("er", "or"),
+        ("or", "er"),
+        ("an", "en"),
+        ("en", "an"),
+        ("tion", "sion"),
+        ("sion", "tion"),
+    ];
+
+    for &(from, to) in &phonetic_substitutions {
+        if domain.contains(from) {
+            let phonetic_variant = domain.replace(from, to);
+            if phonetic_variant != domain {
+                variations.push(format!("{}.{}", phonetic_variant, tld));
+            }
+        }
+    }
+
+    // Compound word separation transformations
+    let common_compounds = [
+        "facebook",
+        "youtube",
+        "linkedin",
+        "instagram",
+        "microsoft",
+        "paypal",
+        "amazon",
+        "google",
+        "twitter",
+        "whatsapp",
+        "airbnb",
+        "spotify",
+        "netflix",
+        "dropbox",
+        "github",
+    ];
+
+    for compound in &common_compounds {
+        if domain.to_lowercase().contains(compound) {
+            // Try to split compound words intelligently
+            match *compound {
+                "facebook" => {
+                    variations.push(format!(
+                        "{}.{}",
+                        domain.to_lowercase().replace("facebook", "face-book"),
+                        tld
+                    ));
+                    variations.push(format!(
+                        "{}.{}",
+                        domain.to_lowercase().replace("facebook", "faceb00k"),
+                        tld
+                    ));
+                }
+                "youtube" => {
+                    variations.push(format!(
+                        "{}.{}",
+                        domain.to_lowercase().replace("youtube", "you-tube"),
+                        tld
+                    ));
+                    variations.push(format!(
+                        "{}.{}",
+                        domain.to_lowercase().replace("youtube", "youtub3"),
+                        tld
+                    ));
+                }
+                "linkedin" => {
+                    variations.push(format!(
+                        "{}.{}",
+                        domain.to_lowercase().replace("linkedin", "linked-in"),
+                        tld
+                    ));
+                    variations.push(format!(
+                        "{}.{}",
+                        domain.to_lowercase().replace("linkedin", "link3din"),
+                        tld
+                    ));
+                }
+                "instagram" => {
+                    variations.push(format!(
+                        "{}.{}",
+                        domain.to_lowercase().replace("instagram", "insta-gram"),
+                        tld
+                    ));
+                    variations.push(format!(
+                        "{}.{}",
+                        domain.to_lowercase().replace("instagram", "instagr4m"),
+                        tld
+                    ));
+                }
+                "microsoft" => {
+                    variations.push(format!(
+                        "{}.{}",
+                        domain.to_lowercase().replace("microsoft", "micro-soft"),
+                        tld
+                    ));
+                    variations.push(format!(
+                        "{}.{}",
+                        domain.to_lowercase().replace("microsoft", "micr0soft"),
+                        tld
+                    ));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    // Business context confusion (authority terms)
+    let business_contexts = [
+        ("bank", vec!["banking", "banc", "finansial"]),
+        ("pay", vec!["payment", "payments", "paying"]),
+        ("shop", vec!["shopping", "store", "market"]),
+        ("mail", vec!["email", "post", "message"]),
+        ("cloud", vec!["server", "hosting", "storage"]),
+        ("data", vec!["database", "info", "information"]),
+        ("web", vec!["website", "site", "online"]),
+        ("mobile", vec!["app", "application", "phone"]),
+        ("digital", vec!["cyber", "online", "virtual"]),
+        ("crypto", vec!["blockchain", "bitcoin", "coin"]),
+    ];
+
+    for &(context_word, ref alternatives) in &business_contexts {
+        if domain.to_lowercase().contains(context_word) {
+            for alt in alternatives {
+                let contextual_variant = domain.to_lowercase().replace(context_word, alt);
+                if contextual_variant != domain.to_lowercase() {
+                    variations.push(format!("{}.{}", contextual_variant, tld));
+                }
+            }
+        }
+    }
+
+    // Remove duplicates and original domain
+    variations.sort();
+    variations.dedup();
+    variations.retain(|v| v != &format!("{}.{}", domain, tld));
+
+    variations
+}
+
+pub fn generate_mixed_encodings(domain: &str, tld: &str) -> Vec<String> {
+    let mut variations = Vec::new();
+    let domain_lower = domain.to_lowercase();
+    let chars: Vec<char> = domain_lower.chars().collect();
+
+    // Comprehensive encoding map based on IronGeek homoglyph research and Unicode homoglyphs
+    let mut encoding_map: std::collections::HashMap<char, Vec<char>> = [
+        // Letters with extensive homoglyph mappings
+        (
+            'a',
+            vec![
+                'а', 'α', 'ａ', 'À', 'Á', 'Â', 'Ã', 'Ä', 'Å', 'à', 'á', 'â', 'ã', 'ä', 'å', 'ɑ',
+                'Α', 'Ꭺ',
+            ],
+        ),
+        ('b', vec!['ь', 'β', 'ｂ', 'ß', 'ʙ', 'Β', 'В', 'Ь', 'Ᏼ', 'ᛒ']),
+        ('c', vec!['с', 'ｃ', 'ϲ', 'Ϲ', 'С', 'Ꮯ', 'Ⅽ', 'ⅽ']),
+        (
+            'd',
+            vec!['д', 'ｄ', 'Ď', 'ď', 'Đ', 'đ', 'ԁ', 'ժ', 'Ꭰ', 'ḍ', 'Ⅾ', 'ⅾ'],
+        ),
+        (
+            'e',
+            vec![
+                'е', 'ε', 'ｅ', 'È', 'É', 'Ê', 'Ë', 'é', 'ê', 'ë', 'Ē', 'ē', 'Ĕ', 'ĕ', 'Ė', 'ė',
+                'Ę', 'Ě', 'ě', 'Ε', 'Е', 'Ꭼ',
+            ],
+        ),
+        ('f', vec!['ф', 'ｆ', 'Ϝ']),
+        ('g', vec!['ѓ', 'ｇ', 'ɡ', 'ɢ', 'Ԍ', 'ն', 'Ꮐ']),
+        ('h', vec!['н', 'η', 'ｈ', 'ʜ', 'Η', 'Н', 'һ', 'Ꮋ']),
+        (
+            'i',
+            vec!['і', 'ι', 'ｉ', 'ɩ', 'Ι', 'І', 'ا', 'Ꭵ', 'ᛁ', 'Ⅰ', 'ⅰ'],
+        ),
+        ('j', vec!['ј', 'ｊ', 'ϳ', 'Ј', 'յ', 'Ꭻ']),
+        ('k', vec!['к', 'κ', 'ｋ', 'Κ', 'К', 'Ꮶ', 'ᛕ']),
+        ('l', vec!['ӏ', 'ｌ', 'ʟ', 'ا', 'Ꮮ', 'Ⅼ', 'ⅼ']),
+        ('m', vec!['м', 'μ', 'ｍ', 'Μ', 'Ϻ', 'М', 'Ꮇ', 'ᛖ', 'Ⅿ', 'ⅿ']),
+        ('n', vec!['п', 'η', 'ｎ', 'ɴ', 'Ν']),
+        ('o', vec!['о', 'ο', 'ｏ', 'Ο', 'О', 'Օ']), // Zero and O are handled separately
+        ('p', vec!['р', 'ρ', 'ｐ', 'Ρ', 'Р', 'Ꮲ']),
+        ('q', vec!['ԛ', 'ｑ', 'Ⴍ', 'Ⴓ']),
+        ('r', vec!['г', 'ρ', 'ｒ', 'ʀ', 'Ի', 'Ꮢ', 'ᚱ']),
+        ('s', vec!['ѕ', 'ｓ', 'Ѕ', 'Տ', 'Ⴝ', 'Ꮪ']),
+        ('t', vec!['т', 'τ', 'ｔ', 'Τ', 'Т', 'Ꭲ']),
+        ('u', vec!['υ', 'ｕ', 'μ', 'Ա', 'Ս', '⋃']),
+        ('v', vec!['ν', 'ｖ', 'Ѵ', 'ѵ', 'Ꮩ', 'Ⅴ', 'ⅴ']),
+        ('w', vec!['ѡ', 'ｗ', 'Ꮃ']),
+        ('x', vec!['х', 'χ', 'ｘ', 'Χ', 'Х', 'Ⅹ', 'ⅹ']),
+        ('y', vec!['у', 'ｙ', 'ʏ', 'Υ', 'γ', 'Ү']),
+        ('z', vec!['ᴢ', 'ｚ', 'Ζ', 'Ꮓ']),
+        // Numbers with homoglyphs
+        ('0', vec!['О', 'о', 'Ο', 'ο', 'Օ', 'ｏ', '٠']), // Zero with O variations
+        ('1', vec!['ا', 'Ⅰ', 'ⅰ', 'ǀ', '１']),           // One with I, l variations
+        ('2', vec!['２']),
+        ('3', vec!['３']),
+        ('4', vec!['４']),
+        ('5', vec!['５']),
+        ('6', vec!['６']),
+        ('7', vec!['７']),
+        ('8', vec!['８', 'Ց']),
+        ('9', vec!['９']),
+        // Only hyphens and dots are valid special characters in domain names
+        ('-', vec!['‐', '－']),
+        ('.', vec!['٠', '۔', '܁', '܂', '…', '‧', '。', '．', '｡']),
+    ]
+    .iter()
+    .cloned()
+    .collect();
+    // Homoglyphs from the bundled confusables data (Armenian, Georgian,
+    // Cherokee, Lisu) come after the built-in ones, so only single
+    // substitutions use them
+    for (ascii, encodings) in encoding_map.iter_mut() {
+        for &homoglyph in confusables::homoglyphs(*ascii) {
+            if !encodings.contains(&homoglyph) {
+                encodings.push(homoglyph);
+            }
+        }
+    }
+
+    // Calculate realistic constraints for Unicode substitutions
+    let max_errors = ((chars.len() as f32 * 0.6).ceil() as usize).max(1); // Up to 60% of chars can be Unicode
+    let max_length = domain.len(); // Unicode substitutions don't change length
+
+    // Find all character positions where encoding substitutions can occur
+    let mut character_encodings = Vec::new();
+
+    for (pos, &ch) in chars.iter().enumerate() {
+        if let Some(encoding_chars) = encoding_map.get(&ch) {
+            let mut pos_encodings = Vec::new();
+            for &encoding_char in encoding_chars {
+                pos_encodings.push((pos, "unicode_sub", encoding_char));
+            }
+            if !pos_encodings.is_empty() {
+                character_encodings.push(pos_encodings);
+            }
+        }
+    }
+
+    // Generate realistic encoding combinations
+    generate_encoding_combinations(
+        &chars,
+        &character_encodings,
+        max_errors,
+        max_length,
+        tld,
+        &mut variations,
+    );
+
+    variations
+}
+
+fn generate_encoding_combinations(
+    original_chars: &[char],
+    character_encodings: &[Vec<(usize, &str, char)>],
+    max_errors: usize,
+    _max_length: usize,
+    tld: &str,
+    variations: &mut Vec<String>,
+) {
+    let domain: String = original_chars.iter().collect();
+
+    // Generate single encoding substitutions first (most common)
+    for pos_encodings in character_encodings {
+        for &(pos, _error_type, replacement) in pos_encodings {
+            let result = apply_single_encoding(original_chars, pos, replacement);
+            if let Some(result_domain) = result {
+                if result_domain != domain {
+                    variations.push(format!("{}.{}", result_domain, tld));
+                }
+            }
+        }
+    }
+
+    // Generate double encoding substitutions for longer domains (length >= 3)
+    if original_chars.len() >= 3 && max_errors >= 2 {
+        for i in 0..character_encodings.len() {
+            for j in (i + 1)..character_encodings.len() {
+                // For Unicode, allow any spacing between substitutions
+                // Take only first few encoding options to prevent explosion
+                for &(pos1, _error_type1, replacement1) in character_encodings[i].iter().take(2) {
+                    for &(pos2, _error_type2, replacement2) in character_encodings[j].iter().take(2)
+                    {
+                        let result = apply_double_encoding(
+                            original_chars,
+                            pos1,
+                            replacement1,
+                            pos2,
+                            replacement2,
+                        );
+                        if let Some(result_domain) = result {
+                            if result_domain != domain {
+                                variations.push(format!("{}.{}", result_domain, tld));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // Generate triple encoding substitutions for longer domains (length >= 5)
+    if original_chars.len() >= 5 && max_errors >= 3 {
+        for i in 0..character_encodings.len() {
+            for j in (i + 2)..character_encodings.len() {
+                for k in (j + 2)..character_encodings.len() {
+                    // Very selective sampling for triple encodings
+                    for &(pos1, _error_type1, replacement1) in character_encodings[i].iter().take(1)
+                    {
+                        for &(pos2, _error_type2, replacement2) in
+                            character_encodings[j].iter().take(1)
+                        {
+                            for &(pos3, _error_type3, replacement3) in
+                                character_encodings[k].iter().take(1)
+                            {
+                                let result = apply_triple_encoding(
+                                    original_chars,
+                                    pos1,
+                                    replacement1,
+                                    pos2,
+                                    replacement2,
+                                    pos3,
+                                    replacement3,
+                                );
+                                if let Some(result_domain) = result {
+                                    if result_domain != domain {
+                                        variations.push(format!("{}.{}", result_domain, tld));
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn apply_single_encoding(chars: &[char], pos: usize, replacement: char) -> Option<String> {
+    if pos >= chars.len() {
+        return None;
+    }
+
+    let mut result_chars = chars.to_vec();
+    result_chars[pos] = replacement;
+    Some(result_chars.iter().collect())
+}
+
+fn apply_double_encoding(
+    chars: &[char],
+    pos1: usize,
+    replacement1: char,
+    pos2: usize,
+    replacement2: char,
+) -> Option<String> {
+    if pos1 >= chars.len() || pos2 >= chars.len() {
+        return None;
+    }
+
+    let mut result_chars = chars.to_vec();
+    result_chars[pos1] = replacement1;
+    result_chars[pos2] = replacement2;
+    Some(result_chars.iter().collect())
+}
+
+fn apply_triple_encoding(
+    chars: &[char],
+    pos1: usize,
+    replacement1: char,
+    pos2: usize,
+    replacement2: char,
+    pos3: usize,
+    replacement3: char,
+) -> Option<String> {
+    if pos1 >= chars.len() || pos2 >= chars.len() || pos3 >= chars.len() {
+        return None;
+    }
+
+    let mut result_chars = chars.to_vec();
+    result_chars[pos1] = replacement1;
+    result_chars[pos2] = replacement2;
+    result_chars[pos3] = replacement3;
+    Some(result_chars.iter().collect())
+}
+
+pub fn generate_misspelling(domain: &str, tld: &str) -> Vec<String> {
+    let mut variations = Vec::new();
+    let chars: Vec<char> = domain.chars().collect();
+
+    // QWERTY keyboard map for adjacent keys
+    let qwerty_map = [
+        ('q', "wa"),
+        ('w', "qes"),
+        ('e', "wrd"),
+        ('r', "etf"),
+        ('t', "rgy"),
+        ('y', "tuh"),
+        ('u', "yio"),
+        ('i', "uop"),
+        ('o', "ip"),
+        ('p', "o"),
+        ('a', "qsz"),
+        ('s', "awdz"),
+        ('d', "sefx"),
+        ('f', "dgrc"),
+        ('g', "fthv"),
+        ('h', "gyjb"),
+        ('j', "hukn"),
+        ('k', "julm"),
+        ('l', "km"),
+        ('z', "asx"),
+        ('x', "zsdc"),
+        ('c', "xdfv"),
+        ('v', "cfgb"),
+        ('b', "vghn"),
+        ('n', "bhjm"),
+        ('m', "njk"),
+    ];
+
+    // Vowel substitution mappings from vowel-swap
+    let vowel_swap_map: std::collections::HashMap<char, Vec<char>> = [
+        ('a', vec!['e', 'i', 'o', 'u']),
+        ('e', vec!['a', 'i', 'o', 'u']),
+        ('i', vec!['a', 'e', 'o', 'u']),
+        ('o', vec!['a', 'e', 'i', 'u']),
+        ('u', vec!['a', 'e', 'i', 'o']),
+    ]
+    .iter()
+    .cloned()
+    .collect();
+
+    // Calculate realistic constraints based on domain length
+    let max_errors = (chars.len() / 2).max(1); // 1 error per 2 characters, minimum 1
+    let max_length = (domain.len() as f32 * 1.8) as usize; // Max 180% of original length (more lenient than fat-finger)
+
+    // Find all character positions where misspelling errors can occur
+    let mut character_errors = Vec::new();
+
+    for (pos, &ch) in chars.iter().enumerate() {
+        let mut pos_errors = Vec::new();
+
+        // Character insertion (add random char before this position)
+        pos_errors.push((pos, "insert", 'a')); // Representative insertion
+
+        // Character deletion
+        pos_errors.push((pos, "delete", ch));
+
+        // Character transposition (with next character)
+        if pos + 1 < chars.len() {
+            pos_errors.push((pos, "transpose", chars[pos + 1]));
+        }
+
+        // Keyboard adjacent substitution
+        let lower_ch = ch.to_ascii_lowercase();
+        for (orig_char, adjacent_chars) in &qwerty_map {
+            if lower_ch == *orig_char {
+                for adj_char in adjacent_chars.chars() {
+                    pos_errors.push((pos, "keyboard_sub", adj_char));
+                }
+                break;
+            }
+        }
+
+        // Vowel substitution
+        if let Some(vowel_substitutes) = vowel_swap_map.get(&lower_ch) {
+            for &substitute_vowel in vowel_substitutes {
+                let final_vowel = if ch.is_uppercase() {
+                    substitute_vowel.to_ascii_uppercase()
+                } else {
+                    substitute_vowel
+                };
+                pos_errors.push((pos, "vowel_sub", final_vowel));
+            }
+        }
+
+        if !pos_errors.is_empty() {
+            character_errors.push(pos_errors);
+        }
+    }
+
+    // Generate realistic combinations with constraints
+    generate_misspelling_combinations(
+        &chars,
+        &character_errors,
+        max_errors,
+        max_length,
+        tld,
+        &mut variations,
+    );
+
+    variations
+}
+
+fn generate_misspelling_combinations(
+    original_chars: &[char],
+    character_errors: &[Vec<(usize, &str, char)>],
+    max_errors: usize,
+    max_length: usize,
+    tld: &str,
+    variations: &mut Vec<String>,
+) {
+    let domain: String = original_chars.iter().collect();
+
+    // Generate single misspelling errors first (most realistic)
+    for pos_errors in character_errors {
+        for &(pos, error_type, replacement) in pos_errors {
+            let result = apply_single_misspelling(original_chars, pos, error_type, replacement);
+            if let Some(result_domain) = result {
+                if result_domain != domain
+                    && result_domain.len() <= max_length
+                    && !result_domain.is_empty()
+                {
+                    variations.push(format!("{}.{}", result_domain, tld));
+                }
+            }
+        }
+    }
+
+    // Generate double misspellings for longer domains (length >= 4)
+    if original_chars.len() >= 4 && max_errors >= 2 {
+        for i in 0..character_errors.len() {
+            for j in (i + 1)..character_errors.len() {
+                // Allow errors on adjacent characters for misspellings (unlike fat-finger)
+                // But limit combinations to avoid explosion
+                if j - i <= 2 {
+                    // Only adjacent or 1-apart characters
+                    for &(pos1, error_type1, replacement1) in character_errors[i].iter().take(2) {
+                        for &(pos2, error_type2, replacement2) in character_errors[j].iter().take(2)
+                        {
+                            // Avoid incompatible error combinations
+                            if !are_incompatible_misspelling_errors(
+                                pos1,
+                                error_type1,
+                                pos2,
+                                error_type2,
+                            ) {
+                                let result = apply_double_misspelling(
+                                    original_chars,
+                                    pos1,
+                                    error_type1,
+                                    replacement1,
+                                    pos2,
+                                    error_type2,
+                                    replacement2,
+                                );
+                                if let Some(result_domain) = result {
+                                    if result_domain != domain
+                                        && result_domain.len() <= max_length
+                                        && !result_domain.is_empty()
+                                    {
+                                        variations.push(format!("{}.{}", result_domain, tld));
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn are_incompatible_misspelling_errors(pos1: usize, type1: &str, pos2: usize, type2: &str) -> bool {
+    // Don't delete adjacent characters (would create too many gaps)
+    if (type1 == "delete" && type2 == "delete") && (pos2 == pos1 + 1 || pos1 == pos2 + 1) {
+        return true;
+    }
+
+    // Don't transpose overlapping ranges
+    if type1 == "transpose" && type2 == "transpose" && (pos2 <= pos1 + 1) {
+        return true;
+    }
+
+    // Don't delete and transpose the same position
+    if (type1 == "delete" && type2 == "transpose" && pos1 == pos2)
+        || (type2 == "delete" && type1 == "transpose" && pos2 == pos1)
+    {
+        return true;
+    }
+
+    false
+}
+
+fn apply_single_misspelling(
+    chars: &[char],
+    pos: usize,
+    error_type: &str,
+    replacement: char,
+) -> Option<String> {
+    let mut result_chars = chars.to_vec();
+
+    match error_type {
+        "insert" => {
+            // Insert a common typo character
+            let typo_chars = ['a', 'e', 'i', 'o', 'u', 's', 't', 'n', 'r'];
+            let typo_char = typo_chars[pos % typo_chars.len()];
+            result_chars.insert(pos, typo_char);
+        }
+        "delete" => {
+            if pos < result_chars.len() {
+                result_chars.remove(pos);
+            }
+        }
+        "transpose" => {
+            if pos + 1 < result_chars.len() {
+                result_chars.swap(pos, pos + 1);
+            }
+        }
+        "keyboard_sub" => {
+            if pos < result_chars.len() {
+                result_chars[pos] = if chars[pos].is_uppercase() {
+                    replacement.to_ascii_uppercase()
+                } else {
+                    replacement
+                };
+            }
+        }
+        "vowel_sub" => {
+            if pos < result_chars.len() {
+                result_chars[pos] = replacement;
+            }
+        }
+        _ => return None,
+    }
+
+    if result_chars.is_empty() {
+        return None;
+    }
+
+    Some(result_chars.iter().collect())
+}
+
+fn apply_double_misspelling(
+    chars: &[char],
+    pos1: usize,
+    error_type1: &str,
+    replacement1: char,
+    pos2: usize,
+    error_type2: &str,
+    replacement2: char,
+) -> Option<String> {
+    // Apply first error
+    let intermediate = apply_single_misspelling(chars, pos1, error_type1, replacement1)?;
+    let intermediate_chars: Vec<char> = intermediate.chars().collect();
+
+    // Adjust position for second error based on first error's effect
+    let adjusted_pos2 = match error_type1 {
+        "insert" => {
+            if pos2 > pos1 {
+                pos2 + 1
+            } else {
+                pos2
+            }
+        }
+        "delete" => {
+            if pos2 > pos1 && pos2 > 0 {
+                pos2 - 1
+            } else {
+                pos2
+            }
+        }
+        _ => pos2,
+    };
+
+    // Apply second error
+    apply_single_misspelling(
+        &intermediate_chars,
+        adjusted_pos2,
+        error_type2,
+        replacement2,
+    )
+}
+
+pub fn generate_subdomain_injection(domain: &str, tld: &str) -> Vec<String> {
+    let mut variations = Vec::new();
+
+    let chars: Vec<char> = domain.chars().collect();
+    for i in 1..chars.len() {
+        // Skip positions that would create consecutive dots
+        if i > 0 && chars[i - 1] == '.' {
+            continue;
+        }
+        if i < chars.len() && chars[i] == '.' {
+            continue;
+        }
+
+        // Convert char index to byte index for insertion
+        let byte_pos = domain
+            .char_indices()
+            .nth(i)
+            .map(|(pos, _)| pos)
+            .unwrap_or(domain.len());
+        let mut new_domain = domain.to_string();
+        new_domain.insert(byte_pos, '.');
+        variations.push(format!("{}.{}", new_domain, tld));
+    }
+
+    variations
+}
+
+/// TLDs most often used for squats: the big gTLDs, new cheap gTLDs, and free ccTLDs
+pub const POPULAR_TLDS: [&str; 29] = [
+    "com", "net", "org", "info", "biz", "us", "co", "io", "me", "app", "dev", "tech", "online",
+    "site", "store", "shop", "uk", "ca", "de", "fr", "ru", "cn", "jp", "au", "br", "tk", "ml",
+    "ga", "cf",
+];
+
+/// Snapshot of every delegated TLD, in IANA's `tlds-alpha-by-domain.txt` format
+const DELEGATED_TLDS: &str = include_str!("../data/tlds.txt");
+
+pub fn generate_tld_variations(domain: &str, tld: &str) -> Vec<String> {
+    let tlds: Vec<String> = POPULAR_TLDS.iter().map(|t| t.to_string()).collect();
+    generate_tld_variations_from(domain, tld, &tlds)
+}
+
+/// The domain under each of `tlds`, most easily confused with `tld` first
+pub fn generate_tld_variations_from(domain: &str, tld: &str, tlds: &[String]) -> Vec<String> {
+    let mut ranked: Vec<(f64, &String)> = tlds
+        .iter()
+        .map(|new_tld| (tld_similarity(tld, new_tld), new_tld))
+        .collect();
+    ranked.sort_by(|a, b| b.0.total_cmp(&a.0));
+    ranked
+        .into_iter()
+        .map(|(_, new_tld)| format!("{}.{}", domain, new_tld))
+        .collect()
+}
+
+/// Parse a TLD list in IANA's `tlds-alpha-by-domain.txt` format: one TLD per
+/// line, `#` comments. TLDs are lowercased and a leading dot is allowed.
+pub fn parse_tld_list(text: &str) -> Vec<String> {
+    text.lines()
+        .map(|line| line.trim().trim_start_matches('.').to_lowercase())
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .collect()
+}
+
+/// Every delegated TLD, from the bundled snapshot of IANA's list
+pub fn delegated_tlds() -> Vec<String> {
+    parse_tld_list(DELEGATED_TLDS)
+}
+
+/// TLDs for `--tld-set`: `popular`, `full` (every delegated TLD), or the path
+/// of a file in IANA's list format
+pub fn load_tld_set(set: &str) -> Result<Vec<String>, DomFuzzError> {
+    let tlds = match set {
+        "popular" => POPULAR_TLDS.iter().map(|t| t.to_string()).collect(),
+        "full" => delegated_tlds(),
+        path => {
+            let contents = std::fs::read_to_string(path).map_err(|e| {
+                DomFuzzError::Config(format!(
+                    "--tld-set must be popular, full, or a readable file; {}: {}",
+                    path, e
+                ))
+            })?;
+            parse_tld_list(&contents)
+        }
+    };
+    if tlds.is_empty() {
+        return Err(DomFuzzError::Config(format!("TLD set {} is empty", set)));
+    }
+    Ok(tlds)
+}
+
+pub fn generate_word_swaps(domain: &str, tld: &str) -> Vec<String> {
+    let mut variations = Vec::new();
+    let chars: Vec<char> = domain.chars().collect();
+
+    if chars.len() >= 4 {
+        let mid = chars.len() / 2;
+        let first_half: String = chars[..mid].iter().collect();
+        let second_half: String = chars[mid..].iter().collect();
+        variations.push(format!("{}{}.{}", second_half, first_half, tld));
+    }
+
+    if chars.len() >= 6 {
+        let third = chars.len() / 3;
+        let first_third: String = chars[..third].iter().collect();
+        let middle_third: String = chars[third..2 * third].iter().collect();
+        let last_third: String = chars[2 * third..].iter().collect();
+        variations.push(format!(
+            "{}{}{}.{}",
+            last_third, middle_third, first_third, tld
+        ));
+    }
+
+    variations
+}
+
+pub fn generate_bitsquatting(domain: &str, tld: &str) -> Vec<String> {
+    let mut variations = Vec::new();
+    let original_full = format!("{}.{}", domain, tld);
+
+    let chars: Vec<char> = domain.chars().collect();
+
+    for (i, &ch) in chars.iter().enumerate() {
+        let ch_code = ch as u8;
+        for bit_pos in 0..8 {
+            let flipped_code = ch_code ^ (1 << bit_pos);
+            if let Some(flipped_char) = char::from_u32(flipped_code as u32) {
+                if flipped_char.is_ascii_alphabetic() || flipped_char.is_ascii_digit() {
+                    let mut new_domain = String::new();
+                    new_domain.push_str(&chars[..i].iter().collect::<String>());
+                    new_domain.push(flipped_char);
+                    new_domain.push_str(&chars[i + 1..].iter().collect::<String>());
+                    let new_full = format!("{}.{}", new_domain, tld);
+
+                    // Don't add if it's the same as original (case-insensitive)
+                    if new_full.to_lowercase() != original_full.to_lowercase() {
+                        variations.push(new_full);
+                    }
+                }
+            }
+        }
+    }
+
+    variations
+}
+
+pub fn generate_fat_finger(domain: &str, tld: &str) -> Vec<String> {
+    generate_fat_finger_with_realism(domain, tld, Realism::Standard)
+}
+
+/// Fat-finger errors; under [`Realism::High`], only the
+/// [`HIGH_REALISM_ERRORS_PER_POSITION`] likeliest per position
+pub fn generate_fat_finger_with_realism(domain: &str, tld: &str, realism: Realism) -> Vec<String> {
+    let mut variations = Vec::new();
+    let chars: Vec<char> = domain.chars().collect();
+    if realism == Realism::High {
+        let character_errors: Vec<Vec<(usize, &str, char)>> = chars
+            .iter()
+            .enumerate()
+            .map(|(pos, &ch)| likeliest_keyboard_errors(&chars, pos, ch))
+            .collect();
+        generate_realistic_combinations(
+            &chars,
+            &character_errors,
+            (chars.len() / 2).max(1),
+            (domain.len() as f32 * 1.5) as usize,
+            tld,
+            &mut variations,
+        );
+        return variations;
+    }
+
+    // QWERTY keyboard map for adjacent keys
+    let qwerty_map = [
+        ('q', "wa"),
+        ('w', "qes"),
+        ('e', "wrd"),
+        ('r', "etf"),
+        ('t', "rgy"),
+        ('y', "tuh"),
+        ('u', "yio"),
+        ('i', "uop"),
+        ('o', "ip"),
+        ('p', "o"),
+        ('a', "qsz"),
+        ('s', "awdz"),
+        ('d', "sefx"),
+        ('f', "dgrc"),
+        ('g', "fthv"),
+        ('h', "gyjb"),
+        ('j', "hukn"),
+        ('k', "julm"),
+        ('l', "km"),
+        ('z', "asx"),
+        ('x', "zsdc"),
+        ('c', "xdfv"),
+        ('v', "cfgb"),
+        ('b', "vghn"),
+        ('n', "bhjm"),
+        ('m', "njk"),
+    ];
+
+    // Calculate realistic constraints based on domain length
+    let max_errors = (chars.len() / 2).max(1); // 1 error per 2 characters, minimum 1
+    let max_length = (domain.len() as f32 * 1.5) as usize; // Max 150% of original length
+
+    // Find all character positions where errors can occur (max 1 error type per position)
+    let mut character_errors = Vec::new();
+
+    for (pos, &ch) in chars.iter().enumerate() {
+        let mut pos_errors = Vec::new();
+
+        // Character repetition
+        pos_errors.push((pos, "repeat", ch));
+
+        // Adjacent key substitution
+        for (orig_char, adjacent_chars) in &qwerty_map {
+            if ch == *orig_char {
+                for adj_char in adjacent_chars.chars() {
+                    pos_errors.push((pos, "substitute", adj_char));
+                }
+                break; // Only one set of adjacent keys per character
+            }
+        }
+
+        // Adjacent key insertion (before this character)
+        for (orig_char, adjacent_chars) in &qwerty_map {
+            if ch == *orig_char {
+                for adj_char in adjacent_chars.chars() {
+                    pos_errors.push((pos, "insert_before", adj_char));
+                }
+                break;
+            }
+        }
+
+        if !pos_errors.is_empty() {
+            character_errors.push(pos_errors);
+        }
+    }
+
+    // Generate realistic combinations with constraints
+    generate_realistic_combinations(
+        &chars,
+        &character_errors,
+        max_errors,
+        max_length,
+        tld,
+        &mut variations,
+    );
+
+    variations
+}
+
+/// The repeat, adjacent substitution, and adjacent insertion errors at `pos`,
+/// likeliest first, keeping [`HIGH_REALISM_ERRORS_PER_POSITION`]
+fn likeliest_keyboard_errors(
+    chars: &[char],
+    pos: usize,
+    ch: char,
+) -> Vec<(usize, &'static str, char)> {
+    let key = ch.to_ascii_lowercase();
+    let previous = pos.checked_sub(1).map(|i| chars[i].to_ascii_lowercase());
+    let mut errors = vec![(typo::REPEAT_INSERTION, (pos, "repeat", ch))];
+    for neighbour in typo::neighbours(key).filter(char::is_ascii_alphanumeric) {
+        errors.push((
+            typo::substitution(key, neighbour),
+            (pos, "substitute", neighbour),
+        ));
+        errors.push((
+            typo::insertion(previous, Some(key), neighbour),
+            (pos, "insert_before", neighbour),
+        ));
+    }
+    // Stable, so ties keep the order above
+    errors.sort_by(|a, b| b.0.total_cmp(&a.0));
+    errors
+        .into_iter()
+        .take(HIGH_REALISM_ERRORS_PER_POSITION)
+        .map(|(_, error)| error)
+        .collect()
+}
+
+fn generate_realistic_combinations(
+    original_chars: &[char],
+    character_errors: &[Vec<(usize, &str, char)>],
+    max_errors: usize,
+    max_length: usize,
+    tld: &str,
+    variations: &mut Vec<String>,
+) {
+    let domain: String = original_chars.iter().collect();
+
+    // Generate single errors first (most realistic)
+    for pos_errors in character_errors {
+        for &(pos, error_type, replacement) in pos_errors {
+            let result = apply_single_error(original_chars, pos, error_type, replacement);
+            if let Some(result_domain) = result {
+                if result_domain != domain && result_domain.len() <= max_length {
+                    variations.push(format!("{}.{}", result_domain, tld));
+                }
+            }
+        }
+    }
+
+    // Generate double errors for longer domains (length >= 4)
+    if original_chars.len() >= 4 && max_errors >= 2 {
+        for i in 0..character_errors.len() {
+            for j in (i + 1)..character_errors.len() {
+                // Only allow errors on non-adjacent characters for realism
+                if j - i > 1 {
+                    for &(pos1, error_type1, replacement1) in &character_errors[i] {
+                        for &(pos2, error_type2, replacement2) in &character_errors[j] {
+                            let result = apply_double_error(
+                                original_chars,
+                                pos1,
+                                error_type1,
+                                replacement1,
+                                pos2,
+                                error_type2,
+                                replacement2,
+                            );
+                            if let Some(result_domain) = result {
+                                if result_domain != domain && result_domain.len() <= max_length {
+                                    variations.push(format!("{}.{}", result_domain, tld));
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // Only generate triple errors for very long domains (length >= 8)
+    if original_chars.len() >= 8 && max_errors >= 3 {
+        for i in 0..character_errors.len() {
+            for j in (i + 2)..character_errors.len() {
+                for k in (j + 2)..character_errors.len() {
+                    // Sample only a few triple combinations to avoid explosion
+                    if i % 2 == 0 && j % 2 == 0 && k % 2 == 0 {
+                        for &(pos1, error_type1, replacement1) in character_errors[i].iter().take(1)
+                        {
+                            for &(pos2, error_type2, replacement2) in
+                                character_errors[j].iter().take(1)
+                            {
+                                for &(pos3, error_type3, replacement3) in
+                                    character_errors[k].iter().take(1)
+                                {
+                                    let error1 = ErrorSpec {
+                                        pos: pos1,
+                                        error_type: error_type1.to_string(),
+                                        replacement: replacement1,
+                                    };
+                                    let error2 = ErrorSpec {
+                                        pos: pos2,
+                                        error_type: error_type2.to_string(),
+                                        replacement: replacement2,
+                                    };
+                                    let error3 = ErrorSpec {
+                                        pos: pos3,
+                                        error_type: error_type3.to_string(),
+                                        replacement: replacement3,
+                                    };
+                                    let result = apply_triple_error(
+                                        original_chars,
+                                        &error1,
+                                        &error2,
+                                        &error3,
+                                    );
+                                    if let Some(result_domain) = result {
+                                        if result_domain != domain
+                                            && result_domain.len() <= max_length
+                                        {
+                                            variations.push(format!("{}.{}", result_domain, tld));
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn apply_single_error(
+    chars: &[char],
+    pos: usize,
+    error_type: &str,
+    replacement: char,
+) -> Option<String> {
+    let mut result_chars = chars.to_vec();
+
+    match error_type {
+        "repeat" => {
+            if pos < result_chars.len() {
+                result_chars.insert(pos, replacement);
+            }
+        }
+        "substitute" => {
+            if pos < result_chars.len() {
+                result_chars[pos] = replacement;
+            }
+        }
+        "insert_before" => {
+            result_chars.insert(pos, replacement);
+        }
+        _ => return None,
+    }
+
+    Some(result_chars.iter().collect())
+}
+
+fn apply_double_error(
+    chars: &[char],
+    pos1: usize,
+    error_type1: &str,
+    replacement1: char,
+    pos2: usize,
+    error_type2: &str,
+    replacement2: char,
+) -> Option<String> {
+    let mut result_chars = chars.to_vec();
+
+    // Apply errors in reverse position order to maintain indices
+    let (first_pos, first_type, first_repl, second_pos, second_type, second_repl) = if pos2 > pos1 {
+        (
+            pos1,
+            error_type1,
+            replacement1,
+            pos2,
+            error_type2,
+            replacement2,
+        )
+    } else {
+        (
+            pos2,
+            error_type2,
+            replacement2,
+            pos1,
+            error_type1,
+            replacement1,
+        )
+    };
+
+    // Apply second error first (higher position)
+    match second_type {
+        "repeat" => {
+            if second_pos < result_chars.len() {
+                result_chars.insert(second_pos, second_repl);
+            }
+        }
+        "substitute" => {
+            if second_pos < result_chars.len() {
+                result_chars[second_pos] = second_repl;
+            }
+        }
+        "insert_before" => {
+            result_chars.insert(second_pos, second_repl);
+        }
+        _ => return None,
+    }
+
+    // Apply first error
+    match first_type {
+        "repeat" => {
+            if first_pos < result_chars.len() {
+                result_chars.insert(first_pos, first_repl);
+            }
+        }
+        "substitute" => {
+            if first_pos < result_chars.len() {
+                result_chars[first_pos] = first_repl;
+            }
+        }
+        "insert_before" => {
+            result_chars.insert(first_pos, first_repl);
+        }
+        _ => return None,
+    }
+
+    Some(result_chars.iter().collect())
+}
+
+#[derive(Clone)]
+struct ErrorSpec {
+    pos: usize,
+    error_type: String,
+    replacement: char,
+}
+
+fn apply_triple_error(
+    chars: &[char],
+    error1: &ErrorSpec,
+    error2: &ErrorSpec,
+    error3: &ErrorSpec,
+) -> Option<String> {
+    // Apply double error first, then add third error
+    let double_result = apply_double_error(
+        chars,
+        error1.pos,
+        &error1.error_type,
+        error1.replacement,
+        error2.pos,
+        &error2.error_type,
+        error2.replacement,
+    )?;
+    let double_chars: Vec<char> = double_result.chars().collect();
+
+    // Adjust position for third error based on insertions from first two errors
+    let adjusted_pos3 = if error3.pos > error2.pos && error3.pos > error1.pos {
+        error3.pos
+            + count_insertions_before(
+                error3.pos,
+                error1.pos,
+                &error1.error_type,
+                error2.pos,
+                &error2.error_type,
+            )
+    } else {
+        error3.pos
+    };
+
+    apply_single_error(
+        &double_chars,
+        adjusted_pos3,
+        &error3.error_type,
+        error3.replacement,
+    )
+}
+
+fn count_insertions_before(
+    target_pos: usize,
+    pos1: usize,
+    type1: &str,
+    pos2: usize,
+    type2: &str,
+) -> usize {
+    let mut count = 0;
+    if pos1 < target_pos && (type1 == "repeat" || type1 == "insert_before") {
+        count += 1;
+    }
+    if pos2 < target_pos && (type2 == "repeat" || type2 == "insert_before") {
+        count += 1;
+    }
+    count
+}
+
+/// Load one dictionary word per line, skipping blank lines
+pub fn load_dictionary(file_path: &str) -> Result<Vec<String>, DomFuzzError> {
+    let contents = std::fs::read_to_string(file_path).map_err(|e| {
+        DomFuzzError::Config(format!("cannot read dictionary {}: {}", file_path, e))
+    })?;
+    Ok(contents
+        .lines()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect())
+}
+
+pub fn default_dictionary() -> Vec<String> {
+    // Try to load from XDG-compliant user data directory first
+    if let Ok(home) = std::env::var("HOME") {
+        let xdg_dict_path = format!("{}/.local/share/domfuzz/dictionary.txt", home);
+        if let Ok(words) = load_dictionary(&xdg_dict_path) {
+            return words;
+        }
+    }
+
+    // Fall back to built-in dictionary
+    vec![
+        "support", "secure", "login", "pay", "help", "service", "account", "portal", "center",
+        "app", "online", "store", "shop", "mail", "cloud", "data", "mobile", "web", "digital",
+        "tech", "pro", "plus", "premium", "official", "admin", "manage", "bank", "finance",
+        "crypto",
+    ]
+    .into_iter()
+    .map(|s| s.to_string())
+    .collect()
+}
+
+pub fn generate_combosquatting(domain: &str, tld: &str, dict_words: &[String]) -> Vec<String> {
+    let mut variations = Vec::new();
+
+    for word in dict_words {
+        variations.push(format!("{}-{}.{}", domain, word, tld));
+        variations.push(format!("{}{}.{}", domain, word, tld));
+        variations.push(format!("{}-{}.{}", word, domain, tld));
+        variations.push(format!("{}{}.{}", word, domain, tld));
+    }
+
+    variations
+}
+
+pub fn generate_hyphenation(domain: &str, tld: &str) -> Vec<String> {
+    let mut variations = Vec::new();
+
+    let chars: Vec<char> = domain.chars().collect();
+    for i in 1..chars.len() {
+        // Skip positions that would create domains starting/ending with hyphens
+        // or consecutive hyphens
+        if i > 0 && chars[i - 1] == '-' {
+            continue;
+        }
+        if i < chars.len() && chars[i] == '-' {
+            continue;
+        }
+
+        // Convert char index to byte index for insertion
+        let byte_pos = domain
+            .char_indices()
+            .nth(i)
+            .map(|(pos, _)| pos)
+            .unwrap_or(domain.len());
+        let mut new_domain = domain.to_string();
+        new_domain.insert(byte_pos, '-');
+
+        // Additional check: don't create domains ending with hyphen
+        if new_domain.ends_with('-') {
+            continue;
+        }
+
+        variations.push(format!("{}.{}", new_domain, tld));
+    }
+
+    variations
+}
+
+pub fn generate_brand_confusion(domain: &str, tld: &str) -> Vec<String> {
+    let mut variations = Vec::new();
+
+    let authority_prefixes = ["www", "secure", "official", "my", "admin", "portal", "app"];
+    let authority_suffixes = ["app", "online", "portal", "center", "pro", "plus", "secure"];
+
+    for prefix in &authority_prefixes {
+        variations.push(format!("{}-{}.{}", prefix, domain, tld));
+        variations.push(format!("{}.{}.{}", prefix, domain, tld));
+    }
+
+    for suffix in &authority_suffixes {
+        variations.push(format!("{}-{}.{}", domain, suffix, tld));
+        variations.push(format!("{}{}.{}", domain, suffix, tld));
+    }
+
+    variations
+}
+
+/// Delegated IDN TLDs as (Latin equivalent, Unicode form, ACE form). The
+/// equivalents of com, net, and org are registries' own IDN versions of them.
+const DELEGATED_IDN_TLDS: [(&str, &str, &str); 38] = [
+    ("com", "ком", "xn--j1aef"),
+    ("com", "كوم", "xn--fhbei"),
+    ("com", "コム", "xn--tckwe"),
+    ("com", "닷컴", "xn--mk1bu44c"),
+    ("com", "קום", "xn--9dbq2a"),
+    ("com", "คอม", "xn--42c2d9a"),
+    ("com", "कॉम", "xn--11b4c3d"),
+    ("com", "点看", "xn--3pxu8k"),
+    ("com", "公司", "xn--55qx5d"),
+    ("net", "网络", "xn--io0a7i"),
+    ("net", "닷넷", "xn--t60b56a"),
+    ("net", "नेट", "xn--c2br7g"),
+    ("net", "شبكة", "xn--ngbc5azd"),
+    ("org", "орг", "xn--c1avg"),
+    ("org", "संगठन", "xn--i1b6b1a6a2e"),
+    ("cn", "中国", "xn--fiqs8s"),
+    ("kr", "한국", "xn--3e0b707e"),
+    ("gr", "ελ", "xn--qxam"),
+    ("th", "ไทย", "xn--o3cw4h"),
+    ("in", "भारत", "xn--h2brj9c"),
+    ("ru", "рф", "xn--p1ai"),
+    ("eu", "ευ", "xn--qxa6a"),
+    ("by", "бел", "xn--90ais"),
+    ("ua", "укр", "xn--j1amh"),
+    ("kz", "қаз", "xn--80ao21a"),
+    ("rs", "срб", "xn--90a3ac"),
+    ("mk", "мкд", "xn--d1alf"),
+    ("mn", "мон", "xn--l1acc"),
+    ("tw", "台灣", "xn--kpry57d"),
+    ("tw", "台湾", "xn--kprw13d"),
+    ("hk", "香港", "xn--j6w193g"),
+    ("sg", "新加坡", "xn--yfro4i67o"),
+    ("eg", "مصر", "xn--wgbh1c"),
+    ("sa", "السعودية", "xn--mgberp4a5d4ar"),
+    ("ae", "امارات", "xn--mgbaam7a8h"),
+    ("om", "عمان", "xn--mgb9awbf"),
+    ("qa", "قطر", "xn--wgbl6a"),
+    ("ir", "ایران", "xn--mgba3a4f16a"),
+];
+
+/// TLDs that merely look or read like real ones; no registry delegates them
+const FAKE_TLDS: [(&str, &str); 13] = [
+    ("net", "нет"),
+    ("org", "منظمة"),
+    ("org", "组织"),
+    ("net", "ネット"),
+    ("org", "オルグ"),
+    ("com", "컴"),
+    ("net", "넷"),
+    ("com", "κομ"),
+    ("net", "δικτυο"),
+    ("org", "οργ"),
+    ("net", "רשת"),
+    ("org", "ארג"),
+    ("net", "เน็ต"),
+];
+
+/// Mixed-script spellings of Latin TLDs, e.g. `сom` with a Cyrillic `с`
+const MIXED_SCRIPT_TLDS: [&str; 8] = [
+    "co.ук", "com.ау", "со.uk", "сom", "nеt", "оrg", "οrg", "cοm",
+];
+
+/// Whether `tld` is one of the generic TLDs whose IDN equivalents are interchangeable
+fn is_generic_tld(tld: &str) -> bool {
+    matches!(tld, "com" | "net" | "org")
+}
+
+/// Both [`generate_idn_tld`] and [`generate_fake_tld`]
+pub fn generate_intl_tld(domain: &str, tld: &str) -> Vec<String> {
+    let mut variations = generate_idn_tld(domain, tld);
+    variations.extend(generate_fake_tld(domain, tld));
+    variations
+}
+
+/// The whole name respelled as it sounds in another alphabet, keeping the TLD:
+/// `yandex.com` → `яндекс.com`, `γιαντεξ.com`, and back
+pub fn generate_transliteration(domain: &str, tld: &str) -> Vec<String> {
+    transliterations(domain)
+        .into_iter()
+        .map(|name| format!("{}.{}", name, tld))
+        .collect()
+}
+
+/// The domain under delegated IDN TLDs equivalent to `tld`, in ACE (`xn--`)
+/// form so the result can be registered and resolved:
+/// `example.com` → `example.xn--j1aef` (`example.ком`)
+pub fn generate_idn_tld(domain: &str, tld: &str) -> Vec<String> {
+    DELEGATED_IDN_TLDS
+        .iter()
+        .filter(|(latin, _, _)| *latin == tld || (is_generic_tld(tld) && is_generic_tld(latin)))
+        .map(|(_, _, ace)| format!("{}.{}", domain, ace))
+        .collect()
+}
+
+/// The domain under TLDs that only look like real ones (undelegated
+/// translations and mixed-script spellings). They cannot be registered; they
+/// show what a victim might be shown in a link or an email.
+pub fn generate_fake_tld(domain: &str, tld: &str) -> Vec<String> {
+    let mut variations: Vec<String> = FAKE_TLDS
+        .iter()
+        .filter(|(latin, _)| *latin == tld || is_generic_tld(tld))
+        .map(|(_, fake)| format!("{}.{}", domain, fake))
+        .collect();
+    for mixed_tld in &MIXED_SCRIPT_TLDS {
+        variations.push(format!("{}.{}", domain, mixed_tld));
+    }
+    variations
+}
+
+pub fn generate_dot_insertion(domain: &str, tld: &str) -> Vec<String> {
+    let mut variations = Vec::new();
+
+    // Insert dots at various positions within the domain (not at start/end)
+    let chars: Vec<char> = domain.chars().collect();
+    for i in 1..chars.len() {
+        // Skip positions that would create consecutive dots
+        if i > 0 && chars[i - 1] == '.' {
+            continue;
+        }
+        if i < chars.len() && chars[i] == '.' {
+            continue;
+        }
+
+        // Convert char index to byte index for insertion
+        let byte_pos = domain
+            .char_indices()
+            .nth(i)
+            .map(|(pos, _)| pos)
+            .unwrap_or(domain.len());
+        let mut new_domain = domain.to_string();
+        new_domain.insert(byte_pos, '.');
+        variations.push(format!("{}.{}", new_domain, tld));
+    }
+
+    variations
+}
+
+pub fn generate_dot_omission(domain: &str, tld: &str) -> Vec<String> {
+    let mut variations = Vec::new();
+
+    // Remove existing dots from the domain
+    if domain.contains('.') {
+        let stripped = domain.replace(".", "");
+        if !stripped.is_empty() {
+            variations.push(format!("{}.{}", stripped, tld));
+        }
+    }
+
+    variations
+}
+
+pub fn generate_dot_hyphen_substitution(domain: &str, tld: &str) -> Vec<String> {
+    let mut variations = Vec::new();
+
+    // Replace dots with hyphens
+    if domain.contains('.') {
+        let hyphenated = domain.replace(".", "-");
+        variations.push(format!("{}.{}", hyphenated, tld));
+    }
+
+    // Replace hyphens with dots
+    if domain.contains('-') {
+        let dotted = domain.replace("-", ".");
+        variations.push(format!("{}.{}", dotted, tld));
+    }
+
+    variations
+}
+
+pub fn generate_cardinal_substitution(domain: &str, tld: &str) -> Vec<String> {
+    let mut variations = Vec::new();
+
+    // Cardinal number mappings (digit to word and word to digit)
+    let cardinals = [
+        ("0", "zero"),
+        ("1", "one"),
+        ("2", "two"),
+        ("3", "three"),
+        ("4", "four"),
+        ("5", "five"),
+        ("6", "six"),
+        ("7", "seven"),
+        ("8", "eight"),
+        ("9", "nine"),
+        ("10", "ten"),
+        ("11", "eleven"),
+        ("12", "twelve"),
+        ("20", "twenty"),
+        ("30", "thirty"),
+        ("40", "forty"),
+        ("50", "fifty"),
+        ("100", "hundred"),
+    ];
+
+    // Replace numbers with words
+    for &(digit, word) in &cardinals {
+        if domain.contains(digit) {
+            let word_variant = domain.replace(digit, word);
+            if word_variant != domain {
+                variations.push(format!("{}.{}", word_variant, tld));
+            }
+        }
+
+        // Replace words with numbers
+        if domain.contains(word) {
+            let digit_variant = domain.replace(word, digit);
+            if digit_variant != domain {
+                variations.push(format!("{}.{}", digit_variant, tld));
+            }
+        }
+    }
+
+    variations
+}
+
+pub fn generate_ordinal_substitution(domain: &str, tld: &str) -> Vec<String> {
+    let mut variations = Vec::new();
+
+    // Ordinal number mappings
+    let ordinals = [
+        ("1st", "first"),
+        ("2nd", "second"),
+        ("3rd", "third"),
+        ("4th", "fourth"),
+        ("5th", "fifth"),
+        ("6th", "sixth"),
+        ("7th", "seventh"),
+        ("8th", "eighth"),
+        ("9th", "ninth"),
+        ("10th", "tenth"),
+        ("11th", "eleventh"),
+        ("12th", "twelfth"),
+        ("20th", "twentieth"),
+        ("21st", "twentyfirst"),
+        ("30th", "thirtieth"),
+        ("100th", "hundredth"),
+    ];
+
+    // Replace ordinal numbers with words
+    for &(ordinal, word) in &ordinals {
+        if domain.contains(ordinal) {
+            let word_variant = domain.replace(ordinal, word);
+            if word_variant != domain {
+                variations.push(format!("{}.{}", word_variant, tld));
+            }
+        }
+
+        // Replace ordinal words with numbers
+        if domain.contains(word) {
+            let ordinal_variant = domain.replace(word, ordinal);
+            if ordinal_variant != domain {
+                variations.push(format!("{}.{}", ordinal_variant, tld));
+            }
+        }
+    }
+
+    variations
+}
+
+/// The name respelled to sound the same, checked by its Metaphone key:
+/// `paypal.com` → `paypall.com`, `security.com` → `sekurity.com`,
+/// `bestbuy.com` → `bestbye.com`
+pub fn generate_homophones(domain: &str, tld: &str) -> Vec<String> {
+    homophones(domain)
+        .into_iter()
+        .map(|name| format!("{}.{}", name, tld))
+        .collect()
+}
+
+pub fn generate_singular_plural(domain: &str, tld: &str) -> Vec<String> {
+    let mut variations = Vec::new();
+
+    // Simple pluralization rules
+    let domain_lower = domain.to_lowercase();
+
+    // Add 's' for simple plural
+    if !domain_lower.ends_with('s') {
+        variations.push(format!("{}s.{}", domain, tld));
+    }
+
+    // Add 'es' for words ending in s, x, z, sh, ch
+    if domain_lower.ends_with('s')
+        || domain_lower.ends_with('x')
+        || domain_lower.ends_with('z')
+        || domain_lower.ends_with("sh")
+        || domain_lower.ends_with("ch")
+    {
+        variations.push(format!("{}es.{}", domain, tld));
+    }
+
+    // Change 'y' to 'ies'
+    if domain_lower.ends_with('y') && domain.len() > 1 {
+        let stem = &domain[..domain.len() - 1];
+        variations.push(format!("{}ies.{}", stem, tld));
+    }
+
+    // Remove 's' for singular (simple case)
+    if domain_lower.ends_with('s') && domain.len() > 1 {
+        let singular = &domain[..domain.len() - 1];
+        variations.push(format!("{}.{}", singular, tld));
+    }
+
+    // Remove 'es' for singular
+    if domain_lower.ends_with("es") && domain.len() > 2 {
+        let singular = &domain[..domain.len() - 2];
+        variations.push(format!("{}.{}", singular, tld));
+    }
+
+    // Change 'ies' to 'y'
+    if domain_lower.ends_with("ies") && domain.len() > 3 {
+        let singular = format!("{}y", &domain[..domain.len() - 3]);
+        variations.push(format!("{}.{}", singular, tld));
+    }
+
+    variations
+}
+
+/// Apply `generate` with each language's word data, or `english` for English
+fn for_languages(
+    domain: &str,
+    tld: &str,
+    languages: &[Language],
+    english: fn(&str, &str) -> Vec<String>,
+    generate: fn(&str, &str, &WordData) -> Vec<String>,
+) -> Vec<String> {
+    languages
+        .iter()
+        .flat_map(|language| match language.words() {
+            Some(words) => generate(domain, tld, words),
+            None => english(domain, tld),
+        })
+        .collect()
+}
+
+/// The brand's product names in place of its registered name, as people
+/// looking for the product type it: `microsoft.com` with `azure` →
+/// `azure.com`, `mail.example.com` with `inbox` → `mail.inbox.com`
+pub fn generate_product_names(domain: &str, tld: &str, products: &[String]) -> Vec<String> {
+    let prefix = domain.rsplit_once('.').map(|(subdomain, _)| subdomain);
+    products
+        .iter()
+        .map(|product| match prefix {
+            Some(subdomain) => format!("{}.{}.{}", subdomain, product, tld),
+            None => format!("{}.{}", product, tld),
+        })
+        .collect()
+}
+
+/// [`generate_cognitive`] with the word confusions and phonetic spellings of
+/// `languages`
+pub fn generate_cognitive_in(domain: &str, tld: &str, languages: &[Language]) -> Vec<String> {
+    for_languages(
+        domain,
+        tld,
+        languages,
+        generate_cognitive,
+        |domain, tld, words| {
+            let name = domain.to_lowercase();
+            let mut variations = Vec::new();
+            for (word, confusions) in &words.cognitive {
+                for confusion in confusions {
+                    if name.contains(word.as_str()) {
+                        variations.push(format!(
+                            "{}.{}",
+                            name.replace(word.as_str(), confusion),
+                            tld
+                        ));
+                    }
+                    if name.contains(confusion.as_str()) {
+                        variations.push(format!(
+                            "{}.{}",
+                            name.replace(confusion.as_str(), word),
+                            tld
+                        ));
+                    }
+                }
+            }
+            for (from, to) in &words.phonetic {
+                if name.contains(from.as_str()) {
+                    variations.push(format!("{}.{}", name.replace(from.as_str(), to), tld));
+                }
+            }
+            variations
+        },
+    )
+}
+
+/// [`generate_homophones`] with the homophones of `languages`
+pub fn generate_homophones_in(domain: &str, tld: &str, languages: &[Language]) -> Vec<String> {
+    for_languages(
+        domain,
+        tld,
+        languages,
+        generate_homophones,
+        |domain, tld, words| {
+            let name = domain.to_lowercase();
+            // Punycode labels are left alone, as by `homophones`
+            let respell = |word: &str, other: &str| {
+                name.split('.')
+                    .map(|label| {
+                        if label.starts_with("xn--") {
+                            label.to_string()
+                        } else {
+                            label.replace(word, other)
+                        }
+                    })
+                    .collect::<Vec<_>>()
+                    .join(".")
+            };
+            let mut variations = Vec::new();
+            for group in &words.homophones {
+                for word in group.iter().filter(|word| name.contains(word.as_str())) {
+                    for other in group.iter().filter(|other| *other != word) {
+                        let respelled = respell(word, other);
+                        if respelled != name {
+                            variations.push(format!("{}.{}", respelled, tld));
+                        }
+                    }
+                }
+            }
+            variations
+        },
+    )
+}
+
+/// [`generate_singular_plural`] with the plural endings of `languages`
+pub fn generate_singular_plural_in(domain: &str, tld: &str, languages: &[Language]) -> Vec<String> {
+    for_languages(
+        domain,
+        tld,
+        languages,
+        generate_singular_plural,
+        |domain, tld, words| {
+            let name = domain.to_lowercase();
+            let mut variations = Vec::new();
+            for (singular, plural) in &words.plural {
+                for (from, to) in [(singular, plural), (plural, singular)] {
+                    // An ending needs a word to attach to
+                    match name.strip_suffix(from.as_str()) {
+                        Some(stem) if !stem.is_empty() && !stem.ends_with('.') => {
+                            variations.push(format!("{}{}.{}", stem, to, tld))
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            variations
+        },
+    )
+}
+
+/// Second-level suffixes of every ccTLD, extracted from the Public Suffix List
+const CCTLD_SLDS: &str = include_str!("../data/cctld-slds.txt");
+
+/// ccTLD -> its second-level labels, in Public Suffix List order
+pub(crate) fn cctld_slds() -> &'static HashMap<&'static str, Vec<&'static str>> {
+    static SLDS: OnceLock<HashMap<&'static str, Vec<&'static str>>> = OnceLock::new();
+    SLDS.get_or_init(|| {
+        CCTLD_SLDS
+            .lines()
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| {
+                let mut labels = line.split_whitespace();
+                Some((labels.next()?, labels.collect()))
+            })
+            .collect()
+    })
+}
+
+/// Split `domain` and `tld` into the registered name, the second-level label
+/// it sits under (if any), and the ccTLD. Accepts both `("example.co", "uk")`
+/// and `("example", "co.uk")`; `None` unless the TLD is a ccTLD with
+/// second-level structure.
+fn split_sld<'a>(domain: &'a str, tld: &'a str) -> Option<(&'a str, Option<&'a str>, &'a str)> {
+    let slds = cctld_slds();
+    if let Some((sld, cc)) = tld.split_once('.') {
+        return slds
+            .get(cc)
+            .filter(|list| list.contains(&sld))
+            .map(|_| (domain, Some(sld), cc));
+    }
+    let list = slds.get(tld)?;
+    match domain.rsplit_once('.') {
+        Some((name, sld)) if list.contains(&sld) => Some((name, Some(sld), tld)),
+        _ => Some((domain, None, tld)),
+    }
+}
+
+/// Register under the wrong second-level domain of any ccTLD: `example.co.uk`
+/// becomes `example.org.uk`, `example.uk`, and `example.co.nz`; `example.com`
+/// becomes `example.com.au` and every other `com.<cc>`.
+pub fn generate_wrong_sld(domain: &str, tld: &str) -> Vec<String> {
+    wrong_sld_variants(domain, tld, false)
+}
+
+/// Like [`generate_wrong_sld`], but only within the original's ccTLD
+/// (`example.co.uk` to `example.org.uk`, never `example.co.nz`)
+pub fn generate_wrong_sld_local(domain: &str, tld: &str) -> Vec<String> {
+    wrong_sld_variants(domain, tld, true)
+}
+
+fn wrong_sld_variants(domain: &str, tld: &str, same_region: bool) -> Vec<String> {
+    let mut variations = Vec::new();
+    let slds = cctld_slds();
+
+    let Some((name, sld, cc)) = split_sld(domain, tld) else {
+        // A generic TLD: the same label as a second-level domain elsewhere
+        if !same_region && tld.len() > 2 && !tld.contains('.') {
+            let mut ccs: Vec<_> = slds
+                .iter()
+                .filter(|(_, list)| list.contains(&tld))
+                .map(|(cc, _)| *cc)
+                .collect();
+            ccs.sort_unstable();
+            for cc in ccs {
+                variations.push(format!("{}.{}.{}", domain, tld, cc));
+            }
+        }
+        return variations;
+    };
+
+    // Other second-level domains of the same ccTLD, and the bare ccTLD
+    if sld.is_some() {
+        variations.push(format!("{}.{}", name, cc));
+    }
+    for other in &slds[cc] {
+        if Some(*other) != sld {
+            variations.push(format!("{}.{}.{}", name, other, cc));
+        }
+    }
+
+    // The same second-level label under other ccTLDs (co.uk -> co.nz)
+    if let (Some(sld), false) = (sld, same_region) {
+        let mut ccs: Vec<_> = slds
+            .iter()
+            .filter(|(other, list)| **other != cc && list.contains(&sld))
+            .map(|(other, _)| *other)
+            .collect();
+        ccs.sort_unstable();
+        for other in ccs {
+            variations.push(format!("{}.{}.{}", name, sld, other));
+        }
+    }
+
+    variations
+}
+
+pub fn generate_domain_prefix(domain: &str, tld: &str) -> Vec<String> {
+    let mut variations = Vec::new();
+
+    // Common domain prefixes
+    let prefixes = [
+        "www", "mail", "secure", "admin", "test", "dev", "api", "cdn", "auth", "login", "support",
+        "help", "shop", "store", "my", "portal", "mobile", "app", "service", "cloud", "server",
+        "vpn", "security", "monitor", "beta",
+    ];
+
+    for prefix in &prefixes {
+        variations.push(format!("{}-{}.{}", prefix, domain, tld));
+        variations.push(format!("{}.{}.{}", prefix, domain, tld));
+        variations.push(format!("{}{}.{}", prefix, domain, tld));
+    }
+
+    variations
+}
+
+pub fn generate_domain_suffix(domain: &str, tld: &str) -> Vec<String> {
+    let mut variations = Vec::new();
+
+    // Common domain suffixes
+    let suffixes = [
+        "app", "site", "web", "online", "pro", "plus", "premium", "club", "group", "tech",
+        "service", "platform", "security", "media", "shop", "store", "finance", "health", "gaming",
+        "demo", "beta",
+    ];
+
+    for suffix in &suffixes {
+        variations.push(format!("{}-{}.{}", domain, suffix, tld));
+        variations.push(format!("{}{}.{}", domain, suffix, tld));
+    }
+
+    variations
+}
+
+/// Whether `domain` could be registered: letters, digits, hyphens, and IDN
+/// characters in labels that neither start nor end with a hyphen, at most 63
+/// characters per label and 253 in all in ASCII (`xn--`) form, and no hyphens in
+/// the third and fourth positions except in valid punycode
+pub fn is_valid_domain(domain: &str) -> bool {
+    if domain.is_empty() {
+        return false;
+    }
+
+    // Domain can't start or end with dot
+    if domain.starts_with('.') || domain.ends_with('.') {
+        return false;
+    }
+
+    // Check for consecutive dots
+    if domain.contains("..") {
+        return false;
+    }
+
+    // Split into labels and validate each
+    let labels: Vec<&str> = domain.split('.').collect();
+
+    for label in &labels {
+        // Label can't be empty (handled by consecutive dots check above, but being explicit)
+        if label.is_empty() {
+            return false;
+        }
+
+        // Label can't start or end with hyphen
+        if label.starts_with('-') || label.ends_with('-') {
+            return false;
+        }
+
+        // Label must contain only valid characters (alphanumeric and hyphens)
+        // Note: We're being permissive to allow Unicode/IDN characters for our use case
+        for ch in label.chars() {
+            if !ch.is_alphanumeric() && ch != '-' && !ch.is_ascii() {
+                // Allow non-ASCII for Unicode/IDN transformations, but reject other invalid chars
+                continue;
+            }
+            if !ch.is_alphanumeric() && ch != '-' && ch.is_ascii() && !ch.is_ascii_alphanumeric() {
+                return false;
+            }
+        }
+    }
+
+    // Must have at least one dot (domain.tld format)
+    if labels.len() < 2 {
+        return false;
+    }
+
+    // TLD (last label) must be at least 2 characters
+    if let Some(tld) = labels.last() {
+        if tld.len() < 2 {
+            return false;
+        }
+    }
+
+    // What registries check beyond syntax: lengths in ASCII form, where
+    // punycode can push a short Unicode label past 63 characters, and no
+    // `ab--` labels other than valid `xn--` ones
+    Domain::parse(domain).is_ok()
+}
+
+pub fn filter_valid_domains(variations: Vec<String>) -> Vec<String> {
+    variations
+        .into_iter()
+        .filter(|domain| is_valid_domain(domain))
+        .map(|domain| domain.to_lowercase())
+        .collect()
+}
@@ -0,0 +1,136 @@
+//! Streaming gRPC service over [`FuzzerBuilder`], served by `domfuzz grpc`.
+//!
+//! The service definition is `proto/domfuzz.proto`. `Fuzz` streams each
+//! variation as soon as it is available: generated variations immediately, or,
+//! with `check_status`, each variation once its status lookup completes.
+
+use std::net::SocketAddr;
+
+use futures::StreamExt;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{Request, Response, Status};
+
+use crate::error::DomFuzzError;
+use crate::fuzzer::{Fuzzer, FuzzerBuilder, ScoredVariation};
+
+/// Generated protobuf types, client, and server for `domfuzz.v1`
+pub mod proto {
+    tonic::include_proto!("domfuzz.v1");
+}
+
+use proto::dom_fuzz_server::{DomFuzz, DomFuzzServer};
+use proto::{FuzzRequest, Variation};
+
+/// Variations buffered per stream before the producer waits on the client
+const STREAM_BUFFER: usize = 64;
+
+/// `max_variations` used when `check_status` is set and the request does not set one
+pub const DEFAULT_CHECK_LIMIT: u64 = 100;
+/// Largest `max_variations` accepted with `check_status`, bounding lookups per request
+pub const MAX_CHECK_LIMIT: u64 = 1000;
+
+/// Implementation of the `domfuzz.v1.DomFuzz` service
+#[derive(Debug, Default)]
+pub struct DomFuzzService;
+
+#[tonic::async_trait]
+impl DomFuzz for DomFuzzService {
+    type FuzzStream = ReceiverStream<Result<Variation, Status>>;
+
+    async fn fuzz(
+        &self,
+        request: Request<FuzzRequest>,
+    ) -> Result<Response<Self::FuzzStream>, Status> {
+        let request = request.into_inner();
+        let check_status = request.check_status;
+        let fuzzer = build_fuzzer(request).map_err(|e| Status::invalid_argument(e.to_string()))?;
+        let (tx, rx) = mpsc::channel(STREAM_BUFFER);
+
+        tokio::spawn(async move {
+            // Generation is CPU-bound; keep it off the async worker threads
+            let generated = tokio::task::spawn_blocking(move || {
+                let variations = fuzzer.generate();
+                (fuzzer, variations)
+            })
+            .await;
+            let (fuzzer, variations) = match generated {
+                Ok(generated) => generated,
+                Err(e) => {
+                    let _ = tx.send(Err(Status::internal(e.to_string()))).await;
+                    return;
+                }
+            };
+            if check_status {
+                let mut checked = std::pin::pin!(fuzzer.check_generated_stream(variations));
+                while let Some(result) = checked.next().await {
+                    let variation = to_proto(result.variation, Some(result.status));
+                    if tx.send(Ok(variation)).await.is_err() {
+                        break; // Client went away
+                    }
+                }
+            } else {
+                for variation in variations {
+                    if tx.send(Ok(to_proto(variation, None))).await.is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+}
+
+/// Serve the gRPC service on `addr` until the process is stopped
+pub async fn serve(addr: SocketAddr) -> Result<(), DomFuzzError> {
+    tonic::transport::Server::builder()
+        .add_service(DomFuzzServer::new(DomFuzzService))
+        .serve(addr)
+        .await
+        .map_err(|e| DomFuzzError::Io(std::io::Error::other(e)))
+}
+
+fn build_fuzzer(request: FuzzRequest) -> Result<Fuzzer, DomFuzzError> {
+    let mut builder = FuzzerBuilder::new(request.domain)
+        .transformations(request.transformations)
+        .combo(request.combo);
+
+    let max_variations = match request.max_variations {
+        Some(max) if request.check_status && max > MAX_CHECK_LIMIT => {
+            return Err(DomFuzzError::Config(format!(
+                "max_variations must be at most {} with check_status",
+                MAX_CHECK_LIMIT
+            )))
+        }
+        Some(max) => Some(max),
+        None if request.check_status => Some(DEFAULT_CHECK_LIMIT),
+        None => None,
+    };
+    if let Some(max) = max_variations {
+        let max = usize::try_from(max)
+            .map_err(|_| DomFuzzError::Config(format!("max_variations too large: {}", max)))?;
+        builder = builder.max_variations(max);
+    }
+    if let Some(threshold) = request.min_similarity {
+        builder = builder.min_similarity(threshold);
+    }
+    if let Some(threshold) = request.max_similarity {
+        builder = builder.max_similarity(threshold);
+    }
+    if let Some(seed) = request.seed {
+        builder = builder.seed(seed);
+    }
+    builder.build()
+}
+
+fn to_proto(variation: ScoredVariation, status: Option<String>) -> Variation {
+    Variation {
+        domain: variation.domain,
+        transformation: variation.transformation.to_string(),
+        score: variation.similarity.combined_score,
+        visual_score: variation.similarity.visual_score,
+        cognitive_score: variation.similarity.cognitive_score,
+        status,
+    }
+}
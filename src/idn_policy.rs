@@ -0,0 +1,190 @@
+//! Which internationalized labels registries accept (`--respect-idn-policies`).
+//!
+//! A homoglyph is only a threat if it can be registered. Registries publish
+//! tables of the characters they accept: `.de` takes a set of Latin letters
+//! with diacritics, `.рф` only Cyrillic, `.com` most scripts but one per
+//! label, and `.uk` no IDNs at all. The simplified tables in
+//! `data/idn-policies.txt` drop variations whose registered label the TLD
+//! would reject; TLDs without a table are not filtered.
+
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+use std::sync::OnceLock;
+
+use icu_properties::props::Script;
+use icu_properties::{CodePointMapData, PropertyParser};
+
+use crate::domain::{label_to_unicode, Domain};
+use crate::error::DomFuzzError;
+
+/// One way a label under a TLD may be written
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Allowed {
+    /// Letters of these scripts, written together by one language
+    Scripts(Vec<Script>),
+    /// Latin letters from the policy's code points only
+    LatinTable,
+    /// Letters of any one script
+    AnyScript,
+}
+
+/// What one TLD's registry accepts
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Policy {
+    /// Empty for ASCII only
+    allowed: Vec<Allowed>,
+    /// Code points accepted along with any entry
+    code_points: Vec<RangeInclusive<char>>,
+}
+
+impl Policy {
+    /// Whether a label whose non-ASCII characters are `chars` and whose letters
+    /// are written in `scripts` stays within one entry
+    fn accepts(&self, chars: &[char], scripts: &[Script]) -> bool {
+        let listed = |c: &char| self.code_points.iter().any(|range| range.contains(c));
+        self.allowed.iter().any(|allowed| match allowed {
+            Allowed::Scripts(allowed) => {
+                chars
+                    .iter()
+                    .all(|c| allowed.contains(&script_of(*c)) || listed(c))
+                    && scripts.iter().all(|script| allowed.contains(script))
+            }
+            Allowed::LatinTable => {
+                chars.iter().all(listed) && scripts.iter().all(|&script| script == Script::Latin)
+            }
+            Allowed::AnyScript => {
+                scripts.len() == 1
+                    && chars
+                        .iter()
+                        .all(|c| script_of(*c) == scripts[0] || listed(c))
+            }
+        })
+    }
+}
+
+/// Per-TLD IDN tables
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IdnPolicies {
+    /// Keyed by TLD in Unicode form
+    policies: HashMap<String, Policy>,
+}
+
+impl IdnPolicies {
+    /// The tables shipped in `data/idn-policies.txt`
+    pub fn builtin() -> &'static IdnPolicies {
+        static POLICIES: OnceLock<IdnPolicies> = OnceLock::new();
+        POLICIES.get_or_init(|| {
+            IdnPolicies::parse(include_str!("../data/idn-policies.txt"))
+                .unwrap_or_else(|e| panic!("built-in IDN policy data is invalid: {}", e))
+        })
+    }
+
+    /// Parse tables, one TLD per line as `TLD ALLOWED...`: script names (joined
+    /// with `+` for scripts written together), `*` for any one script, code
+    /// points and ranges as `U+00E0-U+00FF`, accepted with any entry and on
+    /// their own forming a Latin table, or `-` for ASCII only. Blank lines and
+    /// lines starting with `#` are ignored.
+    pub fn parse(text: &str) -> Result<Self, DomFuzzError> {
+        let mut policies = HashMap::new();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid =
+                |reason: String| DomFuzzError::Parse(format!("line {}: {}", number + 1, reason));
+            let mut fields = line.split_whitespace();
+            let tld = fields.next().expect("line is not blank").to_lowercase();
+            let tld = label_to_unicode(&tld).unwrap_or(tld);
+            let mut allowed = Vec::new();
+            let mut code_points = Vec::new();
+            let mut ascii_only = false;
+            for field in fields {
+                match field {
+                    "*" => allowed.push(Allowed::AnyScript),
+                    "-" => ascii_only = true,
+                    _ if field.starts_with("U+") => code_points.push(
+                        parse_range(field)
+                            .ok_or_else(|| invalid(format!("invalid code points {:?}", field)))?,
+                    ),
+                    _ => {
+                        let scripts = field
+                            .split('+')
+                            .map(|name| {
+                                PropertyParser::<Script>::new()
+                                    .get_loose(name)
+                                    .ok_or_else(|| invalid(format!("unknown script {:?}", name)))
+                            })
+                            .collect::<Result<Vec<_>, _>>()?;
+                        allowed.push(Allowed::Scripts(scripts));
+                    }
+                }
+            }
+            if !code_points.is_empty() {
+                allowed.push(Allowed::LatinTable);
+            }
+            if allowed.is_empty() != ascii_only {
+                return Err(invalid(format!(
+                    "expected `-` alone or scripts and code points for {}",
+                    tld
+                )));
+            }
+            policies.insert(
+                tld,
+                Policy {
+                    allowed,
+                    code_points,
+                },
+            );
+        }
+        Ok(IdnPolicies { policies })
+    }
+
+    /// Whether the registry of `domain`'s TLD would accept its registered label.
+    /// ASCII labels, TLDs without a table, and domains that do not parse are
+    /// accepted.
+    pub fn allows(&self, domain: &str) -> bool {
+        let Ok(domain) = Domain::parse(domain) else {
+            return true;
+        };
+        let label = domain.registered_label();
+        let label = label_to_unicode(label).unwrap_or_else(|| label.to_string());
+        if label.is_ascii() {
+            return true;
+        }
+        let tld = label_to_unicode(domain.tld()).unwrap_or_else(|| domain.tld().to_string());
+        let Some(policy) = self.policies.get(&tld) else {
+            return true;
+        };
+
+        let chars: Vec<char> = label.chars().filter(|c| !c.is_ascii()).collect();
+        let mut scripts = Vec::new();
+        for c in label.chars().filter(|c| c.is_alphabetic()) {
+            let script = script_of(c);
+            if script != Script::Common && script != Script::Inherited && !scripts.contains(&script)
+            {
+                scripts.push(script);
+            }
+        }
+        policy.accepts(&chars, &scripts)
+    }
+}
+
+/// `U+XXXX` or `U+XXXX-U+YYYY`
+fn parse_range(field: &str) -> Option<RangeInclusive<char>> {
+    let code_point = |text: &str| {
+        let hex = text.strip_prefix("U+")?;
+        char::from_u32(u32::from_str_radix(hex, 16).ok()?)
+    };
+    match field.split_once('-') {
+        Some((start, end)) => Some(code_point(start)?..=code_point(end)?),
+        None => {
+            let c = code_point(field)?;
+            Some(c..=c)
+        }
+    }
+}
+
+fn script_of(c: char) -> Script {
+    CodePointMapData::<Script>::new().get(c)
+}
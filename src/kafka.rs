@@ -0,0 +1,146 @@
+//! Findings and monitor events published to Kafka (`--sink kafka`).
+//!
+//! Streaming threat-intel pipelines take their feeds from Kafka topics. Each
+//! finding, or each monitor event, becomes one message whose payload is the
+//! same line the syslog sink would send (CSV, JSON Lines, CEF, or LEEF, and
+//! NDJSON events). Messages are queued and delivered by librdkafka in the
+//! background; deliveries that fail are reported when the sender is flushed,
+//! which fails the run the way an unreachable syslog collector does.
+
+use std::io::{self, Write};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use rdkafka::config::ClientConfig;
+use rdkafka::error::{KafkaError, RDKafkaErrorCode};
+use rdkafka::producer::{BaseRecord, DeliveryResult, Producer, ProducerContext, ThreadedProducer};
+use rdkafka::ClientContext;
+
+use crate::error::DomFuzzError;
+
+/// Default `--topic`
+pub const DEFAULT_TOPIC: &str = "domfuzz";
+/// Time allowed to reach the brokers and learn about the topic
+pub const KAFKA_CONNECT_TIMEOUT_SECS: u64 = 10;
+/// Time allowed to deliver each message, and what is queued when flushing
+pub const KAFKA_FLUSH_TIMEOUT_SECS: u64 = 30;
+/// Wait between attempts to queue a message while the queue is full
+const QUEUE_FULL_WAIT: Duration = Duration::from_millis(100);
+
+/// Counts failed deliveries, which the polling thread reports
+#[derive(Default)]
+struct Deliveries {
+    /// Failed deliveries not yet reported, and the first one's error
+    failed: Mutex<(usize, Option<String>)>,
+}
+
+impl ClientContext for Deliveries {}
+
+impl ProducerContext for Deliveries {
+    type DeliveryOpaque = ();
+
+    fn delivery(&self, result: &DeliveryResult<'_>, _: ()) {
+        if let Err((e, _)) = result {
+            let mut failed = self.failed.lock().unwrap_or_else(|e| e.into_inner());
+            failed.0 += 1;
+            failed.1.get_or_insert_with(|| e.to_string());
+        }
+    }
+}
+
+/// Producer publishing one message per line to a topic
+pub struct KafkaSender {
+    producer: ThreadedProducer<Deliveries>,
+    topic: String,
+    /// Bytes written through [`Write`] that do not end a line yet
+    partial: Vec<u8>,
+}
+
+impl KafkaSender {
+    /// Connect to `brokers` (comma-separated `HOST:PORT`) and make sure they
+    /// know `topic`, which may be created on first use if the cluster allows
+    pub fn connect(brokers: &str, topic: &str) -> Result<Self, DomFuzzError> {
+        let invalid = |message: String| {
+            DomFuzzError::Config(format!("Kafka brokers {}: {}", brokers, message))
+        };
+        if brokers.trim().is_empty() {
+            return Err(DomFuzzError::Config(
+                "--sink kafka requires --brokers".to_string(),
+            ));
+        }
+        if topic.is_empty() {
+            return Err(DomFuzzError::Config("empty Kafka --topic".to_string()));
+        }
+        let producer: ThreadedProducer<Deliveries> = ClientConfig::new()
+            .set("bootstrap.servers", brokers)
+            .set("client.id", "domfuzz")
+            .set(
+                "message.timeout.ms",
+                (KAFKA_FLUSH_TIMEOUT_SECS * 1000).to_string(),
+            )
+            .create_with_context(Deliveries::default())
+            .map_err(|e| invalid(e.to_string()))?;
+        producer
+            .client()
+            .fetch_metadata(Some(topic), Duration::from_secs(KAFKA_CONNECT_TIMEOUT_SECS))
+            .map_err(|e| invalid(e.to_string()))?;
+        Ok(KafkaSender {
+            producer,
+            topic: topic.to_string(),
+            partial: Vec::new(),
+        })
+    }
+
+    /// Queue `message` as the payload of one Kafka message
+    pub fn send(&mut self, message: &str) -> io::Result<()> {
+        let mut record = BaseRecord::<(), str>::to(&self.topic).payload(message);
+        loop {
+            match self.producer.send(record) {
+                Ok(()) => return Ok(()),
+                Err((KafkaError::MessageProduction(RDKafkaErrorCode::QueueFull), returned)) => {
+                    record = returned;
+                    std::thread::sleep(QUEUE_FULL_WAIT);
+                }
+                Err((e, _)) => return Err(io::Error::other(format!("Kafka: {}", e))),
+            }
+        }
+    }
+
+    /// Wait for queued messages to be delivered, failing if any could not be
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.producer
+            .flush(Duration::from_secs(KAFKA_FLUSH_TIMEOUT_SECS))
+            .map_err(|e| io::Error::other(format!("Kafka: {}", e)))?;
+        let mut failed = self
+            .producer
+            .context()
+            .failed
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        match std::mem::take(&mut *failed) {
+            (0, _) => Ok(()),
+            (count, error) => Err(io::Error::other(format!(
+                "Kafka: {} messages not delivered: {}",
+                count,
+                error.unwrap_or_default()
+            ))),
+        }
+    }
+}
+
+/// Each line written is sent as one message, as [`crate::monitor::EventWriter`]
+/// writes events
+impl Write for KafkaSender {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.partial.extend_from_slice(buf);
+        while let Some(end) = self.partial.iter().position(|&byte| byte == b'\n') {
+            let line: Vec<u8> = self.partial.drain(..=end).collect();
+            self.send(String::from_utf8_lossy(&line[..end]).trim_end_matches('\r'))?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        KafkaSender::flush(self)
+    }
+}
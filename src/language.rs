@@ -0,0 +1,152 @@
+//! Languages the word-level transformations know (`--language`).
+//!
+//! The cognitive, homophones, and singular-plural transformations replace
+//! whole words and word endings, so their tables only make sense for names in
+//! the language they were written for. English tables are built into the
+//! generators; other languages are read from `data/languages/<code>.txt`.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use crate::error::DomFuzzError;
+
+/// A language with word data for the word-level transformations
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum Language {
+    #[default]
+    #[cfg_attr(feature = "cli", value(name = "en"))]
+    English,
+    #[cfg_attr(feature = "cli", value(name = "de"))]
+    German,
+    #[cfg_attr(feature = "cli", value(name = "fr"))]
+    French,
+    #[cfg_attr(feature = "cli", value(name = "es"))]
+    Spanish,
+}
+
+impl Language {
+    pub const ALL: [Language; 4] = [
+        Language::English,
+        Language::German,
+        Language::French,
+        Language::Spanish,
+    ];
+
+    /// ISO 639-1 code, as accepted by `--language`
+    pub fn code(&self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::German => "de",
+            Language::French => "fr",
+            Language::Spanish => "es",
+        }
+    }
+
+    /// Bundled word data; `None` for English, whose tables are built into the
+    /// generators
+    pub fn words(&self) -> Option<&'static WordData> {
+        static DATA: OnceLock<HashMap<Language, WordData>> = OnceLock::new();
+        let data = DATA.get_or_init(|| {
+            [
+                (Language::German, include_str!("../data/languages/de.txt")),
+                (Language::French, include_str!("../data/languages/fr.txt")),
+                (Language::Spanish, include_str!("../data/languages/es.txt")),
+            ]
+            .into_iter()
+            .map(|(language, text)| {
+                let words = WordData::parse(text).unwrap_or_else(|e| {
+                    panic!("built-in {} word data is invalid: {}", language, e)
+                });
+                (language, words)
+            })
+            .collect()
+        });
+        data.get(self)
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+impl FromStr for Language {
+    type Err = DomFuzzError;
+
+    fn from_str(code: &str) -> Result<Self, DomFuzzError> {
+        let code = code.trim().to_lowercase();
+        Language::ALL
+            .into_iter()
+            .find(|language| language.code() == code)
+            .ok_or_else(|| {
+                DomFuzzError::Config(format!(
+                    "unknown language {:?} (expected one of: en, de, fr, es)",
+                    code
+                ))
+            })
+    }
+}
+
+/// Word tables of one language, in file order
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WordData {
+    /// Words and the misspellings or near-synonyms they are confused with
+    pub cognitive: Vec<(String, Vec<String>)>,
+    /// Spellings that sound alike, replaced anywhere in a name
+    pub phonetic: Vec<(String, String)>,
+    /// Groups of words that sound alike
+    pub homophones: Vec<Vec<String>>,
+    /// Singular and plural word endings, empty for none
+    pub plural: Vec<(String, String)>,
+}
+
+impl WordData {
+    /// Parse word data: `[cognitive]`, `[phonetic]`, `[homophones]`, and
+    /// `[plural]` sections of space-separated words, one entry per line, with
+    /// `-` for an empty plural ending. Blank lines and lines starting with `#`
+    /// are ignored.
+    pub fn parse(text: &str) -> Result<Self, DomFuzzError> {
+        let mut data = WordData::default();
+        let mut section = None;
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid =
+                |reason: &str| DomFuzzError::Parse(format!("line {}: {}", number + 1, reason));
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                section = Some(name.to_string());
+                continue;
+            }
+            let words: Vec<String> = line.split_whitespace().map(str::to_lowercase).collect();
+            match (section.as_deref(), words.as_slice()) {
+                (Some("cognitive"), [word, confusions @ ..]) if !confusions.is_empty() => {
+                    data.cognitive.push((word.clone(), confusions.to_vec()))
+                }
+                (Some("phonetic"), [from, to]) => data.phonetic.push((from.clone(), to.clone())),
+                (Some("homophones"), group @ [_, _, ..]) => data.homophones.push(group.to_vec()),
+                (Some("plural"), [singular, plural]) => {
+                    let ending = |ending: &str| {
+                        if ending == "-" {
+                            String::new()
+                        } else {
+                            ending.to_string()
+                        }
+                    };
+                    data.plural.push((ending(singular), ending(plural)))
+                }
+                (None, _) => return Err(invalid("entry before the first [section]")),
+                (Some(name @ ("cognitive" | "phonetic" | "homophones" | "plural")), _) => {
+                    return Err(invalid(&format!("malformed entry in [{}]", name)))
+                }
+                (Some(name), _) => return Err(invalid(&format!("unknown section [{}]", name))),
+            }
+        }
+        Ok(data)
+    }
+}
@@ -0,0 +1,121 @@
+//! DomFuzz: domain name variation generation and similarity scoring.
+//!
+//! [`FuzzerBuilder`] is the main entry point for library consumers, and
+//! [`Domain`] is how it reads domain names: labels under a public suffix.
+//! [`confusables`] goes the other way, taking a suspicious domain apart against
+//! a brand. Fallible operations return [`DomFuzzError`]. The `domfuzz` binary
+//! layers output formatting, its [`config`] file, [`target`] profiles, and
+//! [`monitor`] mode on top of this library.
+//!
+//! The [`ffi`] module exposes generation and scoring to C and C++ callers; see
+//! `include/domfuzz.h` for the matching declarations. Optional features add:
+//!
+//! - `network`: domain status checking in [`checker`], hosting intelligence
+//!   about registered variations in [`enrich`], and takedown requests drafted
+//!   from it in [`abuse`]
+//! - `wasm`: the `wasm` module, exposing the library to JavaScript
+//! - `server`: the `server` module, serving it over HTTP
+//! - `grpc`: the `grpc` module, streaming it over gRPC
+//! - `reports`: the `report` module, rendering results as HTML and Markdown
+//! - `pdf`: the `pdf` module, laying results out as PDF
+//! - `kafka`: the `kafka` module, publishing results and monitor events to Kafka
+//! - `s3`: the `upload` module, putting result artifacts in S3
+//! - `scripting`: the `script` module, running transformations written in Rhai
+
+#[cfg(feature = "cli")]
+pub mod abuse;
+#[cfg(feature = "cli")]
+pub mod annotate;
+#[cfg(feature = "network")]
+pub mod checker;
+pub mod combo;
+#[cfg(feature = "cli")]
+pub mod config;
+pub mod confusables;
+pub mod custom;
+pub mod dedupe;
+pub mod domain;
+#[cfg(feature = "network")]
+pub mod enrich;
+pub mod error;
+pub mod ffi;
+#[cfg(feature = "network")]
+pub mod fingerprint;
+pub mod fuzzer;
+pub mod generators;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+pub mod idn_policy;
+#[cfg(feature = "kafka")]
+pub mod kafka;
+pub mod language;
+#[cfg(feature = "network")]
+pub mod metrics;
+#[cfg(feature = "cli")]
+pub mod monitor;
+#[cfg(feature = "network")]
+pub mod net;
+#[cfg(feature = "network")]
+pub mod page;
+#[cfg(feature = "pdf")]
+pub mod pdf;
+pub mod phonetic;
+pub mod plausibility;
+#[cfg(feature = "network")]
+pub mod polite;
+#[cfg(feature = "network")]
+pub mod public;
+#[cfg(feature = "cli")]
+pub mod purchase;
+#[cfg(feature = "network")]
+pub mod registration;
+#[cfg(feature = "reports")]
+pub mod report;
+#[cfg(feature = "screenshots")]
+pub mod screenshot;
+#[cfg(feature = "scripting")]
+pub mod script;
+#[cfg(feature = "server")]
+pub mod server;
+pub mod similarity;
+#[cfg(feature = "network")]
+pub mod sinkhole;
+#[cfg(feature = "network")]
+pub mod smtp;
+#[cfg(feature = "cli")]
+pub mod syslog;
+#[cfg(feature = "cli")]
+pub mod target;
+#[cfg(feature = "network")]
+pub mod throttle;
+#[cfg(feature = "network")]
+pub mod tls;
+pub mod transliteration;
+pub mod typo;
+#[cfg(feature = "s3")]
+pub mod upload;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "network")]
+pub mod wayback;
+#[cfg(feature = "network")]
+pub mod whois;
+
+#[cfg(feature = "network")]
+pub use checker::{check_stream, CheckedDomain};
+pub use domain::Domain;
+pub use error::DomFuzzError;
+#[cfg(feature = "network")]
+pub use fuzzer::CheckedVariation;
+pub use fuzzer::{Fuzzer, FuzzerBuilder, ScoredVariation};
+pub use generators::{
+    filter_valid_domains, generate_variations, is_valid_domain, parse_domain,
+    parse_transformations, parse_transformations_with, transformation_generators, Generator,
+    GeneratorOptions, Realism, Variation,
+};
+pub use language::Language;
+pub use similarity::{
+    calculate_similarity, calculate_similarity_with_mode, score_domain, ScoringAlgorithm,
+    ScoringMode, SimilarityScore,
+};
+pub use typo::typo_likelihood;
@@ -1,48 +1,77 @@
 use clap::Parser;
-use futures::stream::{self, StreamExt};
+use domfuzz::abuse::{abuse_report, AbuseEvidence, MAX_ABUSE_HOSTS};
+use domfuzz::annotate::{self, Annotation, Disposition, FileKind};
+use domfuzz::checker::{
+    self, check_domain_rdap, check_domains, check_whois, extract_tld, get_rdap_endpoint,
+    is_failed_status, is_registered_status, CheckOptions, DNS_RESOLVER, DNS_TIMEOUT_SECS,
+    HTTP_CLIENT, HTTP_TIMEOUT_SECS,
+};
+use domfuzz::combo::{
+    random_combo_chain_memoized, weighted_combo_chain_memoized, ComboMemo, ExhaustiveCombos,
+    SimilarityWeights,
+};
+use domfuzz::config::Config;
+use domfuzz::confusables::{analyze, skeleton, SegmentKind, SkeletonMatch};
+use domfuzz::custom::CustomRules;
+use domfuzz::dedupe::{DomainSet, DEFAULT_FALSE_POSITIVE_RATE};
+use domfuzz::domain::{normalizes_to_original, Domain, NORMALIZES_TO_ORIGINAL};
+use domfuzz::enrich::{enrich, EnrichOptions, Enrichment};
+use domfuzz::error::{DomFuzzError, EXIT_LOOKUP_FAILED};
+use domfuzz::generators::{
+    by_parts, default_dictionary, extract_registrable_domain, filter_valid_domains,
+    generate_1337speak, generate_fat_finger_with_realism, generate_hyphenation,
+    generate_misspelling, generate_mixed_encodings, generate_tld_variations_from,
+    generate_variations_with_progress, is_valid_domain, load_dictionary, load_tld_set,
+    parse_domain, transformation_generators, transformation_names, Generator, GeneratorOptions,
+    Realism, BUNDLES,
+};
+use domfuzz::idn_policy::IdnPolicies;
+#[cfg(feature = "kafka")]
+use domfuzz::kafka::KafkaSender;
+use domfuzz::language::Language;
+use domfuzz::monitor::{post_alert, AlertRule, Event, EventWriter, MonitorState};
+use domfuzz::page::{analyze_page, fetch_page_if_modified, fetch_site_keywords, FOR_SALE};
+use domfuzz::plausibility::plausibility;
+use domfuzz::polite::{self, Politeness};
+use domfuzz::public::{self, PublicMode};
+use domfuzz::purchase::{PurchaseFormat, PurchaseList};
+use domfuzz::registration::{fetch_host_abuse_contact, fetch_registration, Registration};
+#[cfg(feature = "reports")]
+use domfuzz::report::{Report, ReportFormat};
+#[cfg(feature = "scripting")]
+use domfuzz::script::Script;
+use domfuzz::similarity::{
+    calculate_similarity, calculate_similarity_with_mode, check_similarity_range, compare,
+    parse_similarity_range, parse_similarity_threshold, ScoringMode, SimilarityScore,
+};
+use domfuzz::sinkhole::AddressRules;
+use domfuzz::smtp::lookup_mx;
+use domfuzz::syslog::SyslogSender;
+use domfuzz::target::TargetProfile;
+use domfuzz::throttle;
+use domfuzz::tls::fetch_certificate;
+use domfuzz::typo::typo_likelihood;
+#[cfg(feature = "s3")]
+use domfuzz::upload::{S3Location, S3Uploader, UPLOAD_CONCURRENCY};
+use domfuzz::whois::WhoisRules;
+use flate2::write::GzEncoder;
 use hickory_resolver::{
     config::{ResolverConfig, ResolverOpts},
     TokioAsyncResolver,
 };
 use indicatif::{ProgressBar, ProgressStyle};
-use std::sync::Arc;
 use std::{
-    collections::HashSet,
-    io::{self, Write},
-    time::Duration,
+    cell::Cell,
+    cmp::Ordering,
+    collections::{BTreeMap, BinaryHeap, HashMap, HashSet},
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicU64, Ordering as AtomicOrdering},
+    sync::Mutex,
+    time::{Duration, Instant},
 };
-use tokio::sync::Semaphore;
-use tokio::{
-    io::{AsyncReadExt, AsyncWriteExt},
-    net::TcpStream,
-    time::timeout,
-};
-
-// Constants for timeout values
-const RDAP_TIMEOUT_SECS: u64 = 5;
-const DNS_TIMEOUT_SECS: u64 = 5;
-const HTTP_TIMEOUT_SECS: u64 = 10;
-const HTTP_CONTENT_TIMEOUT_SECS: u64 = 5;
-const WHOIS_CONNECT_TIMEOUT_SECS: u64 = 10;
-const WHOIS_WRITE_TIMEOUT_SECS: u64 = 5;
-const WHOIS_READ_TIMEOUT_SECS: u64 = 10;
-const RETRY_DELAY_MS: u64 = 500;
-
-// Type alias for better error handling
-type DomainCheckResult<T> = Result<T, Box<dyn std::error::Error + Send + Sync>>;
-
-// Global HTTP client for connection reuse and performance
-lazy_static::lazy_static! {
-    static ref HTTP_CLIENT: reqwest::Client = {
-        reqwest::Client::builder()
-            .timeout(Duration::from_secs(RDAP_TIMEOUT_SECS))
-            .user_agent("Mozilla/5.0 (compatible; DomFuzz/0.1)")
-            .pool_max_idle_per_host(10)
-            .pool_idle_timeout(Duration::from_secs(30))
-            .build()
-            .expect("Failed to create HTTP client")
-    };
-}
+use tempfile::NamedTempFile;
+use tokio::time::timeout;
 
 #[derive(Parser)]
 #[command(name = "domfuzz")]
@@ -60,8 +89,11 @@ lazy_static::lazy_static! {
 💾 SYSTEM FAULT - Hardware/transmission errors:
   bitsquatting   - Single bit-flip transformations (memory corruption, cosmic rays)
 
+🔤 SCRIPT - Other alphabets:
+  transliteration - Whole name respelled in Cyrillic/Greek, or back to Latin (yandex → яндекс)
+
 🗣️ PHONETIC/SEMANTIC - Language-based variations:
-  homophones     - Sound-alike replacements (right→write, to→two)
+  homophones     - Sound-alike respellings (right→write, phone→fone)
   cognitive      - Semantic confusion (secure→safe, login→signin)
   singular-plural - Grammatical forms (bank→banks, service→services)
 
@@ -78,8 +110,10 @@ lazy_static::lazy_static! {
 
 🌍 EXTENSIONS/BRANDING - TLD and brand exploitation:
   tld-variations - Alternative TLDs (.com→.net/.org/.co)
-  intl-tld      - International domains (.com→.co.uk/.de)
-  wrong-sld     - Wrong 2nd-level domains (.co.uk→.com.uk)
+  idn-tld       - Delegated IDN TLDs, as xn-- (.com→.ком as .xn--j1aef)
+  fake-tld      - Lookalike TLDs that cannot be registered (.com→.сom, .κομ)
+  wrong-sld     - Wrong 2nd-level domains (.co.uk→.org.uk/.co.nz)
+  wrong-sld-local - Wrong 2nd-level domains, same country only
   combosquatting - Dictionary combinations (secure-paypal, google-login)
   brand-confusion - Authority terms (official-, real-, -support)
   domain-prefix  - Common prefixes (my-, the-, secure-)
@@ -91,10 +125,26 @@ USAGE EXAMPLES:
   domfuzz -t 1337speak,fat-finger example.com  # Specific transformations
   domfuzz -t all example.com             # All available transformations
   domfuzz -t lookalike --similarity example.com  # With similarity scoring
-  domfuzz -r -n 100 example.com          # Check 100 registered domains")]
+  domfuzz -r -n 100 example.com          # Check 100 registered domains
+  domfuzz profile -t all example.com     # Show where generation time goes
+  domfuzz transformations                # List transformations and bundles, including the config file's
+  domfuzz report results.jsonl -o report.html  # Render results as an HTML report
+  domfuzz report-abuse examp1e.com --against example.com  # Draft an abuse report to the registrar
+  domfuzz annotate results.jsonl examp1e.com --disposition escalated  # Record a triage decision")]
+#[command(subcommand_negates_reqs = true, args_conflicts_with_subcommands = true)]
 struct Cli {
-    /// Domain to generate variations for
-    domain: String,
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Domain to generate variations for [default: the first of --target's domains]
+    #[arg(required_unless_present = "target")]
+    domain: Option<String>,
+
+    /// Another name of the brand (repeatable); every transformation also runs on each,
+    /// and results name the seed they came from. A name without dots takes the original
+    /// domain's suffix
+    #[arg(long, value_name = "NAME")]
+    alias: Vec<String>,
 
     /// Transformations to enable (comma-separated).
     /// Default: 'lookalike' bundle (1337speak, misspelling, fat-finger, mixed-encodings).
@@ -107,6 +157,16 @@ struct Cli {
     #[arg(long, short = 'n')]
     max_variations: Option<usize>,
 
+    /// Output the N most similar variations of each enabled transformation, grouped by
+    /// transformation, instead of the most similar overall
+    #[arg(long, value_name = "N", conflicts_with = "max_variations")]
+    top_per_transformation: Option<usize>,
+
+    /// Output one variation, the most similar, of each group that reads the same (the same
+    /// skeleton, as `analyze` prints it), with the group's size
+    #[arg(long)]
+    collapse_near_duplicates: bool,
+
     /// Check domain availability status (requires network)
     #[arg(long, short = 's')]
     check_status: bool,
@@ -119,158 +179,781 @@ struct Cli {
     #[arg(long, short = 'a')]
     only_available: bool,
 
-    /// Path to dictionary file for combosquatting
-    #[arg(long)]
-    dictionary: Option<String>,
-
-    /// Run each transformation individually, applying only one transformation per domain (default: enabled)
-    #[arg(long, short = '1', default_value_t = true)]
+    #[command(flatten)]
+    generation: GeneratorArgs,
+
+    /// Run each transformation individually, applying only one transformation per domain
+    /// (default: enabled); `--one-transformation=false` chains several random transformations
+    /// per domain instead (combo mode)
+    #[arg(
+        long,
+        short = '1',
+        default_value_t = true,
+        num_args = 0..=1,
+        default_missing_value = "true",
+        action = clap::ArgAction::Set
+    )]
     one_transformation: bool,
 
+    /// How combo chains are produced: `random` samples chains of 2-5 steps indefinitely,
+    /// `weighted` samples them favoring transformations that keep similarity, `exhaustive`
+    /// enumerates every distinct chain up to --combo-depth, shortest first
+    #[arg(long, value_enum, default_value = "random")]
+    combo_mode: ComboMode,
+
+    /// Longest chain enumerated by `--combo-mode exhaustive`
+    #[arg(long, value_name = "STEPS", default_value_t = DEFAULT_COMBO_DEPTH)]
+    combo_depth: usize,
+
+    /// Drop combo domains that read as gibberish: below this plausibility (0.0-1.0 or
+    /// 0%-100%), where 100% is as pronounceable as the original and each extra bit of
+    /// surprise per letter halves it
+    #[arg(long, value_name = "THRESHOLD")]
+    min_plausibility: Option<String>,
+
+    /// Most combo chains tried before generation stops [default: 10 per requested variation
+    /// and seed, unlimited without --max-variations]
+    #[arg(long, value_name = "N")]
+    max_attempts: Option<usize>,
+
+    /// Stop generating combo chains after this long (e.g. 90s, 30m, 1h30m); candidates
+    /// already generated are still checked and written
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+    max_runtime: Option<Duration>,
+
+    /// How combo mode remembers emitted domains; `probabilistic` uses a Bloom filter of a few
+    /// bits per domain for very long runs, occasionally skipping a new domain
+    #[arg(long, value_enum, default_value = "exact")]
+    dedupe: DedupeMode,
+
+    /// Fraction of new domains `--dedupe probabilistic` may wrongly skip as duplicates
+    #[arg(long, value_name = "RATE", default_value_t = DEFAULT_FALSE_POSITIVE_RATE)]
+    dedupe_fp_rate: f64,
+
     /// Enable verbose output showing what the application is doing
     #[arg(long, short = 'v')]
     verbose: bool,
 
-    /// Calculate and display similarity scores for generated variations
-    #[arg(long)]
+    /// Filter results to minimum similarity threshold (0.0-1.0 or 0%-100%) [default: 50%]
+    #[arg(long, value_name = "THRESHOLD")]
+    min_similarity: Option<String>,
+
+    /// Filter out results above this similarity (0.0-1.0 or 0%-100%), such as exact-looking
+    /// variations already handled elsewhere
+    #[arg(long, value_name = "THRESHOLD")]
+    max_similarity: Option<String>,
+
+    /// Keep only results within a similarity range, either bound optional (e.g. 0.3..0.6,
+    /// 30%..60%, ..80%); replaces --min-similarity and --max-similarity
+    #[arg(
+        long,
+        value_name = "MIN..MAX",
+        conflicts_with_all = ["min_similarity", "max_similarity"]
+    )]
+    similarity_range: Option<String>,
+
+    /// Deprecated: similarity scores are always calculated and shown; accepted so
+    /// existing invocations keep working
+    #[arg(long, hide = true)]
     similarity: bool,
 
-    /// Filter results to minimum similarity threshold (0.0-1.0 or 0%-100%)
-    #[arg(long, value_name = "THRESHOLD", default_value = "50%")]
-    min_similarity: Option<String>,
+    /// How similarity is scored; containment also credits names that contain the domain
+    /// whole, such as combosquats and prefixed or suffixed names
+    #[arg(long, value_enum, default_value = "standard")]
+    scoring: ScoringMode,
+
+    /// Fixed batch size for streaming domain checking (domains processed per batch); by
+    /// default the size adapts to how long batches take to check
+    #[arg(long, value_name = "SIZE")]
+    batch_size: Option<usize>,
+
+    /// Seed for combo mode's random chains, making the candidates generated reproducible
+    #[arg(long, value_name = "N")]
+    seed: Option<u64>,
+
+    /// Write each combo batch in score order rather than as lookups complete, with a fixed
+    /// batch size, so seeded runs produce identical output
+    #[arg(long)]
+    ordered: bool,
+
+    /// Output format (default: table on a terminal, csv when piped)
+    #[arg(long, value_enum)]
+    format: Option<OutputFormat>,
+
+    /// Disable colored output (also honored via the NO_COLOR environment variable)
+    #[arg(long)]
+    no_color: bool,
+
+    /// How progress is reported on stderr
+    #[arg(long, value_enum, value_name = "FORMAT", default_value = "bar")]
+    progress_format: ProgressMode,
+
+    /// Same as --progress-format json
+    #[arg(long, hide = true)]
+    progress_json: bool,
+
+    /// Write results to FILE instead of stdout; the file is replaced atomically when the run completes
+    #[arg(long, short = 'o', value_name = "FILE")]
+    output: Option<PathBuf>,
+
+    /// Skip domains already recorded in a JSON Lines results FILE and append only new findings to it
+    #[arg(long, value_name = "FILE", conflicts_with = "output")]
+    append: Option<PathBuf>,
+
+    /// Label every JSON Lines record with KEY=VALUE in its `tags` object, such as
+    /// engagement=acme-q3 (repeatable)
+    #[arg(long, value_name = "KEY=VALUE", value_parser = parse_tag)]
+    tag: Vec<(String, String)>,
+
+    /// Send each result to a collector instead of stdout
+    #[arg(long, value_enum, conflicts_with_all = ["output", "append"])]
+    sink: Option<Sink>,
+
+    /// Collector for --sink syslog: udp://HOST[:PORT], tcp://HOST[:PORT], or tls://HOST[:PORT]
+    #[arg(long, value_name = "URL", default_value = "udp://localhost:514")]
+    syslog_server: String,
+
+    /// Kafka bootstrap servers for --sink kafka, as comma-separated HOST:PORT
+    #[cfg(feature = "kafka")]
+    #[arg(long, value_name = "LIST", required_if_eq("sink", "kafka"))]
+    brokers: Option<String>,
+
+    /// Kafka topic for --sink kafka
+    #[cfg(feature = "kafka")]
+    #[arg(long, default_value = domfuzz::kafka::DEFAULT_TOPIC)]
+    topic: String,
+
+    /// Upload the result file and screenshots to s3://BUCKET/PREFIX/ when the run completes,
+    /// with AWS credentials from the environment or the AWS config files
+    #[cfg(feature = "s3")]
+    #[arg(long, value_name = "URL")]
+    upload: Option<String>,
+
+    /// Also write the available variations to FILE, each registrable domain once, for a
+    /// registrar's bulk registration tools (implies --check-status)
+    #[arg(long, value_name = "FILE", conflicts_with = "only_registered")]
+    purchase_list: Option<PathBuf>,
+
+    /// Format of --purchase-list: one domain per line, `domain,years` CSV, JSON, or EPP
+    /// create commands
+    #[arg(
+        long,
+        value_enum,
+        value_name = "FORMAT",
+        default_value = "csv",
+        requires = "purchase_list"
+    )]
+    purchase_format: PurchaseFormat,
+
+    /// Registration period written to --purchase-list, in years
+    #[arg(
+        long,
+        value_name = "YEARS",
+        default_value_t = 1,
+        requires = "purchase_list"
+    )]
+    purchase_years: u32,
+
+    /// Shell command run for each result with its JSON record on stdin: exit status 0 keeps the
+    /// result, and a JSON object printed on stdout adds its fields (JSON Lines output). Run with
+    /// `sh -c`, so a POSIX shell must be on the PATH
+    #[arg(long, value_name = "CMD")]
+    post_filter: Option<String>,
+
+    /// Also write the visual and cognitive components of each score: VISUAL and COGNITIVE table
+    /// columns, `visual_score` and `cognitive_score` fields, or extra CSV, CEF, LEEF, and Parquet
+    /// values
+    #[arg(long)]
+    score_columns: bool,
+
+    /// Also write the chance that someone typing the domain by hand types each variation
+    /// instead: a TYPO table column, a `typo_likelihood` field, or an extra CSV, CEF, LEEF, and
+    /// Parquet value
+    #[arg(long)]
+    typo_likelihood: bool,
+
+    /// Resolvers (comma-separated IPs, at least two) that must all agree before a domain is
+    /// reported available; disagreements are reported as `inconsistent`
+    #[arg(long, value_name = "IPS", value_delimiter = ',')]
+    resolvers: Vec<std::net::IpAddr>,
+
+    /// Network profile from the config file (`[profile.NAME]`) bundling timeouts, retries,
+    /// and concurrency limits
+    #[arg(long, value_name = "NAME")]
+    profile: Option<String>,
+
+    /// User-Agent sent with RDAP and HTTP requests; some parking providers serve
+    /// different content to unfamiliar agents
+    #[arg(long, value_name = "UA")]
+    user_agent: Option<String>,
+
+    /// Extra header sent with RDAP and HTTP requests, as `Name: value` (repeatable)
+    #[arg(long, value_name = "HEADER", value_parser = parse_header)]
+    header: Vec<(String, String)>,
+
+    /// Pace requests to the variations' own web servers, ask with HEAD before each GET, and read
+    /// at most --max-body-size of any page, so content checks do not look like scraping
+    #[arg(long)]
+    polite: bool,
+
+    /// Most requests per second to all sites together under --polite
+    #[arg(long, value_name = "N", default_value_t = polite::DEFAULT_RATE, requires = "polite")]
+    polite_rate: f64,
+
+    /// Least time between requests to the same host under --polite, such as 2s or 1m
+    #[arg(
+        long,
+        value_name = "DURATION",
+        default_value = "2s",
+        value_parser = parse_duration,
+        requires = "polite"
+    )]
+    polite_host_interval: Duration,
+
+    /// Most bytes of a page read under --polite, such as 65536, 256k, or 1m; larger pages are
+    /// skipped when the server says so up front, and cut short otherwise
+    #[arg(
+        long,
+        value_name = "BYTES",
+        default_value = "256k",
+        value_parser = parse_byte_size,
+        requires = "polite"
+    )]
+    max_body_size: usize,
+
+    /// Etiquette for running from shared infrastructure: at most two lookups in flight per
+    /// registry, jittered pacing of each, long retry waits, --polite site probing, and a status
+    /// cache on disk answering repeat lookups for 24 hours
+    #[arg(long)]
+    public_mode: bool,
+
+    /// Status cache of --public-mode (default: $XDG_CACHE_HOME/domfuzz/status-cache.json)
+    #[arg(long, value_name = "FILE", requires = "public_mode")]
+    status_cache: Option<PathBuf>,
+
+    /// Extra WHOIS classification rules (`TLD VERDICT PATTERN` per line), tried before the
+    /// built-in ones
+    #[arg(long, value_name = "FILE")]
+    whois_rules: Option<PathBuf>,
+
+    /// Extra sinkhole and placeholder addresses (`VERDICT NETWORK` per line), tried before the
+    /// built-in ones
+    #[arg(long, value_name = "FILE")]
+    sinkholes: Option<PathBuf>,
+
+    /// Print the run's wall-clock time, the requests it sent per protocol and endpoint, and
+    /// the lookup concurrency chosen for each RDAP/WHOIS endpoint when the run ends
+    #[arg(long)]
+    stats: bool,
+
+    /// Write the same statistics as a JSON object to FILE when the run ends
+    #[arg(long, value_name = "FILE")]
+    stats_json: Option<PathBuf>,
+
+    /// List other domains hosted on the IPs of registered variations (JSON Lines output;
+    /// implies --check-status)
+    #[arg(long)]
+    reverse_ip: bool,
+
+    /// Look up the reputation of registered variations' IPs with the providers configured
+    /// in the config file (JSON Lines output; implies --check-status)
+    #[arg(long)]
+    reputation: bool,
+
+    /// Look registered variations up in Google Safe Browsing, or Web Risk, with the API key
+    /// configured in the config file, and flag those already listed as threats (JSON Lines
+    /// output; implies --check-status)
+    #[arg(long)]
+    safe_browsing: bool,
+
+    /// Identify the hosting, CDN, or parking provider of registered variations from ASN,
+    /// name servers, and HTTP headers (JSON Lines output; implies --check-status)
+    #[arg(long)]
+    fingerprint: bool,
+
+    /// Fetch registered variations' landing pages and flag password forms that post
+    /// off-site or mention the brand (JSON Lines output; implies --check-status)
+    #[arg(long)]
+    credential_forms: bool,
+
+    /// Look for marketplace listings (Sedo, Dan, Afternic) and sale notices on registered
+    /// variations' landing pages, report those as `for-sale`, and write the asking price
+    /// where the page shows one (price in JSON Lines output; implies --check-status)
+    #[arg(long)]
+    for_sale: bool,
+
+    /// Retrieve security.txt and robots.txt from registered variations and flag those
+    /// listing the original's own security contacts (JSON Lines output; implies --check-status)
+    #[arg(long)]
+    policy_files: bool,
+
+    /// Connect to port 25 of registered variations' preferred MX host and record its banner
+    /// and EHLO response; no mail is sent (JSON Lines output; implies --check-status)
+    #[arg(long)]
+    smtp_probe: bool,
+
+    /// Test whether registered variations accept connections over IPv4, IPv6, or both
+    /// (JSON Lines output; implies --check-status)
+    #[arg(long)]
+    reachability: bool,
 
-    /// Batch size for streaming domain checking (domains processed per batch)
-    #[arg(long, value_name = "SIZE", default_value = "20")]
-    batch_size: usize,
+    /// Ask the Wayback Machine when registered variations' homepages were first and last
+    /// captured serving content, and flag those never captured (JSON Lines output; implies
+    /// --check-status)
+    #[arg(long)]
+    wayback: bool,
+
+    /// Capture screenshots of registered variations into DIR with a headless Chromium and
+    /// flag near-copies of the original site (JSON Lines output; implies --check-status)
+    #[cfg(feature = "screenshots")]
+    #[arg(long, value_name = "DIR")]
+    screenshots: Option<PathBuf>,
+
+    /// Largest perceptual-hash distance (of 64 bits) reported as a probable clone
+    #[cfg(feature = "screenshots")]
+    #[arg(
+        long,
+        value_name = "BITS",
+        default_value_t = domfuzz::screenshot::DEFAULT_CLONE_DISTANCE,
+        requires = "screenshots"
+    )]
+    clone_distance: u32,
+
+    /// Configuration FILE [default: ~/.config/domfuzz/config.toml]
+    #[arg(long, value_name = "FILE")]
+    config: Option<PathBuf>,
+
+    /// YAML target profile of the brand: domains, aliases, owned registrations, keywords,
+    /// priority TLDs, and thresholds; command-line options take precedence
+    #[arg(long, value_name = "FILE")]
+    target: Option<PathBuf>,
 }
 
-/// Parse similarity threshold from string, supporting both decimal (0.0-1.0) and percentage (0%-100%) formats
-fn parse_similarity_threshold(input: &str) -> Result<f64, String> {
-    let input = input.trim();
+#[derive(clap::Subcommand)]
+enum Command {
+    /// Measure per-transformation generation time and per-protocol check latency
+    Profile(ProfileArgs),
+    /// Re-check variations every interval and stream changes as NDJSON events
+    Monitor(Box<MonitorArgs>),
+    /// Take a suspicious domain apart character by character: confusables, scripts, skeleton
+    Analyze(AnalyzeArgs),
+    /// Print every similarity metric between two domains, for tuning thresholds and rules
+    Compare(CompareArgs),
+    /// Report which squats seen in the wild the transformations would have generated
+    Coverage(CoverageArgs),
+    /// List the transformations and the bundles `-t` accepts, including the config file's
+    Transformations(TransformationsArgs),
+    /// Render a JSON Lines results file as an HTML or Markdown report
+    #[cfg(feature = "reports")]
+    Report(ReportArgs),
+    /// Draft an abuse report email about a registered variation to its registrar and host
+    ReportAbuse(ReportAbuseArgs),
+    /// Record an analyst's disposition or note on a variation in a results or events file
+    Annotate(AnnotateArgs),
+    /// Print the JSON Schema of `--format jsonl` records
+    Schema,
+    /// Serve generation and checking as an HTTP/JSON API
+    #[cfg(feature = "server")]
+    Serve(ServeArgs),
+    /// Serve generation and checking as a streaming gRPC service
+    #[cfg(feature = "grpc")]
+    Grpc(GrpcArgs),
+}
 
-    if input.ends_with('%') {
-        // Parse percentage format (e.g., "73.28%")
-        let percentage_str = input.trim_end_matches('%');
-        match percentage_str.parse::<f64>() {
-            Ok(percentage) => {
-                if !(0.0..=100.0).contains(&percentage) {
-                    Err(format!(
-                        "Percentage must be between 0% and 100%, got: {}%",
-                        percentage
-                    ))
-                } else {
-                    Ok(percentage / 100.0)
-                }
-            }
-            Err(_) => Err(format!("Invalid percentage format: {}", input)),
-        }
-    } else {
-        // Parse decimal format (e.g., "0.7328")
-        match input.parse::<f64>() {
-            Ok(decimal) => {
-                if !(0.0..=1.0).contains(&decimal) {
-                    Err(format!(
-                        "Decimal threshold must be between 0.0 and 1.0, got: {}",
-                        decimal
-                    ))
-                } else {
-                    Ok(decimal)
-                }
-            }
-            Err(_) => Err(format!("Invalid similarity threshold format: {}", input)),
-        }
-    }
+#[cfg(feature = "grpc")]
+#[derive(clap::Args)]
+struct GrpcArgs {
+    /// Address to listen on
+    #[arg(long, value_name = "ADDR", default_value = "127.0.0.1:50051")]
+    listen: std::net::SocketAddr,
+}
+
+#[cfg(feature = "server")]
+#[derive(clap::Args)]
+struct ServeArgs {
+    /// Address to listen on
+    #[arg(long, value_name = "ADDR", default_value = "127.0.0.1:8080")]
+    listen: std::net::SocketAddr,
 }
 
-fn parse_transformations(transformations: &[String]) -> std::collections::HashSet<String> {
-    let mut enabled = std::collections::HashSet::new();
+/// Inputs to the generators, shared by the main command and `profile`
+#[derive(clap::Args)]
+struct GeneratorArgs {
+    /// Path to dictionary file for combosquatting
+    #[arg(long)]
+    dictionary: Option<String>,
 
-    // If no transformations specified, use lookalike bundle by default
-    if transformations.is_empty() {
-        enabled.insert("lookalike".to_string());
-    } else {
-        for transformation in transformations {
-            enabled.insert(transformation.to_lowercase());
-        }
-    }
+    /// TLDs tried by tld-variations: popular (29 common TLDs), full (every delegated TLD), or a
+    /// file in IANA's tlds-alpha-by-domain.txt format
+    #[arg(long, value_name = "SET", default_value = "popular")]
+    tld_set: String,
 
-    // Handle transformation bundles
-    if enabled.contains("lookalike") {
-        enabled.remove("lookalike");
-        // Lookalike bundle: character-level transformations that create visually similar domains
-        enabled.insert("1337speak".to_string());
-        enabled.insert("1337speak".to_string());
-        enabled.insert("misspelling".to_string());
-        enabled.insert("fat-finger".to_string());
-        enabled.insert("mixed-encodings".to_string());
+    /// File of user-defined rules (replace, regex, insert) run as the `custom` transformation
+    #[arg(long, value_name = "FILE")]
+    rules: Option<PathBuf>,
 
-        enabled.insert("fat-finger".to_string());
-        // Character-level additions
-    }
+    /// Rhai script defining generate(domain, tld), run as the `script` transformation
+    #[cfg(feature = "scripting")]
+    #[arg(long, value_name = "FILE")]
+    script: Option<PathBuf>,
 
-    // Handle system-fault bundle
-    if enabled.contains("system-fault") {
-        enabled.remove("system-fault");
-        // System-fault bundle: errors caused by hardware/system failures
-        enabled.insert("bitsquatting".to_string());
-    }
+    /// How closely keyboard errors follow real typing: high keeps only the 3 likeliest fat-finger
+    /// errors per position, favoring same-finger and same-hand slips
+    #[arg(long, value_enum, default_value = "standard")]
+    realism: Realism,
+
+    /// Languages (comma-separated: en, de, fr, es) whose words the cognitive, homophones, and
+    /// singular-plural transformations replace
+    #[arg(long, value_enum, value_delimiter = ',', default_value = "en")]
+    language: Vec<Language>,
+
+    /// Drop internationalized variations the TLD's registry would reject under its IDN policy
+    /// (scripts it does not take, or several scripts in one label)
+    #[arg(long)]
+    respect_idn_policies: bool,
+
+    /// Fetch the original domain's homepage and feed its most prominent words (product and
+    /// service names from the title, headings, and navigation) to combosquatting and cognitive
+    #[arg(long)]
+    site_keywords: bool,
+}
+
+#[derive(clap::Args)]
+struct ProfileArgs {
+    /// Domain to profile
+    domain: String,
 
-    // If "all" is specified, add all transformation names
-    if enabled.contains("all") {
-        enabled.clear();
-        // Basic Typos
-        enabled.insert("1337speak".to_string());
-        enabled.insert("misspelling".to_string());
+    /// Transformations to profile (comma-separated, same names and bundles as the main command)
+    #[arg(long, short = 't', value_delimiter = ',')]
+    transformation: Vec<String>,
 
-        enabled.insert("fat-finger".to_string());
+    #[command(flatten)]
+    generation: GeneratorArgs,
 
-        // Character Manipulation
-        enabled.insert("bitsquatting".to_string());
+    /// Also measure per-protocol status check latency (requires network)
+    #[arg(long, short = 's')]
+    check_status: bool,
 
-        enabled.insert("fat-finger".to_string());
+    /// Number of generated candidates to sample for check latency measurements
+    #[arg(long, value_name = "N", default_value = "10")]
+    samples: usize,
 
-        // Unicode/Script
-        enabled.insert("mixed-encodings".to_string());
+    /// Configuration FILE, for its bundles [default: ~/.config/domfuzz/config.toml]
+    #[arg(long, value_name = "FILE")]
+    config: Option<PathBuf>,
+}
 
-        // Phonetic/Semantic
-        enabled.insert("homophones".to_string());
+#[cfg(feature = "reports")]
+#[derive(clap::Args)]
+struct ReportArgs {
+    /// JSON Lines results file (`--format jsonl`, `--append`), compressed or not
+    file: PathBuf,
 
-        enabled.insert("cognitive".to_string());
-        enabled.insert("singular-plural".to_string());
+    /// Domain the results are variations of, named in the title
+    #[arg(long, value_name = "DOMAIN")]
+    against: Option<String>,
 
-        // Number/Word Substitution
-        enabled.insert("cardinal-substitution".to_string());
-        enabled.insert("ordinal-substitution".to_string());
+    /// Report format [default: from --output's extension, or html]
+    #[arg(long, value_enum)]
+    format: Option<ReportFormat>,
 
-        // Structure Manipulation
-        enabled.insert("word-swap".to_string());
-        enabled.insert("hyphenation".to_string());
+    /// Directory of templates (`report.html`, `report.md`) replacing the built-in ones
+    #[arg(long, value_name = "DIR")]
+    templates: Option<PathBuf>,
 
-        enabled.insert("subdomain".to_string());
-        enabled.insert("dot-insertion".to_string());
-        enabled.insert("dot-omission".to_string());
-        enabled.insert("dot-hyphen-sub".to_string());
+    /// Write the report to FILE [default: stdout]
+    #[arg(long, short = 'o', value_name = "FILE")]
+    output: Option<PathBuf>,
+}
 
-        // Domain Extensions
-        enabled.insert("tld-variations".to_string());
-        enabled.insert("intl-tld".to_string());
-        enabled.insert("wrong-sld".to_string());
-        enabled.insert("combosquatting".to_string());
-        enabled.insert("brand-confusion".to_string());
-        enabled.insert("domain-prefix".to_string());
-        enabled.insert("domain-suffix".to_string());
-    }
+#[derive(clap::Args)]
+struct AnalyzeArgs {
+    /// Suspicious domain, in Unicode or punycode form
+    domain: String,
+
+    /// Brand domain the suspicious one may imitate
+    #[arg(long, value_name = "DOMAIN")]
+    against: String,
+
+    /// Print the analysis as a JSON object
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(clap::Args)]
+struct CompareArgs {
+    /// Domain compared against, such as the brand's
+    first: String,
+
+    /// Domain compared to it
+    second: String,
+
+    /// Print the metrics as a JSON object
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(clap::Args)]
+struct CoverageArgs {
+    /// Domain the squats imitate
+    domain: String,
+
+    /// Squat domains observed in the wild, one per line; `#` starts a comment
+    file: PathBuf,
+
+    /// Transformations to measure (comma-separated, same names and bundles as the main command) [default: all]
+    #[arg(long, short = 't', value_delimiter = ',')]
+    transformation: Vec<String>,
+
+    #[command(flatten)]
+    generation: GeneratorArgs,
+
+    /// Configuration FILE, for its bundles [default: ~/.config/domfuzz/config.toml]
+    #[arg(long, value_name = "FILE")]
+    config: Option<PathBuf>,
+
+    /// Print the report as a JSON object
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(clap::Args)]
+struct TransformationsArgs {
+    /// Configuration FILE, for its bundles [default: ~/.config/domfuzz/config.toml]
+    #[arg(long, value_name = "FILE")]
+    config: Option<PathBuf>,
+
+    /// Print the transformations and bundles as a JSON object
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(clap::Args)]
+struct ReportAbuseArgs {
+    /// Registered variation to report
+    domain: String,
+
+    /// Brand domain the variation imitates
+    #[arg(long, value_name = "DOMAIN")]
+    against: String,
+
+    /// Screenshot attached as evidence (repeatable)
+    #[arg(long, value_name = "FILE")]
+    screenshot: Vec<PathBuf>,
+
+    /// Sender of the report, as `Name <address>`; signs the message and fills its From header
+    #[arg(long, value_name = "SENDER")]
+    from: Option<String>,
+
+    /// Write the draft to FILE [default: abuse-DOMAIN.eml]
+    #[arg(long, short = 'o', value_name = "FILE")]
+    output: Option<PathBuf>,
+}
+
+#[derive(clap::Args)]
+struct AnnotateArgs {
+    /// JSON Lines results file (`--format jsonl`, `--append`) or monitor `--events` file
+    file: PathBuf,
+
+    /// Variation to annotate
+    domain: String,
+
+    /// Triage decision on the variation
+    #[arg(long, value_enum, required_unless_present = "note")]
+    disposition: Option<Disposition>,
+
+    /// Free-text note
+    #[arg(long, value_name = "TEXT")]
+    note: Option<String>,
+
+    /// Who made the annotation [default: $USER]
+    #[arg(long, value_name = "NAME")]
+    analyst: Option<String>,
+}
+
+#[derive(clap::Args)]
+struct MonitorArgs {
+    /// Domain whose variations are monitored [default: the first of --target's domains]
+    #[arg(required_unless_present = "target")]
+    domain: Option<String>,
+
+    /// Another name of the brand whose variations are monitored too (repeatable)
+    #[arg(long, value_name = "NAME")]
+    alias: Vec<String>,
+
+    /// Transformations to monitor (comma-separated, same names and bundles as the main command)
+    #[arg(long, short = 't', value_delimiter = ',')]
+    transformation: Vec<String>,
+
+    #[command(flatten)]
+    generation: GeneratorArgs,
+
+    /// Limit the number of variations monitored, most similar first
+    #[arg(long, short = 'n')]
+    max_variations: Option<usize>,
+
+    /// Monitor only variations at least this similar (0.0-1.0 or 0%-100%) [default: 50%]
+    #[arg(long, value_name = "THRESHOLD")]
+    min_similarity: Option<String>,
+
+    /// Monitor only variations at most this similar (0.0-1.0 or 0%-100%)
+    #[arg(long, value_name = "THRESHOLD")]
+    max_similarity: Option<String>,
+
+    /// How similarity is scored (see the main command's --scoring)
+    #[arg(long, value_enum, default_value = "standard")]
+    scoring: ScoringMode,
+
+    /// Seconds between the starts of consecutive check cycles
+    #[arg(long, value_name = "SECS", default_value_t = DEFAULT_MONITOR_INTERVAL_SECS)]
+    interval: u64,
+
+    /// Stop after N cycles instead of running until interrupted
+    #[arg(long, value_name = "N")]
+    cycles: Option<u64>,
+
+    /// Append events to FILE instead of writing them to stdout; variations annotated
+    /// `false-positive` in it raise no alerts
+    #[arg(long, value_name = "FILE")]
+    events: Option<PathBuf>,
+
+    /// Publish events to a stream instead of writing them to stdout
+    #[cfg(feature = "kafka")]
+    #[arg(long, value_enum, conflicts_with = "events")]
+    sink: Option<EventSink>,
 
-    enabled
+    /// Kafka bootstrap servers for --sink kafka, as comma-separated HOST:PORT
+    #[cfg(feature = "kafka")]
+    #[arg(long, value_name = "LIST", required_if_eq("sink", "kafka"))]
+    brokers: Option<String>,
+
+    /// Kafka topic for --sink kafka
+    #[cfg(feature = "kafka")]
+    #[arg(long, default_value = domfuzz::kafka::DEFAULT_TOPIC)]
+    topic: String,
+
+    /// Label every event with KEY=VALUE in its `tags` object (repeatable)
+    #[arg(long, value_name = "KEY=VALUE", value_parser = parse_tag)]
+    tag: Vec<(String, String)>,
+
+    /// Skip fetching registered variations' TLS certificates (no `cert_observed` events)
+    #[arg(long)]
+    no_certs: bool,
+
+    /// Skip fetching registered variations' landing pages (no `content_changed` events)
+    #[arg(long)]
+    no_content: bool,
+
+    /// Capture registered variations' landing pages into DIR every cycle, keeping each
+    /// capture, and report pages that change (`screenshot_changed` events)
+    #[cfg(feature = "screenshots")]
+    #[arg(long, value_name = "DIR")]
+    screenshots: Option<PathBuf>,
+
+    /// Largest perceptual-hash distance (of 64 bits) between a capture and the previous one
+    /// that still counts as the same page
+    #[cfg(feature = "screenshots")]
+    #[arg(
+        long,
+        value_name = "BITS",
+        default_value_t = domfuzz::screenshot::DEFAULT_CHANGE_DISTANCE,
+        requires = "screenshots"
+    )]
+    change_distance: u32,
+
+    /// Network profile from the config file (`[profile.NAME]`)
+    #[arg(long, value_name = "NAME")]
+    profile: Option<String>,
+
+    /// Configuration FILE [default: ~/.config/domfuzz/config.toml]
+    #[arg(long, value_name = "FILE")]
+    config: Option<PathBuf>,
+
+    /// YAML target profile of the brand (see the main command's --target)
+    #[arg(long, value_name = "FILE")]
+    target: Option<PathBuf>,
 }
 
+/// Least similarity of reported variations when neither the command line nor a
+/// target profile sets one
+const DEFAULT_MIN_SIMILARITY: f64 = 0.5;
+
 #[tokio::main]
 async fn main() {
+    let started = Instant::now();
     let cli = Cli::parse();
 
+    if let Some(command) = &cli.command {
+        match command {
+            Command::Profile(args) => run_profile(args).await,
+            Command::Monitor(args) => run_monitor(args).await,
+            Command::Analyze(args) => run_analyze(args),
+            Command::Compare(args) => run_compare(args),
+            Command::Coverage(args) => run_coverage(args).await,
+            Command::Transformations(args) => run_transformations(args),
+            #[cfg(feature = "reports")]
+            Command::Report(args) => run_report(args),
+            Command::ReportAbuse(args) => run_report_abuse(args).await,
+            Command::Annotate(args) => run_annotate(args).await,
+            Command::Schema => print!("{}", RESULT_SCHEMA),
+            #[cfg(feature = "server")]
+            Command::Serve(args) => {
+                eprintln!("Listening on http://{}", args.listen);
+                domfuzz::server::serve(args.listen)
+                    .await
+                    .unwrap_or_else(|e| exit_with(&e));
+            }
+            #[cfg(feature = "grpc")]
+            Command::Grpc(args) => {
+                eprintln!("gRPC service listening on {}", args.listen);
+                domfuzz::grpc::serve(args.listen)
+                    .await
+                    .unwrap_or_else(|e| exit_with(&e));
+            }
+        }
+        return;
+    }
+
+    let target = cli
+        .target
+        .as_deref()
+        .map(|path| TargetProfile::load(path).unwrap_or_else(|e| exit_with(&e)));
+    let input_domain = cli
+        .domain
+        .as_deref()
+        .or(target.as_ref().map(TargetProfile::domain))
+        .expect("domain is required when no subcommand or target is given");
+    let parsed_domain = Domain::parse(input_domain).unwrap_or_else(|e| exit_with(&e));
+    let one_transformation = cli.one_transformation;
+    let combo_only = [
+        ("--min-plausibility", cli.min_plausibility.is_some()),
+        ("--max-attempts", cli.max_attempts.is_some()),
+        ("--max-runtime", cli.max_runtime.is_some()),
+        ("--seed", cli.seed.is_some()),
+        ("--ordered", cli.ordered),
+    ];
+    let individual_only = [
+        (
+            "--top-per-transformation",
+            cli.top_per_transformation.is_some(),
+        ),
+        ("--collapse-near-duplicates", cli.collapse_near_duplicates),
+    ];
+    let misplaced = if one_transformation {
+        combo_only.iter().find(|(_, given)| *given)
+    } else {
+        individual_only.iter().find(|(_, given)| *given)
+    };
+    if let Some((flag, _)) = misplaced {
+        exit_with(&DomFuzzError::Config(format!(
+            "{} cannot be used with --one-transformation={}",
+            flag, one_transformation
+        )));
+    }
+
     if cli.verbose {
-        eprintln!("DomFuzz starting with domain: {}", cli.domain);
-        if cli.one_transformation {
+        eprintln!("DomFuzz starting with domain: {}", input_domain);
+        if one_transformation {
             eprintln!("Mode: One transformation per domain");
         } else {
             eprintln!("Mode: Combo transformations (default)");
@@ -284,30 +967,210 @@ async fn main() {
         }
     }
 
-    // --only-registered or --only-available implies --check-status
-    let check_status = cli.check_status || cli.only_registered || cli.only_available;
+    let file_config = Config::load(cli.config.as_deref()).unwrap_or_else(|e| exit_with(&e));
+    let enrich_options = EnrichOptions {
+        reverse_ip: cli.reverse_ip,
+        reputation: if cli.reputation {
+            file_config.reputation_providers()
+        } else {
+            Vec::new()
+        },
+        threat_list: if cli.safe_browsing {
+            file_config.threat_list()
+        } else {
+            None
+        },
+        fingerprint: cli.fingerprint,
+        credential_forms: cli.credential_forms,
+        for_sale: cli.for_sale,
+        brand_keywords: brand_keywords(input_domain, target.as_ref()),
+        policy_files: cli.policy_files,
+        original_domain: input_domain.to_string(),
+        smtp_probe: cli.smtp_probe,
+        reachability: cli.reachability,
+        wayback: cli.wayback,
+        #[cfg(feature = "screenshots")]
+        screenshots: match &cli.screenshots {
+            Some(dir) => Some(
+                domfuzz::screenshot::ScreenshotOptions::prepare(
+                    dir,
+                    input_domain,
+                    cli.clone_distance,
+                )
+                .await
+                .unwrap_or_else(|e| exit_with(&e)),
+            ),
+            None => None,
+        },
+    };
+    if cli.safe_browsing && enrich_options.threat_list.is_none() {
+        exit_with(&DomFuzzError::Config(
+            "--safe-browsing requires a [safe_browsing] api_key in the config file".to_string(),
+        ));
+    }
+    if cli.reputation && enrich_options.reputation.is_empty() {
+        exit_with(&DomFuzzError::Config(
+            "--reputation requires a [reputation] provider in the config file".to_string(),
+        ));
+    }
+    // --only-registered, --only-available, --purchase-list, or any enrichment implies
+    // --check-status
+    let check_status = cli.check_status
+        || cli.only_registered
+        || cli.only_available
+        || cli.purchase_list.is_some()
+        || enrich_options.is_enabled();
+    let public_mode = cli.public_mode.then(|| {
+        let cache_path = cli
+            .status_cache
+            .clone()
+            .or_else(public::default_cache_path)
+            .unwrap_or_else(|| {
+                exit_with(&DomFuzzError::Config(
+                    "--public-mode needs a status cache: set XDG_CACHE_HOME or HOME, or give \
+                     --status-cache"
+                        .to_string(),
+                ))
+            });
+        PublicMode::new(cache_path)
+    });
+    let mut network = file_config
+        .network_settings(cli.profile.as_deref())
+        .unwrap_or_else(|e| exit_with(&e));
+    if let Some(public_mode) = &public_mode {
+        public_mode.restrain(&mut network);
+    }
+    checker::configure(CheckOptions {
+        consensus_resolvers: cli.resolvers.clone(),
+        user_agent: cli.user_agent.clone(),
+        headers: cli.header.clone(),
+        network,
+        whois_rules: match &cli.whois_rules {
+            Some(path) => load_whois_rules(path).unwrap_or_else(|e| exit_with(&e)),
+            None => WhoisRules::default(),
+        },
+        sinkholes: match &cli.sinkholes {
+            Some(path) => load_sinkholes(path).unwrap_or_else(|e| exit_with(&e)),
+            None => AddressRules::default(),
+        },
+        polite: (cli.polite || cli.public_mode).then_some(Politeness {
+            rate: cli.polite_rate,
+            host_interval: cli.polite_host_interval,
+            max_body_bytes: cli.max_body_size,
+        }),
+        public: public_mode,
+    })
+    .unwrap_or_else(|e| exit_with(&e));
+    let mut known_domains = match &cli.append {
+        Some(path) => load_known_domains(path).unwrap_or_else(|e| exit_with(&e)),
+        None => HashSet::new(),
+    };
+    if cli.verbose && cli.append.is_some() {
+        eprintln!(
+            "Skipping {} domains already in the results file",
+            known_domains.len()
+        );
+    }
+    // Defensive registrations are the brand's own
+    if let Some(target) = &target {
+        known_domains.extend(target.owned_domains());
+    }
+    let mut output = match &cli.append {
+        Some(path) => OutputWriter::for_append(cli.format, path),
+        None => match cli.sink {
+            Some(Sink::Syslog) => OutputWriter::for_syslog(cli.format, &cli.syslog_server).await,
+            #[cfg(feature = "kafka")]
+            Some(Sink::Kafka) => OutputWriter::for_kafka(
+                cli.format,
+                cli.brokers.as_deref().unwrap_or_default(),
+                &cli.topic,
+            ),
+            None => OutputWriter::from_cli(cli.format, cli.no_color, cli.output.as_deref()),
+        },
+    }
+    .unwrap_or_else(|e| exit_with(&e));
+    #[cfg(feature = "s3")]
+    let uploader = match cli.upload.as_deref() {
+        Some(location) => Some(
+            upload_target(&cli, location)
+                .await
+                .unwrap_or_else(|e| exit_with(&e)),
+        ),
+        None => None,
+    };
+    output.post_filter = cli.post_filter.clone().map(PostFilter);
+    output.score_columns = cli.score_columns;
+    output.typo_likelihood = cli.typo_likelihood;
+    output.original_domain = input_domain.to_string();
+    output.tags = annotate::tags_json(&cli.tag);
+    if cli.purchase_list.is_some() {
+        output.purchases = Some(
+            PurchaseList::new(&parsed_domain, cli.purchase_years).unwrap_or_else(|e| exit_with(&e)),
+        );
+    }
+    let progress_mode = if cli.progress_json {
+        ProgressMode::Json
+    } else {
+        cli.progress_format
+    };
+    let (parsed_min_similarity, parsed_max_similarity) = match cli.similarity_range.as_deref() {
+        Some(range) => parse_similarity_range(range).unwrap_or_else(|e| exit_with(&e)),
+        None => {
+            let parse = |sim_str: &str| {
+                parse_similarity_threshold(sim_str).unwrap_or_else(|e| exit_with(&e))
+            };
+            let thresholds = target
+                .as_ref()
+                .map(|target| target.thresholds.clone())
+                .unwrap_or_default();
+            let min = cli
+                .min_similarity
+                .as_deref()
+                .map(parse)
+                .or(thresholds.min_similarity)
+                .or(Some(DEFAULT_MIN_SIMILARITY));
+            let max = cli
+                .max_similarity
+                .as_deref()
+                .map(parse)
+                .or(thresholds.max_similarity);
+            check_similarity_range(min, max).unwrap_or_else(|e| exit_with(&e));
+            (min, max)
+        }
+    };
 
-    let (domain_name, tld) = parse_domain(&cli.domain);
-    let original_registrable_domain = extract_registrable_domain(&cli.domain);
+    let (domain_name, tld) = (parsed_domain.name(), parsed_domain.suffix().to_string());
+    let original_registrable_domain = parsed_domain.registrable();
+    let alias_names: Vec<String> = target
+        .iter()
+        .flat_map(TargetProfile::seeds)
+        .chain(cli.alias.iter().cloned())
+        .collect();
+    let aliases = parse_aliases(&alias_names, &parsed_domain).unwrap_or_else(|e| exit_with(&e));
     let mut variations = HashSet::new();
     let mut variation_sources: std::collections::HashMap<String, String> =
         std::collections::HashMap::new();
+    // Aliases each variation came from; the original domain's are left out
+    let mut variation_seeds: HashMap<String, String> = HashMap::new();
 
     // Parse enabled transformations
-    let enabled_transformations = parse_transformations(&cli.transformation);
+    let mut enabled_transformations = file_config
+        .transformations(&cli.transformation)
+        .unwrap_or_else(|e| exit_with(&e));
+    cli.generation
+        .enable_transformations(&mut enabled_transformations);
 
     // Generate combo transformations and collect results (unified with individual mode)
-    if !cli.one_transformation {
+    if !one_transformation {
         if cli.verbose {
             eprintln!("Starting combo transformation generation...");
         }
-        let dict_words = if let Some(dict_file) = &cli.dictionary {
-            load_dictionary(dict_file)
-        } else {
-            default_dictionary()
-        };
+        let mut generator_options = cli.generation.options(&parsed_domain.to_ascii()).await;
+        if let Some(target) = &target {
+            target.apply(&mut generator_options);
+        }
 
-        // Use unlimited by default for --combo, even with status checking
+        // Use unlimited by default in combo mode, even with status checking
         let combo_limit = cli.max_variations;
         if cli.verbose {
             match combo_limit {
@@ -316,520 +1179,122 @@ async fn main() {
             }
         }
         let output_limit = cli.max_variations.unwrap_or(usize::MAX);
-        let parsed_min_similarity = if let Some(ref sim_str) = cli.min_similarity {
-            match parse_similarity_threshold(sim_str) {
-                Ok(threshold) => Some(threshold),
-                Err(e) => {
-                    eprintln!("Error parsing similarity threshold: {}", e);
-                    std::process::exit(1);
-                }
-            }
-        } else {
-            None
-        };
-        let config = ComboConfig {
-            domain: &domain_name,
-            tld: &tld,
+        if !(cli.dedupe_fp_rate > 0.0 && cli.dedupe_fp_rate < 1.0) {
+            exit_with(&DomFuzzError::Config(format!(
+                "--dedupe-fp-rate must be between 0 and 1 (exclusive), got: {}",
+                cli.dedupe_fp_rate
+            )));
+        }
+        if cli.combo_depth < 2 {
+            exit_with(&DomFuzzError::Config(format!(
+                "--combo-depth must be at least 2, got: {}",
+                cli.combo_depth
+            )));
+        }
+        let generation = ComboGeneration {
+            domain: parsed_domain.clone(),
             max_variations: combo_limit,
             verbose: cli.verbose,
+            enabled_transformations: enabled_transformations.clone(),
+            generator_options,
+            min_similarity: parsed_min_similarity,
+            max_similarity: parsed_max_similarity,
+            scoring: cli.scoring,
+            known_domains,
+            dedupe: cli.dedupe,
+            dedupe_fp_rate: cli.dedupe_fp_rate,
+            mode: cli.combo_mode,
+            max_depth: cli.combo_depth,
+            seed: cli.seed,
+            max_attempts: cli.max_attempts,
+            max_runtime: cli.max_runtime,
+            min_plausibility: cli.min_plausibility.as_deref().map(|threshold| {
+                parse_similarity_threshold(threshold).unwrap_or_else(|e| exit_with(&e))
+            }),
+            aliases: aliases.clone(),
+        };
+        let original_domain = format!("{}.{}", domain_name, tld);
+        let config = ComboConfig {
+            original_domain: &original_domain,
             only_registered: cli.only_registered,
             only_available: cli.only_available,
             output_count: output_limit,
             check_status,
-            enabled_transformations: &enabled_transformations,
-            min_similarity: parsed_min_similarity,
             batch_size: cli.batch_size,
+            ordered: cli.ordered,
+            progress: progress_mode,
+            enrich: &enrich_options,
         };
-        generate_combo_attacks_streaming(&config, &dict_words).await;
+        generate_combo_attacks_streaming(generation, &config, &mut output)
+            .await
+            .unwrap_or_else(|e| exit_with(&e));
         // Combo mode now handles its own output and status checking
+        write_purchase_list(&cli, &mut output);
+        output.finish().await.unwrap_or_else(|e| exit_with(&e));
+        #[cfg(feature = "s3")]
+        if let Some(uploader) = &uploader {
+            upload_artifacts(uploader, &cli).await;
+        }
+        save_status_cache();
+        report_stats(&cli, started.elapsed());
         return;
     }
 
-    if enabled_transformations.contains("1337speak") {
-        if cli.verbose {
-            eprintln!("Running character substitution transformation...");
-        }
-        let results = filter_valid_domains(generate_1337speak(&domain_name, &tld));
-        if cli.verbose {
-            eprintln!("  Generated {} 1337speak variations", results.len());
-        }
-        for result in &results {
-            variation_sources
-                .entry(result.clone())
-                .or_insert("1337speak".to_string());
-        }
-        variations.extend(results);
-    }
-
-    if enabled_transformations.contains("misspelling") {
-        if cli.verbose {
-            eprintln!("Running misspellings transformation...");
-        }
-        let results = filter_valid_domains(generate_misspelling(&domain_name, &tld));
-        if cli.verbose {
-            eprintln!("  Generated {} misspellings variations", results.len());
-        }
-        for result in &results {
-            variation_sources
-                .entry(result.clone())
-                .or_insert("misspelling".to_string());
-        }
-        variations.extend(results);
+    let mut generator_options = cli.generation.options(&parsed_domain.to_ascii()).await;
+    if let Some(target) = &target {
+        target.apply(&mut generator_options);
     }
-
-    if enabled_transformations.contains("mixed-encodings") {
-        if cli.verbose {
-            eprintln!("Running homoglyphs transformation...");
-        }
-        let results = filter_valid_domains(generate_mixed_encodings(&domain_name, &tld));
-        if cli.verbose {
-            eprintln!("  Generated {} homoglyphs variations", results.len());
-        }
-        for result in &results {
-            variation_sources
-                .entry(result.clone())
-                .or_insert("mixed-encodings".to_string());
+    let transformation_count = transformation_generators(&generator_options)
+        .iter()
+        .filter(|(name, _)| enabled_transformations.contains(*name))
+        .count();
+    let generation_progress = PhaseProgress::new(
+        "generate",
+        (transformation_count * (aliases.len() + 1)) as u64,
+        progress_mode,
+    );
+    let seed_domains: HashSet<String> = aliases.iter().map(Domain::to_string).collect();
+    for seed_domain in std::iter::once(&parsed_domain).chain(&aliases) {
+        let generated = generate_variations_with_progress(
+            seed_domain,
+            &enabled_transformations,
+            &generator_options,
+            |name, added| {
+                record_generated(added as u64);
+                generation_progress.advance(false, &format!("{} (+{})", name, added))
+            },
+        );
+        let seed = seed_domain.to_string();
+        for variation in generated {
+            // The first seed to produce a domain is credited with it
+            if seed_domains.contains(&variation.domain) || variations.contains(&variation.domain) {
+                continue;
+            }
+            if seed_domain != &parsed_domain {
+                variation_seeds.insert(variation.domain.clone(), seed.clone());
+            }
+            variation_sources.insert(
+                variation.domain.clone(),
+                variation.transformation.to_string(),
+            );
+            variations.insert(variation.domain);
         }
-        variations.extend(results);
     }
-
-    if enabled_transformations.contains("tld-variations") {
-        if cli.verbose {
-            eprintln!("Running tld-variations transformation...");
-        }
-        let results = filter_valid_domains(generate_tld_variations(&domain_name, &tld));
-        if cli.verbose {
-            eprintln!("  Generated {} tld-variations variations", results.len());
-        }
-        for result in &results {
-            variation_sources
-                .entry(result.clone())
-                .or_insert("tld-variations".to_string());
+    generation_progress.finish("Generation complete");
+    warn_script_error(&generator_options);
+    variations.retain(|domain| !known_domains.contains(domain));
+    if cli.verbose {
+        for (name, _) in transformation_generators(&generator_options) {
+            if enabled_transformations.contains(name) {
+                let count = variation_sources.values().filter(|t| *t == name).count();
+                eprintln!("  Generated {} {} variations", count, name);
+            }
         }
-        variations.extend(results);
     }
 
-    if enabled_transformations.contains("word-swap") {
-        if cli.verbose {
-            eprintln!("Running word-swap transformation...");
-        }
-        let results = filter_valid_domains(generate_word_swaps(&domain_name, &tld));
-        if cli.verbose {
-            eprintln!("  Generated {} word-swap variations", results.len());
-        }
-        for result in &results {
-            variation_sources
-                .entry(result.clone())
-                .or_insert("word-swap".to_string());
-        }
-        variations.extend(results);
-    }
-
-    if enabled_transformations.contains("bitsquatting") {
-        if cli.verbose {
-            eprintln!("Running bitsquatting transformation...");
-        }
-        let results = filter_valid_domains(generate_bitsquatting(&domain_name, &tld));
-        if cli.verbose {
-            eprintln!("  Generated {} bitsquatting variations", results.len());
-        }
-        for result in &results {
-            variation_sources
-                .entry(result.clone())
-                .or_insert("bitsquatting".to_string());
-        }
-        variations.extend(results);
-    }
-
-    if enabled_transformations.contains("fat-finger") {
-        if cli.verbose {
-            eprintln!("Running repetition transformation...");
-        }
-        let results = filter_valid_domains(generate_fat_finger(&domain_name, &tld));
-        if cli.verbose {
-            eprintln!("  Generated {} repetition variations", results.len());
-        }
-        for result in &results {
-            variation_sources
-                .entry(result.clone())
-                .or_insert("fat-finger".to_string());
-        }
-        variations.extend(results);
-    }
-
-    if enabled_transformations.contains("hyphenation") {
-        if cli.verbose {
-            eprintln!("Running hyphenation transformation...");
-        }
-        let results = filter_valid_domains(generate_hyphenation(&domain_name, &tld));
-        if cli.verbose {
-            eprintln!("  Generated {} hyphenation variations", results.len());
-        }
-        for result in &results {
-            variation_sources
-                .entry(result.clone())
-                .or_insert("hyphenation".to_string());
-        }
-        variations.extend(results);
-    }
-
-    if enabled_transformations.contains("subdomain") {
-        if cli.verbose {
-            eprintln!("Running subdomain transformation...");
-        }
-        let results = filter_valid_domains(generate_subdomain_injection(&domain_name, &tld));
-        if cli.verbose {
-            eprintln!("  Generated {} subdomain variations", results.len());
-        }
-        for result in &results {
-            variation_sources
-                .entry(result.clone())
-                .or_insert("subdomain".to_string());
-        }
-        variations.extend(results);
-    }
-
-    if enabled_transformations.contains("combosquatting") {
-        if cli.verbose {
-            eprintln!("Running combosquatting transformation...");
-        }
-        let dict_words = if let Some(dict_file) = &cli.dictionary {
-            load_dictionary(dict_file)
-        } else {
-            default_dictionary()
-        };
-        let results =
-            filter_valid_domains(generate_combosquatting(&domain_name, &tld, &dict_words));
-        if cli.verbose {
-            eprintln!("  Generated {} combosquatting variations", results.len());
-        }
-        for result in &results {
-            variation_sources
-                .entry(result.clone())
-                .or_insert("combosquatting".to_string());
-        }
-        variations.extend(results);
-    }
-
-    if enabled_transformations.contains("mixed-encodings") {
-        if cli.verbose {
-            eprintln!("Running idn-homograph transformation...");
-        }
-        let results = filter_valid_domains(generate_mixed_encodings(&domain_name, &tld));
-        if cli.verbose {
-            eprintln!("  Generated {} idn-homograph variations", results.len());
-        }
-        for result in &results {
-            variation_sources
-                .entry(result.clone())
-                .or_insert("mixed-encodings".to_string());
-        }
-        variations.extend(results);
-    }
-
-    if enabled_transformations.contains("mixed-encodings") {
-        if cli.verbose {
-            eprintln!("Running mixed-script transformation...");
-        }
-        let results = filter_valid_domains(generate_mixed_encodings(&domain_name, &tld));
-        if cli.verbose {
-            eprintln!("  Generated {} mixed-script variations", results.len());
-        }
-        for result in &results {
-            variation_sources
-                .entry(result.clone())
-                .or_insert("mixed-encodings".to_string());
-        }
-        variations.extend(results);
-    }
-
-    if enabled_transformations.contains("mixed-encodings") {
-        if cli.verbose {
-            eprintln!("Running extended-unicode transformation...");
-        }
-        let results = filter_valid_domains(generate_mixed_encodings(&domain_name, &tld));
-        if cli.verbose {
-            eprintln!("  Generated {} extended-unicode variations", results.len());
-        }
-        for result in &results {
-            variation_sources
-                .entry(result.clone())
-                .or_insert("mixed-encodings".to_string());
-        }
-        variations.extend(results);
-    }
-
-    if enabled_transformations.contains("brand-confusion") {
-        if cli.verbose {
-            eprintln!("Running brand-confusion transformation...");
-        }
-        let results = filter_valid_domains(generate_brand_confusion(&domain_name, &tld));
-        if cli.verbose {
-            eprintln!("  Generated {} brand-confusion variations", results.len());
-        }
-        for result in &results {
-            variation_sources
-                .entry(result.clone())
-                .or_insert("brand-confusion".to_string());
-        }
-        variations.extend(results);
-    }
-
-    if enabled_transformations.contains("intl-tld") {
-        if cli.verbose {
-            eprintln!("Running intl-tld transformation...");
-        }
-        let results = filter_valid_domains(generate_intl_tld(&domain_name, &tld));
-        if cli.verbose {
-            eprintln!("  Generated {} intl-tld variations", results.len());
-        }
-        for result in &results {
-            variation_sources
-                .entry(result.clone())
-                .or_insert("intl-tld".to_string());
-        }
-        variations.extend(results);
-    }
-
-    if enabled_transformations.contains("cognitive") {
-        if cli.verbose {
-            eprintln!("Running cognitive transformation...");
-        }
-        let results = filter_valid_domains(generate_cognitive(&domain_name, &tld));
-        if cli.verbose {
-            eprintln!("  Generated {} cognitive variations", results.len());
-        }
-        for result in &results {
-            variation_sources
-                .entry(result.clone())
-                .or_insert("cognitive".to_string());
-        }
-        variations.extend(results);
-    }
-
-    if enabled_transformations.contains("dot-insertion") {
-        if cli.verbose {
-            eprintln!("Running dot-insertion transformation...");
-        }
-        let results = filter_valid_domains(generate_dot_insertion(&domain_name, &tld));
-        if cli.verbose {
-            eprintln!("  Generated {} dot-insertion variations", results.len());
-        }
-        for result in &results {
-            variation_sources
-                .entry(result.clone())
-                .or_insert("dot-insertion".to_string());
-        }
-        variations.extend(results);
-    }
-
-    if enabled_transformations.contains("dot-omission") {
-        if cli.verbose {
-            eprintln!("Running dot-omission transformation...");
-        }
-        let results = filter_valid_domains(generate_dot_omission(&domain_name, &tld));
-        if cli.verbose {
-            eprintln!("  Generated {} dot-omission variations", results.len());
-        }
-        for result in &results {
-            variation_sources
-                .entry(result.clone())
-                .or_insert("dot-omission".to_string());
-        }
-        variations.extend(results);
-    }
-
-    if enabled_transformations.contains("dot-hyphen-sub") {
-        if cli.verbose {
-            eprintln!("Running dot-hyphen-sub transformation...");
-        }
-        let results = filter_valid_domains(generate_dot_hyphen_substitution(&domain_name, &tld));
-        if cli.verbose {
-            eprintln!("  Generated {} dot-hyphen-sub variations", results.len());
-        }
-        for result in &results {
-            variation_sources
-                .entry(result.clone())
-                .or_insert("dot-hyphen-sub".to_string());
-        }
-        variations.extend(results);
-    }
-
-    if enabled_transformations.contains("misspelling") {
-        if cli.verbose {
-            eprintln!("Running double-char-replacement transformation...");
-        }
-        let results = filter_valid_domains(generate_misspelling(&domain_name, &tld));
-        if cli.verbose {
-            eprintln!(
-                "  Generated {} double-char-replacement variations",
-                results.len()
-            );
-        }
-        for result in &results {
-            variation_sources
-                .entry(result.clone())
-                .or_insert("misspelling".to_string());
-        }
-        variations.extend(results);
-    }
-
-    if enabled_transformations.contains("fat-finger") {
-        if cli.verbose {
-            eprintln!("Running fat-finger transformation...");
-        }
-        let results = filter_valid_domains(generate_fat_finger(&domain_name, &tld));
-        if cli.verbose {
-            eprintln!("  Generated {} fat-finger variations", results.len());
-        }
-        for result in &results {
-            variation_sources
-                .entry(result.clone())
-                .or_insert("fat-finger".to_string());
-        }
-        variations.extend(results);
-    }
-
-    if enabled_transformations.contains("cardinal-substitution") {
-        if cli.verbose {
-            eprintln!("Running cardinal-substitution transformation...");
-        }
-        let results = filter_valid_domains(generate_cardinal_substitution(&domain_name, &tld));
-        if cli.verbose {
-            eprintln!(
-                "  Generated {} cardinal-substitution variations",
-                results.len()
-            );
-        }
-        for result in &results {
-            variation_sources
-                .entry(result.clone())
-                .or_insert("cardinal-substitution".to_string());
-        }
-        variations.extend(results);
-    }
-
-    if enabled_transformations.contains("ordinal-substitution") {
-        if cli.verbose {
-            eprintln!("Running ordinal-substitution transformation...");
-        }
-        let results = filter_valid_domains(generate_ordinal_substitution(&domain_name, &tld));
-        if cli.verbose {
-            eprintln!(
-                "  Generated {} ordinal-substitution variations",
-                results.len()
-            );
-        }
-        for result in &results {
-            variation_sources
-                .entry(result.clone())
-                .or_insert("ordinal-substitution".to_string());
-        }
-        variations.extend(results);
-    }
-
-    if enabled_transformations.contains("homophones") {
-        if cli.verbose {
-            eprintln!("Running homophones transformation...");
-        }
-        let results = filter_valid_domains(generate_homophones(&domain_name, &tld));
-        if cli.verbose {
-            eprintln!("  Generated {} homophones variations", results.len());
-        }
-        for result in &results {
-            variation_sources
-                .entry(result.clone())
-                .or_insert("homophones".to_string());
-        }
-        variations.extend(results);
-    }
-
-    if enabled_transformations.contains("singular-plural") {
-        if cli.verbose {
-            eprintln!("Running singular-plural transformation...");
-        }
-        let results = filter_valid_domains(generate_singular_plural(&domain_name, &tld));
-        if cli.verbose {
-            eprintln!("  Generated {} singular-plural variations", results.len());
-        }
-        for result in &results {
-            variation_sources
-                .entry(result.clone())
-                .or_insert("singular-plural".to_string());
-        }
-        variations.extend(results);
-    }
-
-    if enabled_transformations.contains("wrong-sld") {
-        if cli.verbose {
-            eprintln!("Running wrong-sld transformation...");
-        }
-        let results = filter_valid_domains(generate_wrong_sld(&domain_name, &tld));
-        if cli.verbose {
-            eprintln!("  Generated {} wrong-sld variations", results.len());
-        }
-        for result in &results {
-            variation_sources
-                .entry(result.clone())
-                .or_insert("wrong-sld".to_string());
-        }
-        variations.extend(results);
-    }
-
-    if enabled_transformations.contains("domain-prefix") {
-        if cli.verbose {
-            eprintln!("Running domain-prefix transformation...");
-        }
-        let results = filter_valid_domains(generate_domain_prefix(&domain_name, &tld));
-        if cli.verbose {
-            eprintln!("  Generated {} domain-prefix variations", results.len());
-        }
-        for result in &results {
-            variation_sources
-                .entry(result.clone())
-                .or_insert("domain-prefix".to_string());
-        }
-        variations.extend(results);
-    }
-
-    if enabled_transformations.contains("domain-suffix") {
-        if cli.verbose {
-            eprintln!("Running domain-suffix transformation...");
-        }
-        let results = filter_valid_domains(generate_domain_suffix(&domain_name, &tld));
-        if cli.verbose {
-            eprintln!("  Generated {} domain-suffix variations", results.len());
-        }
-        for result in &results {
-            variation_sources
-                .entry(result.clone())
-                .or_insert("domain-suffix".to_string());
-        }
-        variations.extend(results);
-    }
-
-    if enabled_transformations.contains("cyrillic-comprehensive") {
-        if cli.verbose {
-            eprintln!("Running cyrillic-comprehensive transformation...");
-        }
-        let results = filter_valid_domains(generate_mixed_encodings(&domain_name, &tld));
-        if cli.verbose {
-            eprintln!(
-                "  Generated {} cyrillic-comprehensive variations",
-                results.len()
-            );
-        }
-        for result in &results {
-            variation_sources
-                .entry(result.clone())
-                .or_insert("cyrillic-comprehensive".to_string());
-        }
-        variations.extend(results);
-    }
-
-    // Apply exact max_variations limit - generate more if needed to replace invalid ones
-    let mut all_variations: Vec<_> = variations.into_iter().collect();
-    // Note: Will sort by similarity score after calculating scores
+    // Apply exact max_variations limit - generate more if needed to replace invalid ones
+    let mut all_variations: Vec<_> = variations.into_iter().collect();
+    // Note: Will sort by similarity score after calculating scores
 
     let output_count = if let Some(max) = cli.max_variations {
         if all_variations.len() < max {
@@ -858,9 +1323,14 @@ async fn main() {
                 ("misspelling", Box::new(|d, t| generate_misspelling(d, t))),
                 (
                     "tld_variations",
-                    Box::new(|d, t| generate_tld_variations(d, t)),
+                    Box::new(|d, t| generate_tld_variations_from(d, t, &generator_options.tlds)),
+                ),
+                (
+                    "fat-finger",
+                    Box::new(|d, t| {
+                        generate_fat_finger_with_realism(d, t, generator_options.realism)
+                    }),
                 ),
-                ("fat-finger", Box::new(|d, t| generate_fat_finger(d, t))),
                 ("hyphenation", Box::new(|d, t| generate_hyphenation(d, t))),
             ];
 
@@ -887,7 +1357,11 @@ async fn main() {
                 let final_domain = format!("{}.{}", current_domain, current_tld);
                 if final_domain != format!("{}.{}", domain_name, tld)
                     && is_valid_domain(&final_domain)
+                    && (!generator_options.respect_idn_policies
+                        || IdnPolicies::builtin().allows(&final_domain))
                     && !all_variations.contains(&final_domain)
+                    && !seed_domains.contains(&final_domain)
+                    && !known_domains.contains(&final_domain)
                     && !additional_variations.contains(&final_domain)
                 {
                     additional_variations.insert(final_domain);
@@ -910,26 +1384,22 @@ async fn main() {
 
     // Calculate similarity scores for all variations (always needed for output format)
     let mut similarity_scores: Vec<SimilarityScore> = Vec::new();
+    // Variations standing for others that read the same, with --collapse-near-duplicates
+    let mut cluster_sizes: HashMap<String, usize> = HashMap::new();
     {
         let original_domain = format!("{}.{}", domain_name, tld);
         for variation in &all_variations {
             let unknown_type = "unknown".to_string();
             let transformation_type = variation_sources.get(variation).unwrap_or(&unknown_type);
-            let score = calculate_similarity(&original_domain, variation, transformation_type);
-
-            // Apply minimum similarity filter if specified
-            if let Some(ref sim_str) = cli.min_similarity {
-                let min_sim = match parse_similarity_threshold(sim_str) {
-                    Ok(threshold) => threshold,
-                    Err(e) => {
-                        eprintln!("Error parsing similarity threshold: {}", e);
-                        std::process::exit(1);
-                    }
-                };
-                if score.combined_score >= min_sim {
-                    similarity_scores.push(score);
-                }
-            } else {
+            // Variations of an alias are scored against the alias
+            let seed = variation_seeds.get(variation).unwrap_or(&original_domain);
+            let score =
+                calculate_similarity_with_mode(seed, variation, transformation_type, cli.scoring);
+
+            // Apply the similarity range filter if specified
+            if parsed_min_similarity.is_none_or(|min_sim| score.combined_score >= min_sim)
+                && parsed_max_similarity.is_none_or(|max_sim| score.combined_score <= max_sim)
+            {
                 similarity_scores.push(score);
             }
         }
@@ -940,6 +1410,55 @@ async fn main() {
                 .partial_cmp(&a.combined_score)
                 .unwrap_or(std::cmp::Ordering::Equal)
         });
+
+        // The most similar of each group that reads the same stands for the group
+        if cli.collapse_near_duplicates {
+            // Ties go to the first domain in code point order, so runs agree
+            similarity_scores.sort_by(|a, b| {
+                b.combined_score
+                    .total_cmp(&a.combined_score)
+                    .then_with(|| a.domain.cmp(&b.domain))
+            });
+            let mut representatives: HashMap<String, String> = HashMap::new();
+            similarity_scores.retain(|score| {
+                // Left out or flagged later, so standing for nothing
+                let seed = variation_seeds
+                    .get(&score.domain)
+                    .unwrap_or(&original_domain);
+                if normalizes_to_original(seed, &score.domain) {
+                    return true;
+                }
+                let key = skeleton(&score.domain);
+                if let Some(representative) = representatives.get(&key) {
+                    *cluster_sizes.entry(representative.clone()).or_default() += 1;
+                    return false;
+                }
+                representatives.insert(key, score.domain.clone());
+                cluster_sizes.insert(score.domain.clone(), 1);
+                true
+            });
+        }
+
+        // Each transformation's best, grouped in the order transformations are listed
+        if let Some(per_transformation) = cli.top_per_transformation {
+            let order: HashMap<&str, usize> = transformation_generators(&generator_options)
+                .into_iter()
+                .enumerate()
+                .map(|(i, (name, _))| (name, i))
+                .collect();
+            similarity_scores.sort_by_key(|score| {
+                order
+                    .get(score.transformation.as_str())
+                    .copied()
+                    .unwrap_or(usize::MAX)
+            });
+            let mut kept: HashMap<String, usize> = HashMap::new();
+            similarity_scores.retain(|score| {
+                let count = kept.entry(score.transformation.clone()).or_default();
+                *count += 1;
+                *count <= per_transformation
+            });
+        }
     }
 
     // Always use similarity-sorted variations (highest similarity first)
@@ -948,9 +1467,22 @@ async fn main() {
         .map(|s| s.domain.as_str())
         .collect();
 
+    // Variations a browser maps back to the original lead to the real site
+    let original_domain = format!("{}.{}", domain_name, tld);
+    let seed_of = |variation: &str| {
+        variation_seeds
+            .get(variation)
+            .map_or(original_domain.as_str(), String::as_str)
+    };
+    let normalizes_back = |variation: &str| normalizes_to_original(seed_of(variation), variation);
+    // Written only with --alias
+    let with_seeds = !aliases.is_empty();
+    let row_seed = |variation: &str| with_seeds.then(|| seed_of(variation));
+
+    let mut all_lookups_failed = false;
     let actual_output_count = if check_status {
         // Filter domains to avoid duplicates with original
-        let domains_to_check: Vec<String> = sorted_variations
+        let (normalized, domains_to_check): (Vec<String>, Vec<String>) = sorted_variations
             .iter()
             .take(output_count)
             .filter(|variation| {
@@ -958,20 +1490,29 @@ async fn main() {
                 variation_registrable_domain != original_registrable_domain
             })
             .map(|s| s.to_string())
-            .collect();
-
-        // Use concurrent domain checking with reasonable concurrency limit
-        let concurrency = 15; // Good balance between speed and not overwhelming servers
-
-        let results = check_domains_concurrent(domains_to_check, concurrency).await;
+            .partition(|variation| normalizes_back(variation));
+
+        // Per-endpoint limits tune actual lookup concurrency; this only caps the total
+        let concurrency = throttle::MAX_CONCURRENCY;
+
+        let mut results =
+            check_domains_concurrent(domains_to_check, concurrency, progress_mode).await;
+        all_lookups_failed =
+            !results.is_empty() && results.iter().all(|(_, status)| is_failed_status(status));
+        // Flagged rather than looked up
+        results.extend(
+            normalized
+                .into_iter()
+                .map(|domain| (domain, NORMALIZES_TO_ORIGINAL.to_string())),
+        );
 
         clear_progress_line();
-        let mut output_counter = 0;
+        let mut rows = Vec::new();
 
         // Process results and apply filters
-        for (domain, status) in results {
+        for (domain, status) in &results {
             let should_show = if cli.only_registered {
-                status != "available"
+                is_registered_status(status)
             } else if cli.only_available {
                 status == "available"
             } else {
@@ -980,579 +1521,441 @@ async fn main() {
 
             if should_show {
                 // Find similarity score for this domain
-                if let Some(score) = similarity_scores.iter().find(|s| s.domain == domain) {
-                    let transformation = variation_sources
-                        .get(&domain)
-                        .map(|s| s.as_str())
-                        .unwrap_or("unknown");
-                    println!(
-                        "{:.2}%, {}, {}, {}",
-                        score.combined_score * 100.0,
-                        domain,
-                        transformation,
-                        status
-                    );
-                } else {
-                    let transformation = variation_sources
-                        .get(&domain)
-                        .map(|s| s.as_str())
-                        .unwrap_or("unknown");
-                    println!("0.00%, {}, {}, {}", domain, transformation, status);
-                }
-                output_counter += 1;
+                let score = similarity_scores.iter().find(|s| &s.domain == domain);
+                let transformation = variation_sources
+                    .get(domain)
+                    .map(|s| s.as_str())
+                    .unwrap_or("unknown");
+                rows.push(ResultRow {
+                    score: score.map_or(0.0, |s| s.combined_score),
+                    visual_score: score.map_or(0.0, |s| s.visual_score),
+                    cognitive_score: score.map_or(0.0, |s| s.cognitive_score),
+                    typo_likelihood: None,
+                    domain,
+                    transformation,
+                    seed: row_seed(domain),
+                    cluster_size: cluster_sizes.get(domain).copied(),
+                    status: Some(status),
+                    enrichment: None,
+                });
             }
         }
-        output_counter
+        let enrichments = enrich_rows(&rows, &enrich_options, progress_mode).await;
+        attach_enrichments(&mut rows, &enrichments);
+        output
+            .write_rows(&rows)
+            .await
+            .unwrap_or_else(|e| exit_with(&e))
     } else {
-        for variation in sorted_variations.iter().take(output_count) {
+        // Without a status column to flag them in, leave them out
+        let (normalized, candidates): (Vec<&str>, Vec<&str>) = sorted_variations
+            .iter()
+            .partition(|variation| normalizes_back(variation));
+        if !normalized.is_empty() {
+            eprintln!(
+                "Left out {} variations that normalize back to {} ({})",
+                normalized.len(),
+                original_domain,
+                NORMALIZES_TO_ORIGINAL
+            );
+        }
+        let mut rows = Vec::new();
+        for variation in candidates.iter().take(output_count) {
+            let transformation = variation_sources
+                .get(*variation)
+                .map(|s| s.as_str())
+                .unwrap_or("unknown");
             if cli.verbose {
                 if let Some(attack) = variation_sources.get(*variation) {
-                    let original_domain = format!("{}.{}", domain_name, tld);
-                    let score = calculate_similarity(&original_domain, variation, attack);
-                    eprintln!("  Applied {} transformation: {}.{} -> {} (visual:{:.3}, cognitive:{:.3}, combined:{:.3})", 
-                        attack, domain_name, tld, variation, score.visual_score, score.cognitive_score, score.combined_score);
-                    // Always show combined similarity score with transformation source
-                    if let Some(score) = similarity_scores.iter().find(|s| s.domain == *variation) {
-                        println!(
-                            "{:.2}%, {}, {}",
-                            score.combined_score * 100.0,
-                            variation,
-                            attack
-                        );
-                    } else {
-                        println!("0.00%, {}, {}", variation, attack);
-                    }
-                } else {
-                    // Always show combined similarity score with transformation source
-                    if let Some(score) = similarity_scores.iter().find(|s| s.domain == *variation) {
-                        let transformation = variation_sources
-                            .get(*variation)
-                            .map(|s| s.as_str())
-                            .unwrap_or("unknown");
-                        println!(
-                            "{:.2}%, {}, {}",
-                            score.combined_score * 100.0,
-                            variation,
-                            transformation
-                        );
-                    } else {
-                        let transformation = variation_sources
-                            .get(*variation)
-                            .map(|s| s.as_str())
-                            .unwrap_or("unknown");
-                        println!("0.00%, {}, {}", variation, transformation);
-                    }
-                }
-            } else {
-                // Always show combined similarity score with transformation source
-                if let Some(score) = similarity_scores.iter().find(|s| s.domain == *variation) {
-                    let transformation = variation_sources
-                        .get(*variation)
-                        .map(|s| s.as_str())
-                        .unwrap_or("unknown");
-                    println!(
-                        "{:.2}%, {}, {}",
-                        score.combined_score * 100.0,
+                    let score = calculate_similarity_with_mode(
+                        &original_domain,
                         variation,
-                        transformation
+                        attack,
+                        cli.scoring,
                     );
-                } else {
-                    let transformation = variation_sources
-                        .get(*variation)
-                        .map(|s| s.as_str())
-                        .unwrap_or("unknown");
-                    println!("0.00%, {}, {}", variation, transformation);
+                    eprintln!("  Applied {} transformation: {}.{} -> {} (visual:{:.3}, cognitive:{:.3}, combined:{:.3})", 
+                        attack, domain_name, tld, variation, score.visual_score, score.cognitive_score, score.combined_score);
                 }
             }
-        }
-        output_count
+            // Always show combined similarity score with transformation source
+            let score = similarity_scores.iter().find(|s| s.domain == *variation);
+            rows.push(ResultRow {
+                score: score.map_or(0.0, |s| s.combined_score),
+                visual_score: score.map_or(0.0, |s| s.visual_score),
+                cognitive_score: score.map_or(0.0, |s| s.cognitive_score),
+                typo_likelihood: None,
+                domain: variation,
+                transformation,
+                seed: row_seed(variation),
+                cluster_size: cluster_sizes.get(*variation).copied(),
+                status: None,
+                enrichment: None,
+            });
+        }
+        output
+            .write_rows(&rows)
+            .await
+            .unwrap_or_else(|e| exit_with(&e))
     };
+    write_purchase_list(&cli, &mut output);
+    output.finish().await.unwrap_or_else(|e| exit_with(&e));
+    #[cfg(feature = "s3")]
+    if let Some(uploader) = &uploader {
+        upload_artifacts(uploader, &cli).await;
+    }
+    save_status_cache();
+    report_stats(&cli, started.elapsed());
 
     if cli.only_registered {
         eprintln!("Found {} registered variations ", actual_output_count);
     } else {
         eprintln!("Generated {} variations ", actual_output_count);
     }
+
+    if all_lookups_failed {
+        eprintln!("Error: every status lookup failed; check network connectivity");
+        std::process::exit(EXIT_LOOKUP_FAILED);
+    }
 }
 
-/// Concurrent domain status checking with configurable concurrency limit
-async fn check_domains_concurrent(
-    domains: Vec<String>,
-    concurrency: usize,
-) -> Vec<(String, String)> {
-    let semaphore = Arc::new(Semaphore::new(concurrency));
-
-    // Create progress bar
-    let pb = ProgressBar::new(domains.len() as u64);
-    pb.set_style(
-        ProgressStyle::default_bar()
-            .template(
-                "{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos:>7}/{len:7} {msg}",
-            )
-            .expect("Failed to set progress bar template")
-            .progress_chars("█▉▊▋▌▍▎▏  "),
+/// Write the available variations the run found to `--purchase-list`
+fn write_purchase_list(cli: &Cli, output: &mut OutputWriter) {
+    let (Some(path), Some(purchases)) = (&cli.purchase_list, output.purchases.take()) else {
+        return;
+    };
+    std::fs::write(path, purchases.render(cli.purchase_format)).unwrap_or_else(|e| {
+        exit_with(&DomFuzzError::Config(format!(
+            "cannot write {}: {}",
+            path.display(),
+            e
+        )))
+    });
+    eprintln!(
+        "Wrote {} domains to register to {}",
+        purchases.len(),
+        path.display()
     );
-    pb.set_message("Checking domains...");
-
-    let pb = Arc::new(pb);
-
-    let results: Vec<(String, String)> = stream::iter(domains)
-        .map(|domain| {
-            let sem = Arc::clone(&semaphore);
-            let pb = Arc::clone(&pb);
-            async move {
-                let _permit = sem.acquire().await.expect("Failed to acquire semaphore");
-                let status = check_domain_status(&domain).await;
-                pb.inc(1);
-                (domain, status)
-            }
-        })
-        .buffer_unordered(concurrency)
-        .collect()
-        .await;
-
-    pb.finish_with_message("Domain checking complete!");
-
-    results
 }
 
-/// Fast domain status checking using RDAP (Registration Data Access Protocol) first,
-/// with WHOIS/DNS fallback for unknown TLDs. This provides significant performance
-/// improvements over the original WHOIS-first approach:
-///
-/// Performance improvements:
-/// - RDAP uses HTTP/HTTPS with structured JSON responses (vs TCP WHOIS text parsing)
-/// - HTTP 404 = available, 200 = registered (simple status determination)
-/// - Built-in endpoint mapping for 30+ major TLDs avoids discovery overhead
-/// - Typical speedup: 3-5x faster for supported TLDs
-/// - Concurrent processing: 5-10x speedup with parallel requests
-async fn check_domain_status(domain: &str) -> String {
-    // Extract the registrable domain
-    let registrable_domain = extract_registrable_domain(domain);
-
-    // Try fast RDAP check first (modern protocol, HTTP-based)
-    if let Ok(status) = check_domain_rdap(&registrable_domain).await {
-        return status;
+/// Write `--public-mode`'s status cache back to disk; the results are already
+/// out, so failing to only warns
+fn save_status_cache() {
+    let Some(public_mode) = checker::public_mode() else {
+        return;
+    };
+    if let Err(e) = public::save_cache() {
+        eprintln!("Warning: {}", e);
+    }
+    let hits = public::cache_hits();
+    if hits > 0 {
+        eprintln!(
+            "Answered {} lookups from the status cache at {}",
+            hits,
+            public_mode.cache_path.display()
+        );
     }
-
-    // Fallback to the original implementation for unknown TLDs
-    check_domain_status_legacy(&registrable_domain).await
 }
 
-/// Fast RDAP-based domain checking using built-in registry mapping
-async fn check_domain_rdap(domain: &str) -> DomainCheckResult<String> {
-    let tld = extract_tld(domain)?;
-
-    // Get RDAP endpoint for this TLD
-    let endpoint = get_rdap_endpoint(&tld)?;
-
-    // Build RDAP URL
-    let rdap_url = format!("{}{}", endpoint, domain);
-
-    // Use shared HTTP client for connection reuse
-
-    // Make RDAP request using shared client
-    let response = HTTP_CLIENT.get(&rdap_url).send().await?;
+/// The `--upload` destination, checked before the run so bad credentials do
+/// not surface only at the end
+#[cfg(feature = "s3")]
+async fn upload_target(cli: &Cli, location: &str) -> Result<S3Uploader, DomFuzzError> {
+    #[cfg(feature = "screenshots")]
+    let screenshots = cli.screenshots.is_some();
+    #[cfg(not(feature = "screenshots"))]
+    let screenshots = false;
+    if cli.output.is_none() && cli.append.is_none() && !screenshots {
+        return Err(DomFuzzError::Config(
+            "--upload requires --output, --append, or --screenshots".to_string(),
+        ));
+    }
+    S3Uploader::from_env(S3Location::parse(location)?).await
+}
 
-    match response.status() {
-        reqwest::StatusCode::OK => {
-            // Domain exists (registered), check if it might be parked
-            if let Ok(json) = response.json::<serde_json::Value>().await {
-                if is_domain_parked_rdap(&json) {
-                    Ok("parked".to_string())
-                } else {
-                    Ok("registered".to_string())
-                }
-            } else {
-                Ok("registered".to_string())
+/// `--upload`: PUT the result file under its file name and each screenshot
+/// under `screenshots/`
+#[cfg(feature = "s3")]
+async fn upload_artifacts(uploader: &S3Uploader, cli: &Cli) {
+    use futures::StreamExt;
+
+    let mut artifacts: Vec<(PathBuf, String)> = Vec::new();
+    if let Some(path) = cli.output.as_ref().or(cli.append.as_ref()) {
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "results".to_string());
+        artifacts.push((path.clone(), name));
+    }
+    #[cfg(feature = "screenshots")]
+    if let Some(dir) = &cli.screenshots {
+        let entries = std::fs::read_dir(dir).unwrap_or_else(|e| exit_with(&e.into()));
+        for path in entries.flatten().map(|entry| entry.path()) {
+            if let (true, Some(name)) = (path.is_file(), path.file_name()) {
+                let name = format!("screenshots/{}", name.to_string_lossy());
+                artifacts.push((path, name));
             }
         }
-        reqwest::StatusCode::NOT_FOUND => Ok("available".to_string()),
-        reqwest::StatusCode::TOO_MANY_REQUESTS => {
-            // Rate limited, wait and try once more
-            tokio::time::sleep(Duration::from_millis(RETRY_DELAY_MS)).await;
-            let retry_response = HTTP_CLIENT.get(&rdap_url).send().await?;
-            match retry_response.status() {
-                reqwest::StatusCode::OK => Ok("registered".to_string()),
-                reqwest::StatusCode::NOT_FOUND => Ok("available".to_string()),
-                _ => Err("RDAP server error after retry".into()),
+    }
+
+    let total = artifacts.len();
+    let mut uploads = futures::stream::iter(artifacts)
+        .map(|(path, name)| async move { uploader.put_file(&path, &name).await })
+        .buffer_unordered(UPLOAD_CONCURRENCY);
+    let location = cli.upload.as_deref().unwrap_or_default();
+    let mut failed = 0;
+    while let Some(result) = uploads.next().await {
+        match result {
+            Ok(object) if cli.verbose => eprintln!("Uploaded {}", object),
+            Ok(_) => {}
+            Err(e) => {
+                eprintln!("Warning: {}", e);
+                failed += 1;
             }
         }
-        _ => Err(format!("RDAP server returned status: {}", response.status()).into()),
     }
+    if failed > 0 {
+        exit_with(&DomFuzzError::Io(io::Error::other(format!(
+            "{} of {} uploads to {} failed",
+            failed, total, location
+        ))));
+    }
+    eprintln!(
+        "Uploaded {} files to {}",
+        total,
+        cli.upload.as_deref().unwrap_or_default()
+    );
 }
 
-/// Check if domain appears to be parked based on RDAP data
-fn is_domain_parked_rdap(json: &serde_json::Value) -> bool {
-    // Check status codes for parking indicators
-    if let Some(statuses) = json.get("status").and_then(|s| s.as_array()) {
-        for status in statuses {
-            if let Some(status_str) = status.as_str() {
-                let status_lower = status_str.to_lowercase();
-                if status_lower.contains("client hold")
-                    || status_lower.contains("redemption")
-                    || status_lower.contains("pending delete")
-                {
-                    return true;
-                }
-            }
+/// Report a fatal error and exit with the code the error maps to
+fn exit_with(error: &DomFuzzError) -> ! {
+    eprintln!("Error: {}", error);
+    std::process::exit(error.exit_code());
+}
+
+impl GeneratorArgs {
+    /// Enable the transformations that `--rules` and `--script` add
+    fn enable_transformations(&self, enabled: &mut HashSet<String>) {
+        if self.rules.is_some() {
+            enabled.insert("custom".to_string());
+        }
+        #[cfg(feature = "scripting")]
+        if self.script.is_some() {
+            enabled.insert("script".to_string());
         }
     }
 
-    // Check entities for parking service registrars
-    if let Some(entities) = json.get("entities").and_then(|e| e.as_array()) {
-        for entity in entities {
-            if let Some(roles) = entity.get("roles").and_then(|r| r.as_array()) {
-                if roles.iter().any(|role| role.as_str() == Some("registrar")) {
-                    if let Some(name) = extract_registrar_name(entity) {
-                        let name_lower = name.to_lowercase();
-                        if name_lower.contains("sedo")
-                            || name_lower.contains("parking")
-                            || name_lower.contains("bodis")
-                            || name_lower.contains("hugedomains")
-                        {
-                            return true;
-                        }
-                    }
-                }
-            }
+    /// Load the dictionary, TLD set, rules, and script, and with `--site-keywords` fetch
+    /// `domain`'s homepage, exiting on error
+    async fn options(&self, domain: &str) -> GeneratorOptions {
+        let dict_words = match &self.dictionary {
+            Some(dict_file) => load_dictionary(dict_file).unwrap_or_else(|e| exit_with(&e)),
+            None => default_dictionary(),
+        };
+        let tlds = load_tld_set(&self.tld_set).unwrap_or_else(|e| exit_with(&e));
+        let custom_rules = match &self.rules {
+            Some(path) => load_custom_rules(path).unwrap_or_else(|e| exit_with(&e)),
+            None => CustomRules::default(),
+        };
+        let site_keywords = if self.site_keywords {
+            fetch_site_keywords(domain).await.unwrap_or_else(|e| {
+                eprintln!("Warning: cannot read site keywords: {}", e);
+                Vec::new()
+            })
+        } else {
+            Vec::new()
+        };
+        GeneratorOptions {
+            dict_words,
+            site_keywords,
+            tlds,
+            custom_rules,
+            #[cfg(feature = "scripting")]
+            script: self
+                .script
+                .as_deref()
+                .map(|path| load_script(path).unwrap_or_else(|e| exit_with(&e))),
+            realism: self.realism,
+            languages: self.language.clone(),
+            respect_idn_policies: self.respect_idn_policies,
         }
     }
-
-    false
 }
 
-/// Extract registrar name from RDAP entity
-fn extract_registrar_name(entity: &serde_json::Value) -> Option<String> {
-    // Try vcardArray first
-    if let Some(name) = extract_vcard_name(entity) {
-        return Some(name);
+/// Prefix a parse error with the file it came from
+fn in_file(path: &Path, error: DomFuzzError) -> DomFuzzError {
+    match error {
+        DomFuzzError::Parse(message) => {
+            DomFuzzError::Parse(format!("{}: {}", path.display(), message))
+        }
+        e => e,
     }
+}
 
-    // Fallback to publicIds or handle
-    if let Some(public_ids) = entity.get("publicIds").and_then(|p| p.as_array()) {
-        if let Some(id) = public_ids
-            .first()
-            .and_then(|id| id.get("identifier"))
-            .and_then(|i| i.as_str())
-        {
-            return Some(id.to_string());
-        }
-    }
-
-    entity
-        .get("handle")
-        .or_else(|| entity.get("name"))
-        .and_then(|n| n.as_str())
-        .map(String::from)
-}
-
-/// Extract organization name from vCard format
-fn extract_vcard_name(entity: &serde_json::Value) -> Option<String> {
-    entity
-        .get("vcardArray")
-        .and_then(|v| v.as_array())
-        .and_then(|a| a.get(1))
-        .and_then(|a| a.as_array())
-        .and_then(|items| {
-            for item in items {
-                if let Some(item_array) = item.as_array() {
-                    if item_array.len() >= 4 {
-                        if let Some(first) = item_array.first().and_then(|f| f.as_str()) {
-                            if first == "fn" {
-                                return item_array
-                                    .get(3)
-                                    .and_then(|n| n.as_str())
-                                    .map(String::from);
-                            }
-                        }
-                    }
-                }
-            }
-            None
-        })
+/// Read a `--whois-rules` file
+fn load_whois_rules(path: &Path) -> Result<WhoisRules, DomFuzzError> {
+    let contents = std::fs::read_to_string(path)?;
+    WhoisRules::parse(&contents).map_err(|e| in_file(path, e))
 }
 
-/// Get RDAP endpoint for a TLD
-fn get_rdap_endpoint(tld: &str) -> DomainCheckResult<&'static str> {
-    let endpoint = match tld.to_lowercase().as_str() {
-        // Major gTLDs
-        "com" => "https://rdap.verisign.com/com/v1/domain/",
-        "net" => "https://rdap.verisign.com/net/v1/domain/",
-        "org" => "https://rdap.publicinterestregistry.org/rdap/domain/",
-        "info" => "https://rdap.identitydigital.services/rdap/domain/",
-        "biz" => "https://rdap.nic.biz/domain/",
-        // Google TLDs
-        "app" => "https://rdap.nic.google/domain/",
-        "dev" => "https://rdap.nic.google/domain/",
-        "page" => "https://rdap.nic.google/domain/",
-        // Other popular TLDs
-        "xyz" => "https://rdap.nic.xyz/domain/",
-        "tech" => "https://rdap.nic.tech/domain/",
-        "online" => "https://rdap.nic.online/domain/",
-        "site" => "https://rdap.nic.site/domain/",
-        // ccTLDs
-        "io" => "https://rdap.identitydigital.services/rdap/domain/",
-        "ai" => "https://rdap.nic.ai/domain/",
-        "co" => "https://rdap.nic.co/domain/",
-        "me" => "https://rdap.nic.me/domain/",
-        "us" => "https://rdap.nic.us/domain/",
-        "uk" => "https://rdap.nominet.uk/domain/",
-        "eu" => "https://rdap.eu.org/domain/",
-        "de" => "https://rdap.denic.de/domain/",
-        "ca" => "https://rdap.cira.ca/domain/",
-        "au" => "https://rdap.auda.org.au/domain/",
-        "fr" => "https://rdap.nic.fr/domain/",
-        "jp" => "https://rdap.jprs.jp/domain/",
-        "br" => "https://rdap.registro.br/domain/",
-        "in" => "https://rdap.registry.in/domain/",
-        "cn" => "https://rdap.cnnic.cn/domain/",
-        "tv" => "https://rdap.verisign.com/tv/v1/domain/",
-        "cc" => "https://rdap.verisign.com/cc/v1/domain/",
-        _ => return Err(format!("No RDAP endpoint known for TLD: {}", tld).into()),
-    };
+/// Read a `--sinkholes` file
+fn load_sinkholes(path: &Path) -> Result<AddressRules, DomFuzzError> {
+    let contents = std::fs::read_to_string(path)?;
+    AddressRules::parse(&contents).map_err(|e| in_file(path, e))
+}
 
-    Ok(endpoint)
+/// Read a `--rules` file
+fn load_custom_rules(path: &Path) -> Result<CustomRules, DomFuzzError> {
+    let contents = std::fs::read_to_string(path)?;
+    CustomRules::parse(&contents).map_err(|e| in_file(path, e))
 }
 
-/// Extract TLD from domain
-fn extract_tld(domain: &str) -> DomainCheckResult<String> {
-    let parts: Vec<&str> = domain.split('.').collect();
-    if parts.len() < 2 {
-        return Err("Invalid domain format".into());
-    }
-    Ok(parts
-        .last()
-        .expect("Domain must have at least one part after split")
-        .to_lowercase())
+/// Read and compile a `--script` file
+#[cfg(feature = "scripting")]
+fn load_script(path: &Path) -> Result<Script, DomFuzzError> {
+    let contents = std::fs::read_to_string(path)?;
+    Script::compile(&contents).map_err(|e| in_file(path, e))
 }
 
-/// Legacy domain checking (fallback for unknown TLDs)
-async fn check_domain_status_legacy(domain: &str) -> String {
-    // First check WHOIS for the most accurate information
-    if let Ok(whois_result) = check_whois(domain).await {
-        return whois_result;
+/// Warn that `--script`'s `generate` failed, naming the first failure; failed
+/// calls yield no variations
+#[cfg_attr(not(feature = "scripting"), allow(unused_variables))]
+fn warn_script_error(options: &GeneratorOptions) {
+    #[cfg(feature = "scripting")]
+    if let Some(error) = options.script.as_ref().and_then(Script::error) {
+        eprintln!("Warning: {}", error);
     }
+}
 
-    // Fallback to DNS + HTTP checking
-    let resolver = TokioAsyncResolver::tokio(ResolverConfig::default(), ResolverOpts::default());
-    let dns_result = timeout(
-        Duration::from_secs(DNS_TIMEOUT_SECS),
-        resolver.lookup_ip(domain),
-    )
-    .await;
-
-    match dns_result {
-        Ok(Ok(lookup)) => {
-            if lookup.iter().count() == 0 {
-                return "available".to_string();
-            }
-
-            // Domain has DNS records, check if it's parked or active
-            // Use shared HTTP client for better performance
-
-            // Try HTTP first, then HTTPS
-            for protocol in ["http", "https"] {
-                let url = format!("{}://{}", protocol, domain);
-                if let Ok(Ok(resp)) = timeout(
-                    Duration::from_secs(HTTP_TIMEOUT_SECS),
-                    HTTP_CLIENT.get(&url).send(),
-                )
-                .await
-                {
-                    if resp.status().is_success() {
-                        if let Ok(Ok(content)) =
-                            timeout(Duration::from_secs(HTTP_CONTENT_TIMEOUT_SECS), resp.text())
-                                .await
-                        {
-                            let content_lower = content.to_lowercase();
-                            if content_lower.contains("parked")
-                                || content_lower.contains("domain for sale")
-                                || content_lower.contains("this domain may be for sale")
-                                || content_lower.contains("godaddy")
-                                    && content_lower.contains("parked")
-                                || content_lower.contains("sedo")
-                                || content_lower.contains("parking")
-                                || content_lower.contains("under construction")
-                                || content_lower.contains("coming soon")
-                            {
-                                return "parked".to_string();
-                            }
-                        }
-                        return "registered".to_string();
-                    }
-                }
-            }
+/// Collect the `domain` of every record in a JSON Lines results file.
+/// A missing file is treated as empty so the first `--append` run can create it.
+fn load_known_domains(path: &Path) -> Result<HashSet<String>, DomFuzzError> {
+    let contents = match read_result_file(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(HashSet::new()),
+        Err(e) => return Err(e.into()),
+    };
 
-            "registered".to_string()
+    let mut domains = HashSet::new();
+    for (index, line) in contents.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
         }
-        Ok(Err(_)) => "available".to_string(),
-        Err(_) => "timeout".to_string(),
-    }
+        let domain = serde_json::from_str::<serde_json::Value>(line)
+            .ok()
+            .and_then(|record| record.get("domain")?.as_str().map(str::to_lowercase))
+            .ok_or_else(|| {
+                DomFuzzError::Parse(format!(
+                    "{}:{}: expected a JSON object with a \"domain\" field",
+                    path.display(),
+                    index + 1
+                ))
+            })?;
+        domains.insert(domain);
+    }
+    Ok(domains)
 }
 
-async fn check_whois(domain: &str) -> DomainCheckResult<String> {
-    let tld = domain.split('.').next_back().unwrap_or("");
-    let whois_server = get_whois_server(tld);
-
-    // Connect to WHOIS server
-    let mut stream = timeout(
-        Duration::from_secs(WHOIS_CONNECT_TIMEOUT_SECS),
-        TcpStream::connect(&whois_server),
-    )
-    .await??;
-
-    // Send WHOIS query
-    let query = format!(
-        "{}
-",
-        domain
-    );
-    timeout(
-        Duration::from_secs(WHOIS_WRITE_TIMEOUT_SECS),
-        stream.write_all(query.as_bytes()),
-    )
-    .await??;
-
-    // Read response
-    let mut response = Vec::new();
-    timeout(
-        Duration::from_secs(WHOIS_READ_TIMEOUT_SECS),
-        stream.read_to_end(&mut response),
-    )
-    .await??;
-    let whois_data = String::from_utf8_lossy(&response).to_lowercase();
-
-    // Analyze WHOIS response
-    if whois_data.contains("no match")
-        || whois_data.contains("not found")
-        || whois_data.contains("no entries found")
-        || whois_data.contains("domain status: available")
-        || whois_data.contains("domain not found")
-        || whois_data.contains("no data found")
-    {
-        Ok("available".to_string())
-    } else if whois_data.contains("registrar:")
-        || whois_data.contains("registrant:")
-        || whois_data.contains("creation date:")
-        || whois_data.contains("created:")
-    {
-        // Check if it's parked based on WHOIS data
-        if whois_data.contains("parked")
-            || whois_data.contains("parking")
-            || whois_data.contains("domain for sale")
-            || whois_data.contains("sedo")
-            || whois_data.contains("bodis")
-            || whois_data.contains("sedoparking")
-        {
-            Ok("parked".to_string())
+/// `--alias` names as domains, without the original and repeats; a name without
+/// dots takes the original's suffix
+fn parse_aliases(aliases: &[String], original: &Domain) -> Result<Vec<Domain>, DomFuzzError> {
+    let mut parsed: Vec<Domain> = Vec::new();
+    for alias in aliases {
+        let domain = if alias.trim().trim_end_matches('.').contains('.') {
+            Domain::parse(alias)?
         } else {
-            Ok("registered".to_string())
+            Domain::parse(&format!("{}.{}", alias.trim(), original.suffix()))?
+        };
+        if &domain != original && !parsed.contains(&domain) {
+            parsed.push(domain);
         }
-    } else {
-        Err("unable to determine status".into())
     }
+    Ok(parsed)
 }
 
-fn get_whois_server(tld: &str) -> String {
-    match tld {
-        "com" | "net" => "whois.verisign-grs.com:43".to_string(),
-        "org" => "whois.pir.org:43".to_string(),
-        "info" => "whois.afilias.net:43".to_string(),
-        "biz" => "whois.neulevel.biz:43".to_string(),
-        "us" => "whois.nic.us:43".to_string(),
-        "co" => "whois.nic.co:43".to_string(),
-        "io" => "whois.nic.io:43".to_string(),
-        "me" => "whois.nic.me:43".to_string(),
-        "uk" => "whois.nic.uk:43".to_string(),
-        "ca" => "whois.cira.ca:43".to_string(),
-        "de" => "whois.denic.de:43".to_string(),
-        "fr" => "whois.afnic.fr:43".to_string(),
-        "ru" => "whois.tcinet.ru:43".to_string(),
-        "cn" => "whois.cnnic.net.cn:43".to_string(),
-        "jp" => "whois.jprs.jp:43".to_string(),
-        "au" => "whois.auda.org.au:43".to_string(),
-        "br" => "whois.registro.br:43".to_string(),
-        "tk" => "whois.dot.tk:43".to_string(),
-        "ml" => "whois.dot.ml:43".to_string(),
-        "ga" => "whois.dot.ga:43".to_string(),
-        "cf" => "whois.dot.cf:43".to_string(),
-        "app" => "whois.nic.google:43".to_string(),
-        "dev" => "whois.nic.google:43".to_string(),
-        "tech" => "whois.nic.tech:43".to_string(),
-        _ => "whois.iana.org:43".to_string(), // Fallback to IANA
+/// Words of the original domain name that identify the brand on a page, such as
+/// `paypal` for `paypal-login.com`, and a target profile's keywords; short
+/// fragments of the name match too much to be useful
+fn brand_keywords(domain: &str, target: Option<&TargetProfile>) -> Vec<String> {
+    let (name, _) = parse_domain(domain);
+    let mut keywords: Vec<String> = name
+        .split(['-', '.'])
+        .filter(|word| word.chars().count() >= 4)
+        .map(str::to_lowercase)
+        .collect();
+    for keyword in target.iter().flat_map(|target| &target.keywords) {
+        let keyword = keyword.trim().to_lowercase();
+        if !keyword.is_empty() && !keywords.contains(&keyword) {
+            keywords.push(keyword);
+        }
     }
+    keywords
 }
 
-fn is_valid_domain(domain: &str) -> bool {
-    // Check overall length limit (253 characters for FQDN)
-    if domain.len() > 253 || domain.is_empty() {
-        return false;
-    }
-
-    // Domain can't start or end with dot
-    if domain.starts_with('.') || domain.ends_with('.') {
-        return false;
-    }
+/// Enrichment lookups in flight at once; third-party services are rate limited
+const ENRICH_CONCURRENCY: usize = 4;
 
-    // Check for consecutive dots
-    if domain.contains("..") {
-        return false;
-    }
+/// Concurrent domain status checking with configurable concurrency limit
+async fn check_domains_concurrent(
+    domains: Vec<String>,
+    concurrency: usize,
+    mode: ProgressMode,
+) -> Vec<(String, String)> {
+    let progress = PhaseProgress::new("check", domains.len() as u64, mode);
 
-    // Split into labels and validate each
-    let labels: Vec<&str> = domain.split('.').collect();
+    let results = check_domains(domains, concurrency, |_, status| {
+        record_checked(status);
+        progress.advance(is_failed_status(status), "")
+    })
+    .await;
 
-    for label in &labels {
-        // Label can't be empty (handled by consecutive dots check above, but being explicit)
-        if label.is_empty() {
-            return false;
-        }
+    progress.finish("Domain checking complete!");
 
-        // Label length limit (63 characters)
-        if label.len() > 63 {
-            return false;
-        }
+    results
+}
 
-        // Label can't start or end with hyphen
-        if label.starts_with('-') || label.ends_with('-') {
-            return false;
-        }
+/// Enrich the registered rows when any enrichment is enabled, keyed by domain
+async fn enrich_rows(
+    rows: &[ResultRow<'_>],
+    options: &EnrichOptions,
+    mode: ProgressMode,
+) -> HashMap<String, Enrichment> {
+    use futures::StreamExt;
 
-        // Label must contain only valid characters (alphanumeric and hyphens)
-        // Note: We're being permissive to allow Unicode/IDN characters for our use case
-        for ch in label.chars() {
-            if !ch.is_alphanumeric() && ch != '-' && !ch.is_ascii() {
-                // Allow non-ASCII for Unicode/IDN transformations, but reject other invalid chars
-                continue;
-            }
-            if !ch.is_alphanumeric() && ch != '-' && ch.is_ascii() && !ch.is_ascii_alphanumeric() {
-                return false;
-            }
-        }
+    let domains: Vec<&str> = rows
+        .iter()
+        .filter(|row| row.status.is_some_and(is_registered_status))
+        .map(|row| row.domain)
+        .collect();
+    if !options.is_enabled() || domains.is_empty() {
+        return HashMap::new();
     }
 
-    // Must have at least one dot (domain.tld format)
-    if labels.len() < 2 {
-        return false;
-    }
+    let progress = PhaseProgress::new("enrich", domains.len() as u64, mode);
+    let enrichments = futures::stream::iter(domains)
+        .map(|domain| async move { (domain.to_string(), enrich(domain, options).await) })
+        .buffer_unordered(ENRICH_CONCURRENCY)
+        .inspect(|(_, enrichment)| progress.advance(!enrichment.errors.is_empty(), ""))
+        .collect()
+        .await;
+    progress.finish("Enrichment complete");
+    enrichments
+}
 
-    // TLD (last label) must be at least 2 characters
-    if let Some(tld) = labels.last() {
-        if tld.len() < 2 {
-            return false;
+/// Give each row its enrichment, and the `for-sale` status if its landing page
+/// offers it for sale
+fn attach_enrichments<'a>(
+    rows: &mut [ResultRow<'a>],
+    enrichments: &'a HashMap<String, Enrichment>,
+) {
+    for row in rows {
+        row.enrichment = enrichments.get(row.domain);
+        if row
+            .enrichment
+            .is_some_and(|enrichment| enrichment.listing.is_some())
+        {
+            row.status = Some(FOR_SALE);
         }
     }
-
-    true
-}
-
-fn filter_valid_domains(variations: Vec<String>) -> Vec<String> {
-    variations
-        .into_iter()
-        .filter(|domain| is_valid_domain(domain))
-        .map(|domain| domain.to_lowercase())
-        .collect()
 }
 
 fn clear_progress_line() {
@@ -1560,2438 +1963,3094 @@ fn clear_progress_line() {
     let _ = io::stderr().flush(); // Ignore flush errors
 }
 
-// New function that collects results instead of printing immediately with concurrent domain checking
-struct ComboConfig<'a> {
-    domain: &'a str,
-    tld: &'a str,
-    max_variations: Option<usize>,
-    verbose: bool,
-    only_registered: bool,
-    only_available: bool,
-    output_count: usize,
-    check_status: bool,
-    enabled_transformations: &'a std::collections::HashSet<String>,
-    min_similarity: Option<f64>,
-    batch_size: usize,
+/// Parse a `Name: value` header argument
+fn parse_header(header: &str) -> Result<(String, String), String> {
+    let (name, value) = header
+        .split_once(':')
+        .ok_or_else(|| format!("expected `Name: value`, got {:?}", header))?;
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(format!("missing header name in {:?}", header));
+    }
+    Ok((name.to_string(), value.trim().to_string()))
 }
 
-async fn generate_combo_attacks_streaming(config: &ComboConfig<'_>, _dict_words: &[String]) {
-    use rand::seq::SliceRandom;
-    use rand::thread_rng;
-    use rand::Rng;
-
-    let mut generated_domains = std::collections::HashSet::new();
-    let mut rng = thread_rng();
-    let mut current_batch: Vec<(String, SimilarityScore)> = Vec::new();
-    let mut total_output_count = 0;
-
-    // Define all available transformation functions with names matching CLI arguments
-    #[allow(clippy::type_complexity, clippy::redundant_closure)]
-    let all_transformation_functions: Vec<(&str, Box<dyn Fn(&str, &str) -> Vec<String>>)> = vec![
-        ("1337speak", Box::new(|d, t| generate_1337speak(d, t))),
-        (
-            "mixed-encodings",
-            Box::new(|d, t| generate_mixed_encodings(d, t)),
-        ),
-        ("misspelling", Box::new(|d, t| generate_misspelling(d, t))),
-        ("keyboard", Box::new(|d, t| generate_misspelling(d, t))),
-        ("fat-finger", Box::new(|d, t| generate_fat_finger(d, t))),
-        ("word-swap", Box::new(|d, t| generate_word_swaps(d, t))),
-        ("bitsquatting", Box::new(|d, t| generate_bitsquatting(d, t))),
-        (
-            "dot-insertion",
-            Box::new(|d, t| generate_dot_insertion(d, t)),
-        ),
-        ("dot-omission", Box::new(|d, t| generate_dot_omission(d, t))),
-        (
-            "cardinal-substitution",
-            Box::new(|d, t| generate_cardinal_substitution(d, t)),
-        ),
-        (
-            "ordinal-substitution",
-            Box::new(|d, t| generate_ordinal_substitution(d, t)),
-        ),
-        ("homophones", Box::new(|d, t| generate_homophones(d, t))),
-        (
-            "singular-plural",
-            Box::new(|d, t| generate_singular_plural(d, t)),
-        ),
-        (
-            "cyrillic-comprehensive",
-            Box::new(|d, t| generate_mixed_encodings(d, t)),
-        ),
-        (
-            "tld-variations",
-            Box::new(|d, t| generate_tld_variations(d, t)),
-        ),
-        (
-            "brand-confusion",
-            Box::new(|d, t| generate_brand_confusion(d, t)),
-        ),
-        ("intl-tld", Box::new(|d, t| generate_intl_tld(d, t))),
-        ("cognitive", Box::new(|d, t| generate_cognitive(d, t))),
-        (
-            "dot-hyphen-sub",
-            Box::new(|d, t| generate_dot_hyphen_substitution(d, t)),
-        ),
-        (
-            "subdomain",
-            Box::new(|d, t| generate_subdomain_injection(d, t)),
-        ),
-        (
-            "combosquatting",
-            Box::new(|d, t| generate_combosquatting(d, t, _dict_words)),
-        ),
-        ("wrong-sld", Box::new(|d, t| generate_wrong_sld(d, t))),
-        (
-            "domain-prefix",
-            Box::new(|d, t| generate_domain_prefix(d, t)),
-        ),
-        (
-            "domain-suffix",
-            Box::new(|d, t| generate_domain_suffix(d, t)),
-        ),
-    ];
-
-    // Filter transformation functions based on enabled transformations
-    #[allow(clippy::type_complexity)]
-    let transformation_functions: Vec<(&str, Box<dyn Fn(&str, &str) -> Vec<String>>)> =
-        all_transformation_functions
-            .into_iter()
-            .filter(|(name, _)| config.enabled_transformations.contains(*name))
-            .collect();
-
-    // Generate combo variations by applying random sequences of transformations
-    let target_variations = config.max_variations.unwrap_or(usize::MAX); // Unlimited by default
-    let mut attempts = 0;
-    let max_attempts = config.max_variations.map_or(usize::MAX, |max| max * 10); // Unlimited attempts for unlimited generation
-
-    while (config.max_variations.is_none() || total_output_count < target_variations)
-        && attempts < max_attempts
-        && total_output_count < config.output_count
-    {
-        attempts += 1;
-
-        let mut current_domain = config.domain.to_string();
-        let mut current_tld = config.tld.to_string();
-        let mut applied_attacks: Vec<&str> = Vec::new();
-
-        // Randomly choose number of transformations (1-5)
-        let num_attacks = rng.gen_range(2..=5);
+/// Parse a `--tag` as `KEY=VALUE`
+fn parse_tag(tag: &str) -> Result<(String, String), String> {
+    annotate::parse_tag(tag).map_err(|e| match e {
+        DomFuzzError::Parse(message) => message,
+        e => e.to_string(),
+    })
+}
 
-        // Apply random transformations (allowing repeats)
-        for _ in 0..num_attacks {
-            if let Some((attack_name, transformation_fn)) =
-                transformation_functions.choose(&mut rng)
-            {
-                // Apply the transformation and randomly select one result
-                let transformation_results = transformation_fn(&current_domain, &current_tld);
-                if !transformation_results.is_empty() {
-                    if let Some(selected_result) = transformation_results.choose(&mut rng) {
-                        if config.verbose {
-                            let original_domain = format!("{}.{}", config.domain, config.tld);
-                            let score = calculate_similarity(
-                                &original_domain,
-                                selected_result,
-                                attack_name,
-                            );
-                            eprintln!("  Applied {} transformation: {}.{} -> {} (visual:{:.3}, cognitive:{:.3}, combined:{:.3})", 
-                                attack_name, current_domain, current_tld, selected_result,
-                                score.visual_score, score.cognitive_score, score.combined_score);
-                        }
-                        // Parse the result to separate domain and TLD for next iteration
-                        let (parsed_domain, parsed_tld) = parse_domain(selected_result);
-                        current_domain = parsed_domain;
-                        current_tld = parsed_tld;
-                        applied_attacks.push(attack_name);
-                    }
-                }
-            }
+/// Parse a non-zero duration such as `45`, `90s`, `30m`, or `1h30m`; a bare
+/// number is seconds
+fn parse_duration(text: &str) -> Result<Duration, String> {
+    let invalid = || {
+        format!(
+            "expected a duration such as 90s, 30m, or 1h30m, got {:?}",
+            text
+        )
+    };
+    let text = text.trim();
+    if let Ok(secs) = text.parse::<u64>() {
+        if secs == 0 {
+            return Err(invalid());
+        }
+        return Ok(Duration::from_secs(secs));
+    }
+    let mut total = 0u64;
+    let mut digits = String::new();
+    for c in text.chars() {
+        if c.is_ascii_digit() {
+            digits.push(c);
+            continue;
         }
+        let unit = match c {
+            's' => 1,
+            'm' => 60,
+            'h' => 3600,
+            'd' => 86_400,
+            _ => return Err(invalid()),
+        };
+        let amount: u64 = digits.parse().map_err(|_| invalid())?;
+        total = amount
+            .checked_mul(unit)
+            .and_then(|secs| total.checked_add(secs))
+            .ok_or_else(invalid)?;
+        digits.clear();
+    }
+    if !digits.is_empty() || total == 0 {
+        return Err(invalid());
+    }
+    Ok(Duration::from_secs(total))
+}
 
-        // Create the final domain name for this attempt
-        let final_domain = format!("{}.{}", current_domain, current_tld);
-
-        // Only add if we successfully applied at least 1 transformation
-        if !applied_attacks.is_empty() {
-            let lowercase_original = format!("{}.{}", config.domain, config.tld).to_lowercase();
-
-            if final_domain.to_lowercase() != lowercase_original
-                && !generated_domains.contains(&final_domain)
-                && is_valid_domain(&final_domain)
-            {
-                generated_domains.insert(final_domain.clone());
+/// Parse a size in bytes such as `65536`, `256k`, or `1m`
+fn parse_byte_size(text: &str) -> Result<usize, String> {
+    let invalid = || format!("expected a size such as 65536, 256k, or 1m, got {:?}", text);
+    let text = text.trim().to_lowercase();
+    let (digits, unit) = match text.strip_suffix(['k', 'm']) {
+        Some(digits) if text.ends_with('k') => (digits, 1024),
+        Some(digits) => (digits, 1024 * 1024),
+        None => (text.as_str(), 1),
+    };
+    let size = digits
+        .parse::<usize>()
+        .ok()
+        .and_then(|size| size.checked_mul(unit))
+        .ok_or_else(invalid)?;
+    if size == 0 {
+        return Err(invalid());
+    }
+    Ok(size)
+}
 
-                // Calculate similarity score
-                let original_domain = format!("{}.{}", config.domain, config.tld);
-                let score = calculate_similarity(&original_domain, &final_domain, "combo");
+/// How combo mode produces chains
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum ComboMode {
+    /// Sample random chains until the limit or attempt budget runs out
+    Random,
+    /// Sample like `random`, picking each step's transformation in proportion to the
+    /// similarity its outputs have kept so far
+    Weighted,
+    /// Enumerate every distinct chain up to a depth cap, shortest first
+    Exhaustive,
+}
 
-                // Check if this domain meets minimum similarity threshold
-                let meets_threshold = if let Some(min_sim) = config.min_similarity {
-                    score.combined_score >= min_sim
-                } else {
-                    true // No threshold specified, accept all domains
-                };
+/// Default longest chain for exhaustive combo enumeration; the space grows
+/// roughly with the product of per-step result counts, so keep it shallow
+const DEFAULT_COMBO_DEPTH: usize = 3;
+
+/// How combo mode de-duplicates generated domains
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum DedupeMode {
+    /// Remember every domain; exact but memory grows with the run
+    Exact,
+    /// Bloom filter with a configurable false-positive rate
+    Probabilistic,
+}
 
-                // Only add domains that meet the similarity threshold
-                if meets_threshold {
-                    current_batch.push((final_domain, score));
-
-                    // Process batch when it reaches the specified size
-                    if current_batch.len() >= config.batch_size {
-                        let batch_count = process_batch(
-                            &mut current_batch,
-                            config.check_status,
-                            config.only_registered,
-                            config.only_available,
-                            &mut total_output_count,
-                            config.output_count,
-                        )
-                        .await;
-                        if batch_count == 0 {
-                            break; // Stop if we've reached the output limit
-                        }
-                    }
-                }
-                // If doesn't meet threshold, continue loop to generate another domain
-            }
-        }
-    }
+// ==================== OUTPUT FORMATTING ====================
+
+/// Output formats for generated variations
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum OutputFormat {
+    /// Aligned, color-coded columns for human reading
+    Table,
+    /// Comma-separated lines: score, domain, transformation[, seed][, cluster size][, status]
+    Csv,
+    /// One JSON object per line: {"schema_version", "domain", "transformation"[, "seed"][, "cluster_size"], "score"[, "status"]}
+    Jsonl,
+    /// Typed columnar file for analytics tools; requires --output
+    #[cfg(feature = "parquet")]
+    Parquet,
+    /// One ArcSight Common Event Format record per line
+    Cef,
+    /// One QRadar Log Event Extended Format record per line
+    Leef,
+}
 
-    // Process any remaining domains in the final batch
-    if !current_batch.is_empty() && total_output_count < config.output_count {
-        process_batch(
-            &mut current_batch,
-            config.check_status,
-            config.only_registered,
-            config.only_available,
-            &mut total_output_count,
-            config.output_count,
-        )
-        .await;
-    }
+/// Collectors results can be sent to (`--sink`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum Sink {
+    /// One RFC 5424 message per result (see --syslog-server)
+    Syslog,
+    /// One Kafka message per result (see --brokers and --topic)
+    #[cfg(feature = "kafka")]
+    Kafka,
 }
 
-/// Process a batch of domains for streaming output
-async fn process_batch(
-    batch: &mut Vec<(String, SimilarityScore)>,
-    check_status: bool,
-    only_registered: bool,
-    only_available: bool,
-    total_output_count: &mut usize,
-    max_output_count: usize,
-) -> usize {
-    if batch.is_empty() || *total_output_count >= max_output_count {
-        return 0;
-    }
+/// Streams monitor events can be published to (`monitor --sink`)
+#[cfg(feature = "kafka")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum EventSink {
+    /// One Kafka message per event (see --brokers and --topic)
+    Kafka,
+}
 
-    let mut batch_output_count = 0;
-    let remaining_output_slots = max_output_count - *total_output_count;
-    let batch_to_process: Vec<(String, SimilarityScore)> =
-        batch.drain(..).take(remaining_output_slots).collect();
+/// A single output row describing one generated variation
+#[derive(Clone, Copy)]
+struct ResultRow<'a> {
+    score: f64,
+    /// Components of `score`, written with `--score-columns`
+    visual_score: f64,
+    cognitive_score: f64,
+    /// Chance of typing the variation by mistake, filled in by the writer with
+    /// `--typo-likelihood`
+    typo_likelihood: Option<f64>,
+    domain: &'a str,
+    transformation: &'a str,
+    /// The original domain or alias the variation was generated from, with `--alias`
+    seed: Option<&'a str>,
+    /// Variations that read the same, this one included, with
+    /// `--collapse-near-duplicates`
+    cluster_size: Option<usize>,
+    status: Option<&'a str>,
+    /// Hosting intelligence for registered variations; JSON Lines output only
+    enrichment: Option<&'a Enrichment>,
+}
 
-    if check_status {
-        // Extract domains for checking
-        let domains_to_check: Vec<String> = batch_to_process
-            .iter()
-            .map(|(domain, _)| domain.clone())
-            .collect();
+const ANSI_RESET: &str = "\x1b[0m";
+const ANSI_RED: &str = "\x1b[31m";
+const ANSI_GREEN: &str = "\x1b[32m";
+const ANSI_YELLOW: &str = "\x1b[33m";
+const ANSI_BOLD_RED: &str = "\x1b[1;31m";
+const ANSI_DIM: &str = "\x1b[2m";
+
+/// Where formatted rows are written
+enum OutputSink {
+    Stdout,
+    /// A temporary file next to `path`, renamed over it by [`OutputWriter::finish`]
+    /// so an interrupted run never leaves a truncated result file behind
+    File {
+        file: FileEncoder,
+        path: PathBuf,
+    },
+    Syslog(SyslogSender),
+    #[cfg(feature = "kafka")]
+    Kafka(KafkaSender),
+}
 
-        if !domains_to_check.is_empty() {
-            // Use concurrent domain checking with reasonable concurrency limit
-            let concurrency = 15; // Good balance between speed and not overwhelming servers
-            let results = check_domains_concurrent(domains_to_check, concurrency).await;
+/// Result file writer, compressed according to the target's extension
+/// (`.gz` for gzip, `.zst` for zstd)
+enum FileEncoder {
+    Plain(BufWriter<NamedTempFile>),
+    Gzip(GzEncoder<BufWriter<NamedTempFile>>),
+    Zstd(zstd::Encoder<'static, BufWriter<NamedTempFile>>),
+}
 
-            // Process results and apply filters
-            for (domain, status) in results {
-                let should_show = if only_registered {
-                    status != "available"
-                } else if only_available {
-                    status == "available"
-                } else {
-                    true // Show all domains with status
-                };
+impl FileEncoder {
+    fn new(file: NamedTempFile, path: &Path) -> io::Result<Self> {
+        let file = BufWriter::new(file);
+        Ok(match path.extension().and_then(|ext| ext.to_str()) {
+            Some("gz") => FileEncoder::Gzip(GzEncoder::new(file, flate2::Compression::default())),
+            Some("zst") => FileEncoder::Zstd(zstd::Encoder::new(file, 0)?),
+            _ => FileEncoder::Plain(file),
+        })
+    }
 
-                if should_show && batch_output_count < remaining_output_slots {
-                    // Find similarity score for this domain
-                    if let Some((_, score)) = batch_to_process.iter().find(|(d, _)| d == &domain) {
-                        println!(
-                            "{:.2}%, {}, combo, {}",
-                            score.combined_score * 100.0,
-                            domain,
-                            status
-                        );
-                    } else {
-                        println!("0.00%, {}, combo, {}", domain, status);
-                    }
-                    batch_output_count += 1;
-                }
-            }
-        }
-    } else {
-        // Output without status checking
-        for (domain, score) in batch_to_process.iter().take(remaining_output_slots) {
-            println!("{:.2}%, {}, combo", score.combined_score * 100.0, domain);
-            batch_output_count += 1;
+    fn temp_file(&self) -> &NamedTempFile {
+        match self {
+            FileEncoder::Plain(w) => w.get_ref(),
+            FileEncoder::Gzip(w) => w.get_ref().get_ref(),
+            FileEncoder::Zstd(w) => w.get_ref().get_ref(),
         }
     }
 
-    *total_output_count += batch_output_count;
-    batch_output_count
-}
-
-fn parse_domain(input: &str) -> (String, String) {
-    if let Some(dot_pos) = input.rfind('.') {
-        let (domain_part, tld_part) = input.split_at(dot_pos);
-        let domain = domain_part.to_string();
-        let tld = tld_part.trim_start_matches('.').to_string();
-        (domain, tld)
-    } else {
-        (input.to_string(), "com".to_string())
+    /// Write any compression trailer and return the underlying temporary file
+    fn finish(self) -> io::Result<NamedTempFile> {
+        let file = match self {
+            FileEncoder::Plain(w) => w,
+            FileEncoder::Gzip(w) => w.finish()?,
+            FileEncoder::Zstd(w) => w.finish()?,
+        };
+        file.into_inner().map_err(|e| e.into_error())
     }
 }
 
-fn extract_registrable_domain(input: &str) -> String {
-    // For domains with subdomains like "con.cordiumm.com", extract "cordiumm.com"
-    let parts: Vec<&str> = input.split('.').collect();
-
-    if parts.len() >= 2 {
-        // Take the last two parts (domain + TLD)
-        let domain = parts[parts.len() - 2];
-        let tld = parts[parts.len() - 1];
-        format!("{}.{}", domain, tld)
-    } else {
-        // If somehow there's no dot, return as-is
-        input.to_string()
-    }
-}
-
-fn generate_1337speak(domain: &str, tld: &str) -> Vec<String> {
-    let mut variations = Vec::new();
-
-    // Work with lowercase for consistent matching
-    let domain_lower = domain.to_lowercase();
-    let chars: Vec<char> = domain_lower.chars().collect();
-
-    if chars.is_empty() {
-        return variations;
-    }
-
-    let substitutions = [
-        // Classic numeric leet substitutions
-        ('o', '0'),
-        ('0', 'o'),
-        ('l', '1'),
-        ('1', 'l'),
-        ('i', '1'),
-        ('1', 'i'),
-        ('e', '3'),
-        ('3', 'e'),
-        ('a', '4'),
-        ('4', 'a'),
-        ('s', '5'),
-        ('5', 's'),
-        ('g', '9'),
-        ('9', 'g'),
-        ('b', '6'),
-        ('6', 'b'),
-        ('t', '7'),
-        ('7', 't'),
-        ('z', '2'),
-        ('2', 'z'),
-        // Advanced symbol-based leet substitutions (will be filtered by is_valid_domain)
-        ('a', '@'),
-        ('@', 'a'),
-        ('i', '!'),
-        ('!', 'i'),
-        ('s', '$'),
-        ('$', 's'),
-        ('h', '#'),
-        ('#', 'h'),
-        ('c', '('),
-        ('(', 'c'),
-        ('d', ')'),
-        (')', 'd'),
-        ('p', '%'),
-        ('%', 'p'),
-        ('r', '®'),
-        ('®', 'r'),
-        ('t', '+'),
-        ('+', 't'),
-        ('x', '*'),
-        ('*', 'x'),
-        ('n', '^'),
-        ('^', 'n'),
-        ('l', '|'),
-        ('|', 'l'),
-        ('i', '|'),
-        ('e', '€'),
-        ('€', 'e'),
-        // Letter confusion substitutions
-        ('i', 'l'),
-        ('l', 'i'),
-        ('o', 'q'),
-        ('q', 'o'),
-        ('p', 'q'),
-        ('q', 'p'),
-        ('d', 'b'),
-        ('b', 'd'),
-        ('u', 'v'),
-        ('v', 'u'),
-        ('m', 'n'),
-        ('n', 'm'),
-        ('r', 'n'),
-        ('h', 'n'),
-    ];
-
-    // Build character errors for each position
-    let mut character_errors = Vec::new();
-    for (pos, &ch) in chars.iter().enumerate() {
-        let mut pos_errors = Vec::new();
-
-        // Find all possible 1337speak substitutions for this character
-        for &(from, to) in &substitutions {
-            if from == ch {
-                pos_errors.push((pos, "substitute", to));
-            }
+impl Write for FileEncoder {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            FileEncoder::Plain(w) => w.write(buf),
+            FileEncoder::Gzip(w) => w.write(buf),
+            FileEncoder::Zstd(w) => w.write(buf),
         }
+    }
 
-        if !pos_errors.is_empty() {
-            character_errors.push(pos_errors);
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            FileEncoder::Plain(w) => w.flush(),
+            FileEncoder::Gzip(w) => w.flush(),
+            FileEncoder::Zstd(w) => w.flush(),
         }
     }
+}
 
-    if character_errors.is_empty() {
-        return variations;
-    }
+/// Read a result file, transparently decompressing `.gz` and `.zst` files
+fn read_result_file(path: &Path) -> io::Result<String> {
+    use std::io::Read;
 
-    // Apply realistic constraints similar to fat-finger
-    let max_errors = ((chars.len() as f32 * 0.4).ceil() as usize).clamp(1, 3);
-    let max_length = (domain_lower.len() as f32 * 1.2) as usize; // 1337speak doesn't typically increase length much
+    let file = std::fs::File::open(path)?;
+    let mut contents = String::new();
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("gz") => flate2::read::MultiGzDecoder::new(file).read_to_string(&mut contents)?,
+        Some("zst") => zstd::Decoder::new(file)?.read_to_string(&mut contents)?,
+        _ => io::BufReader::new(file).read_to_string(&mut contents)?,
+    };
+    Ok(contents)
+}
 
-    generate_realistic_combinations(
-        &chars,
-        &character_errors,
-        max_errors,
-        max_length,
-        tld,
-        &mut variations,
-    );
+/// Writes result rows to stdout or a file in the selected format
+struct OutputWriter {
+    format: OutputFormat,
+    color: bool,
+    sink: OutputSink,
+    header_printed: bool,
+    // Column widths only ever grow so streamed batches stay aligned
+    domain_width: usize,
+    transformation_width: usize,
+    seed_width: usize,
+    // Parquet is columnar, so rows are buffered and written by `finish`
+    #[cfg(feature = "parquet")]
+    parquet_columns: ParquetColumns,
+    post_filter: Option<PostFilter>,
+    /// Write visual and cognitive scores next to the combined score
+    score_columns: bool,
+    /// Write each row's typo likelihood against `original_domain`
+    typo_likelihood: bool,
+    /// Domain the variations were generated from, named in PDF, CEF, and LEEF output
+    original_domain: String,
+    /// Available variations written, with `--purchase-list`
+    purchases: Option<PurchaseList>,
+    /// `--tag`s, written in JSON Lines records' `tags`
+    tags: serde_json::Map<String, serde_json::Value>,
+}
 
-    variations
-}
-
-fn generate_cognitive(domain: &str, tld: &str) -> Vec<String> {
-    let mut variations = Vec::new();
-
-    // Cognitive/semantic word confusion transformations
-    // Based on lexical similarity, phonetic similarity, and common business terminology confusion
-
-    // Dictionary of common word confusions for business/tech domains
-    let word_confusions = [
-        // Spelling variations and common misspellings
-        ("amazon", vec!["amazom", "amazone", "amazn"]),
-        ("google", vec!["gogle", "googel", "googlle"]),
-        ("microsoft", vec!["mircosoft", "microsooft", "microsft"]),
-        ("facebook", vec!["facbook", "facebok", "faceboook"]),
-        ("paypal", vec!["payball", "paypall", "paypaul"]),
-        ("apple", vec!["aple", "applle", "aplle"]),
-        ("twitter", vec!["twiter", "twittr", "twittter"]),
-        ("linkedin", vec!["linkdin", "linkin", "linkedinn"]),
-        // Phonetic and semantic confusion
-        ("secure", vec!["secur", "securee", "secuure"]),
-        ("support", vec!["suport", "supportt", "supp0rt"]),
-        ("service", vec!["servic", "servicee", "servise"]),
-        ("account", vec!["acount", "accont", "accountt"]),
-        ("login", vec!["loginn", "log1n", "l0gin"]),
-        ("portal", vec!["portall", "p0rtal", "porttal"]),
-        ("center", vec!["centre", "centr", "centerr"]),
-        ("office", vec!["offic", "officee", "0ffice"]),
-        // Business terminology confusion
-        ("corp", vec!["corporate", "company", "inc"]),
-        ("inc", vec!["incorporated", "corp", "company"]),
-        ("company", vec!["corp", "inc", "co"]),
-        ("group", vec!["grp", "groupe", "groupp"]),
-        ("tech", vec!["technology", "tec", "techno"]),
-        ("solutions", vec!["solution", "solve", "solutionz"]),
-        ("systems", vec!["system", "sys", "systemz"]),
-        ("services", vec!["service", "servs", "servicez"]),
-        // Common domain confusions (sophisticated transformations like concordium->consordium)
-        ("concordium", vec!["consordium", "consortium", "concardium"]),
-        ("consortium", vec!["consordium", "concordium", "consortum"]),
-        ("foundation", vec!["fundation", "foundtion", "foundaton"]),
-        ("enterprise", vec!["enterprize", "enterpise", "enterpris"]),
-        (
-            "international",
-            vec!["internacional", "internation", "intl"],
-        ),
-        ("development", vec!["developement", "developmnt", "develop"]),
-        ("management", vec!["managment", "managem", "manage"]),
-        ("consulting", vec!["consultng", "consult", "consultancy"]),
-        ("financial", vec!["finance", "finacial", "financ"]),
-        ("research", vec!["reserch", "researh", "resarch"]),
-        ("laboratory", vec!["lab", "laborat", "laboratry"]),
-        ("institute", vec!["institut", "institu", "instit"]),
-        ("university", vec!["univrsity", "univ", "universty"]),
-        ("college", vec!["colege", "coleg", "collegee"]),
-        ("academy", vec!["acadmy", "academ", "academie"]),
-        ("network", vec!["netwrk", "net", "nework"]),
-        ("security", vec!["securty", "sec", "securit"]),
-        ("technology", vec!["technlogy", "tech", "tecnology"]),
-        ("innovation", vec!["inovation", "innov", "innovaton"]),
-        ("intelligence", vec!["inteligence", "intel", "intelligenc"]),
-        ("analytics", vec!["analytic", "anlytics", "analytix"]),
-        (
-            "communications",
-            vec!["communication", "comm", "comunications"],
-        ),
-    ];
+/// Format of the lines sent to a `--sink`: JSON Lines unless another
+/// line-based format is chosen
+fn sink_format(format: Option<OutputFormat>, sink: &str) -> Result<OutputFormat, DomFuzzError> {
+    let format = format.unwrap_or(OutputFormat::Jsonl);
+    if !matches!(
+        format,
+        OutputFormat::Csv | OutputFormat::Jsonl | OutputFormat::Cef | OutputFormat::Leef
+    ) {
+        return Err(DomFuzzError::Config(format!(
+            "--sink {} requires --format csv, jsonl, cef, or leef",
+            sink
+        )));
+    }
+    Ok(format)
+}
 
-    // Apply word confusion transformations
-    for &(original_word, ref confusions) in &word_confusions {
-        if domain.to_lowercase().contains(original_word) {
-            for confusion in confusions {
-                let confused_domain = domain.to_lowercase().replace(original_word, confusion);
-                if confused_domain != domain.to_lowercase() {
-                    variations.push(format!("{}.{}", confused_domain, tld));
+impl OutputWriter {
+    fn new(format: OutputFormat, color: bool, sink: OutputSink) -> Self {
+        OutputWriter {
+            format,
+            color,
+            sink,
+            header_printed: false,
+            domain_width: "DOMAIN".len(),
+            transformation_width: "TRANSFORMATION".len(),
+            seed_width: "SEED".len(),
+            #[cfg(feature = "parquet")]
+            parquet_columns: ParquetColumns::default(),
+            post_filter: None,
+            score_columns: false,
+            typo_likelihood: false,
+            original_domain: String::new(),
+            purchases: None,
+            tags: serde_json::Map::new(),
+        }
+    }
+
+    /// Resolve the format, color settings, and destination from CLI flags and the
+    /// environment. Table output is the default on a terminal; piped or file output
+    /// stays comma-separated.
+    fn from_cli(
+        format: Option<OutputFormat>,
+        no_color: bool,
+        output: Option<&Path>,
+    ) -> Result<Self, DomFuzzError> {
+        use std::io::IsTerminal;
+
+        let to_terminal = output.is_none() && io::stdout().is_terminal();
+        let format = format.unwrap_or(if to_terminal {
+            OutputFormat::Table
+        } else {
+            OutputFormat::Csv
+        });
+        let no_color_env = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+        let color = to_terminal && !no_color && !no_color_env;
+        #[cfg(feature = "parquet")]
+        if format == OutputFormat::Parquet && output.is_none() {
+            return Err(DomFuzzError::Config(
+                "--format parquet requires --output".to_string(),
+            ));
+        }
+
+        let sink = match output {
+            None => OutputSink::Stdout,
+            Some(path) => {
+                let dir = match path.parent() {
+                    Some(dir) if !dir.as_os_str().is_empty() => dir,
+                    _ => Path::new("."),
+                };
+                let mut builder = tempfile::Builder::new();
+                builder.prefix(".domfuzz-").suffix(".tmp");
+                // New files get the umask default rather than tempfile's owner-only mode
+                #[cfg(unix)]
+                builder.permissions(std::os::unix::fs::PermissionsExt::from_mode(0o666));
+                let file = builder.tempfile_in(dir).map_err(|e| {
+                    DomFuzzError::Config(format!(
+                        "cannot create output file in {}: {}",
+                        dir.display(),
+                        e
+                    ))
+                })?;
+                // A replaced file keeps its permissions
+                if let Ok(metadata) = std::fs::metadata(path) {
+                    file.as_file().set_permissions(metadata.permissions())?;
+                }
+                OutputSink::File {
+                    file: FileEncoder::new(file, path)?,
+                    path: path.to_path_buf(),
                 }
             }
-        }
-    }
+        };
 
-    // Reverse lookup - check if domain contains any confusion words
-    for &(original_word, ref confusions) in &word_confusions {
-        for confusion in confusions {
-            if domain.to_lowercase().contains(confusion) {
-                let corrected_domain = domain.to_lowercase().replace(confusion, original_word);
-                if corrected_domain != domain.to_lowercase() {
-                    variations.push(format!("{}.{}", corrected_domain, tld));
+        Ok(OutputWriter::new(format, color, sink))
+    }
+
+    /// Output for `--sink syslog`: one message per line, JSON Lines unless
+    /// another line-based format is chosen
+    async fn for_syslog(format: Option<OutputFormat>, server: &str) -> Result<Self, DomFuzzError> {
+        let format = sink_format(format, "syslog")?;
+        let sender = SyslogSender::connect(server).await?;
+        Ok(OutputWriter::new(format, false, OutputSink::Syslog(sender)))
+    }
+
+    /// Output for `--sink kafka`: one message per line, formatted as for syslog
+    #[cfg(feature = "kafka")]
+    fn for_kafka(
+        format: Option<OutputFormat>,
+        brokers: &str,
+        topic: &str,
+    ) -> Result<Self, DomFuzzError> {
+        let format = sink_format(format, "kafka")?;
+        let sender = KafkaSender::connect(brokers, topic)?;
+        Ok(OutputWriter::new(format, false, OutputSink::Kafka(sender)))
+    }
+
+    /// Output for `--append`: JSON Lines written to a temporary copy of the existing
+    /// results file, which replaces the original when the run completes
+    fn for_append(format: Option<OutputFormat>, path: &Path) -> Result<Self, DomFuzzError> {
+        if format.is_some_and(|f| f != OutputFormat::Jsonl) {
+            return Err(DomFuzzError::Config(
+                "--append requires --format jsonl".to_string(),
+            ));
+        }
+        let mut writer = OutputWriter::from_cli(Some(OutputFormat::Jsonl), true, Some(path))?;
+        if let OutputSink::File { file, .. } = &mut writer.sink {
+            match read_result_file(path) {
+                Ok(existing) => {
+                    file.write_all(existing.as_bytes())?;
+                    if !existing.is_empty() && !existing.ends_with('\n') {
+                        file.write_all(b"\n")?;
+                    }
                 }
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e.into()),
             }
         }
-    }
-
-    // Phonetic similarity transformations (sounds-like transformations)
-    let phonetic_substitutions = [
-        ("ph", "f"),
-        ("f", "ph"),
-        ("ck", "k"),
-        ("k", "ck"),
-        ("c", "k"),
-        ("k", "c"),
-        ("s", "z"),
-        ("z", "s"),
-        ("i", "y"),
-        ("y", "i"),
-        ("er", "or"),
-        ("or", "er"),
-        ("an", "en"),
-        ("en", "an"),
-        ("tion", "sion"),
-        ("sion", "tion"),
-    ];
-
-    for &(from, to) in &phonetic_substitutions {
-        if domain.contains(from) {
-            let phonetic_variant = domain.replace(from, to);
-            if phonetic_variant != domain {
-                variations.push(format!("{}.{}", phonetic_variant, tld));
+        Ok(writer)
+    }
+
+    /// Flush buffered rows and, for file output, atomically move the completed
+    /// file into place
+    async fn finish(self) -> Result<(), DomFuzzError> {
+        match self.sink {
+            OutputSink::Stdout => io::stdout().flush()?,
+            OutputSink::Syslog(sender) => sender.close().await?,
+            #[cfg(feature = "kafka")]
+            OutputSink::Kafka(mut sender) => sender.flush()?,
+            OutputSink::File { file, path } => {
+                #[cfg(feature = "parquet")]
+                let file = if self.format == OutputFormat::Parquet {
+                    self.parquet_columns.write(file)?
+                } else {
+                    file
+                };
+                let file = file.finish()?;
+                file.as_file().sync_all()?;
+                file.persist(&path).map_err(|e| e.error)?;
             }
         }
+        Ok(())
     }
 
-    // Compound word separation transformations
-    let common_compounds = [
-        "facebook",
-        "youtube",
-        "linkedin",
-        "instagram",
-        "microsoft",
-        "paypal",
-        "amazon",
-        "google",
-        "twitter",
-        "whatsapp",
-        "airbnb",
-        "spotify",
-        "netflix",
-        "dropbox",
-        "github",
-    ];
-
-    for compound in &common_compounds {
-        if domain.to_lowercase().contains(compound) {
-            // Try to split compound words intelligently
-            match *compound {
-                "facebook" => {
-                    variations.push(format!(
-                        "{}.{}",
-                        domain.to_lowercase().replace("facebook", "face-book"),
-                        tld
-                    ));
-                    variations.push(format!(
-                        "{}.{}",
-                        domain.to_lowercase().replace("facebook", "faceb00k"),
-                        tld
-                    ));
-                }
-                "youtube" => {
-                    variations.push(format!(
-                        "{}.{}",
-                        domain.to_lowercase().replace("youtube", "you-tube"),
-                        tld
-                    ));
-                    variations.push(format!(
-                        "{}.{}",
-                        domain.to_lowercase().replace("youtube", "youtub3"),
-                        tld
-                    ));
-                }
-                "linkedin" => {
-                    variations.push(format!(
-                        "{}.{}",
-                        domain.to_lowercase().replace("linkedin", "linked-in"),
-                        tld
-                    ));
-                    variations.push(format!(
-                        "{}.{}",
-                        domain.to_lowercase().replace("linkedin", "link3din"),
-                        tld
-                    ));
-                }
-                "instagram" => {
-                    variations.push(format!(
-                        "{}.{}",
-                        domain.to_lowercase().replace("instagram", "insta-gram"),
-                        tld
-                    ));
-                    variations.push(format!(
-                        "{}.{}",
-                        domain.to_lowercase().replace("instagram", "instagr4m"),
-                        tld
-                    ));
-                }
-                "microsoft" => {
-                    variations.push(format!(
-                        "{}.{}",
-                        domain.to_lowercase().replace("microsoft", "micro-soft"),
-                        tld
-                    ));
-                    variations.push(format!(
-                        "{}.{}",
-                        domain.to_lowercase().replace("microsoft", "micr0soft"),
-                        tld
-                    ));
-                }
-                _ => {}
+    /// Write one line to the sink; a failed write aborts the run
+    fn write_line(&mut self, line: &str) {
+        let result = match &mut self.sink {
+            OutputSink::Stdout => writeln!(io::stdout(), "{}", line),
+            OutputSink::File { file, .. } => writeln!(file, "{}", line),
+            OutputSink::Syslog(sender) => sender.send(line),
+            #[cfg(feature = "kafka")]
+            OutputSink::Kafka(sender) => sender.send(line),
+        };
+        if let Err(e) = result {
+            if let OutputSink::File { file, .. } = &self.sink {
+                let _ = std::fs::remove_file(file.temp_file().path());
             }
+            exit_with(&e.into());
         }
     }
 
-    // Business context confusion (authority terms)
-    let business_contexts = [
-        ("bank", vec!["banking", "banc", "finansial"]),
-        ("pay", vec!["payment", "payments", "paying"]),
-        ("shop", vec!["shopping", "store", "market"]),
-        ("mail", vec!["email", "post", "message"]),
-        ("cloud", vec!["server", "hosting", "storage"]),
-        ("data", vec!["database", "info", "information"]),
-        ("web", vec!["website", "site", "online"]),
-        ("mobile", vec!["app", "application", "phone"]),
-        ("digital", vec!["cyber", "online", "virtual"]),
-        ("crypto", vec!["blockchain", "bitcoin", "coin"]),
-    ];
-
-    for &(context_word, ref alternatives) in &business_contexts {
-        if domain.to_lowercase().contains(context_word) {
-            for alt in alternatives {
-                let contextual_variant = domain.to_lowercase().replace(context_word, alt);
-                if contextual_variant != domain.to_lowercase() {
-                    variations.push(format!("{}.{}", contextual_variant, tld));
-                }
-            }
+    /// The JSON record of `row`, with the run's tags
+    fn record(&self, row: &ResultRow) -> serde_json::Value {
+        let mut record = row_record(row, self.score_columns);
+        if !self.tags.is_empty() {
+            record["tags"] = self.tags.clone().into();
         }
+        record
     }
 
-    // Remove duplicates and original domain
-    variations.sort();
-    variations.dedup();
-    variations.retain(|v| v != &format!("{}.{}", domain, tld));
-
-    variations
-}
-
-fn generate_mixed_encodings(domain: &str, tld: &str) -> Vec<String> {
-    let mut variations = Vec::new();
-    let domain_lower = domain.to_lowercase();
-    let chars: Vec<char> = domain_lower.chars().collect();
-
-    // Comprehensive encoding map based on IronGeek homoglyph research and Unicode homoglyphs
-    let encoding_map: std::collections::HashMap<char, Vec<char>> = [
-        // Letters with extensive homoglyph mappings
-        (
-            'a',
-            vec![
-                'а', 'α', 'ａ', 'À', 'Á', 'Â', 'Ã', 'Ä', 'Å', 'à', 'á', 'â', 'ã', 'ä', 'å', 'ɑ',
-                'Α', 'Ꭺ',
-            ],
-        ),
-        ('b', vec!['ь', 'β', 'ｂ', 'ß', 'ʙ', 'Β', 'В', 'Ь', 'Ᏼ', 'ᛒ']),
-        ('c', vec!['с', 'ｃ', 'ϲ', 'Ϲ', 'С', 'Ꮯ', 'Ⅽ', 'ⅽ']),
-        (
-            'd',
-            vec!['д', 'ｄ', 'Ď', 'ď', 'Đ', 'đ', 'ԁ', 'ժ', 'Ꭰ', 'ḍ', 'Ⅾ', 'ⅾ'],
-        ),
-        (
-            'e',
-            vec![
-                'е', 'ε', 'ｅ', 'È', 'É', 'Ê', 'Ë', 'é', 'ê', 'ë', 'Ē', 'ē', 'Ĕ', 'ĕ', 'Ė', 'ė',
-                'Ę', 'Ě', 'ě', 'Ε', 'Е', 'Ꭼ',
-            ],
-        ),
-        ('f', vec!['ф', 'ｆ', 'Ϝ']),
-        ('g', vec!['ѓ', 'ｇ', 'ɡ', 'ɢ', 'Ԍ', 'ն', 'Ꮐ']),
-        ('h', vec!['н', 'η', 'ｈ', 'ʜ', 'Η', 'Н', 'һ', 'Ꮋ']),
-        (
-            'i',
-            vec!['і', 'ι', 'ｉ', 'ɩ', 'Ι', 'І', 'ا', 'Ꭵ', 'ᛁ', 'Ⅰ', 'ⅰ'],
-        ),
-        ('j', vec!['ј', 'ｊ', 'ϳ', 'Ј', 'յ', 'Ꭻ']),
-        ('k', vec!['к', 'κ', 'ｋ', 'Κ', 'К', 'Ꮶ', 'ᛕ']),
-        ('l', vec!['ӏ', 'ｌ', 'ʟ', 'ا', 'Ꮮ', 'Ⅼ', 'ⅼ']),
-        ('m', vec!['м', 'μ', 'ｍ', 'Μ', 'Ϻ', 'М', 'Ꮇ', 'ᛖ', 'Ⅿ', 'ⅿ']),
-        ('n', vec!['п', 'η', 'ｎ', 'ɴ', 'Ν']),
-        ('o', vec!['о', 'ο', 'ｏ', 'Ο', 'О', 'Օ']), // Zero and O are handled separately
-        ('p', vec!['р', 'ρ', 'ｐ', 'Ρ', 'Р', 'Ꮲ']),
-        ('q', vec!['ԛ', 'ｑ', 'Ⴍ', 'Ⴓ']),
-        ('r', vec!['г', 'ρ', 'ｒ', 'ʀ', 'Ի', 'Ꮢ', 'ᚱ']),
-        ('s', vec!['ѕ', 'ｓ', 'Ѕ', 'Տ', 'Ⴝ', 'Ꮪ']),
-        ('t', vec!['т', 'τ', 'ｔ', 'Τ', 'Т', 'Ꭲ']),
-        ('u', vec!['υ', 'ｕ', 'μ', 'Ա', 'Ս', '⋃']),
-        ('v', vec!['ν', 'ｖ', 'Ѵ', 'ѵ', 'Ꮩ', 'Ⅴ', 'ⅴ']),
-        ('w', vec!['ѡ', 'ｗ', 'Ꮃ']),
-        ('x', vec!['х', 'χ', 'ｘ', 'Χ', 'Х', 'Ⅹ', 'ⅹ']),
-        ('y', vec!['у', 'ｙ', 'ʏ', 'Υ', 'γ', 'Ү']),
-        ('z', vec!['ᴢ', 'ｚ', 'Ζ', 'Ꮓ']),
-        // Numbers with homoglyphs
-        ('0', vec!['О', 'о', 'Ο', 'ο', 'Օ', 'ｏ', '٠']), // Zero with O variations
-        ('1', vec!['ا', 'Ⅰ', 'ⅰ', 'ǀ', '１']),           // One with I, l variations
-        ('2', vec!['２']),
-        ('3', vec!['３']),
-        ('4', vec!['４']),
-        ('5', vec!['５']),
-        ('6', vec!['６']),
-        ('7', vec!['７']),
-        ('8', vec!['８', 'Ց']),
-        ('9', vec!['９']),
-        // Only hyphens and dots are valid special characters in domain names
-        ('-', vec!['‐', '－']),
-        ('.', vec!['٠', '۔', '܁', '܂', '…', '‧', '。', '．', '｡']),
-    ]
-    .iter()
-    .cloned()
-    .collect();
-
-    // Calculate realistic constraints for Unicode substitutions
-    let max_errors = ((chars.len() as f32 * 0.6).ceil() as usize).max(1); // Up to 60% of chars can be Unicode
-    let max_length = domain.len(); // Unicode substitutions don't change length
-
-    // Find all character positions where encoding substitutions can occur
-    let mut character_encodings = Vec::new();
-
-    for (pos, &ch) in chars.iter().enumerate() {
-        if let Some(encoding_chars) = encoding_map.get(&ch) {
-            let mut pos_encodings = Vec::new();
-            for &encoding_char in encoding_chars {
-                pos_encodings.push((pos, "unicode_sub", encoding_char));
-            }
-            if !pos_encodings.is_empty() {
-                character_encodings.push(pos_encodings);
+    /// Write `rows`, returning how many were written (after `--post-filter`)
+    async fn write_rows(&mut self, rows: &[ResultRow<'_>]) -> Result<usize, DomFuzzError> {
+        let scored: Vec<ResultRow>;
+        let rows = if self.typo_likelihood {
+            scored = rows
+                .iter()
+                .map(|row| ResultRow {
+                    typo_likelihood: Some(typo_likelihood(&self.original_domain, row.domain)),
+                    ..*row
+                })
+                .collect();
+            &scored[..]
+        } else {
+            rows
+        };
+        // Fields added by --post-filter, parallel to the rows it keeps
+        let mut extras = Vec::new();
+        let kept: Vec<ResultRow>;
+        let rows = match &self.post_filter {
+            Some(filter) => {
+                let mut vetted = Vec::new();
+                for row in rows {
+                    if let Some(extra) = filter.run(&self.record(row)).await? {
+                        extras.push(extra);
+                        vetted.push(*row);
+                    }
+                }
+                kept = vetted;
+                &kept[..]
             }
+            None => rows,
+        };
+        if rows.is_empty() {
+            return Ok(0);
         }
-    }
-
-    // Generate realistic encoding combinations
-    generate_encoding_combinations(
-        &chars,
-        &character_encodings,
-        max_errors,
-        max_length,
-        tld,
-        &mut variations,
-    );
-
-    variations
-}
-
-fn generate_encoding_combinations(
-    original_chars: &[char],
-    character_encodings: &[Vec<(usize, &str, char)>],
-    max_errors: usize,
-    _max_length: usize,
-    tld: &str,
-    variations: &mut Vec<String>,
-) {
-    let domain: String = original_chars.iter().collect();
-
-    // Generate single encoding substitutions first (most common)
-    for pos_encodings in character_encodings {
-        for &(pos, _error_type, replacement) in pos_encodings {
-            let result = apply_single_encoding(original_chars, pos, replacement);
-            if let Some(result_domain) = result {
-                if result_domain != domain {
-                    variations.push(format!("{}.{}", result_domain, tld));
-                }
+        if let Some(purchases) = &mut self.purchases {
+            for row in rows.iter().filter(|row| row.status == Some("available")) {
+                purchases.add(row.domain, row.score, row.transformation);
             }
         }
-    }
 
-    // Generate double encoding substitutions for longer domains (length >= 3)
-    if original_chars.len() >= 3 && max_errors >= 2 {
-        for i in 0..character_encodings.len() {
-            for j in (i + 1)..character_encodings.len() {
-                // For Unicode, allow any spacing between substitutions
-                // Take only first few encoding options to prevent explosion
-                for &(pos1, _error_type1, replacement1) in character_encodings[i].iter().take(2) {
-                    for &(pos2, _error_type2, replacement2) in character_encodings[j].iter().take(2)
-                    {
-                        let result = apply_double_encoding(
-                            original_chars,
-                            pos1,
-                            replacement1,
-                            pos2,
-                            replacement2,
-                        );
-                        if let Some(result_domain) = result {
-                            if result_domain != domain {
-                                variations.push(format!("{}.{}", result_domain, tld));
-                            }
-                        }
+        match self.format {
+            OutputFormat::Csv => {
+                for row in rows {
+                    let mut line = format!("{:.2}%", row.score * 100.0);
+                    if self.score_columns {
+                        line.push_str(&format!(
+                            ", {:.2}%, {:.2}%",
+                            row.visual_score * 100.0,
+                            row.cognitive_score * 100.0
+                        ));
+                    }
+                    if let Some(likelihood) = row.typo_likelihood {
+                        line.push_str(&format!(", {:.2e}", likelihood));
+                    }
+                    line.push_str(&format!(", {}, {}", row.domain, row.transformation));
+                    if let Some(seed) = row.seed {
+                        line.push_str(&format!(", {}", seed));
                     }
+                    if let Some(size) = row.cluster_size {
+                        line.push_str(&format!(", {}", size));
+                    }
+                    if let Some(status) = row.status {
+                        line.push_str(&format!(", {}", status));
+                    }
+                    self.write_line(&line);
                 }
             }
-        }
-    }
-
-    // Generate triple encoding substitutions for longer domains (length >= 5)
-    if original_chars.len() >= 5 && max_errors >= 3 {
-        for i in 0..character_encodings.len() {
-            for j in (i + 2)..character_encodings.len() {
-                for k in (j + 2)..character_encodings.len() {
-                    // Very selective sampling for triple encodings
-                    for &(pos1, _error_type1, replacement1) in character_encodings[i].iter().take(1)
+            OutputFormat::Table => self.write_table(rows),
+            OutputFormat::Jsonl => {
+                for (i, row) in rows.iter().enumerate() {
+                    let mut record = self.record(row);
+                    if let (Some(extra), Some(fields)) = (extras.get_mut(i), record.as_object_mut())
                     {
-                        for &(pos2, _error_type2, replacement2) in
-                            character_encodings[j].iter().take(1)
-                        {
-                            for &(pos3, _error_type3, replacement3) in
-                                character_encodings[k].iter().take(1)
-                            {
-                                let result = apply_triple_encoding(
-                                    original_chars,
-                                    pos1,
-                                    replacement1,
-                                    pos2,
-                                    replacement2,
-                                    pos3,
-                                    replacement3,
-                                );
-                                if let Some(result_domain) = result {
-                                    if result_domain != domain {
-                                        variations.push(format!("{}.{}", result_domain, tld));
-                                    }
-                                }
-                            }
+                        for (key, value) in std::mem::take(extra) {
+                            fields.entry(key).or_insert(value);
                         }
                     }
+                    self.write_line(&record.to_string());
                 }
             }
-        }
-    }
-}
-
-fn apply_single_encoding(chars: &[char], pos: usize, replacement: char) -> Option<String> {
-    if pos >= chars.len() {
-        return None;
-    }
-
-    let mut result_chars = chars.to_vec();
-    result_chars[pos] = replacement;
-    Some(result_chars.iter().collect())
-}
-
-fn apply_double_encoding(
-    chars: &[char],
-    pos1: usize,
-    replacement1: char,
-    pos2: usize,
-    replacement2: char,
-) -> Option<String> {
-    if pos1 >= chars.len() || pos2 >= chars.len() {
-        return None;
-    }
-
-    let mut result_chars = chars.to_vec();
-    result_chars[pos1] = replacement1;
-    result_chars[pos2] = replacement2;
-    Some(result_chars.iter().collect())
-}
-
-fn apply_triple_encoding(
-    chars: &[char],
-    pos1: usize,
-    replacement1: char,
-    pos2: usize,
-    replacement2: char,
-    pos3: usize,
-    replacement3: char,
-) -> Option<String> {
-    if pos1 >= chars.len() || pos2 >= chars.len() || pos3 >= chars.len() {
-        return None;
-    }
-
-    let mut result_chars = chars.to_vec();
-    result_chars[pos1] = replacement1;
-    result_chars[pos2] = replacement2;
-    result_chars[pos3] = replacement3;
-    Some(result_chars.iter().collect())
-}
-
-fn generate_misspelling(domain: &str, tld: &str) -> Vec<String> {
-    let mut variations = Vec::new();
-    let chars: Vec<char> = domain.chars().collect();
-
-    // QWERTY keyboard map for adjacent keys
-    let qwerty_map = [
-        ('q', "wa"),
-        ('w', "qes"),
-        ('e', "wrd"),
-        ('r', "etf"),
-        ('t', "rgy"),
-        ('y', "tuh"),
-        ('u', "yio"),
-        ('i', "uop"),
-        ('o', "ip"),
-        ('p', "o"),
-        ('a', "qsz"),
-        ('s', "awdz"),
-        ('d', "sefx"),
-        ('f', "dgrc"),
-        ('g', "fthv"),
-        ('h', "gyjb"),
-        ('j', "hukn"),
-        ('k', "julm"),
-        ('l', "km"),
-        ('z', "asx"),
-        ('x', "zsdc"),
-        ('c', "xdfv"),
-        ('v', "cfgb"),
-        ('b', "vghn"),
-        ('n', "bhjm"),
-        ('m', "njk"),
-    ];
-
-    // Vowel substitution mappings from vowel-swap
-    let vowel_swap_map: std::collections::HashMap<char, Vec<char>> = [
-        ('a', vec!['e', 'i', 'o', 'u']),
-        ('e', vec!['a', 'i', 'o', 'u']),
-        ('i', vec!['a', 'e', 'o', 'u']),
-        ('o', vec!['a', 'e', 'i', 'u']),
-        ('u', vec!['a', 'e', 'i', 'o']),
-    ]
-    .iter()
-    .cloned()
-    .collect();
-
-    // Calculate realistic constraints based on domain length
-    let max_errors = (chars.len() / 2).max(1); // 1 error per 2 characters, minimum 1
-    let max_length = (domain.len() as f32 * 1.8) as usize; // Max 180% of original length (more lenient than fat-finger)
-
-    // Find all character positions where misspelling errors can occur
-    let mut character_errors = Vec::new();
-
-    for (pos, &ch) in chars.iter().enumerate() {
-        let mut pos_errors = Vec::new();
-
-        // Character insertion (add random char before this position)
-        pos_errors.push((pos, "insert", 'a')); // Representative insertion
-
-        // Character deletion
-        pos_errors.push((pos, "delete", ch));
-
-        // Character transposition (with next character)
-        if pos + 1 < chars.len() {
-            pos_errors.push((pos, "transpose", chars[pos + 1]));
-        }
-
-        // Keyboard adjacent substitution
-        let lower_ch = ch.to_ascii_lowercase();
-        for (orig_char, adjacent_chars) in &qwerty_map {
-            if lower_ch == *orig_char {
-                for adj_char in adjacent_chars.chars() {
-                    pos_errors.push((pos, "keyboard_sub", adj_char));
+            #[cfg(feature = "parquet")]
+            OutputFormat::Parquet => self.parquet_columns.push(rows, self.score_columns),
+            OutputFormat::Cef => {
+                let received = chrono::Utc::now().timestamp_millis();
+                for row in rows {
+                    let line = cef_record(row, &self.original_domain, received, self.score_columns);
+                    self.write_line(&line);
                 }
-                break;
             }
-        }
-
-        // Vowel substitution
-        if let Some(vowel_substitutes) = vowel_swap_map.get(&lower_ch) {
-            for &substitute_vowel in vowel_substitutes {
-                let final_vowel = if ch.is_uppercase() {
-                    substitute_vowel.to_ascii_uppercase()
-                } else {
-                    substitute_vowel
-                };
-                pos_errors.push((pos, "vowel_sub", final_vowel));
+            OutputFormat::Leef => {
+                for row in rows {
+                    let line = leef_record(row, &self.original_domain, self.score_columns);
+                    self.write_line(&line);
+                }
             }
         }
-
-        if !pos_errors.is_empty() {
-            character_errors.push(pos_errors);
-        }
+        Ok(rows.len())
     }
 
-    // Generate realistic combinations with constraints
-    generate_misspelling_combinations(
-        &chars,
-        &character_errors,
-        max_errors,
-        max_length,
-        tld,
-        &mut variations,
-    );
-
-    variations
-}
+    fn write_table(&mut self, rows: &[ResultRow]) {
+        for row in rows {
+            self.domain_width = self.domain_width.max(row.domain.chars().count());
+            self.transformation_width = self
+                .transformation_width
+                .max(row.transformation.chars().count());
+            self.seed_width = self
+                .seed_width
+                .max(row.seed.map_or(0, |seed| seed.chars().count()));
+        }
+        let with_seed = rows.iter().any(|row| row.seed.is_some());
+        let with_cluster = rows.iter().any(|row| row.cluster_size.is_some());
+        let with_status = rows.iter().any(|row| row.status.is_some());
 
-fn generate_misspelling_combinations(
-    original_chars: &[char],
-    character_errors: &[Vec<(usize, &str, char)>],
-    max_errors: usize,
-    max_length: usize,
-    tld: &str,
-    variations: &mut Vec<String>,
-) {
-    let domain: String = original_chars.iter().collect();
-
-    // Generate single misspelling errors first (most realistic)
-    for pos_errors in character_errors {
-        for &(pos, error_type, replacement) in pos_errors {
-            let result = apply_single_misspelling(original_chars, pos, error_type, replacement);
-            if let Some(result_domain) = result {
-                if result_domain != domain
-                    && result_domain.len() <= max_length
-                    && !result_domain.is_empty()
-                {
-                    variations.push(format!("{}.{}", result_domain, tld));
-                }
+        if !self.header_printed {
+            let mut header = format!("{:>7}  ", "SCORE");
+            if self.score_columns {
+                header.push_str(&format!("{:>7}  {:>9}  ", "VISUAL", "COGNITIVE"));
+            }
+            if self.typo_likelihood {
+                header.push_str(&format!("{:>9}  ", "TYPO"));
             }
+            header.push_str(&format!(
+                "{}  {}",
+                pad_right("DOMAIN", self.domain_width),
+                pad_right("TRANSFORMATION", self.transformation_width)
+            ));
+            if with_seed {
+                header.push_str(&format!("  {}", pad_right("SEED", self.seed_width)));
+            }
+            if with_cluster {
+                header.push_str(&format!("  {:>7}", "CLUSTER"));
+            }
+            if with_status {
+                header.push_str("  STATUS");
+            }
+            let header = self.paint(header.trim_end(), ANSI_DIM);
+            self.write_line(&header);
+            self.header_printed = true;
+        }
+
+        for row in rows {
+            let score = format!("{:>6.2}%", row.score * 100.0);
+            let mut line = format!("{}  ", self.paint(&score, score_color(row.score)));
+            if self.score_columns {
+                line.push_str(&format!(
+                    "{:>6.2}%  {:>8.2}%  ",
+                    row.visual_score * 100.0,
+                    row.cognitive_score * 100.0
+                ));
+            }
+            if let Some(likelihood) = row.typo_likelihood {
+                line.push_str(&format!("{:>9}  ", format!("{:.2e}", likelihood)));
+            }
+            line.push_str(&format!(
+                "{}  {}",
+                pad_right(row.domain, self.domain_width),
+                pad_right(row.transformation, self.transformation_width)
+            ));
+            if let Some(seed) = row.seed {
+                line.push_str(&format!("  {}", pad_right(seed, self.seed_width)));
+            }
+            if let Some(size) = row.cluster_size {
+                line.push_str(&format!("  {:>7}", size));
+            }
+            if let Some(status) = row.status {
+                line.push_str("  ");
+                line.push_str(&self.paint(status, status_color(status)));
+            }
+            self.write_line(line.trim_end());
         }
     }
 
-    // Generate double misspellings for longer domains (length >= 4)
-    if original_chars.len() >= 4 && max_errors >= 2 {
-        for i in 0..character_errors.len() {
-            for j in (i + 1)..character_errors.len() {
-                // Allow errors on adjacent characters for misspellings (unlike fat-finger)
-                // But limit combinations to avoid explosion
-                if j - i <= 2 {
-                    // Only adjacent or 1-apart characters
-                    for &(pos1, error_type1, replacement1) in character_errors[i].iter().take(2) {
-                        for &(pos2, error_type2, replacement2) in character_errors[j].iter().take(2)
-                        {
-                            // Avoid incompatible error combinations
-                            if !are_incompatible_misspelling_errors(
-                                pos1,
-                                error_type1,
-                                pos2,
-                                error_type2,
-                            ) {
-                                let result = apply_double_misspelling(
-                                    original_chars,
-                                    pos1,
-                                    error_type1,
-                                    replacement1,
-                                    pos2,
-                                    error_type2,
-                                    replacement2,
-                                );
-                                if let Some(result_domain) = result {
-                                    if result_domain != domain
-                                        && result_domain.len() <= max_length
-                                        && !result_domain.is_empty()
-                                    {
-                                        variations.push(format!("{}.{}", result_domain, tld));
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            }
+    fn paint(&self, text: &str, color: &str) -> String {
+        if self.color && !color.is_empty() {
+            format!("{}{}{}", color, text, ANSI_RESET)
+        } else {
+            text.to_string()
         }
     }
 }
 
-fn are_incompatible_misspelling_errors(pos1: usize, type1: &str, pos2: usize, type2: &str) -> bool {
-    // Don't delete adjacent characters (would create too many gaps)
-    if (type1 == "delete" && type2 == "delete") && (pos2 == pos1 + 1 || pos1 == pos2 + 1) {
-        return true;
+/// CEF/LEEF vendor, product, and event ID of every finding
+const SIEM_VENDOR: &str = "DomFuzz";
+const SIEM_PRODUCT: &str = "domfuzz";
+const SIEM_EVENT_ID: &str = "lookalike-domain";
+
+/// Severity of a finding on the 0-10 scale of CEF and LEEF: the similarity
+/// score, raised to at least 7 for registered (including parked) variations
+fn siem_severity(row: &ResultRow) -> u8 {
+    let severity = (row.score * 10.0).round().clamp(0.0, 10.0) as u8;
+    if row.status.is_some_and(is_registered_status) {
+        severity.max(7)
+    } else {
+        severity
     }
+}
 
-    // Don't transpose overlapping ranges
-    if type1 == "transpose" && type2 == "transpose" && (pos2 <= pos1 + 1) {
-        return true;
+/// The CEF record of a row, with `rt` (milliseconds since the epoch) as
+/// `received`. The domain is `dhost`; the transformation, status, and
+/// original domain are labeled custom strings and the score a custom float.
+fn cef_record(
+    row: &ResultRow,
+    original_domain: &str,
+    received: i64,
+    score_columns: bool,
+) -> String {
+    let value = |field: &str| {
+        field
+            .replace('\\', "\\\\")
+            .replace('=', "\\=")
+            .replace('\n', "\\n")
+            .replace('\r', "\\r")
+    };
+    let mut extension = format!(
+        "rt={} dhost={} cs1Label=transformation cs1={} cfp1Label=score cfp1={:.4}",
+        received,
+        value(row.domain),
+        value(row.transformation),
+        row.score
+    );
+    if score_columns {
+        extension.push_str(&format!(
+            " cfp2Label=visualScore cfp2={:.4} cfp3Label=cognitiveScore cfp3={:.4}",
+            row.visual_score, row.cognitive_score
+        ));
     }
-
-    // Don't delete and transpose the same position
-    if (type1 == "delete" && type2 == "transpose" && pos1 == pos2)
-        || (type2 == "delete" && type1 == "transpose" && pos2 == pos1)
-    {
-        return true;
+    if let Some(likelihood) = row.typo_likelihood {
+        extension.push_str(&format!(
+            " cfp4Label=typoLikelihood cfp4={:.10}",
+            likelihood
+        ));
     }
-
-    false
+    if let Some(status) = row.status {
+        extension.push_str(&format!(" cs2Label=status cs2={}", value(status)));
+    }
+    if let Some(seed) = row.seed {
+        extension.push_str(&format!(" cs4Label=seed cs4={}", value(seed)));
+    }
+    if let Some(size) = row.cluster_size {
+        extension.push_str(&format!(" cn1Label=clusterSize cn1={}", size));
+    }
+    if !original_domain.is_empty() {
+        extension.push_str(&format!(
+            " cs3Label=originalDomain cs3={}",
+            value(original_domain)
+        ));
+    }
+    format!(
+        "CEF:0|{}|{}|{}|{}|Lookalike domain|{}|{}",
+        SIEM_VENDOR,
+        SIEM_PRODUCT,
+        env!("CARGO_PKG_VERSION"),
+        SIEM_EVENT_ID,
+        siem_severity(row),
+        extension
+    )
 }
 
-fn apply_single_misspelling(
-    chars: &[char],
-    pos: usize,
-    error_type: &str,
-    replacement: char,
-) -> Option<String> {
-    let mut result_chars = chars.to_vec();
-
-    match error_type {
-        "insert" => {
-            // Insert a common typo character
-            let typo_chars = ['a', 'e', 'i', 'o', 'u', 's', 't', 'n', 'r'];
-            let typo_char = typo_chars[pos % typo_chars.len()];
-            result_chars.insert(pos, typo_char);
-        }
-        "delete" => {
-            if pos < result_chars.len() {
-                result_chars.remove(pos);
-            }
-        }
-        "transpose" => {
-            if pos + 1 < result_chars.len() {
-                result_chars.swap(pos, pos + 1);
-            }
-        }
-        "keyboard_sub" => {
-            if pos < result_chars.len() {
-                result_chars[pos] = if chars[pos].is_uppercase() {
-                    replacement.to_ascii_uppercase()
-                } else {
-                    replacement
-                };
-            }
-        }
-        "vowel_sub" => {
-            if pos < result_chars.len() {
-                result_chars[pos] = replacement;
-            }
-        }
-        _ => return None,
+/// The LEEF 1.0 record of a row: tab-separated `key=value` attributes, with
+/// the domain as `domain`, the transformation as `cat`, and the severity as `sev`
+fn leef_record(row: &ResultRow, original_domain: &str, score_columns: bool) -> String {
+    let value = |field: &str| field.replace(['\t', '\n', '\r'], " ");
+    let mut attributes = vec![
+        format!("cat={}", value(row.transformation)),
+        format!("sev={}", siem_severity(row)),
+        format!("domain={}", value(row.domain)),
+        format!("score={:.4}", row.score),
+    ];
+    if score_columns {
+        attributes.push(format!("visualScore={:.4}", row.visual_score));
+        attributes.push(format!("cognitiveScore={:.4}", row.cognitive_score));
+    }
+    if let Some(likelihood) = row.typo_likelihood {
+        attributes.push(format!("typoLikelihood={:.10}", likelihood));
+    }
+    if let Some(status) = row.status {
+        attributes.push(format!("status={}", value(status)));
+    }
+    if let Some(seed) = row.seed {
+        attributes.push(format!("seed={}", value(seed)));
+    }
+    if let Some(size) = row.cluster_size {
+        attributes.push(format!("clusterSize={}", size));
     }
+    if !original_domain.is_empty() {
+        attributes.push(format!("originalDomain={}", value(original_domain)));
+    }
+    format!(
+        "LEEF:1.0|{}|{}|{}|{}|{}",
+        SIEM_VENDOR,
+        SIEM_PRODUCT,
+        env!("CARGO_PKG_VERSION"),
+        SIEM_EVENT_ID,
+        attributes.join("\t")
+    )
+}
 
-    if result_chars.is_empty() {
-        return None;
+/// Version of the JSON Lines record layout, stamped on every record as
+/// `schema_version`; bumped whenever a field is removed, renamed, or changes meaning
+const SCHEMA_VERSION: u32 = 1;
+/// JSON Schema of the records, printed by `domfuzz schema`
+const RESULT_SCHEMA: &str = include_str!("../data/result.schema.json");
+
+/// The JSON Lines record of a row, with the score's components if
+/// `score_columns` is set
+fn row_record(row: &ResultRow, score_columns: bool) -> serde_json::Value {
+    let mut record = serde_json::json!({
+        "schema_version": SCHEMA_VERSION,
+        "domain": row.domain,
+        "transformation": row.transformation,
+        "score": row.score,
+    });
+    if let Some(seed) = row.seed {
+        record["seed"] = seed.into();
+    }
+    if let Some(size) = row.cluster_size {
+        record["cluster_size"] = size.into();
+    }
+    if score_columns {
+        record["visual_score"] = row.visual_score.into();
+        record["cognitive_score"] = row.cognitive_score.into();
+    }
+    if let Some(likelihood) = row.typo_likelihood {
+        record["typo_likelihood"] = likelihood.into();
+    }
+    if let Some(status) = row.status {
+        record["status"] = status.into();
+    }
+    if let Some(price) = row
+        .enrichment
+        .and_then(|enrichment| enrichment.listing.as_ref()?.price.as_ref())
+    {
+        record["price"] = price.to_json();
     }
+    if let Some(enrichment) = row.enrichment {
+        record["enrichment"] = enrichment.to_json();
+    }
+    record
+}
 
-    Some(result_chars.iter().collect())
+/// `--post-filter`: a shell command that vets each result, run by the POSIX
+/// `sh` on the `PATH`
+struct PostFilter(String);
+
+impl PostFilter {
+    /// Pipe `record` to the command. `None` if it exited non-zero (drop the
+    /// result); otherwise the fields of the JSON object it printed, if any.
+    async fn run(
+        &self,
+        record: &serde_json::Value,
+    ) -> Result<Option<serde_json::Map<String, serde_json::Value>>, DomFuzzError> {
+        use tokio::io::AsyncWriteExt;
+
+        let failed = |e: io::Error| DomFuzzError::Config(format!("--post-filter: {}", e));
+        let mut child = tokio::process::Command::new("sh")
+            .arg("-c")
+            .arg(&self.0)
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .spawn()
+            .map_err(failed)?;
+        if let Some(mut stdin) = child.stdin.take() {
+            // A filter may decide without reading its input
+            let _ = stdin.write_all(format!("{}\n", record).as_bytes()).await;
+        }
+        let output = child.wait_with_output().await.map_err(failed)?;
+        if !output.status.success() {
+            return Ok(None);
+        }
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        if stdout.trim().is_empty() {
+            return Ok(Some(serde_json::Map::new()));
+        }
+        match serde_json::from_str(stdout.trim()) {
+            Ok(serde_json::Value::Object(fields)) => Ok(Some(fields)),
+            _ => Err(DomFuzzError::Parse(format!(
+                "--post-filter must print nothing or a JSON object, got: {}",
+                stdout.trim()
+            ))),
+        }
+    }
 }
 
-fn apply_double_misspelling(
-    chars: &[char],
-    pos1: usize,
-    error_type1: &str,
-    replacement1: char,
-    pos2: usize,
-    error_type2: &str,
-    replacement2: char,
-) -> Option<String> {
-    // Apply first error
-    let intermediate = apply_single_misspelling(chars, pos1, error_type1, replacement1)?;
-    let intermediate_chars: Vec<char> = intermediate.chars().collect();
+/// Result rows buffered column by column for `--format parquet`
+#[cfg(feature = "parquet")]
+#[derive(Default)]
+struct ParquetColumns {
+    domain: Vec<String>,
+    transformation: Vec<String>,
+    score: Vec<f64>,
+    status: Vec<Option<String>>,
+    /// Score components, present with `--score-columns`
+    components: Option<(Vec<f64>, Vec<f64>)>,
+    /// Present with `--typo-likelihood`
+    typo_likelihood: Option<Vec<f64>>,
+    /// Present with `--alias`
+    seed: Option<Vec<String>>,
+    /// Present with `--collapse-near-duplicates`
+    cluster_size: Option<Vec<u64>>,
+}
 
-    // Adjust position for second error based on first error's effect
-    let adjusted_pos2 = match error_type1 {
-        "insert" => {
-            if pos2 > pos1 {
-                pos2 + 1
-            } else {
-                pos2
-            }
+#[cfg(feature = "parquet")]
+impl ParquetColumns {
+    fn push(&mut self, rows: &[ResultRow], score_columns: bool) {
+        for row in rows {
+            self.domain.push(row.domain.to_string());
+            self.transformation.push(row.transformation.to_string());
+            self.score.push(row.score);
+            self.status.push(row.status.map(str::to_string));
+        }
+        if score_columns {
+            let (visual, cognitive) = self.components.get_or_insert_default();
+            visual.extend(rows.iter().map(|row| row.visual_score));
+            cognitive.extend(rows.iter().map(|row| row.cognitive_score));
+        }
+        if rows.iter().any(|row| row.typo_likelihood.is_some()) {
+            self.typo_likelihood.get_or_insert_default().extend(
+                rows.iter()
+                    .map(|row| row.typo_likelihood.unwrap_or_default()),
+            );
         }
-        "delete" => {
-            if pos2 > pos1 && pos2 > 0 {
-                pos2 - 1
-            } else {
-                pos2
-            }
+        if rows.iter().any(|row| row.seed.is_some()) {
+            self.seed.get_or_insert_default().extend(
+                rows.iter()
+                    .map(|row| row.seed.unwrap_or_default().to_string()),
+            );
         }
-        _ => pos2,
-    };
+        if rows.iter().any(|row| row.cluster_size.is_some()) {
+            self.cluster_size
+                .get_or_insert_default()
+                .extend(rows.iter().map(|row| row.cluster_size.unwrap_or(1) as u64));
+        }
+    }
+
+    /// Write all buffered rows as a single zstd-compressed row group, returning `out`
+    fn write<W: Write + Send>(self, out: W) -> Result<W, DomFuzzError> {
+        use arrow_array::{ArrayRef, Float64Array, RecordBatch, StringArray, UInt64Array};
+        use arrow_schema::{DataType, Field, Schema};
+        use parquet::arrow::ArrowWriter;
+        use parquet::basic::{Compression, ZstdLevel};
+        use parquet::file::properties::WriterProperties;
+        use std::sync::Arc;
+
+        let mut fields = vec![
+            Field::new("domain", DataType::Utf8, false),
+            Field::new("transformation", DataType::Utf8, false),
+            Field::new("score", DataType::Float64, false),
+            Field::new("status", DataType::Utf8, true),
+        ];
+        let mut columns: Vec<ArrayRef> = vec![
+            Arc::new(StringArray::from(self.domain)),
+            Arc::new(StringArray::from(self.transformation)),
+            Arc::new(Float64Array::from(self.score)),
+            Arc::new(StringArray::from(self.status)),
+        ];
+        if let Some((visual, cognitive)) = self.components {
+            fields.push(Field::new("visual_score", DataType::Float64, false));
+            fields.push(Field::new("cognitive_score", DataType::Float64, false));
+            columns.push(Arc::new(Float64Array::from(visual)));
+            columns.push(Arc::new(Float64Array::from(cognitive)));
+        }
+        if let Some(likelihood) = self.typo_likelihood {
+            fields.push(Field::new("typo_likelihood", DataType::Float64, false));
+            columns.push(Arc::new(Float64Array::from(likelihood)));
+        }
+        if let Some(seed) = self.seed {
+            fields.push(Field::new("seed", DataType::Utf8, false));
+            columns.push(Arc::new(StringArray::from(seed)));
+        }
+        if let Some(size) = self.cluster_size {
+            fields.push(Field::new("cluster_size", DataType::UInt64, false));
+            columns.push(Arc::new(UInt64Array::from(size)));
+        }
+        let schema = Arc::new(Schema::new(fields));
+        let parquet_error = |e: &dyn std::fmt::Display| {
+            DomFuzzError::Io(io::Error::other(format!("parquet: {}", e)))
+        };
+        let batch = RecordBatch::try_new(schema.clone(), columns).map_err(|e| parquet_error(&e))?;
 
-    // Apply second error
-    apply_single_misspelling(
-        &intermediate_chars,
-        adjusted_pos2,
-        error_type2,
-        replacement2,
-    )
+        let properties = WriterProperties::builder()
+            .set_compression(Compression::ZSTD(ZstdLevel::default()))
+            .build();
+        let mut writer =
+            ArrowWriter::try_new(out, schema, Some(properties)).map_err(|e| parquet_error(&e))?;
+        writer.write(&batch).map_err(|e| parquet_error(&e))?;
+        writer.into_inner().map_err(|e| parquet_error(&e))
+    }
 }
 
-fn generate_subdomain_injection(domain: &str, tld: &str) -> Vec<String> {
-    let mut variations = Vec::new();
-
-    let chars: Vec<char> = domain.chars().collect();
-    for i in 1..chars.len() {
-        // Skip positions that would create consecutive dots
-        if i > 0 && chars[i - 1] == '.' {
-            continue;
-        }
-        if i < chars.len() && chars[i] == '.' {
-            continue;
-        }
+/// Pad a string to a display width counted in characters
+fn pad_right(text: &str, width: usize) -> String {
+    let len = text.chars().count();
+    format!("{}{}", text, " ".repeat(width.saturating_sub(len)))
+}
 
-        // Convert char index to byte index for insertion
-        let byte_pos = domain
-            .char_indices()
-            .nth(i)
-            .map(|(pos, _)| pos)
-            .unwrap_or(domain.len());
-        let mut new_domain = domain.to_string();
-        new_domain.insert(byte_pos, '.');
-        variations.push(format!("{}.{}", new_domain, tld));
+/// Heat color for a similarity score: the closer to the original, the hotter
+fn score_color(score: f64) -> &'static str {
+    if score >= 0.9 {
+        ANSI_BOLD_RED
+    } else if score >= 0.75 {
+        ANSI_RED
+    } else if score >= 0.6 {
+        ANSI_YELLOW
+    } else {
+        ""
     }
+}
 
-    variations
+/// Color for a domain status: available is green, registered red, parked,
+/// sinkholed, and placeholder yellow
+fn status_color(status: &str) -> &'static str {
+    match status {
+        "available" => ANSI_GREEN,
+        "registered" => ANSI_RED,
+        "parked" | "for-sale" | "sinkholed" | "placeholder" => ANSI_YELLOW,
+        _ => ANSI_DIM,
+    }
 }
 
-fn generate_tld_variations(domain: &str, _tld: &str) -> Vec<String> {
-    let mut variations = Vec::new();
+// ==================== PROGRESS REPORTING ====================
 
-    let tlds = [
-        "com", "net", "org", "info", "biz", "us", "co", "io", "me", "app", "dev", "tech", "online",
-        "site", "store", "shop", "uk", "ca", "de", "fr", "ru", "cn", "jp", "au", "br", "tk", "ml",
-        "ga", "cf",
-    ];
+/// How phase progress is reported on stderr
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum ProgressMode {
+    /// Progress bars, hidden when stderr is not a terminal
+    Bar,
+    /// One JSON object per line, for wrapping UIs
+    Json,
+}
 
-    for &new_tld in &tlds {
-        variations.push(format!("{}.{}", domain, new_tld));
-    }
+/// Minimum time between periodic `--progress-format json` events
+const PROGRESS_EVENT_INTERVAL: Duration = Duration::from_secs(1);
 
-    variations
+/// Run-wide totals carried by every JSON progress event, since combo mode
+/// checks in many short phases: candidates generated, domains checked, and
+/// checked domains per status
+static GENERATED: AtomicU64 = AtomicU64::new(0);
+static CHECKED: AtomicU64 = AtomicU64::new(0);
+static STATUS_TALLIES: Mutex<BTreeMap<String, u64>> = Mutex::new(BTreeMap::new());
+
+fn record_generated(count: u64) {
+    GENERATED.fetch_add(count, AtomicOrdering::Relaxed);
 }
 
-fn generate_word_swaps(domain: &str, tld: &str) -> Vec<String> {
-    let mut variations = Vec::new();
-    let chars: Vec<char> = domain.chars().collect();
+fn record_checked(status: &str) {
+    CHECKED.fetch_add(1, AtomicOrdering::Relaxed);
+    let mut tallies = STATUS_TALLIES.lock().unwrap_or_else(|e| e.into_inner());
+    *tallies.entry(status.to_string()).or_default() += 1;
+}
 
-    if chars.len() >= 4 {
-        let mid = chars.len() / 2;
-        let first_half: String = chars[..mid].iter().collect();
-        let second_half: String = chars[mid..].iter().collect();
-        variations.push(format!("{}{}.{}", second_half, first_half, tld));
-    }
+/// Progress of one phase of a run (`generate` or `check`): completed units,
+/// failures, throughput, and ETA
+struct PhaseProgress {
+    phase: &'static str,
+    mode: ProgressMode,
+    bar: ProgressBar,
+    total: u64,
+    done: Cell<u64>,
+    failed: Cell<u64>,
+    started: Instant,
+    last_event: Cell<Instant>,
+}
 
-    if chars.len() >= 6 {
-        let third = chars.len() / 3;
-        let first_third: String = chars[..third].iter().collect();
-        let middle_third: String = chars[third..2 * third].iter().collect();
-        let last_third: String = chars[2 * third..].iter().collect();
-        variations.push(format!(
-            "{}{}{}.{}",
-            last_third, middle_third, first_third, tld
-        ));
+impl PhaseProgress {
+    fn new(phase: &'static str, total: u64, mode: ProgressMode) -> Self {
+        let bar = match mode {
+            ProgressMode::Bar => ProgressBar::new(total),
+            ProgressMode::Json => ProgressBar::hidden(),
+        };
+        let template = if phase == "check" {
+            "{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos:>7}/{len:7} {per_sec} ETA {eta} {msg}"
+        } else {
+            "{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos:>3}/{len:3} {msg}"
+        };
+        bar.set_style(
+            ProgressStyle::default_bar()
+                .template(template)
+                .expect("Failed to set progress bar template")
+                .progress_chars("█▉▊▋▌▍▎▏  "),
+        );
+
+        let now = Instant::now();
+        let progress = PhaseProgress {
+            phase,
+            mode,
+            bar,
+            total,
+            done: Cell::new(0),
+            failed: Cell::new(0),
+            started: now,
+            last_event: Cell::new(now),
+        };
+        progress.emit("phase_start");
+        progress
     }
 
-    variations
-}
+    /// Record one completed unit of work; `detail` replaces the bar message
+    fn advance(&self, failed: bool, detail: &str) {
+        self.done.set(self.done.get() + 1);
+        if failed {
+            self.failed.set(self.failed.get() + 1);
+        }
+        self.bar.inc(1);
 
-fn generate_bitsquatting(domain: &str, tld: &str) -> Vec<String> {
-    let mut variations = Vec::new();
-    let original_full = format!("{}.{}", domain, tld);
+        let failures = self.failed.get();
+        match (detail.is_empty(), failures) {
+            (true, 0) => {}
+            (true, n) => self.bar.set_message(format!("{} failed", n)),
+            (false, 0) => self.bar.set_message(detail.to_string()),
+            (false, n) => self.bar.set_message(format!("{} ({} failed)", detail, n)),
+        }
 
-    let chars: Vec<char> = domain.chars().collect();
+        if self.last_event.get().elapsed() >= PROGRESS_EVENT_INTERVAL {
+            self.last_event.set(Instant::now());
+            self.emit("progress");
+        }
+    }
 
-    for (i, &ch) in chars.iter().enumerate() {
-        let ch_code = ch as u8;
-        for bit_pos in 0..8 {
-            let flipped_code = ch_code ^ (1 << bit_pos);
-            if let Some(flipped_char) = char::from_u32(flipped_code as u32) {
-                if flipped_char.is_ascii_alphabetic() || flipped_char.is_ascii_digit() {
-                    let mut new_domain = String::new();
-                    new_domain.push_str(&chars[..i].iter().collect::<String>());
-                    new_domain.push(flipped_char);
-                    new_domain.push_str(&chars[i + 1..].iter().collect::<String>());
-                    let new_full = format!("{}.{}", new_domain, tld);
+    fn finish(&self, message: &'static str) {
+        self.bar.finish_with_message(message);
+        self.emit("phase_complete");
+    }
 
-                    // Don't add if it's the same as original (case-insensitive)
-                    if new_full.to_lowercase() != original_full.to_lowercase() {
-                        variations.push(new_full);
-                    }
-                }
-            }
+    /// Print one JSON progress event: the phase's own counts, rate, and ETA,
+    /// followed by the run-wide totals
+    fn emit(&self, event: &str) {
+        if self.mode != ProgressMode::Json {
+            return;
         }
+        let elapsed = self.started.elapsed().as_secs_f64();
+        let done = self.done.get();
+        let rate = if elapsed > 0.0 {
+            done as f64 / elapsed
+        } else {
+            0.0
+        };
+        let eta_secs = (rate > 0.0).then(|| self.total.saturating_sub(done) as f64 / rate);
+        let statuses = STATUS_TALLIES
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+        eprintln!(
+            "{}",
+            serde_json::json!({
+                "event": event,
+                "phase": self.phase,
+                "done": done,
+                "total": self.total,
+                "failed": self.failed.get(),
+                "rate": rate,
+                "eta_secs": eta_secs,
+                "elapsed_secs": elapsed,
+                "generated": GENERATED.load(AtomicOrdering::Relaxed),
+                "checked": CHECKED.load(AtomicOrdering::Relaxed),
+                "statuses": statuses,
+            })
+        );
     }
+}
 
-    variations
-}
-
-fn generate_fat_finger(domain: &str, tld: &str) -> Vec<String> {
-    let mut variations = Vec::new();
-    let chars: Vec<char> = domain.chars().collect();
-
-    // QWERTY keyboard map for adjacent keys
-    let qwerty_map = [
-        ('q', "wa"),
-        ('w', "qes"),
-        ('e', "wrd"),
-        ('r', "etf"),
-        ('t', "rgy"),
-        ('y', "tuh"),
-        ('u', "yio"),
-        ('i', "uop"),
-        ('o', "ip"),
-        ('p', "o"),
-        ('a', "qsz"),
-        ('s', "awdz"),
-        ('d', "sefx"),
-        ('f', "dgrc"),
-        ('g', "fthv"),
-        ('h', "gyjb"),
-        ('j', "hukn"),
-        ('k', "julm"),
-        ('l', "km"),
-        ('z', "asx"),
-        ('x', "zsdc"),
-        ('c', "xdfv"),
-        ('v', "cfgb"),
-        ('b', "vghn"),
-        ('n', "bhjm"),
-        ('m', "njk"),
-    ];
+// ==================== MONITORING ====================
 
-    // Calculate realistic constraints based on domain length
-    let max_errors = (chars.len() / 2).max(1); // 1 error per 2 characters, minimum 1
-    let max_length = (domain.len() as f32 * 1.5) as usize; // Max 150% of original length
+/// Default `monitor --interval`: hourly
+const DEFAULT_MONITOR_INTERVAL_SECS: u64 = 3600;
 
-    // Find all character positions where errors can occur (max 1 error type per position)
-    let mut character_errors = Vec::new();
+/// MX, RDAP, or WHOIS lookups or TLS handshakes in flight at once during a
+/// monitor cycle; RDAP and WHOIS are also limited per endpoint
+const PROBE_CONCURRENCY: usize = 16;
 
-    for (pos, &ch) in chars.iter().enumerate() {
-        let mut pos_errors = Vec::new();
+/// A variation watched by `monitor`
+struct MonitoredVariation {
+    domain: String,
+    transformation: &'static str,
+    score: f64,
+}
 
-        // Character repetition
-        pos_errors.push((pos, "repeat", ch));
+async fn run_monitor(args: &MonitorArgs) {
+    use futures::StreamExt;
+
+    let file_config = Config::load(args.config.as_deref()).unwrap_or_else(|e| exit_with(&e));
+    checker::configure(CheckOptions {
+        network: file_config
+            .network_settings(args.profile.as_deref())
+            .unwrap_or_else(|e| exit_with(&e)),
+        ..CheckOptions::default()
+    })
+    .unwrap_or_else(|e| exit_with(&e));
+    let target = args
+        .target
+        .as_deref()
+        .map(|path| TargetProfile::load(path).unwrap_or_else(|e| exit_with(&e)));
+    let input_domain = args
+        .domain
+        .as_deref()
+        .or(target.as_ref().map(TargetProfile::domain))
+        .expect("domain is required when no target is given");
+    let thresholds = target
+        .as_ref()
+        .map(|target| target.thresholds.clone())
+        .unwrap_or_default();
+    let parse =
+        |threshold: &str| parse_similarity_threshold(threshold).unwrap_or_else(|e| exit_with(&e));
+    let min_similarity = args
+        .min_similarity
+        .as_deref()
+        .map(parse)
+        .or(thresholds.min_similarity)
+        .unwrap_or(DEFAULT_MIN_SIMILARITY);
+    let max_similarity = args
+        .max_similarity
+        .as_deref()
+        .map(parse)
+        .or(thresholds.max_similarity);
+    check_similarity_range(Some(min_similarity), max_similarity).unwrap_or_else(|e| exit_with(&e));
+    let mut output = MonitorOutput {
+        events: match &args.events {
+            Some(path) => EventWriter::append(path).unwrap_or_else(|e| exit_with(&e.into())),
+            #[cfg(feature = "kafka")]
+            None if args.sink == Some(EventSink::Kafka) => EventWriter::new(Box::new(
+                KafkaSender::connect(args.brokers.as_deref().unwrap_or_default(), &args.topic)
+                    .unwrap_or_else(|e| exit_with(&e)),
+            )),
+            None => EventWriter::stdout(),
+        }
+        .with_tags(annotate::tags_json(&args.tag)),
+        rules: file_config.alerts.rules.clone(),
+        webhook: file_config.alerts.webhook.clone(),
+        quiet: HashSet::new(),
+    };
+    #[cfg(feature = "screenshots")]
+    let screenshots = match &args.screenshots {
+        Some(dir) => Some(
+            domfuzz::screenshot::ScreenshotOptions::prepare(
+                dir,
+                input_domain,
+                domfuzz::screenshot::DEFAULT_CLONE_DISTANCE,
+            )
+            .await
+            .unwrap_or_else(|e| exit_with(&e)),
+        ),
+        None => None,
+    };
 
-        // Adjacent key substitution
-        for (orig_char, adjacent_chars) in &qwerty_map {
-            if ch == *orig_char {
-                for adj_char in adjacent_chars.chars() {
-                    pos_errors.push((pos, "substitute", adj_char));
-                }
-                break; // Only one set of adjacent keys per character
+    // The variations are fixed for the whole run, so each cycle checks the same set
+    let parsed_domain = Domain::parse(input_domain).unwrap_or_else(|e| exit_with(&e));
+    let original_domain = parsed_domain.to_string();
+    let alias_names: Vec<String> = target
+        .iter()
+        .flat_map(TargetProfile::seeds)
+        .chain(args.alias.iter().cloned())
+        .collect();
+    let seeds: Vec<Domain> = std::iter::once(parsed_domain.clone())
+        .chain(parse_aliases(&alias_names, &parsed_domain).unwrap_or_else(|e| exit_with(&e)))
+        .collect();
+    let seed_registrable_domains: HashSet<String> = seeds.iter().map(Domain::registrable).collect();
+    let owned = target
+        .as_ref()
+        .map(TargetProfile::owned_domains)
+        .unwrap_or_default();
+    let mut enabled_transformations = file_config
+        .transformations(&args.transformation)
+        .unwrap_or_else(|e| exit_with(&e));
+    args.generation
+        .enable_transformations(&mut enabled_transformations);
+    let mut generator_options = args.generation.options(&parsed_domain.to_ascii()).await;
+    if let Some(target) = &target {
+        target.apply(&mut generator_options);
+    }
+    let mut variations: Vec<MonitoredVariation> = Vec::new();
+    let mut seen = HashSet::new();
+    for seed in &seeds {
+        let seed_domain = seed.to_string();
+        let generated = generate_variations_with_progress(
+            seed,
+            &enabled_transformations,
+            &generator_options,
+            |_, _| {},
+        );
+        for variation in generated {
+            if seed_registrable_domains.contains(&extract_registrable_domain(&variation.domain))
+                || normalizes_to_original(&seed_domain, &variation.domain)
+                || owned.contains(&variation.domain)
+                || !seen.insert(variation.domain.clone())
+            {
+                continue;
+            }
+            // Scored against the seed it came from
+            let score = calculate_similarity_with_mode(
+                &seed_domain,
+                &variation.domain,
+                variation.transformation,
+                args.scoring,
+            )
+            .combined_score;
+            if score >= min_similarity && max_similarity.is_none_or(|max| score <= max) {
+                variations.push(MonitoredVariation {
+                    domain: variation.domain,
+                    transformation: variation.transformation,
+                    score,
+                });
             }
         }
+    }
+    warn_script_error(&generator_options);
+    variations.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| a.domain.cmp(&b.domain))
+    });
+    variations.truncate(args.max_variations.unwrap_or(usize::MAX));
+    if let Some(alert_similarity) = thresholds.alert_similarity {
+        output.quiet = variations
+            .iter()
+            .filter(|variation| variation.score < alert_similarity)
+            .map(|variation| variation.domain.clone())
+            .collect();
+    }
+    // Triage already done in the event stream carries over
+    if let Some(contents) = args
+        .events
+        .as_deref()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+    {
+        let dispositions = annotate::dispositions(&contents);
+        output.quiet.extend(
+            variations
+                .iter()
+                .filter(|variation| {
+                    let ascii = Domain::parse(&variation.domain)
+                        .map_or_else(|_| variation.domain.clone(), |d| d.to_ascii());
+                    dispositions.get(&ascii) == Some(&Disposition::FalsePositive)
+                })
+                .map(|variation| variation.domain.clone()),
+        );
+    }
+    let by_domain: HashMap<&str, &MonitoredVariation> =
+        variations.iter().map(|v| (v.domain.as_str(), v)).collect();
+    eprintln!(
+        "Monitoring {} variations of {} every {}s",
+        variations.len(),
+        original_domain,
+        args.interval
+    );
 
-        // Adjacent key insertion (before this character)
-        for (orig_char, adjacent_chars) in &qwerty_map {
-            if ch == *orig_char {
-                for adj_char in adjacent_chars.chars() {
-                    pos_errors.push((pos, "insert_before", adj_char));
-                }
-                break;
+    let interval = Duration::from_secs(args.interval);
+    let mut state = MonitorState::new(brand_keywords(input_domain, target.as_ref()));
+    for cycle in 1.. {
+        let started = Instant::now();
+        let domains = variations.iter().map(|v| v.domain.clone()).collect();
+        let results =
+            check_domains_concurrent(domains, throttle::MAX_CONCURRENCY, ProgressMode::Bar).await;
+        clear_progress_line();
+
+        let mut changes = 0;
+        let mut registered = Vec::new();
+        for (domain, status) in &results {
+            let variation = by_domain[domain.as_str()];
+            for event in
+                state.observe_status(domain, variation.transformation, variation.score, status)
+            {
+                changes += 1;
+                output.publish(cycle, &event).await;
+            }
+            if is_registered_status(status) {
+                registered.push(domain.as_str());
             }
         }
 
-        if !pos_errors.is_empty() {
-            character_errors.push(pos_errors);
+        let mut mail = futures::stream::iter(registered.iter().copied())
+            .map(|domain| async move { (domain, lookup_mx(domain).await) })
+            .buffer_unordered(PROBE_CONCURRENCY);
+        while let Some((domain, mx)) = mail.next().await {
+            let Ok(mx) = mx else { continue };
+            let has_mx = mx.iter().any(|host| !host.is_empty());
+            if let Some(event) = state.observe_mail(domain, has_mx) {
+                changes += 1;
+                output.publish(cycle, &event).await;
+            }
         }
-    }
 
-    // Generate realistic combinations with constraints
-    generate_realistic_combinations(
-        &chars,
-        &character_errors,
-        max_errors,
-        max_length,
-        tld,
-        &mut variations,
-    );
-
-    variations
-}
+        let mut registrations = futures::stream::iter(registered.iter().copied())
+            .map(|domain| async move { (domain, fetch_registration(domain).await) })
+            .buffer_unordered(PROBE_CONCURRENCY);
+        while let Some((domain, registration)) = registrations.next().await {
+            let Ok(Some(registration)) = registration else {
+                continue;
+            };
+            for event in state.observe_registration(domain, registration) {
+                changes += 1;
+                output.publish(cycle, &event).await;
+            }
+        }
 
-fn generate_realistic_combinations(
-    original_chars: &[char],
-    character_errors: &[Vec<(usize, &str, char)>],
-    max_errors: usize,
-    max_length: usize,
-    tld: &str,
-    variations: &mut Vec<String>,
-) {
-    let domain: String = original_chars.iter().collect();
-
-    // Generate single errors first (most realistic)
-    for pos_errors in character_errors {
-        for &(pos, error_type, replacement) in pos_errors {
-            let result = apply_single_error(original_chars, pos, error_type, replacement);
-            if let Some(result_domain) = result {
-                if result_domain != domain && result_domain.len() <= max_length {
-                    variations.push(format!("{}.{}", result_domain, tld));
+        if !args.no_certs {
+            // Unregistered variations have no certificate, so one appearing
+            // later is the variation's first
+            for (domain, status) in &results {
+                if !is_registered_status(status) && !is_failed_status(status) {
+                    state.observe_certificate(domain, None);
+                }
+            }
+            let mut certificates = futures::stream::iter(registered.iter().copied())
+                .map(|domain| async move { (domain, fetch_certificate(domain).await) })
+                .buffer_unordered(PROBE_CONCURRENCY);
+            while let Some((domain, certificate)) = certificates.next().await {
+                let certificate = match certificate {
+                    Ok(certificate) => certificate,
+                    // A slow handshake says nothing about whether there is a
+                    // certificate; a refused connection means there is none
+                    Err(DomFuzzError::Timeout { .. }) => continue,
+                    Err(_) => None,
+                };
+                if let Some(event) = state.observe_certificate(domain, certificate.as_ref()) {
+                    changes += 1;
+                    output.publish(cycle, &event).await;
                 }
             }
         }
-    }
 
-    // Generate double errors for longer domains (length >= 4)
-    if original_chars.len() >= 4 && max_errors >= 2 {
-        for i in 0..character_errors.len() {
-            for j in (i + 1)..character_errors.len() {
-                // Only allow errors on non-adjacent characters for realism
-                if j - i > 1 {
-                    for &(pos1, error_type1, replacement1) in &character_errors[i] {
-                        for &(pos2, error_type2, replacement2) in &character_errors[j] {
-                            let result = apply_double_error(
-                                original_chars,
-                                pos1,
-                                error_type1,
-                                replacement1,
-                                pos2,
-                                error_type2,
-                                replacement2,
-                            );
-                            if let Some(result_domain) = result {
-                                if result_domain != domain && result_domain.len() <= max_length {
-                                    variations.push(format!("{}.{}", result_domain, tld));
-                                }
-                            }
-                        }
-                    }
+        if !args.no_content {
+            let statuses: HashMap<&str, &str> = results
+                .iter()
+                .map(|(domain, status)| (domain.as_str(), status.as_str()))
+                .collect();
+            // Conditional on the last fetch's validators, so unchanged pages
+            // are answered with a 304 and no body
+            let requests: Vec<_> = registered
+                .iter()
+                .map(|domain| (*domain, state.page_validators(domain)))
+                .collect();
+            let mut pages = futures::stream::iter(requests)
+                .map(|(domain, validators)| async move {
+                    (domain, fetch_page_if_modified(domain, &validators).await)
+                })
+                .buffer_unordered(PROBE_CONCURRENCY);
+            while let Some((domain, page)) = pages.next().await {
+                let Ok(page) = page else { continue };
+                if let Some(event) = state.observe_page(domain, statuses[domain], page) {
+                    changes += 1;
+                    output.publish(cycle, &event).await;
                 }
             }
         }
-    }
 
-    // Only generate triple errors for very long domains (length >= 8)
-    if original_chars.len() >= 8 && max_errors >= 3 {
-        for i in 0..character_errors.len() {
-            for j in (i + 2)..character_errors.len() {
-                for k in (j + 2)..character_errors.len() {
-                    // Sample only a few triple combinations to avoid explosion
-                    if i % 2 == 0 && j % 2 == 0 && k % 2 == 0 {
-                        for &(pos1, error_type1, replacement1) in character_errors[i].iter().take(1)
+        #[cfg(feature = "screenshots")]
+        if let Some(options) = &screenshots {
+            // One file per capture, so earlier captures stay available for comparison
+            let taken = chrono::Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+            let statuses: HashMap<&str, &str> = results
+                .iter()
+                .map(|(domain, status)| (domain.as_str(), status.as_str()))
+                .collect();
+            let mut captures = futures::stream::iter(registered.iter().copied())
+                .map(|domain| {
+                    let path = options.dir.join(domain).join(format!("{}.png", taken));
+                    async move {
+                        let capture = match std::fs::create_dir_all(path.parent().unwrap_or(&path))
                         {
-                            for &(pos2, error_type2, replacement2) in
-                                character_errors[j].iter().take(1)
-                            {
-                                for &(pos3, error_type3, replacement3) in
-                                    character_errors[k].iter().take(1)
-                                {
-                                    let error1 = ErrorSpec {
-                                        pos: pos1,
-                                        error_type: error_type1.to_string(),
-                                        replacement: replacement1,
-                                    };
-                                    let error2 = ErrorSpec {
-                                        pos: pos2,
-                                        error_type: error_type2.to_string(),
-                                        replacement: replacement2,
-                                    };
-                                    let error3 = ErrorSpec {
-                                        pos: pos3,
-                                        error_type: error_type3.to_string(),
-                                        replacement: replacement3,
-                                    };
-                                    let result = apply_triple_error(
-                                        original_chars,
-                                        &error1,
-                                        &error2,
-                                        &error3,
-                                    );
-                                    if let Some(result_domain) = result {
-                                        if result_domain != domain
-                                            && result_domain.len() <= max_length
-                                        {
-                                            variations.push(format!("{}.{}", result_domain, tld));
-                                        }
-                                    }
-                                }
+                            Ok(()) => {
+                                domfuzz::screenshot::capture_and_hash_to(domain, options, path)
+                                    .await
                             }
-                        }
+                            Err(e) => Err(e.into()),
+                        };
+                        (domain, capture)
                     }
+                })
+                .buffer_unordered(ENRICH_CONCURRENCY);
+            while let Some((domain, capture)) = captures.next().await {
+                let Ok(capture) = capture else { continue };
+                if let Some(event) = state.observe_screenshot(
+                    domain,
+                    statuses[domain],
+                    capture,
+                    args.change_distance,
+                ) {
+                    changes += 1;
+                    output.publish(cycle, &event).await;
                 }
             }
         }
+
+        let failed = results
+            .iter()
+            .filter(|(_, status)| is_failed_status(status))
+            .count();
+        if failed < results.len() {
+            domfuzz::metrics::record_cycle_success();
+        }
+        output
+            .publish(
+                cycle,
+                &Event::CheckCycleComplete {
+                    checked: results.len(),
+                    registered: registered.len(),
+                    failed,
+                    changes,
+                    duration: started.elapsed(),
+                },
+            )
+            .await;
+
+        if args.cycles.is_some_and(|cycles| cycle >= cycles) {
+            break;
+        }
+        tokio::time::sleep(interval.saturating_sub(started.elapsed())).await;
     }
 }
 
-fn apply_single_error(
-    chars: &[char],
-    pos: usize,
-    error_type: &str,
-    replacement: char,
-) -> Option<String> {
-    let mut result_chars = chars.to_vec();
+/// Where monitor events go: the event stream, plus the alerts they raise
+struct MonitorOutput {
+    events: EventWriter,
+    rules: Vec<AlertRule>,
+    webhook: Option<String>,
+    /// Variations below a target profile's `alert_similarity`, or annotated
+    /// `false-positive`, whose changes raise no alerts
+    quiet: HashSet<String>,
+}
 
-    match error_type {
-        "repeat" => {
-            if pos < result_chars.len() {
-                result_chars.insert(pos, replacement);
-            }
+impl MonitorOutput {
+    /// Write `event`, then an alert for each rule it matches; alerts are also
+    /// posted to the webhook, whose failures are reported but not fatal
+    async fn publish(&mut self, cycle: u64, event: &Event) {
+        self.write(cycle, event);
+        if event
+            .domain()
+            .is_some_and(|domain| self.quiet.contains(domain))
+        {
+            return;
         }
-        "substitute" => {
-            if pos < result_chars.len() {
-                result_chars[pos] = replacement;
+        let alerts: Vec<Event> = self
+            .rules
+            .iter()
+            .filter_map(|rule| rule.alert(event))
+            .collect();
+        for alert in alerts {
+            let record = self.write(cycle, &alert);
+            if let Some(webhook) = &self.webhook {
+                if let Err(e) = post_alert(webhook, &record).await {
+                    eprintln!("Warning: {}", e);
+                }
             }
         }
-        "insert_before" => {
-            result_chars.insert(pos, replacement);
-        }
-        _ => return None,
     }
 
-    Some(result_chars.iter().collect())
+    fn write(&mut self, cycle: u64, event: &Event) -> serde_json::Value {
+        self.events
+            .emit(cycle, event)
+            .unwrap_or_else(|e| exit_with(&e.into()))
+    }
 }
 
-fn apply_double_error(
-    chars: &[char],
-    pos1: usize,
-    error_type1: &str,
-    replacement1: char,
-    pos2: usize,
-    error_type2: &str,
-    replacement2: char,
-) -> Option<String> {
-    let mut result_chars = chars.to_vec();
+// ==================== ANALYSIS ====================
 
-    // Apply errors in reverse position order to maintain indices
-    let (first_pos, first_type, first_repl, second_pos, second_type, second_repl) = if pos2 > pos1 {
-        (
-            pos1,
-            error_type1,
-            replacement1,
-            pos2,
-            error_type2,
-            replacement2,
-        )
-    } else {
-        (
-            pos2,
-            error_type2,
-            replacement2,
-            pos1,
-            error_type1,
-            replacement1,
-        )
-    };
+/// Run the `analyze` subcommand: show which characters of a suspicious domain
+/// imitate the brand, the scripts they come from, and whether the skeletons match
+fn run_analyze(args: &AnalyzeArgs) {
+    use std::io::IsTerminal;
 
-    // Apply second error first (higher position)
-    match second_type {
-        "repeat" => {
-            if second_pos < result_chars.len() {
-                result_chars.insert(second_pos, second_repl);
-            }
-        }
-        "substitute" => {
-            if second_pos < result_chars.len() {
-                result_chars[second_pos] = second_repl;
-            }
-        }
-        "insert_before" => {
-            result_chars.insert(second_pos, second_repl);
-        }
-        _ => return None,
+    let analysis = analyze(&args.domain, &args.against).unwrap_or_else(|e| exit_with(&e));
+    if args.json {
+        println!(
+            "{}",
+            serde_json::to_value(&analysis).expect("analysis serializes")
+        );
+        return;
     }
 
-    // Apply first error
-    match first_type {
-        "repeat" => {
-            if first_pos < result_chars.len() {
-                result_chars.insert(first_pos, first_repl);
-            }
-        }
-        "substitute" => {
-            if first_pos < result_chars.len() {
-                result_chars[first_pos] = first_repl;
-            }
+    let color = io::stdout().is_terminal();
+    let paint = |text: &str, code: &str| {
+        if color {
+            format!("{}{}{}", code, text, ANSI_RESET)
+        } else {
+            text.to_string()
         }
-        "insert_before" => {
-            result_chars.insert(first_pos, first_repl);
-        }
-        _ => return None,
-    }
-
-    Some(result_chars.iter().collect())
-}
-
-#[derive(Clone)]
-struct ErrorSpec {
-    pos: usize,
-    error_type: String,
-    replacement: char,
-}
-
-fn apply_triple_error(
-    chars: &[char],
-    error1: &ErrorSpec,
-    error2: &ErrorSpec,
-    error3: &ErrorSpec,
-) -> Option<String> {
-    // Apply double error first, then add third error
-    let double_result = apply_double_error(
-        chars,
-        error1.pos,
-        &error1.error_type,
-        error1.replacement,
-        error2.pos,
-        &error2.error_type,
-        error2.replacement,
-    )?;
-    let double_chars: Vec<char> = double_result.chars().collect();
-
-    // Adjust position for third error based on insertions from first two errors
-    let adjusted_pos3 = if error3.pos > error2.pos && error3.pos > error1.pos {
-        error3.pos
-            + count_insertions_before(
-                error3.pos,
-                error1.pos,
-                &error1.error_type,
-                error2.pos,
-                &error2.error_type,
-            )
+    };
+    let highlighted: String = analysis
+        .segments
+        .iter()
+        .map(|segment| match segment.kind {
+            SegmentKind::Confusable => paint(&segment.text, ANSI_BOLD_RED),
+            SegmentKind::Unmapped => paint(&segment.text, ANSI_YELLOW),
+            SegmentKind::Plain => segment.text.clone(),
+        })
+        .collect();
+    if analysis.ascii == analysis.domain {
+        println!("DOMAIN    {}", highlighted);
     } else {
-        error3.pos
+        println!("DOMAIN    {} ({})", highlighted, analysis.ascii);
+    }
+    println!("AGAINST   {}", analysis.against);
+    println!();
+
+    let code_points = |text: &str| {
+        text.chars()
+            .map(|c| format!("U+{:04X}", c as u32))
+            .collect::<Vec<_>>()
+            .join(" ")
     };
+    let text_width = analysis
+        .segments
+        .iter()
+        .map(|s| s.text.chars().count())
+        .chain([4])
+        .max()
+        .unwrap_or(4);
+    let code_width = analysis
+        .segments
+        .iter()
+        .map(|s| code_points(&s.text).len())
+        .chain([10])
+        .max()
+        .unwrap_or(10);
+    println!(
+        "{:<text_width$}  {:<code_width$}  {:<10}  READS AS",
+        "CHAR", "CODE POINT", "SCRIPT"
+    );
+    for segment in &analysis.segments {
+        let line = format!(
+            "{:<text_width$}  {:<code_width$}  {:<10}  {}",
+            segment.text,
+            code_points(&segment.text),
+            segment.script,
+            match segment.kind {
+                SegmentKind::Confusable => format!("{} (confusable)", segment.prototype),
+                SegmentKind::Unmapped => "(no ASCII lookalike)".to_string(),
+                SegmentKind::Plain => String::new(),
+            }
+        );
+        match segment.kind {
+            SegmentKind::Confusable => println!("{}", paint(&line, ANSI_BOLD_RED)),
+            SegmentKind::Unmapped => println!("{}", paint(&line, ANSI_YELLOW)),
+            SegmentKind::Plain => println!("{}", line.trim_end()),
+        }
+    }
+    println!();
 
-    apply_single_error(
-        &double_chars,
-        adjusted_pos3,
-        &error3.error_type,
-        error3.replacement,
-    )
+    for (i, label) in analysis.label_scripts.iter().enumerate() {
+        let scripts = if label.scripts.is_empty() {
+            "none".to_string()
+        } else {
+            label.scripts.join(" + ")
+        };
+        let line = format!(
+            "{}{}: {}{}",
+            if i == 0 { "SCRIPTS   " } else { "          " },
+            label.label,
+            scripts,
+            if label.is_mixed() { " (mixed)" } else { "" }
+        );
+        println!("{}", line);
+    }
+    println!("SKELETON  {}", analysis.skeleton);
+    println!("AGAINST   {}", analysis.against_skeleton);
+    let verdict = match analysis.skeleton_match {
+        SkeletonMatch::Exact => paint("match: reads as the brand", ANSI_BOLD_RED),
+        SkeletonMatch::Name => paint(
+            "name match: reads as the brand under another suffix",
+            ANSI_BOLD_RED,
+        ),
+        SkeletonMatch::None => "no match".to_string(),
+    };
+    println!("VERDICT   {}", verdict);
 }
 
-fn count_insertions_before(
-    target_pos: usize,
-    pos1: usize,
-    type1: &str,
-    pos2: usize,
-    type2: &str,
-) -> usize {
-    let mut count = 0;
-    if pos1 < target_pos && (type1 == "repeat" || type1 == "insert_before") {
-        count += 1;
+/// Run the `compare` subcommand: every similarity metric between two domains
+fn run_compare(args: &CompareArgs) {
+    let parse = |domain: &str| Domain::parse(domain).unwrap_or_else(|e| exit_with(&e));
+    let (first, second) = (parse(&args.first), parse(&args.second));
+    let comparison = compare(&first, &second);
+    if args.json {
+        println!(
+            "{}",
+            serde_json::to_value(&comparison).expect("comparison serializes")
+        );
+        return;
     }
-    if pos2 < target_pos && (type2 == "repeat" || type2 == "insert_before") {
-        count += 1;
+
+    // Distances as a share of the longer name, to read alongside the scores
+    let longest = first
+        .name()
+        .chars()
+        .count()
+        .max(second.name().chars().count())
+        .max(1) as f64;
+    let percent = |score: f64| format!("{:.2}%", score * 100.0);
+    let distance = |distance: f64| percent(1.0 - (distance / longest).min(1.0));
+    let rows = [
+        (
+            "levenshtein",
+            comparison.levenshtein.to_string(),
+            distance(comparison.levenshtein as f64),
+        ),
+        (
+            "damerau-levenshtein",
+            comparison.damerau_levenshtein.to_string(),
+            distance(comparison.damerau_levenshtein as f64),
+        ),
+        (
+            "jaro-winkler",
+            String::new(),
+            percent(comparison.jaro_winkler),
+        ),
+        (
+            "keyboard-distance",
+            format!("{:.1}", comparison.keyboard_distance),
+            distance(comparison.keyboard_distance),
+        ),
+        (
+            "phonetic",
+            format!(
+                "{} / {}",
+                comparison.phonetic_codes.0, comparison.phonetic_codes.1
+            ),
+            percent(comparison.phonetic),
+        ),
+        (
+            "skeleton",
+            format!("{} / {}", comparison.skeletons.0, comparison.skeletons.1),
+            if comparison.skeleton_match() {
+                "match".to_string()
+            } else {
+                "no match".to_string()
+            },
+        ),
+        ("visual", String::new(), percent(comparison.visual)),
+        ("cognitive", String::new(), percent(comparison.cognitive)),
+        ("combined", String::new(), percent(comparison.combined)),
+        (
+            "containment",
+            String::new(),
+            comparison.containment.map_or("-".to_string(), percent),
+        ),
+        ("tld", String::new(), percent(comparison.tld)),
+        (
+            "typo-likelihood",
+            format!("{:.2e}", comparison.typo_likelihood),
+            String::new(),
+        ),
+    ];
+
+    println!("FIRST   {}", comparison.first);
+    println!("SECOND  {}", comparison.second);
+    println!();
+    let value_width = rows
+        .iter()
+        .map(|(_, value, _)| value.chars().count())
+        .chain([5])
+        .max()
+        .unwrap_or(5);
+    println!("{:<20} {:<value_width$} SIMILARITY", "METRIC", "VALUE");
+    for (metric, value, similarity) in &rows {
+        let line = format!("{:<20} {:<value_width$} {}", metric, value, similarity);
+        println!("{}", line.trim_end());
     }
-    count
 }
 
-fn load_dictionary(file_path: &str) -> Vec<String> {
-    use std::fs;
-    fs::read_to_string(file_path)
-        .unwrap_or_default()
-        .lines()
-        .map(|s| s.trim().to_string())
-        .filter(|s| !s.is_empty())
-        .collect()
-}
+/// Run the `transformations` subcommand: list every transformation, then the
+/// built-in bundles and the config file's with what each enables
+fn run_transformations(args: &TransformationsArgs) {
+    let file_config = Config::load(args.config.as_deref()).unwrap_or_else(|e| exit_with(&e));
+    let names = transformation_names();
+    let bundles: Vec<(&str, &str, Vec<String>)> = BUNDLES
+        .iter()
+        .map(|&name| (name, "built-in", vec![name.to_string()]))
+        .chain(
+            file_config
+                .bundles
+                .keys()
+                .map(|name| (name.as_str(), "config", vec![name.clone()])),
+        )
+        .map(|(name, source, requested)| {
+            // Registry order, as generation runs them
+            let enabled = file_config
+                .transformations(&requested)
+                .expect("bundles are checked on load");
+            let members = names
+                .iter()
+                .filter(|&&transformation| enabled.contains(transformation))
+                .map(|transformation| transformation.to_string())
+                .collect();
+            (name, source, members)
+        })
+        .collect();
 
-fn default_dictionary() -> Vec<String> {
-    // Try to load from XDG-compliant user data directory first
-    if let Ok(home) = std::env::var("HOME") {
-        let xdg_dict_path = format!("{}/.local/share/domfuzz/dictionary.txt", home);
-        if std::path::Path::new(&xdg_dict_path).exists() {
-            return load_dictionary(&xdg_dict_path);
-        }
+    if args.json {
+        let bundle_records: Vec<serde_json::Value> = bundles
+            .iter()
+            .map(|(name, source, members)| {
+                serde_json::json!({ "name": name, "source": source, "transformations": members })
+            })
+            .collect();
+        println!(
+            "{}",
+            serde_json::json!({ "transformations": names, "bundles": bundle_records })
+        );
+        return;
     }
 
-    // Fall back to built-in dictionary
-    vec![
-        "support", "secure", "login", "pay", "help", "service", "account", "portal", "center",
-        "app", "online", "store", "shop", "mail", "cloud", "data", "mobile", "web", "digital",
-        "tech", "pro", "plus", "premium", "official", "admin", "manage", "bank", "finance",
-        "crypto",
-    ]
-    .into_iter()
-    .map(|s| s.to_string())
-    .collect()
+    println!("TRANSFORMATION");
+    for name in &names {
+        println!("{}", name);
+    }
+    let width = bundles
+        .iter()
+        .map(|(name, _, _)| name.chars().count())
+        .chain([6])
+        .max()
+        .unwrap_or(6);
+    println!();
+    println!("{:<width$} {:<8} TRANSFORMATIONS", "BUNDLE", "SOURCE");
+    for (name, source, members) in &bundles {
+        println!("{:<width$} {:<8} {}", name, source, members.join(", "));
+    }
 }
 
-fn generate_combosquatting(domain: &str, tld: &str, dict_words: &[String]) -> Vec<String> {
-    let mut variations = Vec::new();
-
-    for word in dict_words {
-        variations.push(format!("{}-{}.{}", domain, word, tld));
-        variations.push(format!("{}{}.{}", domain, word, tld));
-        variations.push(format!("{}-{}.{}", word, domain, tld));
-        variations.push(format!("{}{}.{}", word, domain, tld));
+/// Run the `annotate` subcommand: add an analyst's annotation to the
+/// variation's records in a results file, or an `annotated` event to a monitor
+/// event stream
+async fn run_annotate(args: &AnnotateArgs) {
+    let contents = read_result_file(&args.file).unwrap_or_else(|e| {
+        exit_with(&DomFuzzError::Config(format!(
+            "cannot read {}: {}",
+            args.file.display(),
+            e
+        )))
+    });
+    let not_found =
+        || DomFuzzError::Config(format!("{} is not in {}", args.domain, args.file.display()));
+    let analyst = args
+        .analyst
+        .clone()
+        .or_else(|| std::env::var("USER").ok().filter(|user| !user.is_empty()));
+    let annotation = Annotation::new(args.disposition, args.note.clone(), analyst);
+    match annotate::file_kind(&contents) {
+        FileKind::Events => {
+            // Dated by the cycle it follows
+            let cycle = annotate::last_cycle(&contents, &args.domain)
+                .unwrap_or_else(|| exit_with(&not_found()));
+            let event = Event::Annotated {
+                domain: args.domain.trim().to_lowercase(),
+                annotation,
+            };
+            EventWriter::append(&args.file)
+                .and_then(|mut events| events.emit(cycle, &event))
+                .unwrap_or_else(|e| exit_with(&e.into()));
+        }
+        FileKind::Results => {
+            let annotated = annotate::annotate_results(&contents, &args.domain, &annotation)
+                .unwrap_or_else(|| exit_with(&not_found()));
+            // Replaced atomically, compressed as before
+            let mut output =
+                OutputWriter::from_cli(Some(OutputFormat::Jsonl), true, Some(&args.file))
+                    .unwrap_or_else(|e| exit_with(&e));
+            for line in annotated.lines() {
+                output.write_line(line);
+            }
+            output.finish().await.unwrap_or_else(|e| exit_with(&e));
+        }
     }
-
-    variations
+    eprintln!(
+        "Annotated {} in {}{}",
+        args.domain,
+        args.file.display(),
+        args.disposition
+            .map_or(String::new(), |d| format!(" as {}", d.as_str()))
+    );
 }
 
-fn generate_hyphenation(domain: &str, tld: &str) -> Vec<String> {
-    let mut variations = Vec::new();
+async fn run_report_abuse(args: &ReportAbuseArgs) {
+    let domain = Domain::parse(&args.domain).unwrap_or_else(|e| exit_with(&e));
+    let against = Domain::parse(&args.against).unwrap_or_else(|e| exit_with(&e));
+    let screenshots: Vec<(String, Vec<u8>)> = args
+        .screenshot
+        .iter()
+        .map(|path| {
+            let data = std::fs::read(path).unwrap_or_else(|e| {
+                exit_with(&DomFuzzError::Config(format!(
+                    "cannot read screenshot {}: {}",
+                    path.display(),
+                    e
+                )))
+            });
+            let name = path
+                .file_name()
+                .map_or_else(|| "screenshot".into(), |name| name.to_string_lossy());
+            (name.into_owned(), data)
+        })
+        .collect();
 
-    let chars: Vec<char> = domain.chars().collect();
-    for i in 1..chars.len() {
-        // Skip positions that would create domains starting/ending with hyphens
-        // or consecutive hyphens
-        if i > 0 && chars[i - 1] == '-' {
-            continue;
-        }
-        if i < chars.len() && chars[i] == '-' {
-            continue;
+    let registrable = domain.registrable();
+    let registration = match fetch_registration(&registrable).await {
+        Ok(Some(registration)) => registration,
+        Ok(None) => exit_with(&DomFuzzError::Config(format!(
+            "{} is not registered; there is nothing to report",
+            registrable
+        ))),
+        Err(e) => {
+            eprintln!("Warning: registration lookup failed: {}", e);
+            Registration::default()
         }
+    };
+    let host = domain.to_ascii();
+    let addresses: Vec<std::net::IpAddr> = match tokio::time::timeout(
+        Duration::from_secs(DNS_TIMEOUT_SECS),
+        DNS_RESOLVER.lookup_ip(host.as_str()),
+    )
+    .await
+    {
+        Ok(Ok(lookup)) => lookup.iter().collect(),
+        _ => Vec::new(),
+    };
+    let mut host_contacts = Vec::new();
+    for ip in addresses.iter().take(MAX_ABUSE_HOSTS) {
+        match fetch_host_abuse_contact(*ip).await {
+            Ok(Some(email)) if !host_contacts.contains(&email) => host_contacts.push(email),
+            Ok(_) => {}
+            Err(e) => eprintln!("Warning: abuse contact lookup for {} failed: {}", ip, e),
+        }
+    }
+    let keywords = brand_keywords(&args.against, None);
+    let (certificate, page) =
+        tokio::join!(fetch_certificate(&host), analyze_page(&host, &keywords));
+    let evidence = AbuseEvidence {
+        registration,
+        addresses,
+        host_contacts,
+        certificate: certificate.ok().flatten(),
+        page: page.ok(),
+    };
 
-        // Convert char index to byte index for insertion
-        let byte_pos = domain
-            .char_indices()
-            .nth(i)
-            .map(|(pos, _)| pos)
-            .unwrap_or(domain.len());
-        let mut new_domain = domain.to_string();
-        new_domain.insert(byte_pos, '-');
+    let draft = abuse_report(
+        &domain,
+        &against,
+        &evidence,
+        args.from.as_deref(),
+        &screenshots,
+    )
+    .unwrap_or_else(|e| exit_with(&e));
+    let path = args
+        .output
+        .clone()
+        .unwrap_or_else(|| PathBuf::from(format!("abuse-{}.eml", host)));
+    std::fs::write(&path, draft).unwrap_or_else(|e| exit_with(&e.into()));
+
+    let recipients: Vec<&str> = evidence
+        .registration
+        .abuse_email
+        .iter()
+        .chain(&evidence.host_contacts)
+        .map(String::as_str)
+        .collect();
+    if recipients.is_empty() {
+        eprintln!(
+            "Warning: no abuse contact found; fill in the recipients of {}",
+            path.display()
+        );
+    }
+    eprintln!(
+        "Wrote abuse report draft for {} to {}{}",
+        domain.to_unicode(),
+        path.display(),
+        if recipients.is_empty() {
+            String::new()
+        } else {
+            format!(" (to {})", recipients.join(", "))
+        }
+    );
+}
 
-        // Additional check: don't create domains ending with hyphen
-        if new_domain.ends_with('-') {
+/// Read observed squats, one per line, as Unicode domains in file order without
+/// repeats; the first whitespace- or comma-separated field of each line counts,
+/// and lines that are not domains are skipped with a warning
+fn load_observed_squats(path: &Path) -> Result<Vec<String>, DomFuzzError> {
+    let contents = std::fs::read_to_string(path)?;
+    let mut seen = HashSet::new();
+    let mut squats = Vec::new();
+    for (index, line) in contents.lines().enumerate() {
+        let Some(field) = line
+            .split('#')
+            .next()
+            .and_then(|line| line.split([',', ' ', '\t']).find(|f| !f.is_empty()))
+        else {
+            continue;
+        };
+        if !field.contains('.') {
+            eprintln!(
+                "Warning: {}:{}: {:?} is not a domain",
+                path.display(),
+                index + 1,
+                field
+            );
             continue;
         }
-
-        variations.push(format!("{}.{}", new_domain, tld));
+        match Domain::parse(field) {
+            Ok(domain) => {
+                let domain = domain.to_unicode();
+                if seen.insert(domain.clone()) {
+                    squats.push(domain);
+                }
+            }
+            Err(e) => eprintln!("Warning: {}:{}: {}", path.display(), index + 1, e),
+        }
     }
-
-    variations
+    Ok(squats)
 }
 
-fn generate_brand_confusion(domain: &str, tld: &str) -> Vec<String> {
-    let mut variations = Vec::new();
-
-    let authority_prefixes = ["www", "secure", "official", "my", "admin", "portal", "app"];
-    let authority_suffixes = ["app", "online", "portal", "center", "pro", "plus", "secure"];
-
-    for prefix in &authority_prefixes {
-        variations.push(format!("{}-{}.{}", prefix, domain, tld));
-        variations.push(format!("{}.{}.{}", prefix, domain, tld));
-    }
-
-    for suffix in &authority_suffixes {
-        variations.push(format!("{}-{}.{}", domain, suffix, tld));
-        variations.push(format!("{}{}.{}", domain, suffix, tld));
+/// Why a squat no transformation generates may have been missed, to point at
+/// the generator work it calls for
+fn coverage_hint(original: &Domain, squat: &Domain) -> String {
+    let comparison = compare(original, squat);
+    if squat.name() == original.name() {
+        format!("other suffix .{}", squat.suffix())
+    } else if comparison.skeleton_match() {
+        "reads the same".to_string()
+    } else if comparison.containment.is_some() {
+        "contains the brand".to_string()
+    } else if squat.subdomain().is_some() {
+        "subdomain".to_string()
+    } else {
+        format!("{} edits", comparison.damerau_levenshtein)
     }
-
-    variations
 }
 
-fn generate_intl_tld(domain: &str, tld: &str) -> Vec<String> {
-    let mut variations = Vec::new();
-
-    let idn_tlds = [
-        ("com", "ком"),
-        ("net", "нет"),
-        ("org", "орг"),
-        ("com", "كوم"),
-        ("net", "شبكة"),
-        ("org", "منظمة"),
-        ("com", "公司"),
-        ("net", "网络"),
-        ("org", "组织"),
-        ("cn", "中国"),
-        ("com", "コム"),
-        ("net", "ネット"),
-        ("org", "オルグ"),
-        ("com", "컴"),
-        ("net", "넷"),
-        ("kr", "한국"),
-        ("com", "κομ"),
-        ("net", "δικτυο"),
-        ("org", "οργ"),
-        ("gr", "ελ"),
-        ("com", "קום"),
-        ("net", "רשת"),
-        ("org", "ארג"),
-        ("com", "คอม"),
-        ("net", "เน็ต"),
-        ("th", "ไทย"),
-        ("com", "कॉम"),
-        ("net", "नेट"),
-        ("org", "संगठन"),
-        ("in", "भारत"),
-    ];
-
-    for &(latin_tld, idn_tld) in &idn_tlds {
-        if tld == latin_tld || tld == "com" || tld == "net" || tld == "org" {
-            variations.push(format!("{}.{}", domain, idn_tld));
+/// Run the `coverage` subcommand: generate every enabled transformation and
+/// report which observed squats each one reaches, matching registrable domains
+async fn run_coverage(args: &CoverageArgs) {
+    let original = Domain::parse(&args.domain).unwrap_or_else(|e| exit_with(&e));
+    let squats = load_observed_squats(&args.file).unwrap_or_else(|e| exit_with(&e));
+    let file_config = Config::load(args.config.as_deref()).unwrap_or_else(|e| exit_with(&e));
+    let requested = if args.transformation.is_empty() {
+        vec!["all".to_string()]
+    } else {
+        args.transformation.clone()
+    };
+    let mut enabled_transformations = file_config
+        .transformations(&requested)
+        .unwrap_or_else(|e| exit_with(&e));
+    args.generation
+        .enable_transformations(&mut enabled_transformations);
+    let generator_options = args.generation.options(&original.to_ascii()).await;
+
+    // What was registered is the registrable domain: `login.examp1e.com` is
+    // generated by whatever generates `examp1e.com`
+    let registrable = |domain: &str| {
+        Domain::parse(domain)
+            .and_then(|domain| Domain::parse(&domain.registrable()))
+            .map_or_else(|_| domain.to_string(), |domain| domain.to_unicode())
+    };
+    let wanted: HashSet<String> = squats.iter().map(|squat| registrable(squat)).collect();
+    let mut generated_by: HashMap<String, Vec<&'static str>> = HashMap::new();
+    let mut transformations = Vec::new();
+    for (transformation, generator) in transformation_generators(&generator_options) {
+        if !enabled_transformations.contains(transformation) {
+            continue;
+        }
+        transformations.push(transformation);
+        let mut reached = HashSet::new();
+        for variation in filter_valid_domains(generator(&original)) {
+            if generator_options.respect_idn_policies && !IdnPolicies::builtin().allows(&variation)
+            {
+                continue;
+            }
+            let variation = registrable(&variation);
+            if wanted.contains(&variation) && reached.insert(variation.clone()) {
+                generated_by
+                    .entry(variation)
+                    .or_default()
+                    .push(transformation);
+            }
         }
     }
+    warn_script_error(&generator_options);
 
-    let mixed_tlds = [
-        "co.ук", "com.ау", "со.uk", "сom", "nеt", "оrg", "οrg", "cοm",
-    ];
+    let rows: Vec<(&String, &[&'static str])> = squats
+        .iter()
+        .map(|squat| {
+            let by = generated_by
+                .get(&registrable(squat))
+                .map_or(&[][..], Vec::as_slice);
+            (squat, by)
+        })
+        .collect();
+    let covered = rows.iter().filter(|(_, by)| !by.is_empty()).count();
+    let mut hits: Vec<(&'static str, usize)> = transformations
+        .iter()
+        .map(|&transformation| {
+            let count = rows
+                .iter()
+                .filter(|(_, by)| by.contains(&transformation))
+                .count();
+            (transformation, count)
+        })
+        .collect();
+    // Most productive transformations first, then registry order
+    hits.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+    let hint = |squat: &str| {
+        Domain::parse(squat)
+            .map(|squat| coverage_hint(&original, &squat))
+            .unwrap_or_default()
+    };
+    let share = |count: usize| {
+        if squats.is_empty() {
+            0.0
+        } else {
+            count as f64 / squats.len() as f64
+        }
+    };
 
-    for mixed_tld in &mixed_tlds {
-        variations.push(format!("{}.{}", domain, mixed_tld));
+    if args.json {
+        let squat_records: Vec<serde_json::Value> = rows
+            .iter()
+            .map(|(squat, by)| {
+                let mut record = serde_json::json!({
+                    "domain": squat,
+                    "transformations": by,
+                });
+                if by.is_empty() {
+                    record["hint"] = hint(squat).into();
+                }
+                record
+            })
+            .collect();
+        let transformation_records: Vec<serde_json::Value> = hits
+            .iter()
+            .map(|(transformation, count)| {
+                serde_json::json!({ "transformation": transformation, "generated": count })
+            })
+            .collect();
+        println!(
+            "{}",
+            serde_json::json!({
+                "domain": original.to_unicode(),
+                "observed": squats.len(),
+                "generated": covered,
+                "coverage": share(covered),
+                "transformations": transformation_records,
+                "squats": squat_records,
+            })
+        );
+        return;
     }
 
-    variations
-}
-
-fn generate_dot_insertion(domain: &str, tld: &str) -> Vec<String> {
-    let mut variations = Vec::new();
-
-    // Insert dots at various positions within the domain (not at start/end)
-    let chars: Vec<char> = domain.chars().collect();
-    for i in 1..chars.len() {
-        // Skip positions that would create consecutive dots
-        if i > 0 && chars[i - 1] == '.' {
-            continue;
-        }
-        if i < chars.len() && chars[i] == '.' {
-            continue;
+    println!(
+        "COVERAGE  {} of {} observed squats of {} ({:.1}%)",
+        covered,
+        squats.len(),
+        original.to_unicode(),
+        share(covered) * 100.0
+    );
+    println!();
+    println!("{:<24} {:>9}", "TRANSFORMATION", "GENERATED");
+    for (transformation, count) in &hits {
+        println!("{:<24} {:>9}", transformation, count);
+    }
+    let width = squats
+        .iter()
+        .map(|squat| squat.chars().count())
+        .chain([6])
+        .max()
+        .unwrap_or(6);
+    println!();
+    println!("{:<width$} GENERATED BY", "SQUAT");
+    for (squat, by) in rows.iter().filter(|(_, by)| !by.is_empty()) {
+        println!("{:<width$} {}", squat, by.join(", "));
+    }
+    if covered < squats.len() {
+        println!();
+        println!("{:<width$} HINT", "MISSED");
+        for (squat, _) in rows.iter().filter(|(_, by)| by.is_empty()) {
+            println!("{:<width$} {}", squat, hint(squat));
         }
-
-        // Convert char index to byte index for insertion
-        let byte_pos = domain
-            .char_indices()
-            .nth(i)
-            .map(|(pos, _)| pos)
-            .unwrap_or(domain.len());
-        let mut new_domain = domain.to_string();
-        new_domain.insert(byte_pos, '.');
-        variations.push(format!("{}.{}", new_domain, tld));
     }
-
-    variations
 }
 
-fn generate_dot_omission(domain: &str, tld: &str) -> Vec<String> {
-    let mut variations = Vec::new();
-
-    // Remove existing dots from the domain
-    if domain.contains('.') {
-        let stripped = domain.replace(".", "");
-        if !stripped.is_empty() {
-            variations.push(format!("{}.{}", stripped, tld));
+/// Run the `report` subcommand: render a results file through the report
+/// templates
+#[cfg(feature = "reports")]
+fn run_report(args: &ReportArgs) {
+    let contents = read_result_file(&args.file).unwrap_or_else(|e| {
+        exit_with(&DomFuzzError::Config(format!(
+            "cannot read {}: {}",
+            args.file.display(),
+            e
+        )))
+    });
+    let source = args.file.file_name().map_or_else(
+        || args.file.display().to_string(),
+        |name| name.to_string_lossy().into_owned(),
+    );
+    let report = Report::from_jsonl(&contents, &source, args.against.as_deref())
+        .unwrap_or_else(|e| exit_with(&e));
+    let format = args
+        .format
+        .or_else(|| args.output.as_deref().and_then(ReportFormat::from_path))
+        .unwrap_or(ReportFormat::Html);
+    #[cfg(feature = "pdf")]
+    if format == ReportFormat::Pdf && args.output.is_none() {
+        exit_with(&DomFuzzError::Config(
+            "--format pdf requires --output".to_string(),
+        ));
+    }
+    let rendered = report
+        .render(format, args.templates.as_deref())
+        .unwrap_or_else(|e| exit_with(&e));
+    match &args.output {
+        Some(path) => {
+            std::fs::write(path, rendered).unwrap_or_else(|e| {
+                exit_with(&DomFuzzError::Config(format!(
+                    "cannot write {}: {}",
+                    path.display(),
+                    e
+                )))
+            });
+            eprintln!(
+                "Wrote report of {} variations to {}",
+                report.results.len(),
+                path.display()
+            );
         }
+        None => io::stdout()
+            .write_all(&rendered)
+            .unwrap_or_else(|e| exit_with(&e.into())),
     }
-
-    variations
 }
 
-fn generate_dot_hyphen_substitution(domain: &str, tld: &str) -> Vec<String> {
-    let mut variations = Vec::new();
-
-    // Replace dots with hyphens
-    if domain.contains('.') {
-        let hyphenated = domain.replace(".", "-");
-        variations.push(format!("{}.{}", hyphenated, tld));
-    }
-
-    // Replace hyphens with dots
-    if domain.contains('-') {
-        let dotted = domain.replace("-", ".");
-        variations.push(format!("{}.{}", dotted, tld));
-    }
+// ==================== PROFILING ====================
 
-    variations
+/// Generation cost of a single transformation
+struct GenerationProfile {
+    name: &'static str,
+    elapsed: Duration,
+    candidates: usize,
+    valid: usize,
 }
 
-fn generate_cardinal_substitution(domain: &str, tld: &str) -> Vec<String> {
-    let mut variations = Vec::new();
-
-    // Cardinal number mappings (digit to word and word to digit)
-    let cardinals = [
-        ("0", "zero"),
-        ("1", "one"),
-        ("2", "two"),
-        ("3", "three"),
-        ("4", "four"),
-        ("5", "five"),
-        ("6", "six"),
-        ("7", "seven"),
-        ("8", "eight"),
-        ("9", "nine"),
-        ("10", "ten"),
-        ("11", "eleven"),
-        ("12", "twelve"),
-        ("20", "twenty"),
-        ("30", "thirty"),
-        ("40", "forty"),
-        ("50", "fifty"),
-        ("100", "hundred"),
-    ];
+/// Latency samples collected for a single lookup protocol
+struct ProtocolProfile {
+    protocol: &'static str,
+    latencies: Vec<Duration>,
+    errors: usize,
+}
 
-    // Replace numbers with words
-    for &(digit, word) in &cardinals {
-        if domain.contains(digit) {
-            let word_variant = domain.replace(digit, word);
-            if word_variant != domain {
-                variations.push(format!("{}.{}", word_variant, tld));
-            }
+impl ProtocolProfile {
+    fn new(protocol: &'static str) -> Self {
+        ProtocolProfile {
+            protocol,
+            latencies: Vec::new(),
+            errors: 0,
         }
+    }
 
-        // Replace words with numbers
-        if domain.contains(word) {
-            let digit_variant = domain.replace(word, digit);
-            if digit_variant != domain {
-                variations.push(format!("{}.{}", digit_variant, tld));
-            }
+    fn record(&mut self, elapsed: Duration, succeeded: bool) {
+        self.latencies.push(elapsed);
+        if !succeeded {
+            self.errors += 1;
         }
     }
-
-    variations
 }
 
-fn generate_ordinal_substitution(domain: &str, tld: &str) -> Vec<String> {
-    let mut variations = Vec::new();
-
-    // Ordinal number mappings
-    let ordinals = [
-        ("1st", "first"),
-        ("2nd", "second"),
-        ("3rd", "third"),
-        ("4th", "fourth"),
-        ("5th", "fifth"),
-        ("6th", "sixth"),
-        ("7th", "seventh"),
-        ("8th", "eighth"),
-        ("9th", "ninth"),
-        ("10th", "tenth"),
-        ("11th", "eleventh"),
-        ("12th", "twelfth"),
-        ("20th", "twentieth"),
-        ("21st", "twentyfirst"),
-        ("30th", "thirtieth"),
-        ("100th", "hundredth"),
-    ];
+/// Nearest-rank percentile of an ascending list of durations
+fn percentile(sorted: &[Duration], pct: f64) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+    let rank = ((pct / 100.0) * (sorted.len() - 1) as f64).round() as usize;
+    sorted[rank.min(sorted.len() - 1)]
+}
 
-    // Replace ordinal numbers with words
-    for &(ordinal, word) in &ordinals {
-        if domain.contains(ordinal) {
-            let word_variant = domain.replace(ordinal, word);
-            if word_variant != domain {
-                variations.push(format!("{}.{}", word_variant, tld));
-            }
-        }
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
 
-        // Replace ordinal words with numbers
-        if domain.contains(word) {
-            let ordinal_variant = domain.replace(word, ordinal);
-            if ordinal_variant != domain {
-                variations.push(format!("{}.{}", ordinal_variant, tld));
-            }
+/// Run the `profile` subcommand: time every enabled transformation and, optionally,
+/// each lookup protocol against a sample of the generated candidates
+async fn run_profile(args: &ProfileArgs) {
+    let parsed_domain = Domain::parse(&args.domain).unwrap_or_else(|e| exit_with(&e));
+    let (domain_name, tld) = (parsed_domain.name(), parsed_domain.suffix().to_string());
+    let file_config = Config::load(args.config.as_deref()).unwrap_or_else(|e| exit_with(&e));
+    let mut enabled_transformations = file_config
+        .transformations(&args.transformation)
+        .unwrap_or_else(|e| exit_with(&e));
+    args.generation
+        .enable_transformations(&mut enabled_transformations);
+    let generator_options = args.generation.options(&parsed_domain.to_ascii()).await;
+
+    let mut profiles = Vec::new();
+    let mut candidates = HashSet::new();
+    for (name, generator) in transformation_generators(&generator_options) {
+        if !enabled_transformations.contains(name) {
+            continue;
         }
+        let start = Instant::now();
+        let results = generator(&parsed_domain);
+        let elapsed = start.elapsed();
+        let candidate_count = results.len();
+        let valid = filter_valid_domains(results);
+        profiles.push(GenerationProfile {
+            name,
+            elapsed,
+            candidates: candidate_count,
+            valid: valid.len(),
+        });
+        candidates.extend(valid);
     }
+    warn_script_error(&generator_options);
 
-    variations
-}
-
-fn generate_homophones(domain: &str, tld: &str) -> Vec<String> {
-    let mut variations = Vec::new();
-
-    // Common homophones dictionary
-    let homophones = [
-        ("to", vec!["too", "two"]),
-        ("there", vec!["their", "they're"]),
-        ("your", vec!["you're"]),
-        ("hear", vec!["here"]),
-        ("buy", vec!["by", "bye"]),
-        ("site", vec!["sight", "cite"]),
-        ("right", vec!["write", "rite"]),
-        ("four", vec!["for", "fore"]),
-        ("one", vec!["won"]),
-        ("son", vec!["sun"]),
-        ("no", vec!["know"]),
-        ("sea", vec!["see"]),
-        ("be", vec!["bee"]),
-        ("mail", vec!["male"]),
-        ("sale", vec!["sail"]),
-        ("peace", vec!["piece"]),
-        ("break", vec!["brake"]),
-        ("cell", vec!["sell"]),
-        ("blue", vec!["blew"]),
-        ("ate", vec!["eight"]),
-        ("week", vec!["weak"]),
-        ("meet", vec!["meat"]),
-        ("fair", vec!["fare"]),
-        ("pair", vec!["pear", "pare"]),
-        ("bear", vec!["bare"]),
-        ("dear", vec!["deer"]),
-        ("flour", vec!["flower"]),
-        ("hour", vec!["our"]),
-        ("knight", vec!["night"]),
-        ("knew", vec!["new"]),
-        ("tail", vec!["tale"]),
-        ("wait", vec!["weight"]),
-        ("way", vec!["weigh"]),
-        ("would", vec!["wood"]),
-        ("hole", vec!["whole"]),
-        ("role", vec!["roll"]),
-        ("soul", vec!["sole"]),
-        ("steal", vec!["steel"]),
-        ("heal", vec!["heel"]),
-        ("real", vec!["reel"]),
-        ("read", vec!["red"]),
-        ("lead", vec!["led"]),
-        ("threw", vec!["through"]),
-        ("plain", vec!["plane"]),
-        ("rain", vec!["reign"]),
-        ("main", vec!["mane"]),
-        ("pain", vec!["pane"]),
-        ("vain", vec!["vane"]),
-    ];
+    // Most expensive transformations first
+    profiles.sort_by_key(|p| std::cmp::Reverse(p.elapsed));
+    let total_elapsed: Duration = profiles.iter().map(|p| p.elapsed).sum();
+    let total_candidates: usize = profiles.iter().map(|p| p.candidates).sum();
+    let total_valid: usize = profiles.iter().map(|p| p.valid).sum();
 
-    // Apply homophone transformations
-    for &(original, ref replacements) in &homophones {
-        if domain.to_lowercase().contains(original) {
-            for replacement in replacements {
-                let variant = domain.to_lowercase().replace(original, replacement);
-                if variant != domain.to_lowercase() {
-                    variations.push(format!("{}.{}", variant, tld));
-                }
-            }
-        }
+    println!("GENERATION ({}.{})", domain_name, tld);
+    println!(
+        "{:<24} {:>10} {:>7} {:>11} {:>8}",
+        "TRANSFORMATION", "TIME (ms)", "SHARE", "CANDIDATES", "VALID"
+    );
+    for profile in &profiles {
+        let share = if total_elapsed.is_zero() {
+            0.0
+        } else {
+            profile.elapsed.as_secs_f64() / total_elapsed.as_secs_f64() * 100.0
+        };
+        println!(
+            "{:<24} {:>10.3} {:>6.1}% {:>11} {:>8}",
+            profile.name,
+            millis(profile.elapsed),
+            share,
+            profile.candidates,
+            profile.valid
+        );
+    }
+    println!(
+        "{:<24} {:>10.3} {:>7} {:>11} {:>8}",
+        "total",
+        millis(total_elapsed),
+        "",
+        total_candidates,
+        total_valid
+    );
+    println!("{} unique valid variations", candidates.len());
 
-        // Reverse lookup - replace homophones with original
-        for replacement in replacements {
-            if domain.to_lowercase().contains(replacement) {
-                let variant = domain.to_lowercase().replace(replacement, original);
-                if variant != domain.to_lowercase() {
-                    variations.push(format!("{}.{}", variant, tld));
-                }
-            }
-        }
+    if !args.check_status {
+        return;
     }
 
-    variations
-}
-
-fn generate_singular_plural(domain: &str, tld: &str) -> Vec<String> {
-    let mut variations = Vec::new();
+    // Sample the most convincing candidates, since those are the ones that get checked first
+    let original_domain = format!("{}.{}", domain_name, tld);
+    let mut scored: Vec<SimilarityScore> = candidates
+        .iter()
+        .map(|candidate| calculate_similarity(&original_domain, candidate, "unknown"))
+        .collect();
+    scored.sort_by(|a, b| {
+        b.combined_score
+            .partial_cmp(&a.combined_score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.domain.cmp(&b.domain))
+    });
+    let sample: Vec<String> = scored
+        .into_iter()
+        .take(args.samples)
+        .map(|s| extract_registrable_domain(&s.domain))
+        .collect();
 
-    // Simple pluralization rules
-    let domain_lower = domain.to_lowercase();
+    let mut rdap = ProtocolProfile::new("rdap");
+    let mut whois = ProtocolProfile::new("whois");
+    let mut dns = ProtocolProfile::new("dns");
+    let mut http = ProtocolProfile::new("http");
+    let resolver = TokioAsyncResolver::tokio(ResolverConfig::default(), ResolverOpts::default());
 
-    // Add 's' for simple plural
-    if !domain_lower.ends_with('s') {
-        variations.push(format!("{}s.{}", domain, tld));
-    }
+    let pb = ProgressBar::new(sample.len() as u64);
+    pb.set_message("Profiling lookups...");
+    // Lookups run sequentially so each measurement reflects unloaded latency
+    for domain in &sample {
+        if let Ok(tld) = extract_tld(domain) {
+            if get_rdap_endpoint(&tld).is_ok() {
+                let start = Instant::now();
+                let result = check_domain_rdap(domain).await;
+                rdap.record(start.elapsed(), result.is_ok());
+            }
+        }
 
-    // Add 'es' for words ending in s, x, z, sh, ch
-    if domain_lower.ends_with('s')
-        || domain_lower.ends_with('x')
-        || domain_lower.ends_with('z')
-        || domain_lower.ends_with("sh")
-        || domain_lower.ends_with("ch")
-    {
-        variations.push(format!("{}es.{}", domain, tld));
-    }
+        let start = Instant::now();
+        let result = check_whois(domain).await;
+        whois.record(start.elapsed(), result.is_ok());
 
-    // Change 'y' to 'ies'
-    if domain_lower.ends_with('y') && domain.len() > 1 {
-        let stem = &domain[..domain.len() - 1];
-        variations.push(format!("{}ies.{}", stem, tld));
-    }
+        let start = Instant::now();
+        let result = timeout(
+            Duration::from_secs(DNS_TIMEOUT_SECS),
+            resolver.lookup_ip(domain.as_str()),
+        )
+        .await;
+        dns.record(start.elapsed(), matches!(result, Ok(Ok(_))));
 
-    // Remove 's' for singular (simple case)
-    if domain_lower.ends_with('s') && domain.len() > 1 {
-        let singular = &domain[..domain.len() - 1];
-        variations.push(format!("{}.{}", singular, tld));
-    }
+        let start = Instant::now();
+        let url = format!("http://{}", domain);
+        let result = timeout(
+            Duration::from_secs(HTTP_TIMEOUT_SECS),
+            HTTP_CLIENT.get(&url).send(),
+        )
+        .await;
+        http.record(start.elapsed(), matches!(result, Ok(Ok(_))));
 
-    // Remove 'es' for singular
-    if domain_lower.ends_with("es") && domain.len() > 2 {
-        let singular = &domain[..domain.len() - 2];
-        variations.push(format!("{}.{}", singular, tld));
+        pb.inc(1);
     }
+    pb.finish_and_clear();
 
-    // Change 'ies' to 'y'
-    if domain_lower.ends_with("ies") && domain.len() > 3 {
-        let singular = format!("{}y", &domain[..domain.len() - 3]);
-        variations.push(format!("{}.{}", singular, tld));
+    println!();
+    println!("CHECK LATENCY ({} sampled domains)", sample.len());
+    println!(
+        "{:<9} {:>8} {:>7} {:>10} {:>10} {:>10} {:>10}",
+        "PROTOCOL", "SAMPLES", "ERRORS", "P50 (ms)", "P90 (ms)", "P99 (ms)", "MAX (ms)"
+    );
+    for profile in [&mut rdap, &mut whois, &mut dns, &mut http] {
+        profile.latencies.sort();
+        let max = profile.latencies.last().copied().unwrap_or_default();
+        println!(
+            "{:<9} {:>8} {:>7} {:>10.1} {:>10.1} {:>10.1} {:>10.1}",
+            profile.protocol,
+            profile.latencies.len(),
+            profile.errors,
+            millis(percentile(&profile.latencies, 50.0)),
+            millis(percentile(&profile.latencies, 90.0)),
+            millis(percentile(&profile.latencies, 99.0)),
+            millis(max)
+        );
     }
-
-    variations
 }
 
-fn generate_wrong_sld(domain: &str, tld: &str) -> Vec<String> {
-    let mut variations = Vec::new();
-
-    // Common second-level domains for various countries
-    let slds = [
-        (
-            "uk",
-            vec!["co.uk", "org.uk", "net.uk", "ac.uk", "gov.uk", "sch.uk"],
-        ),
-        (
-            "au",
-            vec!["com.au", "net.au", "org.au", "edu.au", "gov.au", "asn.au"],
-        ),
-        (
-            "nz",
-            vec!["co.nz", "net.nz", "org.nz", "ac.nz", "govt.nz", "school.nz"],
-        ),
-        (
-            "za",
-            vec!["co.za", "net.za", "org.za", "edu.za", "gov.za", "ac.za"],
-        ),
-        (
-            "ca",
-            vec!["co.ca", "net.ca", "org.ca", "gc.ca", "ab.ca", "bc.ca"],
-        ),
-        (
-            "br",
-            vec!["com.br", "net.br", "org.br", "edu.br", "gov.br", "mil.br"],
-        ),
-        (
-            "in",
-            vec!["co.in", "net.in", "org.in", "edu.in", "gov.in", "ac.in"],
-        ),
-        (
-            "cn",
-            vec!["com.cn", "net.cn", "org.cn", "edu.cn", "gov.cn", "ac.cn"],
-        ),
-        (
-            "jp",
-            vec!["co.jp", "ne.jp", "or.jp", "ac.jp", "go.jp", "ad.jp"],
-        ),
-    ];
-
-    // Generate wrong SLD variants
-    for &(base_tld, ref sld_list) in &slds {
-        if tld == base_tld {
-            for sld in sld_list {
-                variations.push(format!("{}.{}", domain, sld));
-            }
-        } else {
-            for sld in sld_list {
-                if tld == *sld {
-                    variations.push(format!("{}.{}", domain, base_tld));
-                    for other_sld in sld_list {
-                        if *other_sld != *sld {
-                            variations.push(format!("{}.{}", domain, other_sld));
-                        }
-                    }
-                }
-            }
+/// `--stats` and `--stats-json` at the end of a run that took `elapsed`
+fn report_stats(cli: &Cli, elapsed: Duration) {
+    if cli.stats {
+        print_stats(elapsed);
+    }
+    if let Some(path) = &cli.stats_json {
+        let stats = stats_json(elapsed).to_string() + "\n";
+        if let Err(e) = std::fs::write(path, stats) {
+            eprintln!("Warning: cannot write {}: {}", path.display(), e);
         }
     }
-
-    variations
 }
 
-fn generate_domain_prefix(domain: &str, tld: &str) -> Vec<String> {
-    let mut variations = Vec::new();
-
-    // Common domain prefixes
-    let prefixes = [
-        "www", "mail", "secure", "admin", "test", "dev", "api", "cdn", "auth", "login", "support",
-        "help", "shop", "store", "my", "portal", "mobile", "app", "service", "cloud", "server",
-        "vpn", "security", "monitor", "beta",
-    ];
-
-    for prefix in &prefixes {
-        variations.push(format!("{}-{}.{}", prefix, domain, tld));
-        variations.push(format!("{}.{}.{}", prefix, domain, tld));
-        variations.push(format!("{}{}.{}", prefix, domain, tld));
+/// Print the run's wall-clock time and requests, then the auto-tuned
+/// concurrency of every endpoint used during the run
+fn print_stats(elapsed: Duration) {
+    let requests = domfuzz::metrics::request_counts();
+    eprintln!(
+        "Run took {:.1}s and sent {} requests",
+        elapsed.as_secs_f64(),
+        requests.values().sum::<u64>()
+    );
+    if !requests.is_empty() {
+        eprintln!("{:<8} {:<40} {:>10}", "protocol", "endpoint", "requests");
+        for ((protocol, endpoint), count) in &requests {
+            eprintln!("{:<8} {:<40} {:>10}", protocol, endpoint, count);
+        }
+        eprintln!();
     }
 
-    variations
+    let endpoints = throttle::snapshot();
+    if endpoints.is_empty() {
+        eprintln!("No lookups were made");
+        return;
+    }
+    eprintln!(
+        "{:<40} {:>8} {:>8} {:>10} {:>10} {:>10} {:>6} {:>9}",
+        "endpoint", "limit", "peak", "requests", "decreases", "breaker", "trips", "rejected"
+    );
+    for endpoint in endpoints {
+        eprintln!(
+            "{:<40} {:>8} {:>8} {:>10} {:>10} {:>10} {:>6} {:>9}",
+            endpoint.endpoint,
+            endpoint.limit,
+            endpoint.peak,
+            endpoint.requests,
+            endpoint.decreases,
+            endpoint.breaker.as_str(),
+            endpoint.trips,
+            endpoint.rejected
+        );
+    }
 }
 
-fn generate_domain_suffix(domain: &str, tld: &str) -> Vec<String> {
-    let mut variations = Vec::new();
-
-    // Common domain suffixes
-    let suffixes = [
-        "app", "site", "web", "online", "pro", "plus", "premium", "club", "group", "tech",
-        "service", "platform", "security", "media", "shop", "store", "finance", "health", "gaming",
-        "demo", "beta",
-    ];
+/// What `--stats` prints, as written by `--stats-json`
+fn stats_json(elapsed: Duration) -> serde_json::Value {
+    let requests = domfuzz::metrics::request_counts();
+    let request_records: Vec<serde_json::Value> = requests
+        .iter()
+        .map(|((protocol, endpoint), count)| {
+            serde_json::json!({ "protocol": protocol, "endpoint": endpoint, "count": count })
+        })
+        .collect();
+    let endpoint_records: Vec<serde_json::Value> = throttle::snapshot()
+        .into_iter()
+        .map(|endpoint| {
+            serde_json::json!({
+                "endpoint": endpoint.endpoint,
+                "limit": endpoint.limit,
+                "peak": endpoint.peak,
+                "requests": endpoint.requests,
+                "decreases": endpoint.decreases,
+                "breaker": endpoint.breaker.as_str(),
+                "trips": endpoint.trips,
+                "rejected": endpoint.rejected,
+            })
+        })
+        .collect();
+    serde_json::json!({
+        "elapsed_secs": elapsed.as_secs_f64(),
+        "total_requests": requests.values().sum::<u64>(),
+        "requests": request_records,
+        "concurrency": endpoint_records,
+    })
+}
 
-    for suffix in &suffixes {
-        variations.push(format!("{}-{}.{}", domain, suffix, tld));
-        variations.push(format!("{}{}.{}", domain, suffix, tld));
-    }
+// Combo mode streams candidates from a generator thread to the checking loop
+struct ComboConfig<'a> {
+    /// Variations that normalize back to it are flagged, not looked up
+    original_domain: &'a str,
+    only_registered: bool,
+    only_available: bool,
+    output_count: usize,
+    check_status: bool,
+    /// `--batch-size`; adaptive when unset
+    batch_size: Option<usize>,
+    /// Rows of a batch in batch order rather than lookup completion order, and
+    /// batches of a fixed size (`--ordered`)
+    ordered: bool,
+    progress: ProgressMode,
+    enrich: &'a EnrichOptions,
+}
 
-    variations
+/// Everything the combo generator thread needs, owned so it can run alongside
+/// the checking loop
+struct ComboGeneration {
+    domain: Domain,
+    max_variations: Option<usize>,
+    verbose: bool,
+    enabled_transformations: HashSet<String>,
+    generator_options: GeneratorOptions,
+    min_similarity: Option<f64>,
+    /// Applied to finished domains only: chains pass through closer intermediates
+    max_similarity: Option<f64>,
+    scoring: ScoringMode,
+    /// Domains recorded by earlier runs (`--append`), never generated again
+    known_domains: HashSet<String>,
+    dedupe: DedupeMode,
+    dedupe_fp_rate: f64,
+    mode: ComboMode,
+    /// Longest chain in exhaustive mode
+    max_depth: usize,
+    /// Seed for random chains; from entropy when unset
+    seed: Option<u64>,
+    /// Chains tried before stopping (`--max-attempts`), replacing the default
+    /// budget
+    max_attempts: Option<usize>,
+    /// How long generation may run (`--max-runtime`)
+    max_runtime: Option<Duration>,
+    /// Other names of the brand (`--alias`); chains start from each as well as
+    /// from the original
+    aliases: Vec<Domain>,
+    /// Least plausibility of finished domains (see [`plausibility`])
+    min_plausibility: Option<f64>,
 }
 
-// ==================== SIMILARITY METRICS ====================
+/// Consecutive random combo chains ending on a domain already generated after
+/// which the reachable space is treated as exhausted; chains pruned below the
+/// similarity floor do not count
+const COMBO_EXHAUSTION_STREAK: usize = 10_000;
+
+/// Candidates the generator may run ahead of checking, in batches
+const COMBO_CHANNEL_BATCHES: usize = 4;
+
+async fn generate_combo_attacks_streaming(
+    generation: ComboGeneration,
+    config: &ComboConfig<'_>,
+    output: &mut OutputWriter,
+) -> Result<(), DomFuzzError> {
+    // Candidates awaiting output, best first. When checking, a window several
+    // batches deep is held back so rate-limited lookups go to the most similar
+    // candidates seen so far rather than to whatever was generated first.
+    let mut pending: BinaryHeap<PendingCandidate> = BinaryHeap::new();
+    // Adaptive sizes follow lookup times, so batches would differ between runs
+    let mut sizer = BatchSizer::new(config.batch_size, config.check_status && !config.ordered);
+    let mut total_output_count = 0;
 
-#[derive(Debug, Clone)]
-struct SimilarityScore {
-    domain: String,
-    visual_score: f64,
-    cognitive_score: f64,
-    combined_score: f64,
-}
+    // Generation is CPU-bound and checking is network-bound: run the generator on
+    // a blocking thread that keeps filling a bounded channel while batches are checked
+    let (sender, mut receiver) = tokio::sync::mpsc::channel(sizer.size() * COMBO_CHANNEL_BATCHES);
+    let producer = tokio::task::spawn_blocking(move || produce_combos(generation, sender));
 
-/// Calculate Levenshtein distance between two strings
-fn levenshtein_distance(s1: &str, s2: &str) -> usize {
-    let len1 = s1.chars().count();
-    let len2 = s2.chars().count();
+    while total_output_count < config.output_count {
+        let Some((domain, score, seed)) = receiver.recv().await else {
+            break; // Generation finished
+        };
+        record_generated(1);
+        pending.push(PendingCandidate(domain, score, seed));
 
-    if len1 == 0 {
-        return len2;
+        // Process the best batch once the window is full
+        let pending_window = if config.check_status {
+            sizer.size() * PRIORITY_WINDOW_BATCHES
+        } else {
+            sizer.size()
+        };
+        if pending.len() >= pending_window {
+            let mut batch = pop_best(&mut pending, sizer.size());
+            let checked = batch.len();
+            let started = Instant::now();
+            let batch_count =
+                process_batch(&mut batch, config, &mut total_output_count, output).await?;
+            if batch_count == 0 {
+                break; // Stop if we've reached the output limit
+            }
+            sizer.observe(checked, started.elapsed(), pending.len() + receiver.len());
+        }
     }
-    if len2 == 0 {
-        return len1;
+    // Closing the channel stops the generator at its next candidate
+    receiver.close();
+    while let Some((domain, score, seed)) = receiver.recv().await {
+        record_generated(1);
+        pending.push(PendingCandidate(domain, score, seed));
     }
 
-    let mut matrix = vec![vec![0usize; len2 + 1]; len1 + 1];
-
-    // Initialize first row and column
-    for (i, row) in matrix.iter_mut().enumerate().take(len1 + 1) {
-        row[0] = i;
+    // Process the remaining candidates, best first
+    while !pending.is_empty() && total_output_count < config.output_count {
+        let mut batch = pop_best(&mut pending, sizer.size());
+        let checked = batch.len();
+        let started = Instant::now();
+        process_batch(&mut batch, config, &mut total_output_count, output).await?;
+        sizer.observe(checked, started.elapsed(), pending.len());
     }
-    for j in 0..=len2 {
-        matrix[0][j] = j;
+    if let Err(e) = producer.await {
+        eprintln!("Warning: combo generation failed: {}", e);
     }
+    Ok(())
+}
 
-    let chars1: Vec<char> = s1.chars().collect();
-    let chars2: Vec<char> = s2.chars().collect();
+/// Generate combo candidates that meet the similarity threshold and send them
+/// until the space or attempt budget is used up or the receiver is closed
+fn produce_combos(generation: ComboGeneration, sender: tokio::sync::mpsc::Sender<ComboCandidate>) {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
 
-    for (i, &c1) in chars1.iter().enumerate() {
-        for (j, &c2) in chars2.iter().enumerate() {
-            let cost = if c1 == c2 { 0 } else { 1 };
-            matrix[i + 1][j + 1] = std::cmp::min(
-                std::cmp::min(
-                    matrix[i][j + 1] + 1, // deletion
-                    matrix[i + 1][j] + 1, // insertion
-                ),
-                matrix[i][j] + cost, // substitution
-            );
+    let mut generated_domains = match generation.dedupe {
+        DedupeMode::Exact => DomainSet::exact(generation.known_domains.clone()),
+        DedupeMode::Probabilistic => {
+            DomainSet::probabilistic(&generation.known_domains, generation.dedupe_fp_rate)
         }
-    }
+    };
+    let mut rng = match generation.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
 
-    matrix[len1][len2]
-}
+    // Define all available transformation functions with names matching CLI arguments
+    let mut all_transformation_functions = transformation_generators(&generation.generator_options);
+    // Legacy aliases still accepted in combo mode
+    all_transformation_functions.push(("keyboard", by_parts(generate_misspelling)));
+    all_transformation_functions
+        .push(("cyrillic-comprehensive", by_parts(generate_mixed_encodings)));
 
-/// Calculate homoglyph-weighted visual similarity
-fn visual_similarity(original: &str, variant: &str) -> f64 {
-    let basic_distance = levenshtein_distance(original, variant) as f64;
-    let max_len = std::cmp::max(original.len(), variant.len()) as f64;
+    // Filter transformation functions based on enabled transformations
+    let transformation_functions: Vec<(&str, Generator)> = all_transformation_functions
+        .into_iter()
+        .filter(|(name, _)| generation.enabled_transformations.contains(*name))
+        .collect();
 
-    if max_len == 0.0 {
-        return 1.0;
+    // The original domain first, then each alias; none is a variation of another
+    let seeds: Vec<Domain> = std::iter::once(generation.domain.clone())
+        .chain(generation.aliases.iter().cloned())
+        .collect();
+    let originals: Vec<String> = seeds.iter().map(Domain::to_string).collect();
+    for original in &originals {
+        generated_domains.insert(original);
     }
 
-    // Base similarity from Levenshtein distance
-    let mut similarity = 1.0 - (basic_distance / max_len);
-
-    // Bonus for homoglyph substitutions (characters that look similar)
-    let homoglyph_bonus = calculate_homoglyph_similarity(original, variant);
-
-    // Weight the final score
-    similarity = (similarity * 0.7) + (homoglyph_bonus * 0.3);
-
-    similarity.clamp(0.0, 1.0)
-}
-
-/// Calculate similarity bonus for homoglyph substitutions
-fn calculate_homoglyph_similarity(s1: &str, s2: &str) -> f64 {
-    if s1.len() != s2.len() {
-        return 0.0;
-    }
+    // Generate combo variations by applying random sequences of transformations
+    let mut attempts = 0;
+    let mut pruned = 0;
+    let mut implausible = 0;
+    let max_attempts = match (generation.max_attempts, generation.mode) {
+        (Some(max_attempts), _) => max_attempts,
+        (None, ComboMode::Random | ComboMode::Weighted) => generation
+            .max_variations
+            .map_or(usize::MAX, |max| max * 10 * seeds.len()), // Unlimited attempts for unlimited generation
+        (None, ComboMode::Exhaustive) => usize::MAX, // Bounded by the enumerated space instead
+    };
+    let deadline = generation
+        .max_runtime
+        .and_then(|runtime| Instant::now().checked_add(runtime));
+    let mut out_of_time = false;
+    let similarity = |original: &str, candidate: &str| {
+        calculate_similarity_with_mode(original, candidate, "combo", generation.scoring)
+            .combined_score
+    };
+    let meets_floor = |original: &str, candidate: &str| {
+        generation
+            .min_similarity
+            .is_none_or(|min| similarity(original, candidate) >= min)
+    };
+    // Random chains keep revisiting the same intermediates
+    let mut memo = ComboMemo::default();
+    let mut weights: Vec<_> = originals
+        .iter()
+        .map(|original| {
+            SimilarityWeights::new(move |candidate: &str| similarity(original, candidate))
+        })
+        .collect();
+    // Chains in a row that ended on a domain already generated; a long streak
+    // means the space is used up
+    let mut stale_attempts = 0;
+    let mut exhausted = false;
+    let mut exhaustive: Option<Vec<_>> = (generation.mode == ComboMode::Exhaustive).then(|| {
+        seeds
+            .iter()
+            .zip(&originals)
+            .map(|(domain, original)| {
+                ExhaustiveCombos::new(
+                    domain,
+                    &transformation_functions,
+                    generation.max_depth,
+                    move |candidate: &str| meets_floor(original, candidate),
+                )
+            })
+            .collect()
+    });
+    // Seeds take turns, so that aliases get chains even in an unlimited run
+    let mut turn = 0;
 
-    let chars1: Vec<char> = s1.chars().collect();
-    let chars2: Vec<char> = s2.chars().collect();
+    while attempts < max_attempts && !sender.is_closed() {
+        if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            out_of_time = true;
+            break;
+        }
+        attempts += 1;
+        if exhaustive.is_none() && stale_attempts >= COMBO_EXHAUSTION_STREAK {
+            exhausted = true;
+            break;
+        }
+
+        let (index, chain) = if let Some(combos) = &mut exhaustive {
+            let next = (0..seeds.len())
+                .map(|offset| (turn + offset) % seeds.len())
+                .find_map(|index| combos[index].next().map(|chain| (index, chain)));
+            let Some(next) = next else {
+                // Every chain up to the depth cap has been enumerated
+                exhausted = true;
+                break;
+            };
+            next
+        } else {
+            let index = turn % seeds.len();
+            let domain = &seeds[index];
+            // Abandon chains as soon as an intermediate step drops below the threshold
+            let mut abandoned = false;
+            let keep = |candidate: &str| {
+                let keep = meets_floor(&originals[index], candidate);
+                abandoned |= !keep;
+                keep
+            };
+            let chain = if generation.mode == ComboMode::Weighted {
+                weighted_combo_chain_memoized(
+                    &mut rng,
+                    domain,
+                    &transformation_functions,
+                    &mut memo,
+                    &mut weights[index],
+                    keep,
+                )
+            } else {
+                random_combo_chain_memoized(
+                    &mut rng,
+                    domain,
+                    &transformation_functions,
+                    &mut memo,
+                    keep,
+                )
+            };
+            turn += 1;
+            let Some(chain) = chain else {
+                if abandoned {
+                    pruned += 1;
+                }
+                continue;
+            };
+            (index, chain)
+        };
+        turn = index + 1;
+        let original_domain = &originals[index];
+        if generation.verbose {
+            for step in &chain.steps {
+                let score = calculate_similarity_with_mode(
+                    original_domain,
+                    &step.to,
+                    step.transformation,
+                    generation.scoring,
+                );
+                eprintln!("  Applied {} transformation: {} -> {} (visual:{:.3}, cognitive:{:.3}, combined:{:.3})",
+                    step.transformation, step.from, step.to,
+                    score.visual_score, score.cognitive_score, score.combined_score);
+            }
+        }
 
-    let mut homoglyph_matches = 0;
-    let mut total_positions = 0;
+        // Create the final domain name for this attempt
+        let final_domain = chain.domain;
 
-    // Common homoglyph pairs (visual confusions)
-    let homoglyphs = [
-        ('a', 'α'),
-        ('o', '0'),
-        ('i', '1'),
-        ('l', '1'),
-        ('e', '3'),
-        ('s', '$'),
-        ('g', '9'),
-        ('b', '6'),
-        ('z', '2'),
-        ('s', '5'),
-        ('o', 'ο'),
-        ('a', 'а'),
-        ('p', 'р'),
-        ('c', 'с'),
-        ('e', 'е'),
-        ('x', 'х'),
-        ('y', 'у'),
-        ('k', 'κ'),
-        ('n', 'η'),
-        ('m', 'μ'),
-    ];
+        if final_domain.to_lowercase() == original_domain.to_lowercase()
+            || generated_domains.contains(&final_domain)
+        {
+            stale_attempts += 1;
+        } else if is_valid_domain(&final_domain)
+            && (!generation.generator_options.respect_idn_policies
+                || IdnPolicies::builtin().allows(&final_domain))
+        {
+            generated_domains.insert(&final_domain);
+            stale_attempts = 0;
+
+            // Calculate similarity score against the seed the chain started from
+            let score = calculate_similarity_with_mode(
+                original_domain,
+                &final_domain,
+                "combo",
+                generation.scoring,
+            );
 
-    for (&c1, &c2) in chars1.iter().zip(chars2.iter()) {
-        total_positions += 1;
+            // Check if this domain falls within the similarity range, if any
+            let meets_threshold = generation
+                .min_similarity
+                .is_none_or(|min_sim| score.combined_score >= min_sim)
+                && generation
+                    .max_similarity
+                    .is_none_or(|max_sim| score.combined_score <= max_sim);
+            // Gibberish is only scored once it is known to be in range
+            let plausible = meets_threshold
+                && generation
+                    .min_plausibility
+                    .is_none_or(|min| plausibility(original_domain, &final_domain) >= min);
+            if meets_threshold && !plausible {
+                implausible += 1;
+            }
 
-        if c1 == c2 {
-            homoglyph_matches += 1;
-        } else {
-            // Check if it's a known homoglyph pair
-            for &(h1, h2) in &homoglyphs {
-                if (c1 == h1 && c2 == h2) || (c1 == h2 && c2 == h1) {
-                    homoglyph_matches += 1;
-                    break;
+            // Written only with --alias
+            let seed = (!generation.aliases.is_empty()).then(|| original_domain.clone());
+            // Only send domains that meet the similarity threshold
+            if plausible && sender.blocking_send((final_domain, score, seed)).is_err() {
+                break; // Output limit reached; checking has stopped
+            }
+            // If doesn't meet threshold, continue loop to generate another domain
+        }
+    }
+
+    if out_of_time {
+        eprintln!(
+            "Combo generation stopped after --max-runtime {}s ({} attempts)",
+            generation.max_runtime.unwrap_or_default().as_secs(),
+            attempts
+        );
+    } else if !exhausted && !sender.is_closed() && generation.max_attempts == Some(attempts) {
+        eprintln!("Combo generation stopped after --max-attempts {}", attempts);
+    }
+    if exhausted {
+        match &exhaustive {
+            Some(_) => eprintln!(
+                "Combo space exhausted: every chain up to depth {} was enumerated",
+                generation.max_depth
+            ),
+            None => eprintln!(
+                "Combo space exhausted: the last {} chains ended on domains already generated",
+                COMBO_EXHAUSTION_STREAK
+            ),
+        }
+    }
+    warn_script_error(&generation.generator_options);
+    if generation.verbose {
+        match &exhaustive {
+            Some(combos) => eprintln!(
+                "Combo enumeration: {} chains up to depth {}, {} distinct domains reached",
+                attempts,
+                generation.max_depth,
+                combos.iter().map(ExhaustiveCombos::reached).sum::<usize>()
+            ),
+            None => eprintln!(
+                "Combo generation: {} attempts, {} chains pruned below the similarity threshold, {} of {} transformation outputs reused",
+                attempts,
+                pruned,
+                memo.hits(),
+                memo.hits() + memo.misses()
+            ),
+        }
+        if generation.min_plausibility.is_some() {
+            eprintln!(
+                "Combo plausibility: {} domains dropped as gibberish",
+                implausible
+            );
+        }
+        if generation.mode == ComboMode::Weighted {
+            for (original, weights) in originals.iter().zip(&weights) {
+                if originals.len() > 1 {
+                    eprintln!("  From {}:", original);
+                }
+                for (name, _) in &transformation_functions {
+                    eprintln!(
+                        "  {} keeps {:.1}% of similarity per step ({} steps observed)",
+                        name,
+                        weights.retained(name) * 100.0,
+                        weights.observations(name)
+                    );
                 }
             }
         }
     }
-
-    if total_positions == 0 {
-        0.0
-    } else {
-        homoglyph_matches as f64 / total_positions as f64
-    }
 }
 
-/// Calculate cognitive/phonetic similarity
-fn cognitive_similarity(original: &str, variant: &str) -> f64 {
-    let mut similarity = 0.0;
-
-    // Phonetic similarity using simple Soundex-like approach
-    similarity += phonetic_similarity(original, variant) * 0.4;
+/// Batches of combo candidates held back, when checking status, so the best can
+/// be checked first
+const PRIORITY_WINDOW_BATCHES: usize = 10;
+
+/// Combo batch size before any batch has been checked, and the range adaptive
+/// sizing stays within
+const DEFAULT_BATCH_SIZE: usize = 20;
+const MIN_BATCH_SIZE: usize = 4;
+const MAX_BATCH_SIZE: usize = 200;
+/// Check time per batch that adaptive sizing aims for: long enough to keep many
+/// lookups in flight, short enough that results keep flowing
+const TARGET_BATCH_SECS: f64 = 3.0;
+
+/// Combo batch size. A batch is only written once its slowest lookup returns,
+/// so with status checking the size follows observed check time: batches that
+/// overrun the target shrink, and quick batches grow while candidates queue up.
+struct BatchSizer {
+    size: usize,
+    adaptive: bool,
+}
 
-    // Semantic similarity based on known cognitive confusions
-    similarity += semantic_similarity(original, variant) * 0.3;
+impl BatchSizer {
+    /// A fixed `--batch-size`, or an adaptive size if `adaptive`, as when
+    /// checking status
+    fn new(fixed: Option<usize>, adaptive: bool) -> Self {
+        BatchSizer {
+            size: fixed.unwrap_or(DEFAULT_BATCH_SIZE).max(1),
+            adaptive: fixed.is_none() && adaptive,
+        }
+    }
 
-    // Length-based similarity penalty
-    let length_diff = (original.len() as i32 - variant.len() as i32).abs() as f64;
-    let length_penalty = 1.0 - (length_diff / std::cmp::max(original.len(), variant.len()) as f64);
-    similarity += length_penalty * 0.3;
+    fn size(&self) -> usize {
+        self.size
+    }
 
-    similarity.clamp(0.0, 1.0)
+    /// Adjust after checking a batch of `checked` candidates took `elapsed`,
+    /// with `queued` more waiting
+    fn observe(&mut self, checked: usize, elapsed: Duration, queued: usize) {
+        if !self.adaptive || checked == 0 {
+            return;
+        }
+        let secs = elapsed.as_secs_f64();
+        if secs > TARGET_BATCH_SECS {
+            // Towards the size that would have met the target, at most halving
+            let fitting = (checked as f64 * TARGET_BATCH_SECS / secs) as usize;
+            self.size = fitting.max(self.size / 2).max(MIN_BATCH_SIZE);
+        } else if secs < TARGET_BATCH_SECS / 2.0 && queued >= 2 * self.size {
+            self.size = (self.size + self.size / 2).min(MAX_BATCH_SIZE);
+        }
+    }
 }
 
-/// Simple phonetic similarity calculation
-fn phonetic_similarity(s1: &str, s2: &str) -> f64 {
-    let sound1 = simple_soundex(s1);
-    let sound2 = simple_soundex(s2);
+/// A combo candidate ordered by combined similarity, then by domain so that
+/// equal scores pop in a stable order
+struct PendingCandidate(String, SimilarityScore, Option<String>);
 
-    let distance = levenshtein_distance(&sound1, &sound2) as f64;
-    let max_len = std::cmp::max(sound1.len(), sound2.len()) as f64;
+/// A combo candidate, its score, and with `--alias` the original domain or
+/// alias it was generated from
+type ComboCandidate = (String, SimilarityScore, Option<String>);
 
-    if max_len == 0.0 {
-        1.0
-    } else {
-        1.0 - (distance / max_len)
+impl Ord for PendingCandidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.1
+            .combined_score
+            .total_cmp(&other.1.combined_score)
+            .then_with(|| other.0.cmp(&self.0))
     }
 }
 
-/// Simplified Soundex algorithm for phonetic encoding
-fn simple_soundex(s: &str) -> String {
-    if s.is_empty() {
-        return String::new();
+impl PartialOrd for PendingCandidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
     }
+}
 
-    let mut result = String::new();
-    let mut prev_code = None;
+impl PartialEq for PendingCandidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
 
-    for c in s.to_lowercase().chars() {
-        let code = match c {
-            'b' | 'f' | 'p' | 'v' => Some('1'),
-            'c' | 'g' | 'j' | 'k' | 'q' | 's' | 'x' | 'z' => Some('2'),
-            'd' | 't' => Some('3'),
-            'l' => Some('4'),
-            'm' | 'n' => Some('5'),
-            'r' => Some('6'),
-            _ => None,
-        };
+impl Eq for PendingCandidate {}
 
-        if let Some(code) = code {
-            if prev_code != Some(code) {
-                result.push(code);
-                prev_code = Some(code);
-            }
-        } else {
-            prev_code = None;
-        }
-    }
-
-    result
-}
-
-/// Calculate semantic similarity based on known word confusions
-fn semantic_similarity(original: &str, variant: &str) -> f64 {
-    // Check against known cognitive confusion patterns from generate_cognitive
-    let cognitive_pairs = [
-        ("amazon", "amazom"),
-        ("google", "gogle"),
-        ("microsoft", "mircosoft"),
-        ("facebook", "facbook"),
-        ("paypal", "payball"),
-        ("secure", "secur"),
-        ("support", "suport"),
-        ("service", "servic"),
-        ("account", "acount"),
-        ("login", "loginn"),
-        ("portal", "portall"),
-        ("center", "centre"),
-        ("corp", "corporate"),
-        ("inc", "incorporated"),
-        ("tech", "technology"),
-        ("concordium", "consordium"),
-        ("consortium", "concordium"),
-    ];
+/// Remove up to `count` of the highest-scoring pending candidates
+fn pop_best(pending: &mut BinaryHeap<PendingCandidate>, count: usize) -> Vec<ComboCandidate> {
+    std::iter::from_fn(|| pending.pop())
+        .take(count)
+        .map(|PendingCandidate(domain, score, seed)| (domain, score, seed))
+        .collect()
+}
 
-    // Check if this is a known semantic confusion
-    for (word1, word2) in &cognitive_pairs {
-        if (original.contains(word1) && variant.contains(word2))
-            || (original.contains(word2) && variant.contains(word1))
-        {
-            return 0.8; // High semantic similarity
-        }
+/// Process a batch of domains for streaming output
+async fn process_batch(
+    batch: &mut Vec<ComboCandidate>,
+    config: &ComboConfig<'_>,
+    total_output_count: &mut usize,
+    output: &mut OutputWriter,
+) -> Result<usize, DomFuzzError> {
+    let max_output_count = config.output_count;
+    if batch.is_empty() || *total_output_count >= max_output_count {
+        return Ok(0);
     }
 
-    // Fallback to basic string similarity
-    let distance = levenshtein_distance(original, variant) as f64;
-    let max_len = std::cmp::max(original.len(), variant.len()) as f64;
+    let mut batch_output_count = 0;
+    let remaining_output_slots = max_output_count - *total_output_count;
+    let batch_to_process: Vec<ComboCandidate> =
+        batch.drain(..).take(remaining_output_slots).collect();
 
-    if max_len == 0.0 {
-        1.0
-    } else {
-        1.0 - (distance / max_len)
-    }
-}
+    if config.check_status {
+        // Extract domains for checking; those that normalize back to the
+        // original are flagged instead
+        let (normalized, to_check): (Vec<&ComboCandidate>, Vec<&ComboCandidate>) = batch_to_process
+            .iter()
+            .partition(|candidate| normalizes_back(config, candidate));
+        let domains_to_check: Vec<String> = to_check
+            .into_iter()
+            .map(|(domain, _, _)| domain.clone())
+            .collect();
 
-/// Calculate comprehensive similarity score
-fn calculate_similarity(
-    original: &str,
-    variant: &str,
-    _transformation_type: &str,
-) -> SimilarityScore {
-    let original_domain = original.split('.').next().unwrap_or(original);
-    let variant_domain = variant.split('.').next().unwrap_or(variant);
+        if !domains_to_check.is_empty() || !normalized.is_empty() {
+            // Per-endpoint limits tune actual lookup concurrency; this only caps the total
+            let concurrency = throttle::MAX_CONCURRENCY;
+            let mut results =
+                check_domains_concurrent(domains_to_check, concurrency, config.progress).await;
+            results.extend(
+                normalized
+                    .into_iter()
+                    .map(|(domain, _, _)| (domain.clone(), NORMALIZES_TO_ORIGINAL.to_string())),
+            );
+            if config.ordered {
+                // Back into batch order, best first
+                let position: HashMap<&str, usize> = batch_to_process
+                    .iter()
+                    .enumerate()
+                    .map(|(i, (domain, _, _))| (domain.as_str(), i))
+                    .collect();
+                results.sort_by_key(|(domain, _)| position.get(domain.as_str()).copied());
+            }
 
-    let visual_score = visual_similarity(original_domain, variant_domain);
-    let cognitive_score = cognitive_similarity(original_domain, variant_domain);
+            // Process results and apply filters
+            let mut rows = Vec::new();
+            for (domain, status) in &results {
+                let should_show = if config.only_registered {
+                    is_registered_status(status)
+                } else if config.only_available {
+                    status == "available"
+                } else {
+                    true // Show all domains with status
+                };
 
-    // Weight scores based on transformation type
-    let combined_score = match _transformation_type {
-        "mixed-encodings" | "idn_homograph" | "mixed_script" => {
-            visual_score * 0.8 + cognitive_score * 0.2
+                if should_show && rows.len() < remaining_output_slots {
+                    // Find similarity score for this domain
+                    let candidate = batch_to_process.iter().find(|(d, _, _)| d == domain);
+                    let score = candidate.map(|(_, score, _)| score);
+                    rows.push(ResultRow {
+                        score: score.map_or(0.0, |s| s.combined_score),
+                        visual_score: score.map_or(0.0, |s| s.visual_score),
+                        cognitive_score: score.map_or(0.0, |s| s.cognitive_score),
+                        typo_likelihood: None,
+                        domain,
+                        transformation: "combo",
+                        seed: candidate.and_then(|(_, _, seed)| seed.as_deref()),
+                        cluster_size: None,
+                        status: Some(status),
+                        enrichment: None,
+                    });
+                }
+            }
+            let enrichments = enrich_rows(&rows, config.enrich, config.progress).await;
+            attach_enrichments(&mut rows, &enrichments);
+            batch_output_count = output.write_rows(&rows).await?;
         }
-        "cognitive" | "homophones" => cognitive_score * 0.8 + visual_score * 0.2,
-        "typosquatting" | "omission" | "insertion" => visual_score * 0.6 + cognitive_score * 0.4,
-        _ => visual_score * 0.5 + cognitive_score * 0.5,
-    };
-
-    SimilarityScore {
-        domain: variant.to_string(),
-        visual_score,
-        cognitive_score,
-        combined_score,
+    } else {
+        // Output without status checking
+        let rows: Vec<ResultRow> = batch_to_process
+            .iter()
+            .filter(|candidate| !normalizes_back(config, candidate))
+            .take(remaining_output_slots)
+            .map(|(domain, score, seed)| ResultRow {
+                score: score.combined_score,
+                visual_score: score.visual_score,
+                cognitive_score: score.cognitive_score,
+                typo_likelihood: None,
+                domain,
+                transformation: "combo",
+                seed: seed.as_deref(),
+                cluster_size: None,
+                status: None,
+                enrichment: None,
+            })
+            .collect();
+        batch_output_count = output.write_rows(&rows).await?;
     }
+
+    *total_output_count += batch_output_count;
+    Ok(batch_output_count)
+}
+
+/// Whether a combo candidate leads back, once normalized, to the domain it was
+/// generated from
+fn normalizes_back(config: &ComboConfig, (domain, _, seed): &ComboCandidate) -> bool {
+    normalizes_to_original(seed.as_deref().unwrap_or(config.original_domain), domain)
 }
@@ -0,0 +1,239 @@
+//! Process-wide counters and latency histograms in the Prometheus text format.
+//!
+//! The checker records every lookup here; long-lived modes such as
+//! `domfuzz serve` expose [`render`] at `/metrics` so the service itself can be
+//! monitored and alerted on. Every request sent over the network is also counted
+//! by protocol and endpoint ([`record_request`]), so that a run can report the
+//! load it put on registries and the third-party quota it used.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Upper bounds, in seconds, of the latency histogram buckets
+const LATENCY_BUCKETS: [f64; 9] = [0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0];
+
+#[derive(Default)]
+struct Histogram {
+    // Cumulative counts per bucket in LATENCY_BUCKETS order
+    buckets: [u64; LATENCY_BUCKETS.len()],
+    count: u64,
+    sum: f64,
+}
+
+impl Histogram {
+    fn observe(&mut self, seconds: f64) {
+        for (bucket, bound) in self.buckets.iter_mut().zip(LATENCY_BUCKETS) {
+            if seconds <= bound {
+                *bucket += 1;
+            }
+        }
+        self.count += 1;
+        self.sum += seconds;
+    }
+}
+
+#[derive(Default)]
+struct Registry {
+    checks: u64,
+    results: BTreeMap<String, u64>,
+    rate_limited: BTreeMap<&'static str, u64>,
+    lookup_latency: BTreeMap<&'static str, Histogram>,
+    request_latency: BTreeMap<String, Histogram>,
+    last_success: Option<f64>,
+    requests: BTreeMap<(&'static str, String), u64>,
+}
+
+/// Endpoint of requests to the domains' own servers (web pages, TLS, SMTP)
+/// rather than to a registry or service
+pub const SITES: &str = "sites";
+/// Endpoint of DNS queries answered through the configured resolver
+pub const RESOLVER: &str = "resolver";
+
+lazy_static::lazy_static! {
+    static ref REGISTRY: Mutex<Registry> = Mutex::new(Registry::default());
+}
+
+fn with_registry<F: FnOnce(&mut Registry)>(f: F) {
+    // A panic while holding the lock leaves plain counters, which are still usable
+    let mut registry = REGISTRY.lock().unwrap_or_else(|e| e.into_inner());
+    f(&mut registry);
+}
+
+/// Count one completed domain check and its resulting status
+pub fn record_check(status: &str) {
+    with_registry(|r| {
+        r.checks += 1;
+        *r.results.entry(status.to_string()).or_default() += 1;
+    });
+}
+
+/// Count a rate-limit response from a lookup protocol (e.g. RDAP HTTP 429)
+pub fn record_rate_limit(protocol: &'static str) {
+    with_registry(|r| *r.rate_limited.entry(protocol).or_default() += 1);
+}
+
+/// Record the latency of one `rdap`, `whois`, or `dns` lookup
+pub fn observe_lookup(protocol: &'static str, elapsed: Duration) {
+    with_registry(|r| {
+        r.lookup_latency
+            .entry(protocol)
+            .or_default()
+            .observe(elapsed.as_secs_f64())
+    });
+}
+
+/// Record the latency of one API request, labeled by route
+pub fn observe_request(route: &str, elapsed: Duration) {
+    with_registry(|r| {
+        r.request_latency
+            .entry(route.to_string())
+            .or_default()
+            .observe(elapsed.as_secs_f64())
+    });
+}
+
+/// Count one request sent over the network: `protocol` is `rdap`, `whois`,
+/// `dns`, `http`, `tls`, or `smtp`, and `endpoint` the registry or service it
+/// went to, [`SITES`], or [`RESOLVER`]
+pub fn record_request(protocol: &'static str, endpoint: &str) {
+    with_registry(|r| {
+        *r.requests
+            .entry((protocol, endpoint.to_string()))
+            .or_default() += 1
+    });
+}
+
+/// Requests sent so far by protocol and endpoint
+pub fn request_counts() -> BTreeMap<(&'static str, String), u64> {
+    REGISTRY
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .requests
+        .clone()
+}
+
+/// Host of `url`, the endpoint its requests are counted under
+pub fn url_host(url: &str) -> &str {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    rest.split(['/', '?', '#']).next().unwrap_or(rest)
+}
+
+/// Mark a check cycle (an API check request or a monitor pass) as having
+/// completed with at least one successful lookup
+pub fn record_cycle_success() {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0.0, |d| d.as_secs_f64());
+    with_registry(|r| r.last_success = Some(now));
+}
+
+/// All metrics in the Prometheus text exposition format
+pub fn render() -> String {
+    let registry = REGISTRY.lock().unwrap_or_else(|e| e.into_inner());
+    let mut out = String::new();
+
+    out.push_str("# HELP domfuzz_checks_total Domain status checks performed.\n");
+    out.push_str("# TYPE domfuzz_checks_total counter\n");
+    let _ = writeln!(out, "domfuzz_checks_total {}", registry.checks);
+
+    out.push_str("# HELP domfuzz_check_results_total Domain status check results by status.\n");
+    out.push_str("# TYPE domfuzz_check_results_total counter\n");
+    for (status, count) in &registry.results {
+        let _ = writeln!(
+            out,
+            "domfuzz_check_results_total{{status=\"{}\"}} {}",
+            status, count
+        );
+    }
+
+    out.push_str("# HELP domfuzz_rate_limited_total Rate-limit responses from lookup servers.\n");
+    out.push_str("# TYPE domfuzz_rate_limited_total counter\n");
+    for (protocol, count) in &registry.rate_limited {
+        let _ = writeln!(
+            out,
+            "domfuzz_rate_limited_total{{protocol=\"{}\"}} {}",
+            protocol, count
+        );
+    }
+
+    out.push_str(
+        "# HELP domfuzz_requests_total Network requests sent, by protocol and endpoint.\n",
+    );
+    out.push_str("# TYPE domfuzz_requests_total counter\n");
+    for ((protocol, endpoint), count) in &registry.requests {
+        let _ = writeln!(
+            out,
+            "domfuzz_requests_total{{protocol=\"{}\",endpoint=\"{}\"}} {}",
+            protocol, endpoint, count
+        );
+    }
+
+    write_histograms(
+        &mut out,
+        "domfuzz_lookup_duration_seconds",
+        "Latency of RDAP, WHOIS, and DNS lookups.",
+        "protocol",
+        registry.lookup_latency.iter().map(|(k, v)| (*k, v)),
+    );
+    write_histograms(
+        &mut out,
+        "domfuzz_request_duration_seconds",
+        "Latency of API requests by route.",
+        "route",
+        registry
+            .request_latency
+            .iter()
+            .map(|(k, v)| (k.as_str(), v)),
+    );
+
+    out.push_str(
+        "# HELP domfuzz_last_success_timestamp_seconds Unix time of the last successful check cycle.\n",
+    );
+    out.push_str("# TYPE domfuzz_last_success_timestamp_seconds gauge\n");
+    if let Some(timestamp) = registry.last_success {
+        let _ = writeln!(
+            out,
+            "domfuzz_last_success_timestamp_seconds {:.3}",
+            timestamp
+        );
+    }
+
+    out
+}
+
+fn write_histograms<'a>(
+    out: &mut String,
+    name: &str,
+    help: &str,
+    label: &str,
+    histograms: impl Iterator<Item = (&'a str, &'a Histogram)>,
+) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} histogram", name);
+    for (value, histogram) in histograms {
+        for (bound, count) in LATENCY_BUCKETS.iter().zip(histogram.buckets) {
+            let _ = writeln!(
+                out,
+                "{}_bucket{{{}=\"{}\",le=\"{}\"}} {}",
+                name, label, value, bound, count
+            );
+        }
+        let _ = writeln!(
+            out,
+            "{}_bucket{{{}=\"{}\",le=\"+Inf\"}} {}",
+            name, label, value, histogram.count
+        );
+        let _ = writeln!(
+            out,
+            "{}_sum{{{}=\"{}\"}} {}",
+            name, label, value, histogram.sum
+        );
+        let _ = writeln!(
+            out,
+            "{}_count{{{}=\"{}\"}} {}",
+            name, label, value, histogram.count
+        );
+    }
+}
@@ -0,0 +1,985 @@
+//! Monitor mode: the same variations checked every interval, and what changed.
+//!
+//! `domfuzz monitor` re-checks a fixed set of variations each cycle.
+//! [`MonitorState`] remembers what earlier cycles found and turns differences
+//! into [`Event`]s, which [`EventWriter`] writes as NDJSON so consumers can
+//! tail a single stream instead of diffing reports. [`AlertRule`]s from the
+//! config file pick out the changes that matter and raise `alert` events,
+//! optionally posted to a webhook. Analysts' `annotated` events (see
+//! [`crate::annotate`]) share the stream.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::path::Path;
+use std::time::Duration;
+
+use chrono::{SecondsFormat, Utc};
+use ring::digest::{digest, SHA256};
+use serde::Deserialize;
+
+use crate::annotate::Annotation;
+use crate::checker::{is_failed_status, is_registered_status, network, HTTP_CLIENT};
+use crate::error::DomFuzzError;
+use crate::metrics;
+use crate::page::{stable_text, ConditionalPage, PageValidators};
+use crate::registration::Registration;
+#[cfg(feature = "screenshots")]
+use crate::screenshot::{hamming_distance, Screenshot};
+use crate::tls::Certificate;
+
+/// Something a check cycle found
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    /// A variation was seen registered for the first time, or was registered
+    /// again after being available
+    VariationRegistered {
+        domain: String,
+        transformation: String,
+        score: f64,
+        status: String,
+    },
+    /// A variation's status differs from the one an earlier cycle recorded
+    StatusChanged {
+        domain: String,
+        from: String,
+        to: String,
+    },
+    /// A registered variation gained or lost its MX records
+    MxChanged {
+        domain: String,
+        from: String,
+        to: String,
+    },
+    /// A registered variation's landing page no longer looks like its previous
+    /// capture; `from` is `parked` if that capture was taken while the
+    /// variation was parked, and `to` is always `content`
+    ScreenshotChanged {
+        domain: String,
+        from: String,
+        to: String,
+        distance: u32,
+        path: String,
+        previous_path: String,
+    },
+    /// A registered variation's landing page body differs from the previous
+    /// fetch's; `from` and `to` are page states, as in `screenshot_changed`
+    ContentChanged {
+        domain: String,
+        from: String,
+        to: String,
+        /// Final URL after redirects
+        url: String,
+        sha256: String,
+        previous_sha256: String,
+    },
+    /// A registered variation's registrar, name servers, or status codes
+    /// differ from the previous cycle's; the registrar is a one-item list
+    RegistrationChanged {
+        domain: String,
+        field: RegistrationField,
+        from: Vec<String>,
+        to: Vec<String>,
+    },
+    /// A variation presented a certificate other than the last one seen
+    CertObserved {
+        domain: String,
+        sha256: String,
+        previous_sha256: Option<String>,
+        issuer: Option<String>,
+        sans: Vec<String>,
+        /// Earlier cycles saw the variation without a certificate
+        first: bool,
+        /// Subject alternative names containing a brand keyword
+        brand_sans: Vec<String>,
+    },
+    /// A change matched an [`AlertRule`]
+    Alert {
+        rule: String,
+        severity: Severity,
+        /// The change that matched
+        trigger: Box<Event>,
+    },
+    /// An analyst annotated a variation (`domfuzz annotate`)
+    Annotated {
+        domain: String,
+        annotation: Annotation,
+    },
+    /// Every variation has been checked once more
+    CheckCycleComplete {
+        checked: usize,
+        registered: usize,
+        failed: usize,
+        changes: usize,
+        duration: Duration,
+    },
+}
+
+impl Event {
+    /// Value of the record's `event` field
+    pub fn name(&self) -> &'static str {
+        match self {
+            Event::VariationRegistered { .. } => "variation_registered",
+            Event::StatusChanged { .. } => "status_changed",
+            Event::MxChanged { .. } => "mx_changed",
+            Event::ScreenshotChanged { .. } => "screenshot_changed",
+            Event::ContentChanged { .. } => "content_changed",
+            Event::RegistrationChanged { .. } => "registration_changed",
+            Event::CertObserved { .. } => "cert_observed",
+            Event::Alert { .. } => "alert",
+            Event::Annotated { .. } => "annotated",
+            Event::CheckCycleComplete { .. } => "check_cycle_complete",
+        }
+    }
+
+    /// The variation the event is about; `None` for a cycle summary
+    pub fn domain(&self) -> Option<&str> {
+        match self {
+            Event::VariationRegistered { domain, .. }
+            | Event::StatusChanged { domain, .. }
+            | Event::MxChanged { domain, .. }
+            | Event::ScreenshotChanged { domain, .. }
+            | Event::ContentChanged { domain, .. }
+            | Event::RegistrationChanged { domain, .. }
+            | Event::CertObserved { domain, .. }
+            | Event::Annotated { domain, .. } => Some(domain),
+            Event::Alert { trigger, .. } => trigger.domain(),
+            Event::CheckCycleComplete { .. } => None,
+        }
+    }
+
+    /// The NDJSON record for this event: `event`, `timestamp` (RFC 3339, UTC),
+    /// and `cycle`, followed by the event's own fields
+    pub fn to_json(&self, cycle: u64, timestamp: &str) -> serde_json::Value {
+        let mut json = serde_json::json!({
+            "event": self.name(),
+            "timestamp": timestamp,
+            "cycle": cycle,
+        });
+        if let (Some(json), serde_json::Value::Object(fields)) =
+            (json.as_object_mut(), self.fields())
+        {
+            json.extend(fields);
+        }
+        json
+    }
+
+    /// The event's own fields; an alert has its trigger's, plus `trigger`
+    /// naming the triggering event, `rule`, and `severity`
+    fn fields(&self) -> serde_json::Value {
+        match self {
+            Event::VariationRegistered {
+                domain,
+                transformation,
+                score,
+                status,
+            } => serde_json::json!({
+                "domain": domain,
+                "transformation": transformation,
+                "score": score,
+                "status": status,
+            }),
+            Event::StatusChanged { domain, from, to } | Event::MxChanged { domain, from, to } => {
+                serde_json::json!({
+                    "domain": domain,
+                    "from": from,
+                    "to": to,
+                })
+            }
+            Event::ScreenshotChanged {
+                domain,
+                from,
+                to,
+                distance,
+                path,
+                previous_path,
+            } => serde_json::json!({
+                "domain": domain,
+                "from": from,
+                "to": to,
+                "distance": distance,
+                "path": path,
+                "previous_path": previous_path,
+            }),
+            Event::ContentChanged {
+                domain,
+                from,
+                to,
+                url,
+                sha256,
+                previous_sha256,
+            } => serde_json::json!({
+                "domain": domain,
+                "from": from,
+                "to": to,
+                "url": url,
+                "sha256": sha256,
+                "previous_sha256": previous_sha256,
+            }),
+            Event::RegistrationChanged {
+                domain,
+                field,
+                from,
+                to,
+            } => {
+                let values = |values: &Vec<String>| match field {
+                    RegistrationField::Registrar => serde_json::json!(values.first()),
+                    _ => serde_json::json!(values),
+                };
+                serde_json::json!({
+                    "domain": domain,
+                    "field": field.as_str(),
+                    "from": values(from),
+                    "to": values(to),
+                })
+            }
+            Event::CertObserved {
+                domain,
+                sha256,
+                previous_sha256,
+                issuer,
+                sans,
+                first,
+                brand_sans,
+            } => serde_json::json!({
+                "domain": domain,
+                "sha256": sha256,
+                "previous_sha256": previous_sha256,
+                "issuer": issuer,
+                "sans": sans,
+                "first": first,
+                "brand_sans": brand_sans,
+            }),
+            Event::Alert {
+                rule,
+                severity,
+                trigger,
+            } => {
+                let mut fields = trigger.fields();
+                fields["trigger"] = trigger.name().into();
+                fields["rule"] = rule.clone().into();
+                fields["severity"] = severity.as_str().into();
+                fields
+            }
+            Event::Annotated { domain, annotation } => {
+                let mut fields = annotation.to_json();
+                fields["domain"] = domain.clone().into();
+                // The record's own timestamp is when the annotation was made
+                if let Some(fields) = fields.as_object_mut() {
+                    fields.remove("timestamp");
+                }
+                fields
+            }
+            Event::CheckCycleComplete {
+                checked,
+                registered,
+                failed,
+                changes,
+                duration,
+            } => serde_json::json!({
+                "checked": checked,
+                "registered": registered,
+                "failed": failed,
+                "changes": changes,
+                "duration_secs": duration.as_secs_f64(),
+            }),
+        }
+    }
+}
+
+/// What earlier cycles found, per variation
+#[derive(Debug, Clone, Default)]
+pub struct MonitorState {
+    /// Last conclusive status; timeouts and errors do not replace it
+    statuses: HashMap<String, String>,
+    /// Whether the last MX lookup found a mail host
+    mail: HashMap<String, bool>,
+    /// Last certificate seen, or `None` while none has been
+    certificates: HashMap<String, Option<Certificate>>,
+    /// Last landing page fetched
+    pages: HashMap<String, PageSnapshot>,
+    /// Last registration details found
+    registrations: HashMap<String, Registration>,
+    /// Last capture, and whether the variation was parked when it was taken
+    #[cfg(feature = "screenshots")]
+    screenshots: HashMap<String, (Screenshot, bool)>,
+    /// Words of the monitored domain looked for in certificate SANs
+    brand_keywords: Vec<String>,
+}
+
+/// What is kept of a landing page between cycles: enough to ask the server
+/// whether it changed, and to tell when it did
+#[derive(Debug, Clone)]
+struct PageSnapshot {
+    validators: PageValidators,
+    /// SHA-256 of the body, lowercase hex
+    sha256: String,
+    /// Whether the variation was parked when the page was fetched
+    parked: bool,
+}
+
+impl MonitorState {
+    /// State for variations of a domain identified by `brand_keywords`
+    pub fn new(brand_keywords: Vec<String>) -> Self {
+        MonitorState {
+            brand_keywords,
+            ..MonitorState::default()
+        }
+    }
+
+    /// Record this cycle's `status` of `domain`. A domain seen for the first
+    /// time only produces `variation_registered`, if it is registered; a failed
+    /// lookup produces nothing, so flaky registries do not look like changes.
+    pub fn observe_status(
+        &mut self,
+        domain: &str,
+        transformation: &str,
+        score: f64,
+        status: &str,
+    ) -> Vec<Event> {
+        if is_failed_status(status) {
+            return Vec::new();
+        }
+        let previous = self.statuses.insert(domain.to_string(), status.to_string());
+        let mut events = Vec::new();
+        if let Some(previous) = &previous {
+            if previous != status {
+                events.push(Event::StatusChanged {
+                    domain: domain.to_string(),
+                    from: previous.clone(),
+                    to: status.to_string(),
+                });
+            }
+        }
+        let was_registered = previous.as_deref().is_some_and(is_registered_status);
+        if is_registered_status(status) && !was_registered {
+            events.push(Event::VariationRegistered {
+                domain: domain.to_string(),
+                transformation: transformation.to_string(),
+                score,
+                status: status.to_string(),
+            });
+        }
+        events
+    }
+
+    /// Record whether `domain` has MX records pointing at a mail host this
+    /// cycle; the first lookup only sets the baseline
+    pub fn observe_mail(&mut self, domain: &str, has_mx: bool) -> Option<Event> {
+        let previous = self.mail.insert(domain.to_string(), has_mx)?;
+        let state = |has_mx| if has_mx { MX } else { NO_MX }.to_string();
+        (previous != has_mx).then(|| Event::MxChanged {
+            domain: domain.to_string(),
+            from: state(previous),
+            to: state(has_mx),
+        })
+    }
+
+    /// Validators of `domain`'s last fetched landing page, to make this cycle's
+    /// fetch conditional
+    pub fn page_validators(&self, domain: &str) -> PageValidators {
+        self.pages
+            .get(domain)
+            .map(|page| page.validators.clone())
+            .unwrap_or_default()
+    }
+
+    /// Record this cycle's fetch of `domain`'s landing page, made while its
+    /// status was `status`. Pages are compared by a hash of their
+    /// [`stable_text`], so `304 Not Modified` and a body differing only in
+    /// markup, whitespace, or digits are no change, and the first fetch only
+    /// sets the baseline.
+    pub fn observe_page(
+        &mut self,
+        domain: &str,
+        status: &str,
+        page: ConditionalPage,
+    ) -> Option<Event> {
+        let ConditionalPage::Modified {
+            url,
+            body,
+            validators,
+        } = page
+        else {
+            return None;
+        };
+        let sha256: String = digest(&SHA256, stable_text(&body).as_bytes())
+            .as_ref()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+        let parked = status == "parked";
+        let previous = self.pages.insert(
+            domain.to_string(),
+            PageSnapshot {
+                validators,
+                sha256: sha256.clone(),
+                parked,
+            },
+        )?;
+        (previous.sha256 != sha256).then(|| Event::ContentChanged {
+            domain: domain.to_string(),
+            from: page_state(previous.parked),
+            to: page_state(parked),
+            url: url.to_string(),
+            sha256,
+            previous_sha256: previous.sha256,
+        })
+    }
+
+    /// Record this cycle's capture of `domain`, taken while its status was
+    /// `status`. A capture more than `max_distance` pHash bits from the previous
+    /// one is a change; otherwise the previous capture stays the baseline, so
+    /// gradual drift still adds up to a change.
+    #[cfg(feature = "screenshots")]
+    pub fn observe_screenshot(
+        &mut self,
+        domain: &str,
+        status: &str,
+        screenshot: Screenshot,
+        max_distance: u32,
+    ) -> Option<Event> {
+        let parked = status == "parked";
+        let Some((previous, was_parked)) = self.screenshots.get(domain) else {
+            self.screenshots
+                .insert(domain.to_string(), (screenshot, parked));
+            return None;
+        };
+        let distance = hamming_distance(previous.phash, screenshot.phash);
+        if distance <= max_distance {
+            return None;
+        }
+        let event = Event::ScreenshotChanged {
+            domain: domain.to_string(),
+            from: page_state(*was_parked),
+            to: page_state(parked),
+            distance,
+            path: screenshot.path.display().to_string(),
+            previous_path: previous.path.display().to_string(),
+        };
+        self.screenshots
+            .insert(domain.to_string(), (screenshot, parked));
+        Some(event)
+    }
+
+    /// Record `domain`'s registration details this cycle; the first lookup
+    /// only sets the baseline
+    pub fn observe_registration(&mut self, domain: &str, registration: Registration) -> Vec<Event> {
+        let Some(previous) = self
+            .registrations
+            .insert(domain.to_string(), registration.clone())
+        else {
+            return Vec::new();
+        };
+        let registrar = |r: &Registration| r.registrar.iter().cloned().collect::<Vec<_>>();
+        [
+            (
+                RegistrationField::Registrar,
+                registrar(&previous),
+                registrar(&registration),
+            ),
+            (
+                RegistrationField::Nameservers,
+                previous.nameservers,
+                registration.nameservers,
+            ),
+            (
+                RegistrationField::StatusCodes,
+                previous.status_codes,
+                registration.status_codes,
+            ),
+        ]
+        .into_iter()
+        .filter(|(_, from, to)| from != to)
+        .map(|(field, from, to)| Event::RegistrationChanged {
+            domain: domain.to_string(),
+            field,
+            from,
+            to,
+        })
+        .collect()
+    }
+
+    /// Record the certificate `domain` presented this cycle, or `None` if it
+    /// was checked and presented none. A certificate seen in the first cycle
+    /// that checked `domain` is reported with `first` unset, as a baseline;
+    /// once a certificate has been seen, `None` no longer replaces it.
+    pub fn observe_certificate(
+        &mut self,
+        domain: &str,
+        certificate: Option<&Certificate>,
+    ) -> Option<Event> {
+        let previous = self.certificates.get(domain);
+        let Some(certificate) = certificate else {
+            if previous.is_none() {
+                self.certificates.insert(domain.to_string(), None);
+            }
+            return None;
+        };
+        let first = matches!(previous, Some(None));
+        let previous_sha256 =
+            previous.and_then(|previous| previous.as_ref().map(|c| c.sha256.clone()));
+        if previous_sha256.as_ref() == Some(&certificate.sha256) {
+            return None;
+        }
+        self.certificates
+            .insert(domain.to_string(), Some(certificate.clone()));
+        let brand_sans = certificate
+            .sans
+            .iter()
+            .filter(|san| {
+                self.brand_keywords
+                    .iter()
+                    .any(|keyword| san.contains(keyword.as_str()))
+            })
+            .cloned()
+            .collect();
+        Some(Event::CertObserved {
+            domain: domain.to_string(),
+            sha256: certificate.sha256.clone(),
+            previous_sha256,
+            issuer: certificate.issuer.clone(),
+            sans: certificate.sans.clone(),
+            first,
+            brand_sans,
+        })
+    }
+}
+
+/// NDJSON event sink: one record per line, flushed as it is written
+pub struct EventWriter {
+    out: Box<dyn Write + Send>,
+    /// `--tag`s, written in every record's `tags`
+    tags: serde_json::Map<String, serde_json::Value>,
+}
+
+impl EventWriter {
+    pub fn stdout() -> Self {
+        EventWriter {
+            out: Box::new(io::stdout()),
+            tags: serde_json::Map::new(),
+        }
+    }
+
+    /// Write records to `out`, such as a Kafka producer, which takes each line
+    /// as a message
+    pub fn new(out: Box<dyn Write + Send>) -> Self {
+        EventWriter {
+            out,
+            tags: serde_json::Map::new(),
+        }
+    }
+
+    /// Append to `path`, creating it if needed, so a restarted monitor extends
+    /// the stream that consumers are tailing
+    pub fn append(path: &Path) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(EventWriter {
+            out: Box::new(file),
+            tags: serde_json::Map::new(),
+        })
+    }
+
+    /// Write `tags` in every record, as a `tags` object
+    pub fn with_tags(mut self, tags: serde_json::Map<String, serde_json::Value>) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    /// Write `event`, timestamped now, and return the record written
+    pub fn emit(&mut self, cycle: u64, event: &Event) -> io::Result<serde_json::Value> {
+        let timestamp = match event {
+            Event::Annotated { annotation, .. } => annotation.timestamp.clone(),
+            _ => Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+        };
+        let mut record = event.to_json(cycle, &timestamp);
+        if !self.tags.is_empty() {
+            record["tags"] = self.tags.clone().into();
+        }
+        writeln!(self.out, "{}", record)?;
+        self.out.flush()?;
+        Ok(record)
+    }
+}
+
+/// MX state of a domain whose MX records name a mail host
+pub const MX: &str = "mx";
+/// MX state of a domain without MX records, or with a null MX
+pub const NO_MX: &str = "no-mx";
+/// Page state of a capture or fetch taken while the variation was parked
+pub const PAGE_PARKED: &str = "parked";
+/// Page state of a capture or fetch taken while the variation was not parked
+pub const PAGE_CONTENT: &str = "content";
+
+fn page_state(parked: bool) -> String {
+    if parked { PAGE_PARKED } else { PAGE_CONTENT }.to_string()
+}
+
+/// How urgent an alert is, attached to its payload
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl Severity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Info => "info",
+            Severity::Low => "low",
+            Severity::Medium => "medium",
+            Severity::High => "high",
+            Severity::Critical => "critical",
+        }
+    }
+}
+
+/// A registration detail tracked across cycles
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RegistrationField {
+    Registrar,
+    Nameservers,
+    StatusCodes,
+}
+
+impl RegistrationField {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RegistrationField::Registrar => "registrar",
+            RegistrationField::Nameservers => "nameservers",
+            RegistrationField::StatusCodes => "status_codes",
+        }
+    }
+}
+
+/// What a transition is a change of
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dimension {
+    /// Registration status, as in `status_changed`; `active` means registered
+    /// and in use, and `registered` also matches `parked`, `sinkholed`, and
+    /// `placeholder`
+    Status,
+    /// MX state, as in `mx_changed`
+    Mx,
+    /// Landing page appearance or content, as in `screenshot_changed` and
+    /// `content_changed`
+    Page,
+}
+
+impl Dimension {
+    /// In order of precedence, for sides that several dimensions share
+    const ALL: [Dimension; 3] = [Dimension::Status, Dimension::Mx, Dimension::Page];
+
+    fn states(&self) -> &'static [&'static str] {
+        match self {
+            Dimension::Status => &[
+                "available",
+                "registered",
+                "parked",
+                "sinkholed",
+                "placeholder",
+                "active",
+                "inconsistent",
+            ],
+            Dimension::Mx => &[MX, NO_MX],
+            Dimension::Page => &[PAGE_PARKED, PAGE_CONTENT],
+        }
+    }
+
+    /// The dimension of changes `event` reports, if it reports one
+    fn of(event: &Event) -> Option<Dimension> {
+        match event {
+            Event::StatusChanged { .. } => Some(Dimension::Status),
+            Event::MxChanged { .. } => Some(Dimension::Mx),
+            Event::ScreenshotChanged { .. } | Event::ContentChanged { .. } => Some(Dimension::Page),
+            _ => None,
+        }
+    }
+}
+
+/// A change from one state to another, written `FROM->TO` (or `FROM→TO`),
+/// where `*` matches any state. Both sides belong to one [`Dimension`];
+/// `parked` is read as a status unless the other side is `content`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct Transition {
+    /// `None` when both sides are `*`
+    dimension: Option<Dimension>,
+    from: String,
+    to: String,
+}
+
+impl TryFrom<String> for Transition {
+    type Error = String;
+
+    fn try_from(transition: String) -> Result<Self, String> {
+        let (from, to) = transition
+            .split_once("->")
+            .or_else(|| transition.split_once('→'))
+            .ok_or_else(|| format!("expected FROM->TO, got {:?}", transition))?;
+        let (from, to) = (from.trim().to_lowercase(), to.trim().to_lowercase());
+        for state in [&from, &to] {
+            let known = state == "*"
+                || Dimension::ALL
+                    .iter()
+                    .any(|dimension| dimension.states().contains(&state.as_str()));
+            if !known {
+                let mut states: Vec<&str> = Vec::new();
+                for state in Dimension::ALL.iter().flat_map(|d| d.states()) {
+                    if !states.contains(state) {
+                        states.push(state);
+                    }
+                }
+                return Err(format!(
+                    "unknown state {:?} in {:?}; expected one of {}, or *",
+                    state,
+                    transition,
+                    states.join(", ")
+                ));
+            }
+        }
+        let covers = |dimension: &Dimension, state: &str| {
+            state == "*" || dimension.states().contains(&state)
+        };
+        let dimension = match Dimension::ALL
+            .into_iter()
+            .find(|dimension| covers(dimension, &from) && covers(dimension, &to))
+        {
+            _ if from == "*" && to == "*" => None,
+            Some(dimension) => Some(dimension),
+            None => {
+                return Err(format!(
+                    "{:?} mixes states of different kinds (status, MX, page)",
+                    transition
+                ))
+            }
+        };
+        Ok(Transition {
+            dimension,
+            from,
+            to,
+        })
+    }
+}
+
+impl fmt::Display for Transition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}->{}", self.from, self.to)
+    }
+}
+
+impl Transition {
+    /// Whether a change of `dimension` from `from` to `to` is this transition
+    pub fn matches(&self, dimension: Dimension, from: &str, to: &str) -> bool {
+        self.dimension.is_none_or(|own| own == dimension)
+            && state_matches(&self.from, from)
+            && state_matches(&self.to, to)
+    }
+}
+
+/// Whether a transition side `pattern` covers the observed `state`
+fn state_matches(pattern: &str, state: &str) -> bool {
+    match pattern {
+        "*" => true,
+        "registered" => is_registered_status(state),
+        "active" => state == "registered",
+        _ => pattern == state,
+    }
+}
+
+/// A change worth an alert, from `[[alerts.rule]]` in the config file
+#[derive(Debug, Clone, Deserialize)]
+#[serde(try_from = "AlertRuleConfig")]
+pub struct AlertRule {
+    pub condition: AlertCondition,
+    pub severity: Severity,
+    /// Reported as the alert's `rule`; defaults to a description of the condition
+    pub name: Option<String>,
+}
+
+/// The changes an [`AlertRule`] matches
+#[derive(Debug, Clone)]
+pub enum AlertCondition {
+    /// A status, MX, or page transition (`transition = "FROM->TO"`)
+    Transition(Transition),
+    /// A registration detail changed (`change = "FIELD"`); with patterns
+    /// (`to = [...]`), only when a value that was not there before matches one
+    Change {
+        field: RegistrationField,
+        to: Vec<String>,
+    },
+    /// A variation presented a new certificate (`certificate = "first"` or `"brand"`)
+    Certificate(CertificateCondition),
+}
+
+/// Which `cert_observed` events a `certificate` rule matches. The first
+/// certificate seen for a variation is a baseline and matches neither.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CertificateCondition {
+    /// The variation obtained its first certificate
+    First,
+    /// A new certificate's SANs contain a brand keyword
+    Brand,
+}
+
+impl CertificateCondition {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CertificateCondition::First => "first",
+            CertificateCondition::Brand => "brand",
+        }
+    }
+}
+
+/// `[[alerts.rule]]` as written: exactly one of `transition`, `change`, and
+/// `certificate`
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AlertRuleConfig {
+    transition: Option<Transition>,
+    change: Option<RegistrationField>,
+    certificate: Option<CertificateCondition>,
+    #[serde(default)]
+    to: Vec<String>,
+    severity: Severity,
+    name: Option<String>,
+}
+
+impl TryFrom<AlertRuleConfig> for AlertRule {
+    type Error = String;
+
+    fn try_from(rule: AlertRuleConfig) -> Result<Self, String> {
+        let condition = match (rule.transition, rule.change, rule.certificate) {
+            (Some(_), None, None) | (None, None, Some(_)) if !rule.to.is_empty() => {
+                return Err("`to` patterns apply to change rules only".to_string())
+            }
+            (Some(transition), None, None) => AlertCondition::Transition(transition),
+            (None, Some(field), None) => AlertCondition::Change { field, to: rule.to },
+            (None, None, Some(certificate)) => AlertCondition::Certificate(certificate),
+            _ => {
+                return Err(
+                    "an alert rule needs exactly one of transition, change, or certificate"
+                        .to_string(),
+                )
+            }
+        };
+        Ok(AlertRule {
+            condition,
+            severity: rule.severity,
+            name: rule.name,
+        })
+    }
+}
+
+impl AlertRule {
+    /// The alert `event` raises under this rule, if it is a matching change
+    pub fn alert(&self, event: &Event) -> Option<Event> {
+        let matches = match (&self.condition, event) {
+            (
+                AlertCondition::Transition(transition),
+                Event::StatusChanged { from, to, .. }
+                | Event::MxChanged { from, to, .. }
+                | Event::ScreenshotChanged { from, to, .. }
+                | Event::ContentChanged { from, to, .. },
+            ) => transition.matches(Dimension::of(event)?, from, to),
+            (
+                AlertCondition::Change {
+                    field,
+                    to: patterns,
+                },
+                Event::RegistrationChanged {
+                    field: changed,
+                    from,
+                    to,
+                    ..
+                },
+            ) => {
+                field == changed
+                    && (patterns.is_empty()
+                        || to
+                            .iter()
+                            .filter(|value| !from.contains(value))
+                            .any(|value| {
+                                patterns.iter().any(|pattern| glob_matches(pattern, value))
+                            }))
+            }
+            (
+                AlertCondition::Certificate(condition),
+                Event::CertObserved {
+                    previous_sha256,
+                    first,
+                    brand_sans,
+                    ..
+                },
+            ) => match condition {
+                CertificateCondition::First => *first,
+                CertificateCondition::Brand => {
+                    (*first || previous_sha256.is_some()) && !brand_sans.is_empty()
+                }
+            },
+            _ => false,
+        };
+        matches.then(|| Event::Alert {
+            rule: self.name.clone().unwrap_or_else(|| self.describe()),
+            severity: self.severity,
+            trigger: Box::new(event.clone()),
+        })
+    }
+
+    /// The default rule name: the transition, or e.g. `nameservers change`
+    fn describe(&self) -> String {
+        match &self.condition {
+            AlertCondition::Transition(transition) => transition.to_string(),
+            AlertCondition::Change { field, .. } => format!("{} change", field.as_str()),
+            AlertCondition::Certificate(condition) => {
+                format!("{} certificate", condition.as_str())
+            }
+        }
+    }
+}
+
+/// Case-insensitive match of `value` against `pattern`, where `*` matches any
+/// run of characters
+fn glob_matches(pattern: &str, value: &str) -> bool {
+    let (pattern, value) = (pattern.to_lowercase(), value.to_lowercase());
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = value.strip_prefix(first) else {
+        return false;
+    };
+    let mut parts: Vec<&str> = parts.collect();
+    let Some(last) = parts.pop() else {
+        return rest.is_empty();
+    };
+    for part in parts {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+/// POST an alert record to `webhook` as JSON
+pub async fn post_alert(webhook: &str, record: &serde_json::Value) -> Result<(), DomFuzzError> {
+    metrics::record_request("http", metrics::url_host(webhook));
+    HTTP_CLIENT
+        .post(webhook)
+        .timeout(network().http_timeout)
+        .json(record)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| DomFuzzError::Http {
+            url: webhook.to_string(),
+            message: e.to_string(),
+        })?;
+    Ok(())
+}
@@ -0,0 +1,145 @@
+//! TCP connectivity over IPv4 and IPv6.
+//!
+//! Connections race the addresses of a host Happy Eyeballs style (RFC 8305):
+//! families alternate, IPv6 first, and each further attempt starts after a short
+//! delay or as soon as the previous one fails, so a dead address family costs
+//! milliseconds rather than a full timeout. [`reachability`] tests each family
+//! separately, since some phishing infrastructure is only reachable over IPv6.
+
+use std::io;
+use std::net::{IpAddr, SocketAddr};
+use std::time::Duration;
+
+use futures::stream::{FuturesUnordered, StreamExt};
+use tokio::net::TcpStream;
+use tokio::time::timeout;
+
+use crate::checker::{network, DNS_RESOLVER};
+use crate::metrics;
+
+/// Time allowed for one address family to accept a connection
+pub const CONNECT_TIMEOUT_SECS: u64 = 5;
+/// Delay before racing the next address, per RFC 8305's recommendation
+pub const CONNECTION_ATTEMPT_DELAY_MS: u64 = 250;
+
+/// Ports tried, in order, when testing whether a web host is reachable
+const PROBE_PORTS: [u16; 2] = [443, 80];
+
+/// Which address families of a domain accept TCP connections
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Reachability {
+    pub ipv4: bool,
+    pub ipv6: bool,
+}
+
+impl Reachability {
+    /// `v4`, `v6`, `both`, or `none`
+    pub fn family(&self) -> &'static str {
+        match (self.ipv4, self.ipv6) {
+            (true, true) => "both",
+            (true, false) => "v4",
+            (false, true) => "v6",
+            (false, false) => "none",
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "ipv4": self.ipv4,
+            "ipv6": self.ipv6,
+            "family": self.family(),
+        })
+    }
+}
+
+/// Resolve `host` (a name or address literal) and connect to `port` on the
+/// first of its addresses to answer
+pub async fn connect(host: &str, port: u16) -> io::Result<TcpStream> {
+    metrics::record_request("dns", metrics::RESOLVER);
+    let lookup = timeout(network().dns_timeout, DNS_RESOLVER.lookup_ip(host))
+        .await
+        .map_err(|_| io::Error::new(io::ErrorKind::TimedOut, format!("resolving {}", host)))?
+        .map_err(|e| io::Error::other(format!("resolving {}: {}", host, e)))?;
+    let addresses: Vec<SocketAddr> = lookup.iter().map(|ip| SocketAddr::new(ip, port)).collect();
+    connect_any(interleave(addresses)).await
+}
+
+/// Race connections to `addresses` in order, starting each attempt after
+/// [`CONNECTION_ATTEMPT_DELAY_MS`] or the previous attempt's failure
+pub async fn connect_any(addresses: Vec<SocketAddr>) -> io::Result<TcpStream> {
+    let mut remaining = addresses.into_iter();
+    let mut attempts = FuturesUnordered::new();
+    let mut last_error = None;
+    loop {
+        if attempts.is_empty() {
+            match remaining.next() {
+                Some(address) => attempts.push(TcpStream::connect(address)),
+                None => {
+                    return Err(last_error.unwrap_or_else(|| {
+                        io::Error::new(io::ErrorKind::NotFound, "no addresses to connect to")
+                    }))
+                }
+            }
+        }
+        tokio::select! {
+            Some(result) = attempts.next() => match result {
+                Ok(stream) => return Ok(stream),
+                Err(e) => last_error = Some(e),
+            },
+            _ = tokio::time::sleep(Duration::from_millis(CONNECTION_ATTEMPT_DELAY_MS)),
+                if remaining.len() > 0 =>
+            {
+                if let Some(address) = remaining.next() {
+                    attempts.push(TcpStream::connect(address));
+                }
+            }
+        }
+    }
+}
+
+/// Test whether any of `addresses` accepts web connections, per address family
+pub async fn reachability(addresses: &[IpAddr]) -> Reachability {
+    let family = |v6: bool| {
+        let addresses: Vec<IpAddr> = addresses
+            .iter()
+            .copied()
+            .filter(|ip| ip.is_ipv6() == v6)
+            .collect();
+        async move {
+            if addresses.is_empty() {
+                return false;
+            }
+            for port in PROBE_PORTS {
+                let candidates = addresses
+                    .iter()
+                    .map(|ip| SocketAddr::new(*ip, port))
+                    .collect();
+                if let Ok(Ok(_)) = timeout(
+                    Duration::from_secs(CONNECT_TIMEOUT_SECS),
+                    connect_any(candidates),
+                )
+                .await
+                {
+                    return true;
+                }
+            }
+            false
+        }
+    };
+    let (ipv4, ipv6) = tokio::join!(family(false), family(true));
+    Reachability { ipv4, ipv6 }
+}
+
+/// Order addresses IPv6 first, alternating families
+fn interleave(addresses: Vec<SocketAddr>) -> Vec<SocketAddr> {
+    let (v6, v4): (Vec<_>, Vec<_>) = addresses.into_iter().partition(SocketAddr::is_ipv6);
+    let mut ordered = Vec::with_capacity(v6.len() + v4.len());
+    let (mut v6, mut v4) = (v6.into_iter(), v4.into_iter());
+    loop {
+        match (v6.next(), v4.next()) {
+            (None, None) => break,
+            (a, b) => ordered.extend(a.into_iter().chain(b)),
+        }
+    }
+    ordered
+}
@@ -0,0 +1,844 @@
+//! Analysis of the landing pages served by live variations.
+//!
+//! Pages are scanned for the markers of a credential-harvesting kit: password
+//! inputs, forms that submit to a different origin than the page itself, and
+//! the impersonated brand's name. A lightweight tag scanner is used instead of
+//! a full HTML parser; phishing kits are rarely subtle about their forms.
+//!
+//! `security.txt` and `robots.txt` are also retrieved: a variation publishing the
+//! original brand's own security contacts is most likely a defensive registration.
+//!
+//! With `--for-sale`, pages are also checked for domain marketplace listings
+//! (Sedo, Dan, Afternic) and sale notices, and the asking price is taken from
+//! the page's text where it shows one.
+//!
+//! The original domain's own homepage is read for its product and service
+//! names (`--site-keywords`), which combosquatting and cognitive then use.
+
+use std::sync::OnceLock;
+
+use regex::Regex;
+use reqwest::Url;
+use tokio::time::timeout;
+
+use crate::checker::{network, HTTP_CLIENT};
+use crate::error::DomFuzzError;
+use crate::generators::extract_registrable_domain;
+use crate::metrics;
+use crate::polite;
+
+/// Flag recorded for pages that look like credential-harvesting kits
+pub const CREDENTIAL_HARVEST_SUSPECT: &str = "credential-harvest-suspect";
+
+/// Flag recorded for variations whose security.txt points at the original brand
+pub const DEFENSIVE_REGISTRATION_LIKELY: &str = "defensive-registration-likely";
+
+/// Status reported, with `--for-sale`, for registered variations whose landing
+/// page offers them for sale
+pub const FOR_SALE: &str = "for-sale";
+
+/// Bytes of a page read before analysis; login forms sit near the top
+pub const MAX_PAGE_BYTES: usize = 1024 * 1024;
+
+/// Most keywords taken from a site by [`fetch_site_keywords`]
+pub const MAX_SITE_KEYWORDS: usize = 20;
+
+/// Words on every site, which say nothing about the brand; words the default
+/// combosquatting dictionary already has are left out too
+const SITE_STOPWORDS: &str = "\
+    the and for with your you our are from that this more all new about home menu search sign \
+    log contact privacy terms cookie cookies policy skip content main learn see get now here \
+    how what why who can will has have not its out use view close open next back page site \
+    website www com http https explore discover like just also today product products services \
+    english language copyright rights reserved inc support secure login pay help service \
+    account portal center app online store shop mail cloud data mobile web digital tech pro \
+    plus premium official admin manage bank finance";
+
+/// Login-related markers found on a page
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PageAnalysis {
+    /// Final URL after redirects
+    pub url: String,
+    pub password_inputs: usize,
+    /// Resolved `action` URLs of forms that submit to another origin
+    pub cross_origin_forms: Vec<String>,
+    /// Brand keywords that appear in the page
+    pub brand_keywords: Vec<String>,
+}
+
+impl PageAnalysis {
+    /// A password field combined with either an off-site form or the brand's name
+    pub fn is_credential_harvest_suspect(&self) -> bool {
+        self.password_inputs > 0
+            && (!self.cross_origin_forms.is_empty() || !self.brand_keywords.is_empty())
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "url": self.url,
+            "password_inputs": self.password_inputs,
+            "cross_origin_forms": self.cross_origin_forms,
+            "brand_keywords": self.brand_keywords,
+        })
+    }
+}
+
+/// Fetch the landing page of `domain` (HTTPS, then HTTP) and analyze it
+pub async fn analyze_page(
+    domain: &str,
+    brand_keywords: &[String],
+) -> Result<PageAnalysis, DomFuzzError> {
+    let (url, html) = fetch_page(domain).await?;
+    Ok(analyze_html(&url, &html, brand_keywords))
+}
+
+/// The final URL and the first [`MAX_PAGE_BYTES`] of the body of `domain`'s landing page
+pub async fn fetch_page(domain: &str) -> Result<(Url, String), DomFuzzError> {
+    match fetch_page_if_modified(domain, &PageValidators::default()).await? {
+        ConditionalPage::Modified { url, body, .. } => Ok((url, body)),
+        ConditionalPage::NotModified => Err(DomFuzzError::Http {
+            url: format!("https://{}", domain),
+            message: "304 Not Modified to an unconditional request".to_string(),
+        }),
+    }
+}
+
+/// Validators a server sent with a page. Sent back on the next fetch, they let
+/// an unchanged page be answered with `304 Not Modified` and no body.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PageValidators {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+impl PageValidators {
+    fn from_headers(headers: &reqwest::header::HeaderMap) -> Self {
+        let header = |name| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+        PageValidators {
+            etag: header(reqwest::header::ETAG),
+            last_modified: header(reqwest::header::LAST_MODIFIED),
+        }
+    }
+}
+
+/// A landing page fetched with [`fetch_page_if_modified`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConditionalPage {
+    /// The server answered `304 Not Modified`
+    NotModified,
+    /// The final URL, the first [`MAX_PAGE_BYTES`] of the body, and the
+    /// validators to send next time
+    Modified {
+        url: Url,
+        body: String,
+        validators: PageValidators,
+    },
+}
+
+/// Fetch `domain`'s landing page (HTTPS, then HTTP) with `If-None-Match` and
+/// `If-Modified-Since` from `validators`, so an unchanged page costs a `304`
+pub async fn fetch_page_if_modified(
+    domain: &str,
+    validators: &PageValidators,
+) -> Result<ConditionalPage, DomFuzzError> {
+    let mut last_error = None;
+    for url in [format!("https://{}", domain), format!("http://{}", domain)] {
+        if let Err(message) = polite::admit(&url).await {
+            last_error = Some(DomFuzzError::Http { url, message });
+            continue;
+        }
+        match timeout(network().http_timeout, read_page(&url, validators)).await {
+            Ok(Ok(page)) => return Ok(page),
+            Ok(Err(e)) => last_error = Some(e),
+            Err(_) => {
+                last_error = Some(DomFuzzError::Timeout {
+                    protocol: "HTTP",
+                    domain: domain.to_string(),
+                })
+            }
+        }
+    }
+    Err(last_error.expect("at least one URL was tried"))
+}
+
+async fn read_page(
+    url: &str,
+    validators: &PageValidators,
+) -> Result<ConditionalPage, DomFuzzError> {
+    let mut request = HTTP_CLIENT.get(url);
+    if let Some(etag) = &validators.etag {
+        request = request.header(reqwest::header::IF_NONE_MATCH, etag);
+    }
+    if let Some(last_modified) = &validators.last_modified {
+        request = request.header(reqwest::header::IF_MODIFIED_SINCE, last_modified);
+    }
+    metrics::record_request("http", metrics::SITES);
+    let response = request.send().await.map_err(|e| DomFuzzError::Http {
+        url: url.to_string(),
+        message: e.to_string(),
+    })?;
+    if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+        return Ok(ConditionalPage::NotModified);
+    }
+    let final_url = response.url().clone();
+    let validators = PageValidators::from_headers(response.headers());
+    Ok(ConditionalPage::Modified {
+        url: final_url,
+        body: read_body(url, response).await?,
+        validators,
+    })
+}
+
+/// The first [`MAX_PAGE_BYTES`] of a response body, or fewer under
+/// `--max-body-size`, decoded lossily
+async fn read_body(url: &str, response: reqwest::Response) -> Result<String, DomFuzzError> {
+    let body = polite::read_body(response, polite::body_limit(MAX_PAGE_BYTES))
+        .await
+        .map_err(|e| DomFuzzError::Http {
+            url: url.to_string(),
+            message: e.to_string(),
+        })?;
+    Ok(String::from_utf8_lossy(&body).into_owned())
+}
+
+/// Scan `html`, served from `url`, for login forms and brand keywords
+pub fn analyze_html(url: &Url, html: &str, brand_keywords: &[String]) -> PageAnalysis {
+    let password_inputs = tags(html, "input")
+        .filter(|tag| attribute(tag, "type").is_some_and(|t| t.eq_ignore_ascii_case("password")))
+        .count();
+
+    let mut cross_origin_forms = Vec::new();
+    for form in tags(html, "form") {
+        let Some(action) = attribute(form, "action") else {
+            continue; // Submits to the page itself
+        };
+        if let Ok(target) = url.join(action.trim()) {
+            let off_site =
+                matches!(target.scheme(), "http" | "https") && target.origin() != url.origin();
+            if off_site && !cross_origin_forms.contains(&target.to_string()) {
+                cross_origin_forms.push(target.to_string());
+            }
+        }
+    }
+
+    let lowercase = html.to_lowercase();
+    let brand_keywords = brand_keywords
+        .iter()
+        .filter(|keyword| lowercase.contains(&keyword.to_lowercase()))
+        .cloned()
+        .collect();
+
+    PageAnalysis {
+        url: url.to_string(),
+        password_inputs,
+        cross_origin_forms,
+        brand_keywords,
+    }
+}
+
+/// Marketplaces whose listing pages a variation may redirect to or link to,
+/// with the hosts they sell from
+const MARKETPLACES: &[(&str, &[&str])] = &[
+    ("sedo", &["sedo.com", "sedoparking.com"]),
+    ("dan", &["dan.com", "undeveloped.com"]),
+    ("afternic", &["afternic.com"]),
+];
+
+/// Phrases of pages offering their own domain for sale, lowercase
+const SALE_PHRASES: &[&str] = &[
+    "domain is for sale",
+    "domain for sale",
+    "may be for sale",
+    "buy this domain",
+    "purchase this domain",
+    "make an offer",
+    "available for purchase",
+];
+
+/// Words after which an amount is taken as the asking price, lowercase
+const PRICE_CUES: &[&str] = &["price", "buy", "for sale", "offer", "purchase"];
+
+/// Characters before an amount searched for a [`PRICE_CUES`] word
+const PRICE_CUE_DISTANCE: usize = 120;
+
+/// Currency symbols and the ISO 4217 codes they stand for
+const CURRENCY_SYMBOLS: &[(char, &str)] = &[('$', "USD"), ('€', "EUR"), ('£', "GBP"), ('¥', "JPY")];
+
+/// An asking price
+#[derive(Debug, Clone, PartialEq)]
+pub struct Price {
+    pub amount: f64,
+    /// ISO 4217 code, such as `USD`
+    pub currency: String,
+}
+
+impl Price {
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "amount": self.amount,
+            "currency": self.currency,
+        })
+    }
+}
+
+/// A variation's landing page offering the domain for sale
+#[derive(Debug, Clone, PartialEq)]
+pub struct SaleListing {
+    /// Final URL after redirects
+    pub url: String,
+    /// `sedo`, `dan`, or `afternic`, if the page is or points to one of their listings
+    pub marketplace: Option<&'static str>,
+    /// The asking price, if the page shows one
+    pub price: Option<Price>,
+}
+
+impl SaleListing {
+    pub fn to_json(&self) -> serde_json::Value {
+        let mut json = serde_json::json!({ "url": self.url });
+        if let Some(marketplace) = self.marketplace {
+            json["marketplace"] = marketplace.into();
+        }
+        if let Some(price) = &self.price {
+            json["price"] = price.to_json();
+        }
+        json
+    }
+}
+
+/// The sale listing `html`, served from `url`, is, if any: a page on a
+/// marketplace, a page linking to one, or a page saying the domain is for sale
+pub fn detect_sale_listing(url: &Url, html: &str) -> Option<SaleListing> {
+    let marketplace_of = |host: &str| {
+        let host = host.to_ascii_lowercase();
+        MARKETPLACES
+            .iter()
+            .find(|(_, hosts)| {
+                hosts
+                    .iter()
+                    .any(|known| host == *known || host.ends_with(&format!(".{}", known)))
+            })
+            .map(|(name, _)| *name)
+    };
+
+    let text = visible_text(html);
+    let lowercase = text.to_lowercase();
+    let says_for_sale = SALE_PHRASES.iter().any(|phrase| lowercase.contains(phrase));
+    let marketplace = url.host_str().and_then(marketplace_of).or_else(|| {
+        // Parking pages link to their marketplace whether or not the domain
+        // is for sale, so links count only alongside a sale phrase
+        if !says_for_sale {
+            return None;
+        }
+        ["a", "form", "iframe"]
+            .into_iter()
+            .flat_map(|name| {
+                tags(html, name).filter_map(|tag| {
+                    attribute(tag, "href")
+                        .or_else(|| attribute(tag, "action"))
+                        .or_else(|| attribute(tag, "src"))
+                })
+            })
+            .filter_map(|link| url.join(link.trim()).ok())
+            .find_map(|link| link.host_str().and_then(marketplace_of))
+    });
+    if marketplace.is_none() && !says_for_sale {
+        return None;
+    }
+    Some(SaleListing {
+        url: url.to_string(),
+        marketplace,
+        price: asking_price(&text),
+    })
+}
+
+/// The first amount in `text` with a currency and a [`PRICE_CUES`] word shortly
+/// before it: `$2,500`, `USD 2500`, `1.500,00 EUR`
+fn asking_price(text: &str) -> Option<Price> {
+    // Thousands grouped by commas, dots, or spaces, then two decimals or none
+    const AMOUNT: &str = r"(?:\d{1,3}(?:[.,\x{a0} ]\d{3})+|\d+)(?:[.,]\d{2})?";
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    let pattern = PATTERN.get_or_init(|| {
+        Regex::new(
+            r"(?i)(?:([$€£¥])\s?|\b(USD|EUR|GBP|JPY|CAD|AUD|CHF)\s?)(NUMBER)|(NUMBER)\s?(USD|EUR|GBP|JPY|CAD|AUD|CHF)\b"
+                .replace("NUMBER", AMOUNT)
+                .as_str(),
+        )
+        .expect("valid price pattern")
+    });
+    pattern.captures_iter(text).find_map(|captures| {
+        let start = captures.get(0)?.start();
+        let before = text
+            [text.floor_char_boundary(start.saturating_sub(PRICE_CUE_DISTANCE))..start]
+            .to_lowercase();
+        if !PRICE_CUES.iter().any(|cue| before.contains(cue)) {
+            return None;
+        }
+        let currency = match (captures.get(1), captures.get(2).or(captures.get(5))) {
+            (Some(symbol), _) => {
+                let symbol = symbol.as_str().chars().next()?;
+                CURRENCY_SYMBOLS
+                    .iter()
+                    .find(|(known, _)| *known == symbol)?
+                    .1
+                    .to_string()
+            }
+            (None, Some(code)) => code.as_str().to_uppercase(),
+            (None, None) => return None,
+        };
+        let amount = parse_amount(captures.get(3).or(captures.get(4))?.as_str())?;
+        Some(Price { amount, currency })
+    })
+}
+
+/// An amount written with thousands separators and decimals either way:
+/// `2,500`, `2.500`, `2 500`, `1,999.99`, `1.999,99`. A last separator followed
+/// by exactly two digits is the decimal point.
+fn parse_amount(text: &str) -> Option<f64> {
+    let text = text.trim();
+    let (whole, cents) = match text.rfind(['.', ',']) {
+        Some(at) if text.len() - at == 3 => (&text[..at], &text[at + 1..]),
+        _ => (text, ""),
+    };
+    let digits: String = whole.chars().filter(char::is_ascii_digit).collect();
+    let amount: f64 = format!("{}.{}", digits, if cents.is_empty() { "0" } else { cents })
+        .parse()
+        .ok()?;
+    (amount > 0.0).then_some(amount)
+}
+
+/// The text a browser shows for `html`: tags, scripts, and styles dropped, and
+/// the character references of currency symbols decoded
+fn visible_text(html: &str) -> String {
+    let lowercase = html.to_ascii_lowercase();
+    let mut text = String::new();
+    let mut at = 0;
+    while let Some(offset) = lowercase[at..].find('<') {
+        text.push_str(&html[at..at + offset]);
+        let start = at + offset;
+        let end = lowercase[start..]
+            .find('>')
+            .map_or(html.len(), |end| start + end + 1);
+        at = end;
+        for element in ["script", "style"] {
+            let rest = &lowercase[start + 1..];
+            if rest.starts_with(element) {
+                let close = format!("</{}", element);
+                at = lowercase[end..]
+                    .find(&close)
+                    .map_or(html.len(), |close| end + close);
+            }
+        }
+        text.push(' ');
+    }
+    text.push_str(&html[at.min(html.len())..]);
+    for (entity, decoded) in [
+        ("&nbsp;", " "),
+        ("&#36;", "$"),
+        ("&dollar;", "$"),
+        ("&euro;", "€"),
+        ("&pound;", "£"),
+        ("&yen;", "¥"),
+        ("&amp;", "&"),
+    ] {
+        text = text.replace(entity, decoded);
+    }
+    text
+}
+
+/// The visible text of `html` reduced to what stays put between loads of a
+/// dynamic page: lowercased, digits (timestamps, counters, ad IDs) dropped, and
+/// whitespace collapsed
+pub fn stable_text(html: &str) -> String {
+    visible_text(html)
+        .to_lowercase()
+        .replace(|c: char| c.is_ascii_digit(), "")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Fetch `domain`'s homepage and take its most prominent words, such as
+/// product names, leaving out `domain`'s own name; see [`site_keywords`]
+pub async fn fetch_site_keywords(domain: &str) -> Result<Vec<String>, DomFuzzError> {
+    let (_, html) = fetch_page(domain).await?;
+    let name = extract_registrable_domain(domain);
+    let name = name.split('.').next().unwrap_or(&name);
+    Ok(site_keywords(&html, name))
+}
+
+/// The words of `html` most prominent on the page, at most
+/// [`MAX_SITE_KEYWORDS`]. Words count for where they appear: the title, meta
+/// keywords, Open Graph names, and top headings most, descriptions and lower
+/// headings less, link text least. Words of `brand`, common words, and words
+/// shorter than 3 letters are left out.
+pub fn site_keywords(html: &str, brand: &str) -> Vec<String> {
+    let brand = brand.to_lowercase();
+    let mut scores: Vec<(String, usize)> = Vec::new();
+    let mut add = |text: &str, weight: usize| {
+        for word in text
+            .to_lowercase()
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|word| (3..=20).contains(&word.len()))
+            .filter(|word| !word.bytes().all(|c| c.is_ascii_digit()))
+            .filter(|word| {
+                !SITE_STOPWORDS.split_whitespace().any(|stop| stop == *word)
+                    && !brand.contains(word)
+            })
+        {
+            match scores.iter_mut().find(|(known, _)| known == word) {
+                Some((_, score)) => *score += weight,
+                None => scores.push((word.to_string(), weight)),
+            }
+        }
+    };
+
+    for meta in tags(html, "meta") {
+        let key = attribute(meta, "name").or_else(|| attribute(meta, "property"));
+        let weight = match key.map(str::to_ascii_lowercase).as_deref() {
+            Some("keywords" | "og:site_name" | "og:title" | "application-name") => 4,
+            Some("description" | "og:description") => 2,
+            _ => continue,
+        };
+        add(
+            &decode_entities(attribute(meta, "content").unwrap_or("")),
+            weight,
+        );
+    }
+    for (element, weight) in [("title", 4), ("h1", 4), ("h2", 2), ("h3", 1), ("a", 1)] {
+        for text in element_texts(html, element) {
+            add(&text, weight);
+        }
+    }
+
+    // Highest score first, ties in the order first seen
+    scores.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+    scores
+        .into_iter()
+        .take(MAX_SITE_KEYWORDS)
+        .map(|(word, _)| word)
+        .collect()
+}
+
+/// Text inside every `<name>...</name>` element, with nested tags dropped
+fn element_texts(html: &str, name: &str) -> Vec<String> {
+    let lowercase = html.to_ascii_lowercase();
+    let close = format!("</{}", name);
+    let mut texts = Vec::new();
+    let mut from = 0;
+    for attributes in tags(html, name) {
+        // `tags` yields slices of `html`, so the offset locates the element
+        let start = attributes.as_ptr() as usize - html.as_ptr() as usize + attributes.len();
+        if start < from {
+            continue;
+        }
+        let inner_start = (start + 1).min(html.len());
+        let Some(end) = lowercase[inner_start..].find(&close) else {
+            break;
+        };
+        let inner = &html[inner_start..inner_start + end];
+        let mut text = String::new();
+        let mut in_tag = false;
+        for c in inner.chars() {
+            match c {
+                '<' => in_tag = true,
+                '>' if in_tag => {
+                    in_tag = false;
+                    text.push(' ');
+                }
+                _ if !in_tag => text.push(c),
+                _ => {}
+            }
+        }
+        texts.push(decode_entities(&text));
+        from = inner_start + end;
+    }
+    texts
+}
+
+/// `text` with character references (`&amp;`, `&#39;`) as spaces; keywords
+/// are made of letters and digits only
+fn decode_entities(text: &str) -> String {
+    let mut decoded = String::new();
+    let mut rest = text;
+    while let Some(at) = rest.find('&') {
+        decoded.push_str(&rest[..at]);
+        let after = &rest[at + 1..];
+        match after.find(';') {
+            Some(end)
+                if end <= 8
+                    && after[..end]
+                        .bytes()
+                        .all(|c| c.is_ascii_alphanumeric() || c == b'#') =>
+            {
+                decoded.push(' ');
+                rest = &after[end + 1..];
+            }
+            _ => {
+                decoded.push('&');
+                rest = after;
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+/// The attribute text of every `<name ...>` tag, without the angle brackets
+fn tags<'a>(html: &'a str, name: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+    // ASCII lowercasing keeps byte offsets aligned with `html`
+    let lowercase = html.to_ascii_lowercase();
+    let open = format!("<{}", name);
+    let mut starts = Vec::new();
+    let mut from = 0;
+    while let Some(offset) = lowercase[from..].find(&open) {
+        let start = from + offset + open.len();
+        from = start;
+        // Require a delimiter so `<input` doesn't match `<inputs`
+        if lowercase[start..]
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_whitespace() || c == '>' || c == '/')
+        {
+            starts.push(start);
+        }
+    }
+    starts.into_iter().map(move |start| {
+        let end = html[start..]
+            .find('>')
+            .map_or(html.len(), |end| start + end);
+        &html[start..end]
+    })
+}
+
+/// Value of attribute `name` in a tag's attribute text, unquoted
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let mut rest = tag;
+    loop {
+        rest = rest.trim_start_matches(|c: char| c.is_ascii_whitespace() || c == '/');
+        if rest.is_empty() {
+            return None;
+        }
+        let key_end = rest
+            .find(|c: char| c.is_ascii_whitespace() || c == '=' || c == '/')
+            .unwrap_or(rest.len());
+        let key = &rest[..key_end];
+        rest = rest[key_end..].trim_start();
+
+        let value = if let Some(after_eq) = rest.strip_prefix('=') {
+            let after_eq = after_eq.trim_start();
+            let (value, remainder) = match after_eq.chars().next() {
+                Some(quote @ ('"' | '\'')) => {
+                    let inner = &after_eq[1..];
+                    let end = inner.find(quote).unwrap_or(inner.len());
+                    (&inner[..end], inner.get(end + 1..).unwrap_or(""))
+                }
+                _ => {
+                    let end = after_eq
+                        .find(|c: char| c.is_ascii_whitespace())
+                        .unwrap_or(after_eq.len());
+                    (&after_eq[..end], &after_eq[end..])
+                }
+            };
+            rest = remainder;
+            value
+        } else {
+            ""
+        };
+
+        if key.eq_ignore_ascii_case(name) {
+            return Some(value);
+        }
+    }
+}
+
+/// Fields of a `/.well-known/security.txt` file (RFC 9116)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SecurityTxt {
+    pub url: String,
+    /// `Contact:` values: `mailto:`, `https:`, or `tel:` URIs
+    pub contacts: Vec<String>,
+    /// `Policy:` URLs
+    pub policies: Vec<String>,
+    pub canonical: Vec<String>,
+    pub expires: Option<String>,
+}
+
+impl SecurityTxt {
+    /// Whether any contact, policy, or canonical URL belongs to `registrable_domain`
+    pub fn points_to(&self, registrable_domain: &str) -> bool {
+        self.contacts
+            .iter()
+            .chain(&self.policies)
+            .chain(&self.canonical)
+            .filter_map(|value| uri_domain(value))
+            .any(|domain| extract_registrable_domain(&domain) == registrable_domain)
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        let mut json = serde_json::json!({
+            "url": self.url,
+            "contacts": self.contacts,
+            "policies": self.policies,
+            "canonical": self.canonical,
+        });
+        if let Some(expires) = &self.expires {
+            json["expires"] = expires.clone().into();
+        }
+        json
+    }
+}
+
+/// What a `robots.txt` says about crawling
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RobotsTxt {
+    pub url: String,
+    /// `Sitemap:` URLs
+    pub sitemaps: Vec<String>,
+    /// Whether all crawlers are disallowed from the whole site, common on
+    /// freshly staged phishing hosts hiding from search engines
+    pub disallow_all: bool,
+}
+
+impl RobotsTxt {
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "url": self.url,
+            "sitemaps": self.sitemaps,
+            "disallow_all": self.disallow_all,
+        })
+    }
+}
+
+/// Fetch and parse `domain`'s security.txt; `None` if it has none
+pub async fn fetch_security_txt(domain: &str) -> Result<Option<SecurityTxt>, DomFuzzError> {
+    let Some((url, text)) = fetch_text_file(domain, "/.well-known/security.txt").await? else {
+        return Ok(None);
+    };
+    let mut security = SecurityTxt {
+        url: url.to_string(),
+        ..SecurityTxt::default()
+    };
+    for (field, value) in text_fields(&text) {
+        match field.as_str() {
+            "contact" => security.contacts.push(value.to_string()),
+            "policy" => security.policies.push(value.to_string()),
+            "canonical" => security.canonical.push(value.to_string()),
+            "expires" => security.expires = Some(value.to_string()),
+            _ => {}
+        }
+    }
+    Ok(Some(security))
+}
+
+/// Fetch and parse `domain`'s robots.txt; `None` if it has none
+pub async fn fetch_robots_txt(domain: &str) -> Result<Option<RobotsTxt>, DomFuzzError> {
+    let Some((url, text)) = fetch_text_file(domain, "/robots.txt").await? else {
+        return Ok(None);
+    };
+    let mut robots = RobotsTxt {
+        url: url.to_string(),
+        ..RobotsTxt::default()
+    };
+    // Group state: whether the current group applies to every user agent
+    let mut for_all_agents = false;
+    let mut in_agent_lines = false;
+    for (field, value) in text_fields(&text) {
+        match field.as_str() {
+            "user-agent" => {
+                if !in_agent_lines {
+                    for_all_agents = false;
+                }
+                in_agent_lines = true;
+                for_all_agents |= value == "*";
+            }
+            "disallow" => {
+                in_agent_lines = false;
+                robots.disallow_all |= for_all_agents && value == "/";
+            }
+            "sitemap" => robots.sitemaps.push(value.to_string()),
+            _ => in_agent_lines = false,
+        }
+    }
+    Ok(Some(robots))
+}
+
+/// `path` on `domain` (HTTPS, then HTTP) if served as `text/plain`. Parking
+/// pages answer every path with HTML, so anything else counts as absent.
+async fn fetch_text_file(domain: &str, path: &str) -> Result<Option<(Url, String)>, DomFuzzError> {
+    let mut last_error = None;
+    for url in [
+        format!("https://{}{}", domain, path),
+        format!("http://{}{}", domain, path),
+    ] {
+        if polite::admit(&url).await.is_err() {
+            return Ok(None);
+        }
+        metrics::record_request("http", metrics::SITES);
+        let response = match timeout(network().http_timeout, HTTP_CLIENT.get(&url).send()).await {
+            Ok(Ok(response)) => response,
+            Ok(Err(e)) => {
+                last_error = Some(DomFuzzError::Http {
+                    url,
+                    message: e.to_string(),
+                });
+                continue;
+            }
+            Err(_) => {
+                last_error = Some(DomFuzzError::Timeout {
+                    protocol: "HTTP",
+                    domain: domain.to_string(),
+                });
+                continue;
+            }
+        };
+
+        let is_text = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.to_ascii_lowercase().starts_with("text/plain"));
+        if !response.status().is_success() || !is_text {
+            return Ok(None);
+        }
+        let final_url = response.url().clone();
+        let text = timeout(network().http_timeout, read_body(&url, response))
+            .await
+            .map_err(|_| DomFuzzError::Timeout {
+                protocol: "HTTP",
+                domain: domain.to_string(),
+            })??;
+        return Ok(Some((final_url, text)));
+    }
+    Err(last_error.expect("at least one URL was tried"))
+}
+
+/// `Field: value` lines of a text file, with lowercased field names and
+/// comments and blank lines skipped
+fn text_fields(text: &str) -> impl Iterator<Item = (String, &str)> {
+    text.lines().filter_map(|line| {
+        let line = line.trim();
+        if line.starts_with('#') {
+            return None;
+        }
+        let line = line.split(" #").next()?;
+        let (field, value) = line.split_once(':')?;
+        Some((field.trim().to_ascii_lowercase(), value.trim()))
+    })
+}
+
+/// Domain of a `mailto:` address or `http(s):` URL
+fn uri_domain(uri: &str) -> Option<String> {
+    if let Some(address) = uri.strip_prefix("mailto:") {
+        let domain = address.rsplit_once('@')?.1;
+        return Some(domain.trim_end_matches('>').to_ascii_lowercase());
+    }
+    Url::parse(uri)
+        .ok()?
+        .host_str()
+        .map(|host| host.to_ascii_lowercase())
+}
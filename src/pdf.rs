@@ -0,0 +1,299 @@
+//! PDF reports (`domfuzz report --format pdf`).
+//!
+//! Brand-protection deliverables to legal teams are still overwhelmingly PDFs.
+//! The report is laid out with printpdf as a paginated A4 table: a title naming
+//! the original domain, how many variations there are and when the report was
+//! made, then every variation with its score and status colored as on the
+//! terminal. Only the standard PDF fonts are used, which cover Latin-1, so
+//! internationalized domains are shown in their `xn--` form.
+
+use printpdf::{BuiltinFont, Color, IndirectFontRef, Mm, PdfDocument, PdfLayerReference, Pt, Rgb};
+
+use crate::domain::Domain;
+use crate::error::DomFuzzError;
+use crate::report::Report;
+
+/// Page size (A4 portrait) and margin, in points
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+const MARGIN: f32 = 40.0;
+/// Table rows are set in 8pt Courier, whose glyphs are 0.6em wide
+const FONT_SIZE: f32 = 8.0;
+const CHAR_WIDTH: f32 = FONT_SIZE * 0.6;
+const LINE_HEIGHT: f32 = 11.0;
+/// Characters that fit across the table
+const TABLE_CHARS: usize = ((PAGE_WIDTH - 2.0 * MARGIN) / CHAR_WIDTH) as usize;
+const SCORE_CHARS: usize = "100.00%".len();
+const STATUS_CHARS: usize = "inconsistent".len();
+const MAX_TRANSFORMATION_CHARS: usize = 24;
+
+const BLACK: (f32, f32, f32) = (0.0, 0.0, 0.0);
+const GREY: (f32, f32, f32) = (0.45, 0.45, 0.45);
+const RED: (f32, f32, f32) = (0.75, 0.1, 0.1);
+const AMBER: (f32, f32, f32) = (0.7, 0.5, 0.0);
+const GREEN: (f32, f32, f32) = (0.1, 0.5, 0.1);
+
+/// One row of the table
+struct Row {
+    score: f64,
+    domain: String,
+    transformation: String,
+    status: Option<String>,
+}
+
+impl Row {
+    fn from_record(record: &serde_json::Value) -> Row {
+        let field = |name: &str| record.get(name).and_then(|value| value.as_str());
+        let domain = field("domain").unwrap_or_default();
+        let transformation = field("transformation").unwrap_or_default();
+        let seed = field("seed");
+        let size = record.get("cluster_size").and_then(|size| size.as_u64());
+        Row {
+            score: record.get("score").and_then(|s| s.as_f64()).unwrap_or(0.0),
+            domain: Domain::parse(domain).map_or_else(|_| domain.to_string(), |d| d.to_ascii()),
+            transformation: match (seed, size) {
+                (Some(seed), Some(size)) => {
+                    format!("{} ({}, {} alike)", transformation, seed, size)
+                }
+                (Some(seed), None) => format!("{} ({})", transformation, seed),
+                (None, Some(size)) => format!("{} ({} alike)", transformation, size),
+                (None, None) => transformation.to_string(),
+            },
+            status: field("status").map(str::to_string),
+        }
+    }
+}
+
+/// The fonts of the report
+struct Fonts {
+    table: IndirectFontRef,
+    table_header: IndirectFontRef,
+    text: IndirectFontRef,
+    title: IndirectFontRef,
+}
+
+/// `report` laid out as a PDF document
+pub fn render(report: &Report) -> Result<Vec<u8>, DomFuzzError> {
+    let failed =
+        |e: printpdf::Error| DomFuzzError::Io(std::io::Error::other(format!("pdf: {}", e)));
+    let rows: Vec<Row> = report.results.iter().map(Row::from_record).collect();
+    let title = match &report.domain {
+        Some(domain) => format!(
+            "DomFuzz report: {}",
+            Domain::parse(domain).map_or_else(|_| domain.clone(), |d| d.to_ascii())
+        ),
+        None => report.title(),
+    };
+    let summary = format!(
+        "{} variations from {}, reported {}",
+        rows.len(),
+        report.source,
+        report.generated
+    );
+
+    let transformation_width = rows
+        .iter()
+        .map(|row| row.transformation.chars().count())
+        .max()
+        .unwrap_or(0)
+        .clamp("TRANSFORMATION".len(), MAX_TRANSFORMATION_CHARS);
+    let domain_width = TABLE_CHARS - SCORE_CHARS - transformation_width - STATUS_CHARS - 6;
+    let columns = [
+        0,
+        SCORE_CHARS + 2,
+        SCORE_CHARS + domain_width + 4,
+        SCORE_CHARS + domain_width + transformation_width + 6,
+    ]
+    .map(|offset| MARGIN + offset as f32 * CHAR_WIDTH);
+    let labels: &[&str] = if rows.iter().any(|row| row.status.is_some()) {
+        &["SCORE", "DOMAIN", "TRANSFORMATION", "STATUS"]
+    } else {
+        &["SCORE", "DOMAIN", "TRANSFORMATION"]
+    };
+
+    // The first page gives up three lines to the title and summary
+    let top = PAGE_HEIGHT - MARGIN;
+    let bottom = MARGIN + 2.0 * LINE_HEIGHT;
+    let first_header = top - 4.0 * LINE_HEIGHT;
+    let fits = |header: f32| ((header - bottom) / LINE_HEIGHT) as usize;
+    let mut pages: Vec<&[Row]> = Vec::new();
+    let (first, mut rest) = rows.split_at(rows.len().min(fits(first_header)));
+    pages.push(first);
+    while !rest.is_empty() {
+        let (page, remaining) = rest.split_at(rest.len().min(fits(top)));
+        pages.push(page);
+        rest = remaining;
+    }
+
+    let (document, first_page, first_layer) = PdfDocument::new(
+        latin1(&title),
+        Mm::from(Pt(PAGE_WIDTH)),
+        Mm::from(Pt(PAGE_HEIGHT)),
+        "Table",
+    );
+    let document = document.with_producer(format!("DomFuzz {}", env!("CARGO_PKG_VERSION")));
+    let font = |font: BuiltinFont| document.add_builtin_font(font).map_err(failed);
+    let fonts = Fonts {
+        table: font(BuiltinFont::Courier)?,
+        table_header: font(BuiltinFont::CourierBold)?,
+        text: font(BuiltinFont::Helvetica)?,
+        title: font(BuiltinFont::HelveticaBold)?,
+    };
+    for (index, page_rows) in pages.iter().enumerate() {
+        let layer = if index == 0 {
+            document.get_page(first_page).get_layer(first_layer)
+        } else {
+            let (page, layer) =
+                document.add_page(Mm::from(Pt(PAGE_WIDTH)), Mm::from(Pt(PAGE_HEIGHT)), "Table");
+            document.get_page(page).get_layer(layer)
+        };
+        let mut header = top;
+        if index == 0 {
+            text(
+                &layer,
+                &fonts.title,
+                14.0,
+                MARGIN,
+                top - 10.0,
+                BLACK,
+                &title,
+            );
+            text(&layer, &fonts.text, 9.0, MARGIN, top - 24.0, GREY, &summary);
+            header = first_header;
+        }
+        for (x, label) in columns.iter().zip(labels) {
+            text(
+                &layer,
+                &fonts.table_header,
+                FONT_SIZE,
+                *x,
+                header,
+                BLACK,
+                label,
+            );
+        }
+        for (line, row) in page_rows.iter().enumerate() {
+            let y = header - (line + 1) as f32 * LINE_HEIGHT;
+            let mut cells = vec![
+                (
+                    format!("{:>6.2}%", row.score * 100.0),
+                    score_color(row.score),
+                ),
+                (truncate_chars(&row.domain, domain_width), BLACK),
+                (
+                    truncate_chars(&row.transformation, transformation_width),
+                    BLACK,
+                ),
+            ];
+            if let Some(status) = &row.status {
+                cells.push((status.clone(), status_color(status)));
+            }
+            for (x, (cell, color)) in columns.iter().zip(cells) {
+                text(&layer, &fonts.table, FONT_SIZE, *x, y, color, &cell);
+            }
+        }
+        let footer = format!("{} - page {} of {}", title, index + 1, pages.len());
+        text(&layer, &fonts.text, 7.0, MARGIN, MARGIN, GREY, &footer);
+    }
+    document.save_to_bytes().map_err(failed)
+}
+
+/// Set `content` at (`x`, `y`) points from the bottom left
+fn text(
+    layer: &PdfLayerReference,
+    font: &IndirectFontRef,
+    size: f32,
+    x: f32,
+    y: f32,
+    (r, g, b): (f32, f32, f32),
+    content: &str,
+) {
+    layer.set_fill_color(Color::Rgb(Rgb::new(r, g, b, None)));
+    layer.use_text(
+        latin1(content),
+        size,
+        Mm::from(Pt(x)),
+        Mm::from(Pt(y)),
+        font,
+    );
+}
+
+/// `text` with what the standard fonts cannot show replaced by `?`
+fn latin1(text: &str) -> String {
+    text.chars()
+        .map(|c| {
+            if (' '..='\u{ff}').contains(&c) {
+                c
+            } else {
+                '?'
+            }
+        })
+        .collect()
+}
+
+/// `text` cut to `width` characters, marking the cut with `~`
+fn truncate_chars(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+    let mut truncated: String = text.chars().take(width.saturating_sub(1)).collect();
+    truncated.push('~');
+    truncated
+}
+
+/// Color for a similarity score, as the terminal table colors it
+fn score_color(score: f64) -> (f32, f32, f32) {
+    if score >= 0.75 {
+        RED
+    } else if score >= 0.6 {
+        AMBER
+    } else {
+        BLACK
+    }
+}
+
+/// Color for a domain status, as the terminal table colors it
+fn status_color(status: &str) -> (f32, f32, f32) {
+    match status {
+        "available" => GREEN,
+        "registered" => RED,
+        "parked" | "for-sale" | "sinkholed" | "placeholder" => AMBER,
+        _ => GREY,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use printpdf::lopdf;
+
+    fn report(variations: usize) -> Report {
+        let contents: String = (0..variations)
+            .map(|i| {
+                format!(
+                    "{{\"schema_version\":1,\"domain\":\"examp{}e.com\",\"transformation\":\"1337speak\",\"score\":0.9,\"status\":\"registered\"}}\n",
+                    i
+                )
+            })
+            .collect();
+        Report::from_jsonl(&contents, "results.jsonl", Some("exämple.com")).unwrap()
+    }
+
+    #[test]
+    fn output_parses_as_pdf() {
+        let bytes = render(&report(3)).unwrap();
+        assert!(bytes.starts_with(b"%PDF-"));
+        let document = lopdf::Document::load_mem(&bytes).unwrap();
+        assert_eq!(document.get_pages().len(), 1);
+        let text = document.extract_text(&[1]).unwrap();
+        assert!(text.contains("xn--exmple-cua.com"), "{}", text);
+        assert!(text.contains("examp0e.com"), "{}", text);
+    }
+
+    #[test]
+    fn long_reports_span_pages() {
+        let bytes = render(&report(200)).unwrap();
+        let document = lopdf::Document::load_mem(&bytes).unwrap();
+        assert_eq!(document.get_pages().len(), 4);
+    }
+}
@@ -0,0 +1,463 @@
+//! Sound-alike respellings for the homophones transformation.
+//!
+//! A name is respelled the ways English writes the same sounds: `ph` for `f`,
+//! `c` for `k`, `ight` for `ite`, a doubled or single consonant. A respelling is
+//! kept only if its Metaphone key, the consonant skeleton of how it sounds,
+//! matches the name's, which drops swaps that change the sound: `cell` for
+//! `sell`, but not `cale` for `sale`. Names are split into the words of
+//! `data/words-en.txt` where they can be (`paypal` is `pay` and `pal`), so each
+//! is respelled on its own and words with irregular homophones (`buy`, `by`,
+//! `bye`) are swapped whole; respellings that are words themselves rank first.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::OnceLock;
+
+/// Most respellings produced per word of the name, letters between digits and
+/// hyphens
+pub const MAX_HOMOPHONES: usize = 32;
+
+/// Spellings and another way to write the same sound, applied anywhere and
+/// kept if the Metaphone key is unchanged
+const RESPELLINGS: &[(&str, &str)] = &[
+    ("ph", "f"),
+    ("f", "ph"),
+    ("ck", "k"),
+    ("ck", "c"),
+    ("c", "k"),
+    ("k", "c"),
+    ("c", "s"),
+    ("s", "c"),
+    ("q", "k"),
+    ("qu", "kw"),
+    ("kw", "qu"),
+    ("x", "ks"),
+    ("ks", "x"),
+    ("cks", "x"),
+    ("s", "z"),
+    ("z", "s"),
+    ("g", "j"),
+    ("j", "g"),
+    ("wh", "w"),
+    ("w", "wh"),
+    ("wr", "r"),
+    ("kn", "n"),
+    ("tion", "shun"),
+    ("ight", "ite"),
+    ("ite", "ight"),
+    ("i", "y"),
+    ("y", "i"),
+    ("ee", "ea"),
+    ("ea", "ee"),
+    ("oo", "u"),
+    ("u", "oo"),
+    ("ai", "ay"),
+    ("ay", "ai"),
+    ("er", "ur"),
+    ("ur", "er"),
+    ("our", "or"),
+    ("or", "our"),
+];
+
+/// Vowel teams and the vowel they sound like before a consonant and a silent
+/// `e`: `mail` is `male`, `road` is `rode`. The first team for a vowel is the
+/// one a silent `e` spelling turns into, except for `o`, where too many words
+/// (`move`, `love`, `done`) do not sound like `oa`.
+const SILENT_E: &[(&str, char)] = &[
+    ("ai", 'a'),
+    ("ea", 'e'),
+    ("ee", 'e'),
+    ("oa", 'o'),
+    ("igh", 'i'),
+];
+
+/// Consonants written single or doubled alike where no vowel follows; between
+/// vowels, doubling shortens the first
+const DOUBLED: &str = "bdfglmnprstz";
+
+/// Words that sound alike where no spelling rule leads from one to the other,
+/// swapped only where the name has the word whole
+const IRREGULAR: &[&[&str]] = &[
+    &["to", "too", "two"],
+    &["one", "won"],
+    &["hour", "our"],
+    &["four", "for", "fore"],
+    &["eight", "ate"],
+    &["right", "write", "rite"],
+    &["buy", "by", "bye"],
+    &["know", "no"],
+    &["knew", "new"],
+    &["would", "wood"],
+    &["through", "threw"],
+    &["there", "their"],
+    &["way", "weigh"],
+    &["wait", "weight"],
+    &["flour", "flower"],
+    &["rain", "reign"],
+    &["son", "sun"],
+    &["read", "red"],
+    &["lead", "led"],
+    &["blue", "blew"],
+    &["be", "bee"],
+    &["sea", "see"],
+    &["peace", "piece"],
+    &["break", "brake"],
+    &["whole", "hole"],
+    &["role", "roll"],
+    &["soul", "sole"],
+];
+
+/// One change to a word: `len` bytes at `at` replaced by `with`
+#[derive(Debug, Clone)]
+struct Edit {
+    at: usize,
+    len: usize,
+    with: String,
+    /// Whether the result must keep the word's Metaphone key; irregular
+    /// homophones need not
+    checked: bool,
+}
+
+/// The Metaphone key of `word`: its consonant sounds as letters, with `X` for
+/// `sh`, `0` for `th`, and a vowel only where the word starts with one.
+/// Characters other than ASCII letters are ignored.
+pub fn metaphone(word: &str) -> String {
+    let word: Vec<u8> = word
+        .bytes()
+        .filter(u8::is_ascii_alphabetic)
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    let at = |i: usize| word.get(i).copied();
+    let vowel = |c: Option<u8>| matches!(c, Some(b'a' | b'e' | b'i' | b'o' | b'u'));
+    let mut key = String::new();
+
+    let mut start = 0;
+    match (at(0), at(1)) {
+        (Some(b'a'), Some(b'e'))
+        | (Some(b'g' | b'k' | b'p'), Some(b'n'))
+        | (Some(b'w'), Some(b'r')) => start = 1,
+        (Some(b'x'), _) => {
+            key.push('S');
+            start = 1;
+        }
+        (Some(b'w'), Some(b'h')) => {
+            key.push('W');
+            start = 2;
+        }
+        _ => {}
+    }
+
+    for i in start..word.len() {
+        let c = word[i];
+        let prev = i.checked_sub(1).and_then(at);
+        let next = at(i + 1);
+        if prev == Some(c) && c != b'c' {
+            continue;
+        }
+        match c {
+            b'a' | b'e' | b'i' | b'o' | b'u' => {
+                if i == 0 {
+                    key.push(c.to_ascii_uppercase() as char);
+                }
+            }
+            b'b' => {
+                if !(prev == Some(b'm') && i + 1 == word.len()) {
+                    key.push('B');
+                }
+            }
+            b'c' => match next {
+                Some(b'i') if at(i + 2) == Some(b'a') => key.push('X'),
+                Some(b'h') if prev == Some(b's') => key.push('K'),
+                Some(b'h') => key.push('X'),
+                Some(b'i' | b'e' | b'y') if prev == Some(b's') => {}
+                Some(b'i' | b'e' | b'y') => key.push('S'),
+                _ => key.push('K'),
+            },
+            b'd' => {
+                if next == Some(b'g') && matches!(at(i + 2), Some(b'e' | b'i' | b'y')) {
+                    key.push('J');
+                } else {
+                    key.push('T');
+                }
+            }
+            b'g' => match next {
+                Some(b'h') if i + 2 == word.len() => key.push('F'),
+                Some(b'h') if !vowel(at(i + 2)) => {}
+                Some(b'n') if i + 2 == word.len() || &word[i + 1..] == b"ned" => {}
+                Some(b'i' | b'e' | b'y') if prev != Some(b'g') => key.push('J'),
+                _ => key.push('K'),
+            },
+            b'h' => {
+                let silent = matches!(prev, Some(b'c' | b's' | b'p' | b't' | b'g'))
+                    || (vowel(prev) && !vowel(next));
+                if !silent {
+                    key.push('H');
+                }
+            }
+            b'k' => {
+                if prev != Some(b'c') {
+                    key.push('K');
+                }
+            }
+            b'p' => key.push(if next == Some(b'h') { 'F' } else { 'P' }),
+            b'q' => key.push('K'),
+            b's' => {
+                if next == Some(b'h')
+                    || (next == Some(b'i') && matches!(at(i + 2), Some(b'o' | b'a')))
+                {
+                    key.push('X');
+                } else {
+                    key.push('S');
+                }
+            }
+            b't' => {
+                if next == Some(b'i') && matches!(at(i + 2), Some(b'o' | b'a')) {
+                    key.push('X');
+                } else if next == Some(b'h') {
+                    key.push('0');
+                } else if !(next == Some(b'c') && at(i + 2) == Some(b'h')) {
+                    key.push('T');
+                }
+            }
+            b'v' => key.push('F'),
+            b'w' | b'y' => {
+                if vowel(next) {
+                    key.push(c.to_ascii_uppercase() as char);
+                }
+            }
+            b'x' => key.push_str("KS"),
+            b'z' => key.push('S'),
+            _ => key.push(c.to_ascii_uppercase() as char),
+        }
+    }
+    key
+}
+
+/// `name` respelled to sound the same, one word of it at a time: for each run
+/// of ASCII letters, respellings with one change, then two, at most
+/// [`MAX_HOMOPHONES`] per run, those that are words first. Punycode (`xn--`)
+/// labels are left alone: their letters encode other characters and are not
+/// read aloud.
+pub fn homophones(name: &str) -> Vec<String> {
+    let name = name.to_lowercase();
+    let mut seen = HashSet::new();
+    let mut results = Vec::new();
+    let bytes = name.as_bytes();
+    let mut start = 0;
+    while start < bytes.len() {
+        if (start == 0 || bytes[start - 1] == b'.') && name[start..].starts_with("xn--") {
+            start = name[start..]
+                .find('.')
+                .map_or(bytes.len(), |dot| start + dot);
+            continue;
+        }
+        if !bytes[start].is_ascii_lowercase() {
+            start += 1;
+            continue;
+        }
+        let end = (start..bytes.len())
+            .find(|&i| !bytes[i].is_ascii_lowercase())
+            .unwrap_or(bytes.len());
+        for respelling in respell(&name[start..end]) {
+            let result = format!("{}{}{}", &name[..start], respelling, &name[end..]);
+            if seen.insert(result.clone()) {
+                results.push(result);
+            }
+        }
+        start = end;
+    }
+    results
+}
+
+/// Respellings of one run of letters, best first
+fn respell(token: &str) -> Vec<String> {
+    let key = metaphone(token);
+    let segments = segment(token);
+    let edits: Vec<Edit> = segments
+        .iter()
+        .flat_map(|&(at, end)| edits(&token[at..end], at))
+        .collect();
+
+    // Each respelling with its number of changes and how word-like its
+    // respelled segments are (lower is better)
+    let mut candidates: Vec<(usize, usize, String)> = Vec::new();
+    let mut seen = HashSet::new();
+    let mut consider = |chosen: &[&Edit]| {
+        let respelling = apply(token, chosen);
+        if respelling == token || !seen.insert(respelling.clone()) {
+            return;
+        }
+        if chosen.iter().any(|edit| edit.checked) && metaphone(&respelling) != key {
+            return;
+        }
+        let score = word_score(&respelling, &segments, chosen);
+        candidates.push((chosen.len(), score, respelling));
+    };
+    for (i, first) in edits.iter().enumerate() {
+        consider(&[first]);
+        for second in &edits[i + 1..] {
+            // Irregular homophones replace a whole word and are not combined
+            if first.checked && second.checked && first.at + first.len <= second.at {
+                consider(&[first, second]);
+            }
+        }
+    }
+    candidates.sort();
+    candidates
+        .into_iter()
+        .take(MAX_HOMOPHONES)
+        .map(|(_, _, respelling)| respelling)
+        .collect()
+}
+
+/// Changes that could respell `segment`, found at byte `offset` of its word
+fn edits(segment: &str, offset: usize) -> Vec<Edit> {
+    let bytes = segment.as_bytes();
+    let mut edits = Vec::new();
+    let mut push = |at: usize, len: usize, with: String, checked: bool| {
+        edits.push(Edit {
+            at: offset + at,
+            len,
+            with,
+            checked,
+        });
+    };
+
+    for group in IRREGULAR.iter().filter(|group| group.contains(&segment)) {
+        for other in group.iter().filter(|other| **other != segment) {
+            push(0, segment.len(), other.to_string(), false);
+        }
+    }
+    for at in 0..bytes.len() {
+        let rest = &segment[at..];
+        for (from, to) in RESPELLINGS
+            .iter()
+            .filter(|(from, _)| rest.starts_with(from))
+        {
+            push(at, from.len(), to.to_string(), true);
+        }
+        let c = bytes[at] as char;
+        if DOUBLED.contains(c) && at > 0 {
+            if bytes.get(at + 1) == Some(&bytes[at]) {
+                push(at, 2, c.to_string(), true);
+            } else if is_vowel(bytes[at - 1]) && !bytes.get(at + 1).copied().is_some_and(is_vowel) {
+                push(at, 1, format!("{}{}", c, c), true);
+            }
+        }
+    }
+
+    // A vowel team before a final consonant, and a vowel before a final
+    // consonant and silent `e`
+    for (team, vowel) in SILENT_E {
+        if let Some(stem) = segment.strip_suffix(|c: char| !is_vowel(c as u8)) {
+            if stem.ends_with(team) {
+                let at = stem.len() - team.len();
+                let consonant = &segment[stem.len()..];
+                push(
+                    at,
+                    segment.len() - at,
+                    format!("{}{}e", vowel, consonant),
+                    true,
+                );
+            }
+        }
+    }
+    if let [.., v, c, b'e'] = bytes {
+        if !is_vowel(*c) && is_vowel(*v) && *v != b'o' && bytes.len() > 3 {
+            if let Some((team, _)) = SILENT_E.iter().find(|(_, vowel)| *vowel as u8 == *v) {
+                let at = bytes.len() - 3;
+                push(at, 3, format!("{}{}", team, *c as char), true);
+            }
+        }
+    }
+    edits
+}
+
+/// `token` with `chosen` edits, in order and not overlapping, applied
+fn apply(token: &str, chosen: &[&Edit]) -> String {
+    let mut result = String::new();
+    let mut at = 0;
+    for edit in chosen {
+        result.push_str(&token[at..edit.at]);
+        result.push_str(&edit.with);
+        at = edit.at + edit.len;
+    }
+    result.push_str(&token[at..]);
+    result
+}
+
+/// Sum of the ranks in the word list of the segments `chosen` respells, with
+/// respelled segments that are not words ranking after every word
+fn word_score(respelling: &str, segments: &[(usize, usize)], chosen: &[&Edit]) -> usize {
+    let words = words();
+    let growth = |edit: &&Edit| edit.with.len() as isize - edit.len as isize;
+    segments
+        .iter()
+        .filter(|&&(start, end)| chosen.iter().any(|edit| edit.at >= start && edit.at < end))
+        .map(|&(start, end)| {
+            // A segment moves with the changes before it and grows or shrinks
+            // with those in it
+            let before: isize = chosen
+                .iter()
+                .filter(|edit| edit.at < start)
+                .map(growth)
+                .sum();
+            let within: isize = chosen
+                .iter()
+                .filter(|edit| edit.at >= start && edit.at < end)
+                .map(growth)
+                .sum();
+            let from = (start as isize + before) as usize;
+            let to = (end as isize + before + within) as usize;
+            words
+                .get(&respelling[from..to])
+                .copied()
+                .unwrap_or(words.len())
+        })
+        .sum()
+}
+
+/// `token` split into words of the word list as byte ranges, fewest words
+/// first; the whole token if it cannot be split
+fn segment(token: &str) -> Vec<(usize, usize)> {
+    let words = words();
+    // Fewest words covering each prefix, and where the last one starts
+    let mut best: Vec<Option<(usize, usize)>> = vec![None; token.len() + 1];
+    best[0] = Some((0, 0));
+    for end in 1..=token.len() {
+        best[end] = (0..end)
+            .filter(|&start| end - start >= 2 && words.contains_key(&token[start..end]))
+            .filter_map(|start| best[start].map(|(count, _)| (count + 1, start)))
+            .min();
+    }
+    let mut segments = Vec::new();
+    let mut end = token.len();
+    while end > 0 {
+        match best[end] {
+            Some((_, start)) => {
+                segments.push((start, end));
+                end = start;
+            }
+            None => return vec![(0, token.len())],
+        }
+    }
+    segments.reverse();
+    segments
+}
+
+fn is_vowel(c: u8) -> bool {
+    matches!(c, b'a' | b'e' | b'i' | b'o' | b'u')
+}
+
+/// Words of `data/words-en.txt` and their ranks, most frequent 0
+fn words() -> &'static HashMap<String, usize> {
+    static WORDS: OnceLock<HashMap<String, usize>> = OnceLock::new();
+    WORDS.get_or_init(|| {
+        include_str!("../data/words-en.txt")
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .enumerate()
+            .map(|(rank, word)| (word.to_string(), rank))
+            .collect()
+    })
+}
@@ -0,0 +1,122 @@
+//! How pronounceable a domain reads, for dropping combo gibberish
+//! (`--min-plausibility`).
+//!
+//! Deep combo chains pile edit on edit until a name such as `fqcxbpok` no longer
+//! reads as anything, and nobody falls for it. A character bigram model trained
+//! on the English words of `data/words-en.txt` measures how surprising each
+//! letter is after the one before, in bits. A variation's plausibility compares
+//! its surprise with the domain it came from: 100% reads as easily as the brand
+//! itself, and each extra bit per letter halves the score. Homoglyphs and
+//! leetspeak digits are read as the letters they imitate first, so `faceb00k` is
+//! as plausible as `facebook`.
+
+use std::sync::OnceLock;
+
+use crate::confusables::skeleton;
+use crate::generators::parse_domain;
+
+/// Letters `a` to `z`, then the start and end of a word
+const SYMBOLS: usize = 28;
+const START: usize = 26;
+const END: usize = 27;
+/// Added to every bigram count, so that pairs missing from the word list are
+/// unlikely rather than impossible
+const SMOOTHING: f64 = 0.5;
+
+/// Letters that leetspeak digits the confusables table leaves alone stand for
+const LEET: &[(char, char)] = &[
+    ('2', 'z'),
+    ('3', 'e'),
+    ('4', 'a'),
+    ('5', 's'),
+    ('6', 'b'),
+    ('7', 't'),
+    ('8', 'b'),
+    ('9', 'g'),
+];
+
+/// Character bigram model of English words
+pub struct BigramModel {
+    /// Bits of surprise of each symbol after each symbol
+    bits: [[f64; SYMBOLS]; SYMBOLS],
+}
+
+impl BigramModel {
+    /// The model trained on `data/words-en.txt`
+    pub fn builtin() -> &'static BigramModel {
+        static MODEL: OnceLock<BigramModel> = OnceLock::new();
+        MODEL.get_or_init(|| {
+            BigramModel::train(
+                include_str!("../data/words-en.txt")
+                    .lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty() && !line.starts_with('#')),
+            )
+        })
+    }
+
+    /// A model of `words`; characters other than ASCII letters are skipped
+    pub fn train<'a>(words: impl IntoIterator<Item = &'a str>) -> BigramModel {
+        let mut counts = [[SMOOTHING; SYMBOLS]; SYMBOLS];
+        for word in words {
+            let mut previous = START;
+            for symbol in letters(word).chain(std::iter::once(END)) {
+                counts[previous][symbol] += 1.0;
+                previous = symbol;
+            }
+        }
+        let mut bits = [[0.0; SYMBOLS]; SYMBOLS];
+        for (row, counts) in bits.iter_mut().zip(&counts) {
+            let total: f64 = counts.iter().sum();
+            for (bits, count) in row.iter_mut().zip(counts) {
+                *bits = -(count / total).log2();
+            }
+        }
+        BigramModel { bits }
+    }
+
+    /// Average bits of surprise per letter of `domain`'s name, each run of
+    /// letters taken as a word; `None` if the name has no letters
+    pub fn bits_per_letter(&self, domain: &str) -> Option<f64> {
+        let (name, _) = parse_domain(domain);
+        let read: String = skeleton(&name)
+            .chars()
+            .map(|c| {
+                LEET.iter()
+                    .find(|(digit, _)| *digit == c)
+                    .map_or(c, |&(_, letter)| letter)
+            })
+            .collect();
+        let (mut total, mut transitions) = (0.0, 0);
+        for word in read
+            .split(|c: char| !c.is_ascii_alphabetic())
+            .filter(|word| !word.is_empty())
+        {
+            let mut previous = START;
+            for symbol in letters(word).chain(std::iter::once(END)) {
+                total += self.bits[previous][symbol];
+                transitions += 1;
+                previous = symbol;
+            }
+        }
+        (transitions > 0).then(|| total / transitions as f64)
+    }
+}
+
+/// How pronounceable `candidate` reads next to `original`, from 0.0 to 1.0:
+/// 1.0 when it reads as easily as the original or more, halved for each extra
+/// bit of surprise per letter. Names without letters score 0.0.
+pub fn plausibility(original: &str, candidate: &str) -> f64 {
+    let model = BigramModel::builtin();
+    let Some(candidate_bits) = model.bits_per_letter(candidate) else {
+        return 0.0;
+    };
+    let original_bits = model.bits_per_letter(original).unwrap_or(candidate_bits);
+    (original_bits - candidate_bits).exp2().min(1.0)
+}
+
+fn letters(word: &str) -> impl Iterator<Item = usize> + '_ {
+    word.bytes()
+        .filter(u8::is_ascii_alphabetic)
+        .map(|b| (b.to_ascii_lowercase() - b'a') as usize)
+}
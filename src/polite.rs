@@ -0,0 +1,147 @@
+//! Polite probing of the domains' own web servers (`--polite`).
+//!
+//! Content checks fetch pages from thousands of live domains, many of them on
+//! the same shared hosting. Sent as fast as lookups complete, that reads as a
+//! scraping attack from the egress IP, and gets it blocked. In polite mode,
+//! requests to sites are spaced out across the run and per host, each GET is
+//! preceded by a HEAD so that bodies that are not text or are too large are
+//! never downloaded, and no more than a set number of bytes of any body is read.
+//! Requests to registries and services are paced by [`crate::throttle`]
+//! instead.
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tokio::time::Instant;
+
+use crate::checker::{network, politeness, HTTP_CLIENT};
+use crate::metrics;
+
+/// Default `--polite-rate`: requests per second to all sites together
+pub const DEFAULT_RATE: f64 = 10.0;
+/// Default `--polite-host-interval`, in seconds
+pub const DEFAULT_HOST_INTERVAL_SECS: u64 = 2;
+/// Default `--max-body-size`, in bytes
+pub const DEFAULT_MAX_BODY_BYTES: usize = 256 * 1024;
+
+/// Pacing and limits of requests to sites
+#[derive(Debug, Clone, PartialEq)]
+pub struct Politeness {
+    /// Most requests per second to all sites together
+    pub rate: f64,
+    /// Least time between requests to the same host
+    pub host_interval: Duration,
+    /// Most bytes of a response body read
+    pub max_body_bytes: usize,
+}
+
+impl Default for Politeness {
+    fn default() -> Self {
+        Politeness {
+            rate: DEFAULT_RATE,
+            host_interval: Duration::from_secs(DEFAULT_HOST_INTERVAL_SECS),
+            max_body_bytes: DEFAULT_MAX_BODY_BYTES,
+        }
+    }
+}
+
+/// When the next request may go out, overall and per host
+struct Schedule {
+    next: Option<Instant>,
+    hosts: BTreeMap<String, Instant>,
+}
+
+/// Hosts remembered before those whose interval has passed are forgotten
+const MAX_SCHEDULED_HOSTS: usize = 10_000;
+
+static SCHEDULE: Mutex<Schedule> = Mutex::new(Schedule {
+    next: None,
+    hosts: BTreeMap::new(),
+});
+
+/// Most bytes of a body to read where `default` would be read otherwise
+pub fn body_limit(default: usize) -> usize {
+    politeness().map_or(default, |polite| polite.max_body_bytes.min(default))
+}
+
+/// Wait for the turn of a request to `url`'s host; immediate unless polite
+pub async fn pace(url: &str) {
+    let Some(polite) = politeness() else {
+        return;
+    };
+    let host = metrics::url_host(url).to_lowercase();
+    let now = Instant::now();
+    let slot = {
+        let mut schedule = SCHEDULE.lock().unwrap_or_else(|e| e.into_inner());
+        let host_next = schedule.hosts.get(&host).copied();
+        let slot = [Some(now), schedule.next, host_next]
+            .into_iter()
+            .flatten()
+            .max()
+            .expect("now is always present");
+        if polite.rate > 0.0 {
+            schedule.next = Some(slot + Duration::from_secs_f64(1.0 / polite.rate));
+        }
+        if schedule.hosts.len() >= MAX_SCHEDULED_HOSTS {
+            schedule.hosts.retain(|_, next| *next > now);
+        }
+        schedule.hosts.insert(host, slot + polite.host_interval);
+        slot
+    };
+    tokio::time::sleep_until(slot).await;
+}
+
+/// Get ready to GET `url` from a site: wait for its turn and, in polite mode,
+/// ask with a HEAD first. An error names why the body is not worth fetching:
+/// it is not text, or is longer than the body limit. A failed HEAD lets the GET
+/// go ahead, since some servers do not implement it.
+pub async fn admit(url: &str) -> Result<(), String> {
+    pace(url).await;
+    let Some(polite) = politeness() else {
+        return Ok(());
+    };
+    metrics::record_request("http", metrics::SITES);
+    let head = tokio::time::timeout(network().http_timeout, HTTP_CLIENT.head(url).send()).await;
+    let Ok(Ok(response)) = head else {
+        return Ok(());
+    };
+    let headers = response.headers();
+    if let Some(content_type) = headers
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+    {
+        let content_type = content_type.trim().to_lowercase();
+        if !(content_type.starts_with("text/") || content_type.contains("xml")) {
+            return Err(format!("skipped by --polite: {} is not text", content_type));
+        }
+    }
+    if let Some(length) = headers
+        .get(reqwest::header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok()?.parse::<usize>().ok())
+    {
+        if length > polite.max_body_bytes {
+            return Err(format!(
+                "skipped by --polite: {} bytes is over --max-body-size {}",
+                length, polite.max_body_bytes
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// The first `limit` bytes of a response body
+pub async fn read_body(
+    mut response: reqwest::Response,
+    limit: usize,
+) -> Result<Vec<u8>, reqwest::Error> {
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        body.extend_from_slice(&chunk);
+        if body.len() >= limit {
+            body.truncate(limit);
+            break;
+        }
+    }
+    Ok(body)
+}
@@ -0,0 +1,262 @@
+//! Registry etiquette for runs from shared infrastructure (`--public-mode`).
+//!
+//! Registries judge abuse by source address, and an address many researchers
+//! share (a university NAT, a cloud egress, a hosted scanner) adds up all of
+//! their traffic. Adaptive concurrency ([`crate::throttle`]) finds the fastest
+//! rate an endpoint tolerates, which is exactly what such an address cannot
+//! afford. Public mode trades speed for staying well under abuse thresholds:
+//! few lookups in flight per endpoint, a least interval between requests to
+//! each endpoint, jittered so that runs started together do not fire in
+//! lockstep, long waits before retrying, and a status cache on disk that
+//! repeated runs answer from instead of asking the registries again.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use tokio::time::Instant;
+
+use crate::checker::{is_failed_status, public_mode, NetworkSettings};
+use crate::error::DomFuzzError;
+
+/// Most lookups in flight per endpoint
+pub const MAX_CONCURRENCY: usize = 2;
+/// Least time between requests to one RDAP registry or service
+pub const INTERVAL: Duration = Duration::from_secs(1);
+/// Least time between queries to one WHOIS server, which rate-limit harder
+pub const WHOIS_INTERVAL: Duration = Duration::from_secs(4);
+/// Most of an interval's length added to it at random
+pub const JITTER: f64 = 0.5;
+/// Least wait before retrying a rate-limited lookup
+pub const RETRY_DELAY: Duration = Duration::from_secs(10);
+/// How long a cached status is answered from the cache
+pub const CACHE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Etiquette of public mode
+#[derive(Debug, Clone, PartialEq)]
+pub struct PublicMode {
+    /// Least time between requests to one RDAP or service endpoint
+    pub interval: Duration,
+    /// Least time between queries to one WHOIS server
+    pub whois_interval: Duration,
+    /// Share of each interval, from 0.0 to 1.0, added at random; intervals are
+    /// never shortened
+    pub jitter: f64,
+    /// Status cache file, JSON
+    pub cache_path: PathBuf,
+    pub cache_ttl: Duration,
+}
+
+impl PublicMode {
+    /// The built-in etiquette, with the status cache at `cache_path`
+    pub fn new(cache_path: PathBuf) -> Self {
+        PublicMode {
+            interval: INTERVAL,
+            whois_interval: WHOIS_INTERVAL,
+            jitter: JITTER,
+            cache_path,
+            cache_ttl: CACHE_TTL,
+        }
+    }
+
+    /// `settings`, made no more aggressive than public mode allows
+    pub fn restrain(&self, settings: &mut NetworkSettings) {
+        settings.max_concurrency = settings.max_concurrency.min(MAX_CONCURRENCY);
+        settings.retry_delay = settings.retry_delay.max(RETRY_DELAY);
+    }
+}
+
+/// `$XDG_CACHE_HOME/domfuzz/status-cache.json`, or `~/.cache/domfuzz/status-cache.json`
+pub fn default_cache_path() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CACHE_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))?;
+    Some(base.join("domfuzz").join("status-cache.json"))
+}
+
+/// When the next request may go to each endpoint
+struct Schedule {
+    next: BTreeMap<String, Instant>,
+    rng: Option<StdRng>,
+}
+
+static SCHEDULE: Mutex<Schedule> = Mutex::new(Schedule {
+    next: BTreeMap::new(),
+    rng: None,
+});
+
+/// Wait for the turn of a request to `endpoint`, as [`crate::throttle`] names
+/// it; immediate unless in public mode
+pub async fn pace(endpoint: &str) {
+    let Some(public) = public_mode() else {
+        return;
+    };
+    let interval = if endpoint.starts_with("whois:") {
+        public.whois_interval
+    } else {
+        public.interval
+    };
+    let slot = {
+        let mut schedule = SCHEDULE.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        let slot = schedule
+            .next
+            .get(endpoint)
+            .map_or(now, |next| (*next).max(now));
+        let rng = schedule
+            .rng
+            .get_or_insert_with(|| StdRng::seed_from_u64(unix_time().as_nanos() as u64));
+        let factor = 1.0 + public.jitter * rng.gen_range(0.0..=1.0);
+        schedule
+            .next
+            .insert(endpoint.to_string(), slot + interval.mul_f64(factor));
+        slot
+    };
+    tokio::time::sleep_until(slot).await;
+}
+
+/// A status, and when it was looked up in seconds since the Unix epoch
+#[derive(Debug, Clone)]
+struct CachedStatus {
+    status: String,
+    checked: u64,
+}
+
+#[derive(Debug, Default)]
+struct StatusCache {
+    entries: HashMap<String, CachedStatus>,
+    hits: u64,
+}
+
+static CACHE: OnceLock<Mutex<StatusCache>> = OnceLock::new();
+
+/// The status cache, read from disk the first time; `None` unless in public
+/// mode. An unreadable or corrupt file starts an empty cache, which replaces it.
+fn cache() -> Option<std::sync::MutexGuard<'static, StatusCache>> {
+    let public = public_mode()?;
+    let cache = CACHE.get_or_init(|| {
+        Mutex::new(StatusCache {
+            entries: read_cache(&public.cache_path).unwrap_or_default(),
+            hits: 0,
+        })
+    });
+    Some(cache.lock().unwrap_or_else(|e| e.into_inner()))
+}
+
+/// The cached status of the registrable `domain`, unless missing or older than
+/// the cache's lifetime
+pub fn cached_status(domain: &str) -> Option<String> {
+    let public = public_mode()?;
+    let mut cache = cache()?;
+    let now = unix_time().as_secs();
+    let status = cache
+        .entries
+        .get(domain)
+        .filter(|cached| now.saturating_sub(cached.checked) < public.cache_ttl.as_secs())?
+        .status
+        .clone();
+    cache.hits += 1;
+    Some(status)
+}
+
+/// Remember the status just looked up for the registrable `domain`; failed
+/// lookups are not cached
+pub fn cache_status(domain: &str, status: &str) {
+    if is_failed_status(status) {
+        return;
+    }
+    if let Some(mut cache) = cache() {
+        let checked = unix_time().as_secs();
+        cache.entries.insert(
+            domain.to_string(),
+            CachedStatus {
+                status: status.to_string(),
+                checked,
+            },
+        );
+    }
+}
+
+/// Lookups answered from the status cache so far
+pub fn cache_hits() -> u64 {
+    CACHE.get().map_or(0, |cache| {
+        cache.lock().unwrap_or_else(|e| e.into_inner()).hits
+    })
+}
+
+/// Write the status cache back to disk, leaving out expired statuses. Statuses
+/// other runs saved meanwhile are kept, the newer of two winning. Nothing to do
+/// unless in public mode.
+pub fn save_cache() -> Result<(), DomFuzzError> {
+    let Some(public) = public_mode() else {
+        return Ok(());
+    };
+    let Some(cache) = cache() else {
+        return Ok(());
+    };
+    let mut merged = read_cache(&public.cache_path).unwrap_or_default();
+    for (domain, cached) in &cache.entries {
+        if merged
+            .get(domain)
+            .is_none_or(|saved| saved.checked < cached.checked)
+        {
+            merged.insert(domain.clone(), cached.clone());
+        }
+    }
+    drop(cache);
+    let now = unix_time().as_secs();
+    let entries: serde_json::Map<String, serde_json::Value> = merged
+        .iter()
+        .filter(|(_, cached)| now.saturating_sub(cached.checked) < public.cache_ttl.as_secs())
+        .map(|(domain, cached)| {
+            (
+                domain.clone(),
+                serde_json::json!({ "status": cached.status, "checked": cached.checked }),
+            )
+        })
+        .collect();
+    let path = &public.cache_path;
+    let failed = |e: std::io::Error| {
+        DomFuzzError::Config(format!(
+            "cannot write status cache {}: {}",
+            path.display(),
+            e
+        ))
+    };
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir).map_err(failed)?;
+    }
+    // Written aside and renamed, so that concurrent runs never read half a file
+    let partial = path.with_extension(format!("{}.partial", std::process::id()));
+    std::fs::write(&partial, serde_json::Value::Object(entries).to_string()).map_err(failed)?;
+    std::fs::rename(&partial, path).map_err(failed)
+}
+
+fn read_cache(path: &Path) -> Option<HashMap<String, CachedStatus>> {
+    let json: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(path).ok()?).ok()?;
+    Some(
+        json.as_object()?
+            .iter()
+            .filter_map(|(domain, entry)| {
+                Some((
+                    domain.clone(),
+                    CachedStatus {
+                        status: entry.get("status")?.as_str()?.to_string(),
+                        checked: entry.get("checked")?.as_u64()?,
+                    },
+                ))
+            })
+            .collect(),
+    )
+}
+
+fn unix_time() -> Duration {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+}
@@ -0,0 +1,214 @@
+//! Defensive registration lists, ready for a registrar (`--purchase-list`).
+//!
+//! Available variations are the ones worth registering before someone else
+//! does, and copying them out of the results one by one into a registrar's cart
+//! is where mistakes creep in. The list is written the way registrars take
+//! domains in bulk: one per line for bulk search and register boxes, `domain,years`
+//! CSV for bulk upload forms, JSON for registrar APIs and scripts, and EPP
+//! `<create>` commands (RFC 5731) for registrars and resellers with a registry
+//! connection. Each registrable domain is listed once, in the ASCII form
+//! registries take, most similar first.
+
+use std::collections::HashMap;
+
+use rand::Rng;
+
+use crate::domain::Domain;
+use crate::error::DomFuzzError;
+
+/// Most years a domain can be registered for at once (RFC 5731 `period`)
+pub const MAX_YEARS: u32 = 10;
+
+/// EPP namespaces of the `<create>` commands
+const EPP_NAMESPACE: &str = "urn:ietf:params:xml:ns:epp-1.0";
+const EPP_DOMAIN_NAMESPACE: &str = "urn:ietf:params:xml:ns:domain-1.0";
+
+/// Characters of generated EPP transfer passwords; lookalike characters are
+/// left out, since the password may be read out to a registrar
+const AUTH_INFO_CHARS: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnpqrstuvwxyz23456789-+=@";
+const AUTH_INFO_LEN: usize = 16;
+
+/// How `--purchase-list` is written
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum PurchaseFormat {
+    /// One domain per line
+    Txt,
+    /// `domain,years` with a header row
+    Csv,
+    /// An array of objects with the domain, years, similarity, and transformation
+    Json,
+    /// One EPP `<domain:create>` command per domain
+    Epp,
+}
+
+/// A registrable domain recommended for defensive registration
+#[derive(Debug, Clone, PartialEq)]
+pub struct Purchase {
+    /// ASCII (punycode) form
+    pub domain: String,
+    /// Unicode form, for internationalized domains
+    pub unicode: Option<String>,
+    /// Similarity of the most similar variation under it
+    pub similarity: f64,
+    pub transformation: String,
+}
+
+/// Available registrable domains gathered across a run
+#[derive(Debug, Clone)]
+pub struct PurchaseList {
+    years: u32,
+    purchases: Vec<Purchase>,
+    /// Position of each listed domain in `purchases`
+    positions: HashMap<String, usize>,
+    /// The original's own registrable domain, never listed
+    original: String,
+}
+
+impl PurchaseList {
+    /// An empty list of registrations for `years` years each, leaving out
+    /// `original`'s own registrable domain
+    pub fn new(original: &Domain, years: u32) -> Result<PurchaseList, DomFuzzError> {
+        if !(1..=MAX_YEARS).contains(&years) {
+            return Err(DomFuzzError::Config(format!(
+                "--purchase-years must be from 1 to {}",
+                MAX_YEARS
+            )));
+        }
+        Ok(PurchaseList {
+            years,
+            purchases: Vec::new(),
+            positions: HashMap::new(),
+            original: registrable_ascii(original),
+        })
+    }
+
+    /// Add an available `variation`'s registrable domain; one already listed
+    /// keeps the most similar variation's similarity and transformation
+    pub fn add(&mut self, variation: &str, similarity: f64, transformation: &str) {
+        let Ok(domain) = Domain::parse(variation) else {
+            return;
+        };
+        let ascii = registrable_ascii(&domain);
+        if ascii == self.original {
+            return;
+        }
+        if let Some(&position) = self.positions.get(&ascii) {
+            let listed = &mut self.purchases[position];
+            if similarity > listed.similarity {
+                listed.similarity = similarity;
+                listed.transformation = transformation.to_string();
+            }
+            return;
+        }
+        self.positions.insert(ascii.clone(), self.purchases.len());
+        let registrable = Domain::parse(&ascii).map_or(ascii.clone(), |d| d.to_unicode());
+        self.purchases.push(Purchase {
+            unicode: (registrable != ascii).then_some(registrable),
+            domain: ascii,
+            similarity,
+            transformation: transformation.to_string(),
+        });
+    }
+
+    pub fn len(&self) -> usize {
+        self.purchases.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.purchases.is_empty()
+    }
+
+    /// The listed domains, most similar first
+    pub fn purchases(&self) -> Vec<&Purchase> {
+        let mut purchases: Vec<&Purchase> = self.purchases.iter().collect();
+        purchases.sort_by(|a, b| {
+            b.similarity
+                .total_cmp(&a.similarity)
+                .then_with(|| a.domain.cmp(&b.domain))
+        });
+        purchases
+    }
+
+    /// The list in `format`
+    pub fn render(&self, format: PurchaseFormat) -> String {
+        let purchases = self.purchases();
+        match format {
+            PurchaseFormat::Txt => purchases
+                .iter()
+                .map(|purchase| format!("{}\n", purchase.domain))
+                .collect(),
+            PurchaseFormat::Csv => std::iter::once("domain,years\n".to_string())
+                .chain(
+                    purchases
+                        .iter()
+                        .map(|purchase| format!("{},{}\n", purchase.domain, self.years)),
+                )
+                .collect(),
+            PurchaseFormat::Json => {
+                let entries: Vec<serde_json::Value> = purchases
+                    .iter()
+                    .map(|purchase| {
+                        let mut entry = serde_json::json!({
+                            "domain": purchase.domain,
+                            "years": self.years,
+                            "similarity": (purchase.similarity * 10_000.0).round() / 10_000.0,
+                            "transformation": purchase.transformation,
+                        });
+                        if let Some(unicode) = &purchase.unicode {
+                            entry["unicode"] = unicode.clone().into();
+                        }
+                        entry
+                    })
+                    .collect();
+                serde_json::to_string_pretty(&entries).expect("JSON values always serialize") + "\n"
+            }
+            PurchaseFormat::Epp => {
+                let mut rng = rand::thread_rng();
+                purchases
+                    .iter()
+                    .enumerate()
+                    .map(|(i, purchase)| epp_create(purchase, self.years, i + 1, &mut rng))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            }
+        }
+    }
+}
+
+/// `domain`'s registrable domain in ASCII form
+fn registrable_ascii(domain: &Domain) -> String {
+    let registrable = domain.registrable();
+    Domain::parse(&registrable).map_or(registrable, |d| d.to_ascii())
+}
+
+/// An EPP command registering `purchase` for `years` years, with a fresh
+/// transfer password; `number` makes the client transaction ID unique
+fn epp_create(purchase: &Purchase, years: u32, number: usize, rng: &mut impl Rng) -> String {
+    let password: String = (0..AUTH_INFO_LEN)
+        .map(|_| AUTH_INFO_CHARS[rng.gen_range(0..AUTH_INFO_CHARS.len())] as char)
+        .collect();
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="no"?>
+<epp xmlns="{epp}">
+  <command>
+    <create>
+      <domain:create xmlns:domain="{domain_ns}">
+        <domain:name>{name}</domain:name>
+        <domain:period unit="y">{years}</domain:period>
+        <domain:authInfo>
+          <domain:pw>{password}</domain:pw>
+        </domain:authInfo>
+      </domain:create>
+    </create>
+    <clTRID>domfuzz-{number:04}</clTRID>
+  </command>
+</epp>
+"#,
+        epp = EPP_NAMESPACE,
+        domain_ns = EPP_DOMAIN_NAMESPACE,
+        name = purchase.domain,
+        years = years,
+        password = password,
+        number = number,
+    )
+}
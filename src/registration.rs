@@ -0,0 +1,244 @@
+//! Registration details of registered variations: registrar, name servers,
+//! and status codes.
+//!
+//! A squat that moves to a new registrar or to name servers favoured by
+//! phishing operators is changing hands or getting ready for use, even though
+//! its status stays `registered`. Details come from RDAP where the TLD has a
+//! known endpoint and from WHOIS otherwise.
+//!
+//! Abuse reports go to the registrar's abuse contact, recorded with the
+//! registration, and to the hosting network's, looked up over RDAP for the
+//! address with [`fetch_host_abuse_contact`].
+
+use std::net::IpAddr;
+
+use tokio::time::timeout;
+
+use crate::checker::{
+    extract_registrar_name, extract_tld, fetch_rdap, get_rdap_endpoint, network, whois_response,
+    HTTP_CLIENT,
+};
+use crate::error::DomFuzzError;
+use crate::metrics;
+use crate::throttle;
+
+/// RDAP bootstrap service, redirecting IP lookups to the regional registry
+/// holding the address
+pub const IP_RDAP_ENDPOINT: &str = "https://rdap.org/ip/";
+
+/// Who holds a domain at which registrar, and where it is delegated
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Registration {
+    pub registrar: Option<String>,
+    /// Name server host names, lowercase and sorted
+    pub nameservers: Vec<String>,
+    /// EPP status codes in RDAP form (`client transfer prohibited`), sorted
+    pub status_codes: Vec<String>,
+    /// The registrar's abuse contact email address
+    pub abuse_email: Option<String>,
+}
+
+impl Registration {
+    /// Details from an RDAP domain record
+    pub fn from_rdap(json: &serde_json::Value) -> Self {
+        let array = |key: &str| -> Vec<&serde_json::Value> {
+            json.get(key)
+                .and_then(|v| v.as_array())
+                .map(|items| items.iter().collect())
+                .unwrap_or_default()
+        };
+        let registrar_entity = array("entities")
+            .into_iter()
+            .find(|entity| has_role(entity, "registrar"));
+        let registrar = registrar_entity.and_then(extract_registrar_name);
+        let abuse_email = registrar_entity.and_then(abuse_email);
+        let nameservers = array("nameservers")
+            .into_iter()
+            .filter_map(|ns| ns.get("ldhName").and_then(|n| n.as_str()))
+            .map(normalize_host)
+            .collect();
+        let status_codes = array("status")
+            .into_iter()
+            .filter_map(|status| status.as_str())
+            .map(normalize_status)
+            .collect();
+        let mut registration = Registration::new(registrar, nameservers, status_codes);
+        registration.abuse_email = abuse_email;
+        registration
+    }
+
+    /// Details from a WHOIS response, using the common `Registrar:`,
+    /// `Name Server:`, and `Domain Status:` fields and their short forms
+    pub fn from_whois(response: &str) -> Self {
+        let mut registrar = None;
+        let mut abuse_email = None;
+        let mut nameservers = Vec::new();
+        let mut status_codes = Vec::new();
+        for line in response.lines() {
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+            if value.is_empty() {
+                continue;
+            }
+            match key.trim().to_lowercase().as_str() {
+                "registrar" | "sponsoring registrar" if registrar.is_none() => {
+                    registrar = Some(value.to_string());
+                }
+                "registrar abuse contact email" | "abuse contact email" | "abuse-mailbox"
+                    if abuse_email.is_none() =>
+                {
+                    abuse_email = Some(value.to_lowercase());
+                }
+                "name server" | "nserver" | "nameserver" => {
+                    // Some registries append glue addresses
+                    if let Some(host) = value.split_whitespace().next() {
+                        nameservers.push(normalize_host(host));
+                    }
+                }
+                // The code is followed by a link to its ICANN explanation
+                "domain status" | "status" => {
+                    if let Some(code) = value.split_whitespace().next() {
+                        status_codes.push(normalize_status(code));
+                    }
+                }
+                _ => {}
+            }
+        }
+        let mut registration = Registration::new(registrar, nameservers, status_codes);
+        registration.abuse_email = abuse_email;
+        registration
+    }
+
+    fn new(
+        registrar: Option<String>,
+        mut nameservers: Vec<String>,
+        mut status_codes: Vec<String>,
+    ) -> Self {
+        nameservers.sort();
+        nameservers.dedup();
+        status_codes.sort();
+        status_codes.dedup();
+        Registration {
+            registrar,
+            nameservers,
+            status_codes,
+            abuse_email: None,
+        }
+    }
+}
+
+/// Look up `domain`'s registration over RDAP, or WHOIS for TLDs without a
+/// known RDAP endpoint; `None` if the registry has no such domain
+pub async fn fetch_registration(domain: &str) -> Result<Option<Registration>, DomFuzzError> {
+    let tld = extract_tld(domain)?;
+    if get_rdap_endpoint(&tld).is_ok() {
+        return Ok(fetch_rdap(domain)
+            .await?
+            .map(|json| Registration::from_rdap(&json)));
+    }
+    let registration = Registration::from_whois(&whois_response(domain).await?);
+    Ok((registration != Registration::default()).then_some(registration))
+}
+
+/// The abuse contact email address of the network `ip` belongs to, from its
+/// regional registry's RDAP record; `None` if the record names none
+pub async fn fetch_host_abuse_contact(ip: IpAddr) -> Result<Option<String>, DomFuzzError> {
+    let url = format!("{}{}", IP_RDAP_ENDPOINT, ip);
+    let rdap_error = |message: String| DomFuzzError::Rdap {
+        domain: ip.to_string(),
+        message,
+    };
+    let host = metrics::url_host(IP_RDAP_ENDPOINT);
+    let Some(permit) = throttle::acquire(host).await else {
+        return Err(DomFuzzError::CircuitOpen {
+            endpoint: host.to_string(),
+            domain: ip.to_string(),
+        });
+    };
+    metrics::record_request("rdap", host);
+    let response = match timeout(network().rdap_timeout, HTTP_CLIENT.get(&url).send()).await {
+        Ok(Ok(response)) => response,
+        Ok(Err(e)) => {
+            permit.finish(false);
+            return Err(rdap_error(e.to_string()));
+        }
+        Err(_) => {
+            permit.time_out();
+            return Err(DomFuzzError::Timeout {
+                protocol: "RDAP",
+                domain: ip.to_string(),
+            });
+        }
+    };
+    permit.finish(response.status().is_success());
+    if !response.status().is_success() {
+        return Err(rdap_error(format!("HTTP {}", response.status())));
+    }
+    let json: serde_json::Value = response
+        .json()
+        .await
+        .map_err(|e| rdap_error(e.to_string()))?;
+    Ok(abuse_email(&json))
+}
+
+/// Email address of the first entity with the `abuse` role at or below
+/// `entity`, searched breadth-first so the closest contact wins
+fn abuse_email(entity: &serde_json::Value) -> Option<String> {
+    let mut level = vec![entity];
+    while !level.is_empty() {
+        if let Some(email) = level
+            .iter()
+            .filter(|entity| has_role(entity, "abuse"))
+            .find_map(|entity| vcard_email(entity))
+        {
+            return Some(email);
+        }
+        level = level
+            .iter()
+            .filter_map(|entity| entity.get("entities").and_then(|e| e.as_array()))
+            .flatten()
+            .collect();
+    }
+    None
+}
+
+fn has_role(entity: &serde_json::Value, role: &str) -> bool {
+    entity
+        .get("roles")
+        .and_then(|r| r.as_array())
+        .is_some_and(|roles| roles.iter().any(|r| r.as_str() == Some(role)))
+}
+
+/// The `email` property of an entity's vCard, lowercase
+fn vcard_email(entity: &serde_json::Value) -> Option<String> {
+    entity
+        .get("vcardArray")?
+        .get(1)?
+        .as_array()?
+        .iter()
+        .filter_map(|property| property.as_array())
+        .find(|property| property.first().and_then(|name| name.as_str()) == Some("email"))?
+        .get(3)?
+        .as_str()
+        .map(|email| email.trim().to_lowercase())
+        .filter(|email| email.contains('@'))
+}
+
+fn normalize_host(host: &str) -> String {
+    host.trim_end_matches('.').to_lowercase()
+}
+
+/// `clientTransferProhibited` and `client transfer prohibited` both become the
+/// latter, as RDAP writes it
+fn normalize_status(code: &str) -> String {
+    let mut normalized = String::new();
+    for c in code.trim().chars() {
+        if c.is_uppercase() && !normalized.is_empty() && !normalized.ends_with(' ') {
+            normalized.push(' ');
+        }
+        normalized.extend(c.to_lowercase());
+    }
+    normalized
+}
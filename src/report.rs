@@ -0,0 +1,206 @@
+//! HTML and Markdown reports of results files (`domfuzz report`).
+//!
+//! Results are data; the people a brand-protection engagement reports to want a
+//! document. Reports are rendered from JSON Lines results with Jinja templates
+//! (minijinja), so that an organization can brand and restructure them, choose
+//! sections, columns, and thresholds, by dropping its own `report.html` or
+//! `report.md` into a `--templates` directory instead of patching the binary.
+//! The built-in templates stay available to those as `builtin/report.html` and
+//! `builtin/report.md`, so a custom template can extend one and override only
+//! its blocks.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use chrono::{SecondsFormat, Utc};
+use minijinja::{context, Environment, UndefinedBehavior, Value};
+
+use crate::error::DomFuzzError;
+
+/// Built-in templates, by name
+const BUILTIN_TEMPLATES: [(&str, &str); 2] = [
+    ("report.html", include_str!("../data/templates/report.html")),
+    ("report.md", include_str!("../data/templates/report.md")),
+];
+
+/// What `domfuzz report` renders
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ReportFormat {
+    /// A standalone HTML page
+    Html,
+    /// Markdown, for wikis and tickets
+    Markdown,
+    /// A paginated A4 document; requires --output
+    #[cfg(feature = "pdf")]
+    Pdf,
+}
+
+impl ReportFormat {
+    /// The format an output file's extension names, if any
+    pub fn from_path(path: &Path) -> Option<ReportFormat> {
+        match path.extension()?.to_str()?.to_lowercase().as_str() {
+            "html" | "htm" => Some(ReportFormat::Html),
+            "md" | "markdown" => Some(ReportFormat::Markdown),
+            #[cfg(feature = "pdf")]
+            "pdf" => Some(ReportFormat::Pdf),
+            _ => None,
+        }
+    }
+}
+
+/// Results read for a report, most similar first
+#[derive(Debug, Clone)]
+pub struct Report {
+    /// Domain the results are variations of, if known
+    pub domain: Option<String>,
+    /// Name of the results file
+    pub source: String,
+    /// RFC 3339, UTC
+    pub generated: String,
+    /// JSON Lines records as written
+    pub results: Vec<serde_json::Value>,
+}
+
+impl Report {
+    /// Read the records of a JSON Lines results file; blank lines are skipped
+    pub fn from_jsonl(
+        contents: &str,
+        source: &str,
+        domain: Option<&str>,
+    ) -> Result<Report, DomFuzzError> {
+        let mut results = Vec::new();
+        for (number, line) in contents.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let record: serde_json::Value = serde_json::from_str(line).map_err(|e| {
+                DomFuzzError::Parse(format!("{} line {}: {}", source, number + 1, e))
+            })?;
+            if record.get("domain").and_then(|d| d.as_str()).is_some() {
+                results.push(record);
+            }
+        }
+        results.sort_by(|a, b| score(b).total_cmp(&score(a)));
+        Ok(Report {
+            domain: domain.map(str::to_string),
+            source: source.to_string(),
+            generated: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+            results,
+        })
+    }
+
+    /// `DomFuzz report: DOMAIN`, or `DomFuzz report` if the domain is unknown
+    pub fn title(&self) -> String {
+        match &self.domain {
+            Some(domain) => format!("DomFuzz report: {}", domain),
+            None => "DomFuzz report".to_string(),
+        }
+    }
+
+    /// How many results have each value of `field`, most common first; results
+    /// without it are left out
+    pub fn counts(&self, field: &str) -> Vec<(String, usize)> {
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for record in &self.results {
+            if let Some(value) = record.get(field).and_then(|v| v.as_str()) {
+                *counts.entry(value).or_default() += 1;
+            }
+        }
+        let mut counts: Vec<(String, usize)> = counts
+            .into_iter()
+            .map(|(value, count)| (value.to_string(), count))
+            .collect();
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        counts
+    }
+
+    /// What templates are rendered with: `title`, `domain`, `source`,
+    /// `generated`, `results`, and `summary` with `total` and the `statuses`,
+    /// `transformations`, and `dispositions` counted as `{name, count}` lists
+    pub fn context(&self) -> Value {
+        let counted = |field: &str| {
+            self.counts(field)
+                .into_iter()
+                .map(|(name, count)| context! { name, count })
+                .collect::<Vec<_>>()
+        };
+        context! {
+            title => self.title(),
+            domain => self.domain,
+            source => self.source,
+            generated => self.generated,
+            results => Value::from_serialize(&self.results),
+            summary => context! {
+                total => self.results.len(),
+                statuses => counted("status"),
+                transformations => counted("transformation"),
+                dispositions => counted("disposition"),
+            },
+        }
+    }
+
+    /// The report in `format`, from `templates`' template for it if there is
+    /// one, or the built-in template otherwise; `templates` does not apply to
+    /// PDF
+    pub fn render(
+        &self,
+        format: ReportFormat,
+        templates: Option<&Path>,
+    ) -> Result<Vec<u8>, DomFuzzError> {
+        let template = match format {
+            ReportFormat::Html => "report.html",
+            ReportFormat::Markdown => "report.md",
+            // Laid out without a template
+            #[cfg(feature = "pdf")]
+            ReportFormat::Pdf => return crate::pdf::render(self),
+        };
+        let failed = |e: minijinja::Error| DomFuzzError::Config(format!("report template: {}", e));
+        if let Some(dir) = templates.filter(|dir| !dir.is_dir()) {
+            return Err(DomFuzzError::Config(format!(
+                "--templates {} is not a directory",
+                dir.display()
+            )));
+        }
+        let mut env = Environment::new();
+        // Most record fields are optional: `result.enrichment.flags` is simply
+        // undefined on a record without enrichment
+        env.set_undefined_behavior(UndefinedBehavior::Chainable);
+        // A line holding only a block tag leaves nothing behind, which keeps
+        // Markdown tables and paragraphs together
+        env.set_trim_blocks(true);
+        env.set_lstrip_blocks(true);
+        env.add_filter("percent", percent);
+        env.add_filter("md_escape", md_escape);
+        for (name, source) in BUILTIN_TEMPLATES {
+            env.add_template_owned(format!("builtin/{}", name), source)
+                .map_err(failed)?;
+            // Templates added here win over the loader's
+            if !templates.is_some_and(|dir| dir.join(name).is_file()) {
+                env.add_template(name, source).map_err(failed)?;
+            }
+        }
+        if let Some(dir) = templates {
+            env.set_loader(minijinja::path_loader(dir));
+        }
+        env.get_template(template)
+            .and_then(|template| template.render(self.context()))
+            .map(String::into_bytes)
+            .map_err(failed)
+    }
+}
+
+fn score(record: &serde_json::Value) -> f64 {
+    record.get("score").and_then(|s| s.as_f64()).unwrap_or(0.0)
+}
+
+/// `0.9167` as `91.67%`
+fn percent(value: f64) -> String {
+    format!("{:.2}%", value * 100.0)
+}
+
+/// Text made safe for a Markdown table cell
+fn md_escape(text: String) -> String {
+    text.replace('\\', "\\\\")
+        .replace('|', "\\|")
+        .replace(['\r', '\n'], " ")
+}
@@ -0,0 +1,280 @@
+//! Screenshots of live variations and perceptual hashing for clone detection.
+//!
+//! Pages are captured with a locally installed headless Chromium or Chrome.
+//! Each capture is reduced to a 64-bit perceptual hash (pHash): the sign of the
+//! low-frequency DCT coefficients of a 32x32 grayscale thumbnail. Visually
+//! similar pages, such as a phishing kit cloning the original's login page,
+//! have hashes a small Hamming distance apart even when pixels differ.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::error::DomFuzzError;
+
+/// Browser viewport used for captures
+pub const SCREENSHOT_WIDTH: u32 = 1280;
+pub const SCREENSHOT_HEIGHT: u32 = 800;
+
+/// Time allowed for the browser to load and capture one page
+pub const SCREENSHOT_TIMEOUT_SECS: u64 = 30;
+
+/// Default largest pHash distance (of 64 bits) reported as a probable clone
+pub const DEFAULT_CLONE_DISTANCE: u32 = 10;
+
+/// Default largest pHash distance between consecutive monitor captures that
+/// still counts as the same page; rotating ads on parking pages stay below it
+pub const DEFAULT_CHANGE_DISTANCE: u32 = 16;
+
+/// Flag recorded for variations whose screenshot nearly matches the original's
+pub const PROBABLE_CLONE: &str = "probable-clone";
+
+/// Browser executables searched for on `PATH`, in order
+const BROWSER_NAMES: &[&str] = &[
+    "chromium",
+    "chromium-browser",
+    "google-chrome",
+    "google-chrome-stable",
+    "chrome",
+];
+
+/// Side length of the grayscale thumbnail the DCT runs on
+const HASH_SIZE: usize = 32;
+/// Side length of the low-frequency block kept from the DCT
+const HASH_BLOCK: usize = 8;
+
+/// How and where to capture screenshots
+#[derive(Debug, Clone)]
+pub struct ScreenshotOptions {
+    pub browser: PathBuf,
+    /// Directory captures are written to, as `<domain>.png`
+    pub dir: PathBuf,
+    /// pHash of the original site, if its capture succeeded
+    pub reference_hash: Option<u64>,
+    /// Why the original site's capture failed, recorded among the errors of
+    /// each variation captured without a distance
+    pub reference_error: Option<String>,
+    /// Largest pHash distance reported as a probable clone
+    pub max_distance: u32,
+}
+
+impl ScreenshotOptions {
+    /// Locate a browser, create `dir`, and capture `original` as the reference for
+    /// clone detection. A failed reference capture leaves `reference_hash` unset
+    /// and sets `reference_error`, so captures still run without distances.
+    pub async fn prepare(
+        dir: &Path,
+        original: &str,
+        max_distance: u32,
+    ) -> Result<ScreenshotOptions, DomFuzzError> {
+        let browser = find_browser().ok_or_else(|| {
+            DomFuzzError::Config(
+                "no Chromium or Chrome found; install one or set DOMFUZZ_BROWSER".to_string(),
+            )
+        })?;
+        std::fs::create_dir_all(dir)?;
+        let mut options = ScreenshotOptions {
+            browser,
+            dir: dir.to_path_buf(),
+            reference_hash: None,
+            reference_error: None,
+            max_distance,
+        };
+        match capture_and_hash(original, &options).await {
+            Ok(reference) => options.reference_hash = Some(reference.phash),
+            Err(e) => {
+                options.reference_error =
+                    Some(format!("cannot capture {} for comparison: {}", original, e))
+            }
+        }
+        Ok(options)
+    }
+}
+
+/// A captured screenshot and how close it is to the original site's
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Screenshot {
+    pub path: PathBuf,
+    pub phash: u64,
+    /// Hamming distance to the original's pHash, if the original was captured
+    pub distance: Option<u32>,
+}
+
+impl Screenshot {
+    pub fn to_json(&self) -> serde_json::Value {
+        let mut json = serde_json::json!({
+            "path": self.path.display().to_string(),
+            "phash": format!("{:016x}", self.phash),
+        });
+        if let Some(distance) = self.distance {
+            json["distance"] = distance.into();
+        }
+        json
+    }
+}
+
+/// The browser named by `DOMFUZZ_BROWSER`, or the first Chromium-family browser on `PATH`
+pub fn find_browser() -> Option<PathBuf> {
+    if let Some(browser) = std::env::var_os("DOMFUZZ_BROWSER").filter(|b| !b.is_empty()) {
+        return Some(PathBuf::from(browser));
+    }
+    let path = std::env::var_os("PATH")?;
+    std::env::split_paths(&path)
+        .flat_map(|dir| BROWSER_NAMES.iter().map(move |name| dir.join(name)))
+        .find(|candidate| candidate.is_file())
+}
+
+/// Capture `domain`'s landing page into the screenshot directory and hash it
+pub async fn capture_and_hash(
+    domain: &str,
+    options: &ScreenshotOptions,
+) -> Result<Screenshot, DomFuzzError> {
+    capture_and_hash_to(domain, options, options.dir.join(format!("{}.png", domain))).await
+}
+
+/// Capture `domain`'s landing page into `path` and hash it
+pub async fn capture_and_hash_to(
+    domain: &str,
+    options: &ScreenshotOptions,
+    path: PathBuf,
+) -> Result<Screenshot, DomFuzzError> {
+    capture(&options.browser, &format!("https://{}", domain), &path).await?;
+    let phash = perceptual_hash(&path)?;
+    Ok(Screenshot {
+        distance: options
+            .reference_hash
+            .map(|reference| hamming_distance(reference, phash)),
+        path,
+        phash,
+    })
+}
+
+/// Render `url` with a headless browser and save a PNG to `out`
+pub async fn capture(browser: &Path, url: &str, out: &Path) -> Result<(), DomFuzzError> {
+    let mut command = tokio::process::Command::new(browser);
+    command
+        .arg("--headless")
+        .arg("--disable-gpu")
+        .arg("--hide-scrollbars")
+        .arg("--no-first-run")
+        .arg(format!(
+            "--window-size={},{}",
+            SCREENSHOT_WIDTH, SCREENSHOT_HEIGHT
+        ))
+        .arg(format!("--screenshot={}", out.display()))
+        .arg(url)
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .kill_on_drop(true);
+
+    let status = tokio::time::timeout(
+        Duration::from_secs(SCREENSHOT_TIMEOUT_SECS),
+        command.status(),
+    )
+    .await
+    .map_err(|_| DomFuzzError::Timeout {
+        protocol: "screenshot",
+        domain: url.to_string(),
+    })??;
+    if !status.success() || !out.is_file() {
+        return Err(DomFuzzError::Http {
+            url: url.to_string(),
+            message: format!(
+                "{} could not capture the page ({})",
+                browser.display(),
+                status
+            ),
+        });
+    }
+    Ok(())
+}
+
+/// 64-bit perceptual hash of a PNG image
+pub fn perceptual_hash(path: &Path) -> Result<u64, DomFuzzError> {
+    let parse_error = |e: png::DecodingError| {
+        DomFuzzError::Parse(format!("cannot decode {}: {}", path.display(), e))
+    };
+    let mut decoder = png::Decoder::new(std::fs::File::open(path)?);
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info().map_err(parse_error)?;
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buffer).map_err(parse_error)?;
+    let (width, height) = (info.width as usize, info.height as usize);
+    if width == 0 || height == 0 {
+        return Err(DomFuzzError::Parse(format!("{} is empty", path.display())));
+    }
+    let channels = info.color_type.samples();
+
+    // Area-average into a HASH_SIZE x HASH_SIZE grayscale thumbnail
+    let mut sums = [[0.0f64; HASH_SIZE]; HASH_SIZE];
+    let mut counts = [[0u32; HASH_SIZE]; HASH_SIZE];
+    for (y, row) in buffer[..info.buffer_size()]
+        .chunks_exact(info.line_size)
+        .enumerate()
+    {
+        let cell_y = y * HASH_SIZE / height;
+        for x in 0..width {
+            let pixel = &row[x * channels..(x + 1) * channels];
+            let luma = if channels >= 3 {
+                0.299 * pixel[0] as f64 + 0.587 * pixel[1] as f64 + 0.114 * pixel[2] as f64
+            } else {
+                pixel[0] as f64
+            };
+            let cell_x = x * HASH_SIZE / width;
+            sums[cell_y][cell_x] += luma;
+            counts[cell_y][cell_x] += 1;
+        }
+    }
+    let mut pixels = [[0.0f64; HASH_SIZE]; HASH_SIZE];
+    for y in 0..HASH_SIZE {
+        for x in 0..HASH_SIZE {
+            pixels[y][x] = sums[y][x] / counts[y][x].max(1) as f64;
+        }
+    }
+
+    let coefficients = dct_2d(&pixels);
+    let mut low: Vec<f64> = (0..HASH_BLOCK)
+        .flat_map(|y| (0..HASH_BLOCK).map(move |x| (y, x)))
+        .map(|(y, x)| coefficients[y][x])
+        .collect();
+    // The DC term is overall brightness, not structure; leave it out of the median
+    let mut sorted = low[1..].to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let median = sorted[sorted.len() / 2];
+    low[0] = median;
+
+    Ok(low
+        .iter()
+        .enumerate()
+        .filter(|(_, coefficient)| **coefficient > median)
+        .fold(0u64, |hash, (bit, _)| hash | 1 << bit))
+}
+
+/// Number of differing bits between two hashes
+pub fn hamming_distance(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+/// Separable 2D DCT-II of the thumbnail
+fn dct_2d(pixels: &[[f64; HASH_SIZE]; HASH_SIZE]) -> [[f64; HASH_SIZE]; HASH_SIZE] {
+    let n = HASH_SIZE as f64;
+    let mut cosines = [[0.0f64; HASH_SIZE]; HASH_SIZE];
+    for (k, row) in cosines.iter_mut().enumerate() {
+        for (i, cosine) in row.iter_mut().enumerate() {
+            *cosine = (std::f64::consts::PI * (2 * i + 1) as f64 * k as f64 / (2.0 * n)).cos();
+        }
+    }
+
+    let mut rows = [[0.0f64; HASH_SIZE]; HASH_SIZE];
+    for y in 0..HASH_SIZE {
+        for k in 0..HASH_SIZE {
+            rows[y][k] = (0..HASH_SIZE).map(|i| pixels[y][i] * cosines[k][i]).sum();
+        }
+    }
+    let mut result = [[0.0f64; HASH_SIZE]; HASH_SIZE];
+    for x in 0..HASH_SIZE {
+        for k in 0..HASH_SIZE {
+            result[k][x] = (0..HASH_SIZE).map(|i| rows[i][x] * cosines[k][i]).sum();
+        }
+    }
+    result
+}
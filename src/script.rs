@@ -0,0 +1,103 @@
+//! Transformations written in Rhai (`--script FILE`).
+//!
+//! For logic beyond what [`crate::custom`] rules can declare, a script defines
+//!
+//! ```text
+//! fn generate(domain, tld) {
+//!     [domain + "-login." + tld, domain.to_upper() + "." + tld]
+//! }
+//! ```
+//!
+//! and runs as the `script` transformation: it is part of the `all` bundle,
+//! is chained in combo mode, and its results are scored and labeled like any
+//! built-in transformation's.
+
+use std::sync::{Arc, Mutex};
+
+use rhai::{Array, Engine, Scope, AST};
+
+use crate::error::DomFuzzError;
+
+/// Operations one `generate` call may run before it is aborted, so a runaway
+/// loop cannot hang generation
+pub const MAX_OPERATIONS: u64 = 1_000_000;
+
+/// A compiled script exposing `generate(domain, tld)`
+#[derive(Clone)]
+pub struct Script {
+    engine: Arc<Engine>,
+    ast: Arc<AST>,
+    /// First runtime error of a `generate` call made as a transformation,
+    /// shared by clones
+    error: Arc<Mutex<Option<String>>>,
+}
+
+impl std::fmt::Debug for Script {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Script").finish_non_exhaustive()
+    }
+}
+
+impl Script {
+    /// Compile `source`, which must define `generate` with two parameters
+    pub fn compile(source: &str) -> Result<Self, DomFuzzError> {
+        let mut engine = Engine::new();
+        engine.set_max_operations(MAX_OPERATIONS);
+        let ast = engine
+            .compile(source)
+            .map_err(|e| DomFuzzError::Parse(e.to_string()))?;
+        if !ast
+            .iter_functions()
+            .any(|f| f.name == "generate" && f.params.len() == 2)
+        {
+            return Err(DomFuzzError::Parse(
+                "script must define fn generate(domain, tld)".to_string(),
+            ));
+        }
+        Ok(Script {
+            engine: Arc::new(engine),
+            ast: Arc::new(ast),
+            error: Arc::new(Mutex::new(None)),
+        })
+    }
+
+    /// Run `generate(domain, tld)`, lowercasing the results. Non-string items
+    /// are skipped.
+    pub fn generate(&self, domain: &str, tld: &str) -> Result<Vec<String>, DomFuzzError> {
+        let items = self
+            .engine
+            .call_fn::<Array>(
+                &mut Scope::new(),
+                &self.ast,
+                "generate",
+                (domain.to_string(), tld.to_string()),
+            )
+            .map_err(|e| {
+                DomFuzzError::Config(format!(
+                    "script generate({}, {}) failed: {}",
+                    domain, tld, e
+                ))
+            })?;
+        Ok(items
+            .into_iter()
+            .filter_map(|item| item.into_string().ok())
+            .map(|domain| domain.to_lowercase())
+            .collect())
+    }
+
+    /// [`Script::generate`] as the `script` transformation: a runtime error
+    /// yields no variations, and the first is kept for [`Script::error`]
+    pub fn generate_or_record(&self, domain: &str, tld: &str) -> Vec<String> {
+        self.generate(domain, tld).unwrap_or_else(|e| {
+            let mut error = self.error.lock().unwrap_or_else(|e| e.into_inner());
+            error.get_or_insert_with(|| e.to_string());
+            Vec::new()
+        })
+    }
+
+    /// The first error of a `generate` call made as the `script`
+    /// transformation, if any call failed
+    pub fn error(&self) -> Option<String> {
+        self.error.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
@@ -0,0 +1,248 @@
+//! HTTP/JSON API over [`FuzzerBuilder`], served by `domfuzz serve`.
+//!
+//! - `POST /generate` returns scored variations of a domain
+//! - `POST /check` also returns the registration status of each variation
+//! - `GET /health` returns `ok`
+//! - `GET /metrics` returns Prometheus metrics (see [`crate::metrics`])
+//!
+//! Both POST endpoints accept the same JSON body:
+//!
+//! ```json
+//! {"domain": "example.com", "transformations": ["lookalike"], "max_variations": 50,
+//!  "min_similarity": 0.7, "max_similarity": 0.95, "combo": false, "seed": 42}
+//! ```
+
+use std::net::SocketAddr;
+use std::time::Instant;
+
+use axum::extract::rejection::JsonRejection;
+use axum::extract::MatchedPath;
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+
+use crate::checker::is_failed_status;
+use crate::error::DomFuzzError;
+use crate::fuzzer::{Fuzzer, FuzzerBuilder, ScoredVariation};
+use crate::metrics;
+
+/// `max_variations` used by `/check` when the request does not set one
+pub const DEFAULT_CHECK_LIMIT: usize = 100;
+/// Largest `max_variations` `/check` accepts, bounding lookups per request
+pub const MAX_CHECK_LIMIT: usize = 1000;
+/// Largest `max_variations` `/generate` accepts, and its default, bounding the
+/// response size and the chains combo requests build
+pub const MAX_GENERATE_LIMIT: usize = 10_000;
+
+/// Request body shared by `/generate` and `/check`
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FuzzRequest {
+    pub domain: String,
+    #[serde(default)]
+    pub transformations: Vec<String>,
+    pub max_variations: Option<usize>,
+    /// Minimum combined similarity, 0.0-1.0
+    pub min_similarity: Option<f64>,
+    /// Maximum combined similarity, 0.0-1.0
+    pub max_similarity: Option<f64>,
+    #[serde(default)]
+    pub combo: bool,
+    pub seed: Option<u64>,
+}
+
+/// One variation in a response; `status` is only present for `/check`
+#[derive(Debug, Serialize)]
+pub struct VariationResult {
+    pub domain: String,
+    pub transformation: &'static str,
+    pub score: f64,
+    pub visual_score: f64,
+    pub cognitive_score: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+}
+
+/// Response body for `/generate` and `/check`
+#[derive(Debug, Serialize)]
+pub struct FuzzResponse {
+    pub domain: String,
+    pub variations: Vec<VariationResult>,
+}
+
+/// Error response: `{"error": "..."}` with a 4xx/5xx status
+#[derive(Debug)]
+pub struct ApiError {
+    status: StatusCode,
+    message: String,
+}
+
+impl From<DomFuzzError> for ApiError {
+    fn from(error: DomFuzzError) -> Self {
+        let status = match error {
+            DomFuzzError::Parse(_) | DomFuzzError::Config(_) => StatusCode::BAD_REQUEST,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        ApiError {
+            status,
+            message: error.to_string(),
+        }
+    }
+}
+
+impl From<JsonRejection> for ApiError {
+    fn from(rejection: JsonRejection) -> Self {
+        ApiError {
+            status: rejection.status(),
+            message: rejection.body_text(),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let body = Json(serde_json::json!({ "error": self.message }));
+        (self.status, body).into_response()
+    }
+}
+
+/// Routes for the API, for embedding in a larger application
+pub fn router() -> Router {
+    Router::new()
+        .route("/health", get(|| async { "ok" }))
+        .route("/metrics", get(metrics_text))
+        .route("/generate", post(generate))
+        .route("/check", post(check))
+        .layer(middleware::from_fn(track_latency))
+}
+
+/// Record request latency per matched route
+async fn track_latency(request: axum::extract::Request, next: Next) -> Response {
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map_or_else(|| "unmatched".to_string(), |path| path.as_str().to_string());
+    let start = Instant::now();
+    let response = next.run(request).await;
+    metrics::observe_request(&route, start.elapsed());
+    response
+}
+
+async fn metrics_text() -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        metrics::render(),
+    )
+}
+
+/// Serve the API on `addr` until the process is stopped
+pub async fn serve(addr: SocketAddr) -> Result<(), DomFuzzError> {
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    axum::serve(listener, router()).await?;
+    Ok(())
+}
+
+async fn generate(
+    request: Result<Json<FuzzRequest>, JsonRejection>,
+) -> Result<Json<FuzzResponse>, ApiError> {
+    let Json(request) = request?;
+    let domain = request.domain.clone();
+    let fuzzer = build_fuzzer(request, "/generate", MAX_GENERATE_LIMIT, MAX_GENERATE_LIMIT)?;
+
+    // Generation is CPU-bound; keep it off the async worker threads
+    let variations = tokio::task::spawn_blocking(move || fuzzer.generate())
+        .await
+        .map_err(|e| DomFuzzError::Io(std::io::Error::other(e)))?;
+
+    Ok(Json(FuzzResponse {
+        domain,
+        variations: variations
+            .into_iter()
+            .map(|variation| variation_result(variation, None))
+            .collect(),
+    }))
+}
+
+async fn check(
+    request: Result<Json<FuzzRequest>, JsonRejection>,
+) -> Result<Json<FuzzResponse>, ApiError> {
+    let Json(request) = request?;
+    let domain = request.domain.clone();
+    let fuzzer = build_fuzzer(request, "/check", DEFAULT_CHECK_LIMIT, MAX_CHECK_LIMIT)?;
+
+    // Generation is CPU-bound; keep it off the async worker threads
+    let (fuzzer, variations) = tokio::task::spawn_blocking(move || {
+        let variations = fuzzer.generate();
+        (fuzzer, variations)
+    })
+    .await
+    .map_err(|e| DomFuzzError::Io(std::io::Error::other(e)))?;
+    let mut checked: Vec<_> = fuzzer.check_generated_stream(variations).collect().await;
+    if checked.iter().any(|c| !is_failed_status(&c.status)) {
+        metrics::record_cycle_success();
+    }
+    checked.sort_by(|a, b| {
+        b.variation
+            .similarity
+            .combined_score
+            .partial_cmp(&a.variation.similarity.combined_score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.variation.domain.cmp(&b.variation.domain))
+    });
+
+    Ok(Json(FuzzResponse {
+        domain,
+        variations: checked
+            .into_iter()
+            .map(|checked| variation_result(checked.variation, Some(checked.status)))
+            .collect(),
+    }))
+}
+
+/// Build a fuzzer from a request to `endpoint`, returning `default_limit`
+/// variations unless the request sets `max_variations`, and never more than
+/// `max_limit`
+fn build_fuzzer(
+    request: FuzzRequest,
+    endpoint: &str,
+    default_limit: usize,
+    max_limit: usize,
+) -> Result<Fuzzer, DomFuzzError> {
+    let mut builder = FuzzerBuilder::new(request.domain)
+        .transformations(request.transformations)
+        .combo(request.combo);
+
+    let max_variations = request.max_variations.unwrap_or(default_limit);
+    if max_variations > max_limit {
+        return Err(DomFuzzError::Config(format!(
+            "max_variations must be at most {} for {}",
+            max_limit, endpoint
+        )));
+    }
+    builder = builder.max_variations(max_variations);
+    if let Some(threshold) = request.min_similarity {
+        builder = builder.min_similarity(threshold);
+    }
+    if let Some(threshold) = request.max_similarity {
+        builder = builder.max_similarity(threshold);
+    }
+    if let Some(seed) = request.seed {
+        builder = builder.seed(seed);
+    }
+    builder.build()
+}
+
+fn variation_result(variation: ScoredVariation, status: Option<String>) -> VariationResult {
+    VariationResult {
+        domain: variation.domain,
+        transformation: variation.transformation,
+        score: variation.similarity.combined_score,
+        visual_score: variation.similarity.visual_score,
+        cognitive_score: variation.similarity.cognitive_score,
+        status,
+    }
+}
@@ -0,0 +1,758 @@
+//! Similarity metrics used to rank variations by how convincing they are.
+//!
+//! [`calculate_similarity`] scores a variation produced by a known
+//! transformation, and [`score_domain`] scores any candidate domain, such as
+//! one collected from certificate transparency logs or a registrar feed,
+//! against the brand's domain.
+//!
+//! Edit distance punishes variations that add to the name, such as
+//! `example-login` for `example`, even though they contain the brand whole;
+//! [`ScoringMode::Containment`] ranks those by how much of the name the brand
+//! fills instead.
+//!
+//! [`compare`] reports every metric here, and a few more kept for comparison
+//! (Damerau-Levenshtein, Jaro-Winkler, keyboard distance), between two domains.
+
+use crate::confusables::skeleton;
+use crate::domain::Domain;
+use crate::error::DomFuzzError;
+use crate::typo::typo_likelihood;
+
+/// Transformation name for candidates that no generator produced
+pub const UNKNOWN_TRANSFORMATION: &str = "unknown";
+
+/// Similarity of a variation to the original domain; all scores are in 0.0..=1.0.
+/// With the `serde` feature (part of `cli`), it serializes with these field
+/// names and `algorithm` in kebab case.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SimilarityScore {
+    pub domain: String,
+    pub visual_score: f64,
+    pub cognitive_score: f64,
+    pub combined_score: f64,
+    /// Transformation the variation was scored as, or [`UNKNOWN_TRANSFORMATION`]
+    pub transformation: String,
+    /// How `combined_score` was derived from the component scores
+    pub algorithm: ScoringAlgorithm,
+}
+
+/// How a combined score weighs visual and cognitive similarity, chosen by the
+/// transformation that produced the variation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum ScoringAlgorithm {
+    /// 80% visual, 20% cognitive: homographs and mixed scripts, which fool the eye
+    VisualWeighted,
+    /// 80% cognitive, 20% visual: homophones and other confusions of meaning
+    CognitiveWeighted,
+    /// 60% visual, 40% cognitive: typos
+    TypoWeighted,
+    /// Equal weights, for everything else
+    Balanced,
+    /// Equal weights, scaled by how similar the TLDs are: TLD swaps, where the
+    /// name itself is unchanged
+    TldAdjusted,
+    /// The name contains the original whole; scored by where it appears and
+    /// how much of the name it fills (only with [`ScoringMode::Containment`])
+    Containment,
+}
+
+/// How a run scores variations; chosen once for all of them
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum ScoringMode {
+    /// Edit distance and phonetics alone
+    #[default]
+    Standard,
+    /// Also credit names that contain the original whole, such as combosquats
+    /// and prefixed or suffixed names, which edit distance ranks low for their
+    /// length
+    Containment,
+}
+
+impl ScoringAlgorithm {
+    /// The algorithm [`calculate_similarity`] uses for `transformation`
+    pub fn for_transformation(transformation: &str) -> Self {
+        match transformation {
+            "mixed-encodings" | "idn_homograph" | "mixed_script" => {
+                ScoringAlgorithm::VisualWeighted
+            }
+            "cognitive" | "homophones" => ScoringAlgorithm::CognitiveWeighted,
+            "typosquatting" | "omission" | "insertion" => ScoringAlgorithm::TypoWeighted,
+            "tld-variations" => ScoringAlgorithm::TldAdjusted,
+            _ => ScoringAlgorithm::Balanced,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ScoringAlgorithm::VisualWeighted => "visual-weighted",
+            ScoringAlgorithm::CognitiveWeighted => "cognitive-weighted",
+            ScoringAlgorithm::TypoWeighted => "typo-weighted",
+            ScoringAlgorithm::Balanced => "balanced",
+            ScoringAlgorithm::TldAdjusted => "tld-adjusted",
+            ScoringAlgorithm::Containment => "containment",
+        }
+    }
+}
+
+/// Calculate Levenshtein distance between two strings
+pub fn levenshtein_distance(s1: &str, s2: &str) -> usize {
+    let len1 = s1.chars().count();
+    let len2 = s2.chars().count();
+
+    if len1 == 0 {
+        return len2;
+    }
+    if len2 == 0 {
+        return len1;
+    }
+
+    let mut matrix = vec![vec![0usize; len2 + 1]; len1 + 1];
+
+    // Initialize first row and column
+    for (i, row) in matrix.iter_mut().enumerate().take(len1 + 1) {
+        row[0] = i;
+    }
+    for (j, cell) in matrix[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+
+    let chars1: Vec<char> = s1.chars().collect();
+    let chars2: Vec<char> = s2.chars().collect();
+
+    for (i, &c1) in chars1.iter().enumerate() {
+        for (j, &c2) in chars2.iter().enumerate() {
+            let cost = if c1 == c2 { 0 } else { 1 };
+            matrix[i + 1][j + 1] = std::cmp::min(
+                std::cmp::min(
+                    matrix[i][j + 1] + 1, // deletion
+                    matrix[i + 1][j] + 1, // insertion
+                ),
+                matrix[i][j] + cost, // substitution
+            );
+        }
+    }
+
+    matrix[len1][len2]
+}
+
+/// Levenshtein distance counting a swap of two adjacent characters as one
+/// edit (optimal string alignment)
+pub fn damerau_levenshtein_distance(s1: &str, s2: &str) -> usize {
+    let (a, b): (Vec<char>, Vec<char>) = (s1.chars().collect(), s2.chars().collect());
+    let mut d = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1)
+                .min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[a.len()][b.len()]
+}
+
+/// Longest common prefix Jaro-Winkler credits, and its weight per character
+const JARO_WINKLER_PREFIX: usize = 4;
+const JARO_WINKLER_SCALE: f64 = 0.1;
+
+/// Jaro-Winkler similarity in 0.0..=1.0: characters in common within a window
+/// and in the same order, with a bonus for a common prefix, where readers look
+/// first
+pub fn jaro_winkler_similarity(s1: &str, s2: &str) -> f64 {
+    let (a, b): (Vec<char>, Vec<char>) = (s1.chars().collect(), s2.chars().collect());
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+    let window = (a.len().max(b.len()) / 2).saturating_sub(1);
+    let mut b_matched = vec![false; b.len()];
+    let mut a_matches = Vec::new();
+    for (i, &c) in a.iter().enumerate() {
+        let range = i.saturating_sub(window)..(i + window + 1).min(b.len());
+        if let Some(j) = range.into_iter().find(|&j| !b_matched[j] && b[j] == c) {
+            b_matched[j] = true;
+            a_matches.push(c);
+        }
+    }
+    if a_matches.is_empty() {
+        return 0.0;
+    }
+    let b_matches = b
+        .iter()
+        .zip(&b_matched)
+        .filter(|(_, &m)| m)
+        .map(|(&c, _)| c);
+    let transpositions = a_matches
+        .iter()
+        .zip(b_matches)
+        .filter(|(&x, y)| x != *y)
+        .count() as f64
+        / 2.0;
+    let m = a_matches.len() as f64;
+    let jaro = (m / a.len() as f64 + m / b.len() as f64 + (m - transpositions) / m) / 3.0;
+    let prefix = a
+        .iter()
+        .zip(&b)
+        .take(JARO_WINKLER_PREFIX)
+        .take_while(|(x, y)| x == y)
+        .count();
+    jaro + prefix as f64 * JARO_WINKLER_SCALE * (1.0 - jaro)
+}
+
+/// Cost [`keyboard_distance`] gives a slip onto a neighbouring key
+pub const KEYBOARD_NEIGHBOUR_COST: f64 = 0.5;
+
+/// Edit distance in which substituting or inserting a key next to the
+/// intended one on a QWERTY keyboard costs [`KEYBOARD_NEIGHBOUR_COST`]: the
+/// slips typists make cost less than arbitrary edits
+pub fn keyboard_distance(s1: &str, s2: &str) -> f64 {
+    let (a, b): (Vec<char>, Vec<char>) = (
+        s1.to_lowercase().chars().collect(),
+        s2.to_lowercase().chars().collect(),
+    );
+    let near = |x: char, y: char| crate::typo::neighbours(x).any(|n| n == y);
+    // Inserting `typed` costs less beside itself or a key it neighbours
+    let insertion = |typed: char, beside: &[Option<&char>]| {
+        if beside
+            .iter()
+            .flatten()
+            .any(|&&key| key == typed || near(key, typed))
+        {
+            KEYBOARD_NEIGHBOUR_COST
+        } else {
+            1.0
+        }
+    };
+    let mut d = vec![vec![0.0; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i as f64;
+    }
+    for j in 1..=b.len() {
+        d[0][j] = d[0][j - 1] + insertion(b[j - 1], &[a.first()]);
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let substitution = match (a[i - 1], b[j - 1]) {
+                (x, y) if x == y => 0.0,
+                (x, y) if near(x, y) => KEYBOARD_NEIGHBOUR_COST,
+                _ => 1.0,
+            };
+            let inserted = insertion(b[j - 1], &[a.get(i - 1), a.get(i)]);
+            d[i][j] = (d[i - 1][j] + 1.0)
+                .min(d[i][j - 1] + inserted)
+                .min(d[i - 1][j - 1] + substitution);
+        }
+    }
+    d[a.len()][b.len()]
+}
+
+/// Calculate homoglyph-weighted visual similarity
+pub fn visual_similarity(original: &str, variant: &str) -> f64 {
+    let basic_distance = levenshtein_distance(original, variant) as f64;
+    let max_len = std::cmp::max(original.len(), variant.len()) as f64;
+
+    if max_len == 0.0 {
+        return 1.0;
+    }
+
+    // Base similarity from Levenshtein distance
+    let mut similarity = 1.0 - (basic_distance / max_len);
+
+    // Bonus for homoglyph substitutions (characters that look similar)
+    let homoglyph_bonus = calculate_homoglyph_similarity(original, variant);
+
+    // Weight the final score
+    similarity = (similarity * 0.7) + (homoglyph_bonus * 0.3);
+
+    similarity.clamp(0.0, 1.0)
+}
+
+/// Calculate similarity bonus for homoglyph substitutions
+fn calculate_homoglyph_similarity(s1: &str, s2: &str) -> f64 {
+    if s1.len() != s2.len() {
+        return 0.0;
+    }
+
+    let chars1: Vec<char> = s1.chars().collect();
+    let chars2: Vec<char> = s2.chars().collect();
+
+    let mut homoglyph_matches = 0;
+    let mut total_positions = 0;
+
+    // Common homoglyph pairs (visual confusions)
+    let homoglyphs = [
+        ('a', 'α'),
+        ('o', '0'),
+        ('i', '1'),
+        ('l', '1'),
+        ('e', '3'),
+        ('s', '$'),
+        ('g', '9'),
+        ('b', '6'),
+        ('z', '2'),
+        ('s', '5'),
+        ('o', 'ο'),
+        ('a', 'а'),
+        ('p', 'р'),
+        ('c', 'с'),
+        ('e', 'е'),
+        ('x', 'х'),
+        ('y', 'у'),
+        ('k', 'κ'),
+        ('n', 'η'),
+        ('m', 'μ'),
+    ];
+
+    for (&c1, &c2) in chars1.iter().zip(chars2.iter()) {
+        total_positions += 1;
+
+        if c1 == c2 {
+            homoglyph_matches += 1;
+        } else {
+            // Check if it's a known homoglyph pair
+            for &(h1, h2) in &homoglyphs {
+                if (c1 == h1 && c2 == h2) || (c1 == h2 && c2 == h1) {
+                    homoglyph_matches += 1;
+                    break;
+                }
+            }
+        }
+    }
+
+    if total_positions == 0 {
+        0.0
+    } else {
+        homoglyph_matches as f64 / total_positions as f64
+    }
+}
+
+/// Letter sequences that render like a single letter, or digits like letters
+const TLD_LOOKALIKES: [(&str, &str); 6] = [
+    ("rn", "m"),
+    ("vv", "w"),
+    ("cl", "d"),
+    ("nn", "m"),
+    ("0", "o"),
+    ("1", "l"),
+];
+
+/// How easily TLD `variant` passes for `original` at a glance, in 0.0..=1.0:
+/// `.corn` for `.com` scores near 1, `.co` and `.cm` about 0.5, `.shop` near 0.
+/// Blends visual similarity with how close the two lengths are.
+pub fn tld_similarity(original: &str, variant: &str) -> f64 {
+    let (original, variant) = (original.to_lowercase(), variant.to_lowercase());
+    if original == variant {
+        return 1.0;
+    }
+    let normalize = |tld: &str| {
+        TLD_LOOKALIKES
+            .iter()
+            .fold(tld.to_string(), |tld, (from, to)| tld.replace(from, to))
+    };
+    if normalize(&original) == normalize(&variant) {
+        return 0.95;
+    }
+    let (len1, len2) = (original.chars().count(), variant.chars().count());
+    let length_similarity = len1.min(len2) as f64 / len1.max(len2).max(1) as f64;
+    visual_similarity(&original, &variant) * 0.8 + length_similarity * 0.2
+}
+
+/// Calculate cognitive/phonetic similarity
+pub fn cognitive_similarity(original: &str, variant: &str) -> f64 {
+    let mut similarity = 0.0;
+
+    // Phonetic similarity using simple Soundex-like approach
+    similarity += phonetic_similarity(original, variant) * 0.4;
+
+    // Semantic similarity based on known cognitive confusions
+    similarity += semantic_similarity(original, variant) * 0.3;
+
+    // Length-based similarity penalty
+    let length_diff = (original.len() as i32 - variant.len() as i32).abs() as f64;
+    let length_penalty = 1.0 - (length_diff / std::cmp::max(original.len(), variant.len()) as f64);
+    similarity += length_penalty * 0.3;
+
+    similarity.clamp(0.0, 1.0)
+}
+
+/// Simple phonetic similarity calculation
+fn phonetic_similarity(s1: &str, s2: &str) -> f64 {
+    let sound1 = simple_soundex(s1);
+    let sound2 = simple_soundex(s2);
+
+    let distance = levenshtein_distance(&sound1, &sound2) as f64;
+    let max_len = std::cmp::max(sound1.len(), sound2.len()) as f64;
+
+    if max_len == 0.0 {
+        1.0
+    } else {
+        1.0 - (distance / max_len)
+    }
+}
+
+/// Simplified Soundex algorithm for phonetic encoding
+fn simple_soundex(s: &str) -> String {
+    if s.is_empty() {
+        return String::new();
+    }
+
+    let mut result = String::new();
+    let mut prev_code = None;
+
+    for c in s.to_lowercase().chars() {
+        let code = match c {
+            'b' | 'f' | 'p' | 'v' => Some('1'),
+            'c' | 'g' | 'j' | 'k' | 'q' | 's' | 'x' | 'z' => Some('2'),
+            'd' | 't' => Some('3'),
+            'l' => Some('4'),
+            'm' | 'n' => Some('5'),
+            'r' => Some('6'),
+            _ => None,
+        };
+
+        if let Some(code) = code {
+            if prev_code != Some(code) {
+                result.push(code);
+                prev_code = Some(code);
+            }
+        } else {
+            prev_code = None;
+        }
+    }
+
+    result
+}
+
+/// Calculate semantic similarity based on known word confusions
+fn semantic_similarity(original: &str, variant: &str) -> f64 {
+    // Check against known cognitive confusion patterns from generate_cognitive
+    let cognitive_pairs = [
+        ("amazon", "amazom"),
+        ("google", "gogle"),
+        ("microsoft", "mircosoft"),
+        ("facebook", "facbook"),
+        ("paypal", "payball"),
+        ("secure", "secur"),
+        ("support", "suport"),
+        ("service", "servic"),
+        ("account", "acount"),
+        ("login", "loginn"),
+        ("portal", "portall"),
+        ("center", "centre"),
+        ("corp", "corporate"),
+        ("inc", "incorporated"),
+        ("tech", "technology"),
+        ("concordium", "consordium"),
+        ("consortium", "concordium"),
+    ];
+
+    // Check if this is a known semantic confusion
+    for (word1, word2) in &cognitive_pairs {
+        if (original.contains(word1) && variant.contains(word2))
+            || (original.contains(word2) && variant.contains(word1))
+        {
+            return 0.8; // High semantic similarity
+        }
+    }
+
+    // Fallback to basic string similarity
+    let distance = levenshtein_distance(original, variant) as f64;
+    let max_len = std::cmp::max(original.len(), variant.len()) as f64;
+
+    if max_len == 0.0 {
+        1.0
+    } else {
+        1.0 - (distance / max_len)
+    }
+}
+
+/// Shortest name credited for containment; shorter ones turn up inside
+/// unrelated words
+const MIN_CONTAINED_LEN: usize = 3;
+/// Lowest containment score for a name that starts or ends with the original,
+/// and for one with the original in the middle; the rest of the range is
+/// earned by the share of the name the original fills
+const AFFIX_CONTAINMENT_BASE: f64 = 0.6;
+const INFIX_CONTAINMENT_BASE: f64 = 0.5;
+
+/// Score of a name `variant` that contains the name `original` whole, ignoring
+/// hyphens; `None` if it does not, or is no longer
+fn containment_score(original: &str, variant: &str) -> Option<f64> {
+    let original: String = original.chars().filter(|&c| c != '-').collect();
+    let variant: String = variant.chars().filter(|&c| c != '-').collect();
+    let (original_len, variant_len) = (original.chars().count(), variant.chars().count());
+    if original_len < MIN_CONTAINED_LEN || variant_len <= original_len {
+        return None;
+    }
+    let base = if variant.starts_with(&original) || variant.ends_with(&original) {
+        AFFIX_CONTAINMENT_BASE
+    } else if variant.contains(&original) {
+        INFIX_CONTAINMENT_BASE
+    } else {
+        return None;
+    };
+    Some(base + (1.0 - base) * original_len as f64 / variant_len as f64)
+}
+
+/// The label `domain` registers under its public suffix, and the suffix; the
+/// leftmost label and the rest if it does not parse
+fn registered_label(domain: &str) -> (String, String) {
+    match Domain::parse(domain) {
+        Ok(parsed) => (
+            parsed.registered_label().to_string(),
+            parsed.suffix().to_string(),
+        ),
+        Err(_) => {
+            let (label, suffix) = domain.split_once('.').unwrap_or((domain, ""));
+            (label.to_string(), suffix.to_string())
+        }
+    }
+}
+
+/// Score `variant`, produced by `transformation`, against `original`
+pub fn calculate_similarity(
+    original: &str,
+    variant: &str,
+    transformation: &str,
+) -> SimilarityScore {
+    calculate_similarity_with_mode(original, variant, transformation, ScoringMode::Standard)
+}
+
+/// [`calculate_similarity`] under `mode`. With [`ScoringMode::Containment`], a
+/// name containing the original scores the higher of its usual combined score
+/// and its containment score, and reports [`ScoringAlgorithm::Containment`]
+/// when the latter wins; the component scores are unchanged.
+pub fn calculate_similarity_with_mode(
+    original: &str,
+    variant: &str,
+    transformation: &str,
+    mode: ScoringMode,
+) -> SimilarityScore {
+    // `login.paypa1.com` imitates `paypal.com` through `paypa1`, not `login`
+    let (original_label, original_suffix) = registered_label(original);
+    let (variant_label, variant_suffix) = registered_label(variant);
+    let (original_domain, variant_domain) = (original_label.as_str(), variant_label.as_str());
+
+    let visual_score = visual_similarity(original_domain, variant_domain);
+    let cognitive_score = cognitive_similarity(original_domain, variant_domain);
+
+    let score = |algorithm: ScoringAlgorithm| match algorithm {
+        ScoringAlgorithm::VisualWeighted => visual_score * 0.8 + cognitive_score * 0.2,
+        ScoringAlgorithm::CognitiveWeighted => cognitive_score * 0.8 + visual_score * 0.2,
+        ScoringAlgorithm::TypoWeighted => visual_score * 0.6 + cognitive_score * 0.4,
+        ScoringAlgorithm::Balanced => visual_score * 0.5 + cognitive_score * 0.5,
+        // The name is unchanged, so how convincing the swap is depends on the TLD alone
+        ScoringAlgorithm::TldAdjusted => {
+            (visual_score * 0.5 + cognitive_score * 0.5)
+                * (0.5 + 0.5 * tld_similarity(&original_suffix, &variant_suffix))
+        }
+        // A name without the original in it earns no containment credit
+        ScoringAlgorithm::Containment => {
+            containment_score(original_domain, variant_domain).unwrap_or(0.0)
+        }
+    };
+    // Weight scores based on transformation type
+    let mut algorithm = ScoringAlgorithm::for_transformation(transformation);
+    let mut combined_score = score(algorithm);
+    if mode == ScoringMode::Containment {
+        let containment = score(ScoringAlgorithm::Containment);
+        if containment > combined_score {
+            combined_score = containment;
+            algorithm = ScoringAlgorithm::Containment;
+        }
+    }
+
+    SimilarityScore {
+        domain: variant.to_string(),
+        visual_score,
+        cognitive_score,
+        combined_score,
+        transformation: transformation.to_string(),
+        algorithm,
+    }
+}
+
+/// Score a `candidate` domain from anywhere against the brand's `original`
+/// domain, without knowing how it was made
+pub fn score_domain(original: &str, candidate: &str) -> SimilarityScore {
+    calculate_similarity(original, candidate, UNKNOWN_TRANSFORMATION)
+}
+
+/// Every metric DomFuzz has, between two domains; for tuning thresholds and
+/// writing detection rules (`domfuzz compare`). String metrics compare the
+/// names below the suffix; the skeletons and typo likelihood whole domains.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Comparison {
+    pub first: String,
+    pub second: String,
+    pub levenshtein: usize,
+    pub damerau_levenshtein: usize,
+    pub jaro_winkler: f64,
+    pub keyboard_distance: f64,
+    /// Soundex-style codes of the names, and how alike they are
+    pub phonetic_codes: (String, String),
+    pub phonetic: f64,
+    /// What each domain reads as at a glance (UTS #39)
+    pub skeletons: (String, String),
+    pub visual: f64,
+    pub cognitive: f64,
+    /// The combined score `second` gets as a variation of `first` from an
+    /// unknown transformation
+    pub combined: f64,
+    /// Containment score, when the second name contains the first whole
+    pub containment: Option<f64>,
+    pub tld: f64,
+    /// Chance that typing `first` produces `second`
+    pub typo_likelihood: f64,
+}
+
+impl Comparison {
+    /// Whether the two domains read the same at a glance
+    pub fn skeleton_match(&self) -> bool {
+        self.skeletons.0 == self.skeletons.1
+    }
+}
+
+/// Compare `second` to `first` by every metric
+pub fn compare(first: &Domain, second: &Domain) -> Comparison {
+    let (first_name, second_name) = (first.name(), second.name());
+    let (first_domain, second_domain) = (first.to_string(), second.to_string());
+    let score = score_domain(&first_domain, &second_domain);
+    Comparison {
+        levenshtein: levenshtein_distance(&first_name, &second_name),
+        damerau_levenshtein: damerau_levenshtein_distance(&first_name, &second_name),
+        jaro_winkler: jaro_winkler_similarity(&first_name, &second_name),
+        keyboard_distance: keyboard_distance(&first_name, &second_name),
+        phonetic_codes: (simple_soundex(&first_name), simple_soundex(&second_name)),
+        phonetic: phonetic_similarity(&first_name, &second_name),
+        skeletons: (skeleton(&first_domain), skeleton(&second_domain)),
+        visual: score.visual_score,
+        cognitive: score.cognitive_score,
+        combined: score.combined_score,
+        containment: containment_score(&first_name, &second_name),
+        tld: tld_similarity(first.suffix(), second.suffix()),
+        typo_likelihood: typo_likelihood(&first_domain, &second_domain),
+        first: first_domain,
+        second: second_domain,
+    }
+}
+
+/// Parse similarity threshold from string, supporting both decimal (0.0-1.0) and percentage (0%-100%) formats
+pub fn parse_similarity_threshold(input: &str) -> Result<f64, DomFuzzError> {
+    let input = input.trim();
+
+    if input.ends_with('%') {
+        // Parse percentage format (e.g., "73.28%")
+        let percentage_str = input.trim_end_matches('%');
+        match percentage_str.parse::<f64>() {
+            Ok(percentage) => {
+                if !(0.0..=100.0).contains(&percentage) {
+                    Err(DomFuzzError::Parse(format!(
+                        "Percentage must be between 0% and 100%, got: {}%",
+                        percentage
+                    )))
+                } else {
+                    Ok(percentage / 100.0)
+                }
+            }
+            Err(_) => Err(DomFuzzError::Parse(format!(
+                "Invalid percentage format: {}",
+                input
+            ))),
+        }
+    } else {
+        // Parse decimal format (e.g., "0.7328")
+        match input.parse::<f64>() {
+            Ok(decimal) => {
+                if !(0.0..=1.0).contains(&decimal) {
+                    Err(DomFuzzError::Parse(format!(
+                        "Decimal threshold must be between 0.0 and 1.0, got: {}",
+                        decimal
+                    )))
+                } else {
+                    Ok(decimal)
+                }
+            }
+            Err(_) => Err(DomFuzzError::Parse(format!(
+                "Invalid similarity threshold format: {}",
+                input
+            ))),
+        }
+    }
+}
+
+/// Parse a similarity range `MIN..MAX`, each bound in either format accepted by
+/// [`parse_similarity_threshold`]; a bound left out (`..60%`, `0.3..`) is open
+pub fn parse_similarity_range(input: &str) -> Result<(Option<f64>, Option<f64>), DomFuzzError> {
+    let (min, max) = input.split_once("..").ok_or_else(|| {
+        DomFuzzError::Parse(format!(
+            "Invalid similarity range format (expected MIN..MAX): {}",
+            input
+        ))
+    })?;
+    let bound = |bound: &str| {
+        let bound = bound.trim();
+        (!bound.is_empty())
+            .then(|| parse_similarity_threshold(bound))
+            .transpose()
+    };
+    let (min, max) = (bound(min)?, bound(max)?);
+    check_similarity_range(min, max)?;
+    Ok((min, max))
+}
+
+/// Reject a minimum similarity above the maximum, which no score could meet
+pub fn check_similarity_range(min: Option<f64>, max: Option<f64>) -> Result<(), DomFuzzError> {
+    match (min, max) {
+        (Some(min), Some(max)) if min > max => Err(DomFuzzError::Parse(format!(
+            "Minimum similarity {} is above maximum similarity {}",
+            min, max
+        ))),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn subdomains_do_not_stand_in_for_the_registered_label() {
+        let score = score_domain("paypal.com", "login.paypa1.com");
+        assert!(score.combined_score < 1.0, "{:?}", score);
+        assert!(score.combined_score > 0.5, "{:?}", score);
+
+        let score = score_domain("example.com", "example.evil.net");
+        assert!(score.combined_score < 1.0, "{:?}", score);
+    }
+
+    #[test]
+    fn tld_swaps_compare_public_suffixes() {
+        let swapped = |original, variant| {
+            calculate_similarity(original, variant, "tld-variations").combined_score
+        };
+        assert_eq!(
+            swapped("mail.example.com", "mail.example.corn"),
+            swapped("example.com", "example.corn")
+        );
+        assert!(
+            swapped("example.co.uk", "example.co.uk") > swapped("example.co.uk", "example.com")
+        );
+    }
+}
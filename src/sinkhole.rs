@@ -0,0 +1,121 @@
+//! Addresses that show a registered domain is not really in use.
+//!
+//! A squatted domain pointing at `127.0.0.1` or a documentation range has been
+//! taken down or never set up, and needs no analyst's attention. Registered
+//! domains whose addresses are all listed in `data/sinkholes.txt`, or a file in
+//! the same format (see [`AddressRules::parse`]), are reported as
+//! [`SINKHOLED`] or [`PLACEHOLDER`] instead of `registered`.
+
+use std::net::IpAddr;
+
+use ipnet::IpNet;
+
+use crate::error::DomFuzzError;
+
+/// The built-in rules, shipped with DomFuzz
+const BUILTIN_RULES: &str = include_str!("../data/sinkholes.txt");
+
+/// Status of a registered domain resolving only to sinkholes (and placeholders)
+pub const SINKHOLED: &str = "sinkholed";
+/// Status of a registered domain resolving only to placeholder addresses
+pub const PLACEHOLDER: &str = "placeholder";
+
+/// What an address says about the domain resolving to it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddressVerdict {
+    /// Taken down: a null route, loopback, or known sinkhole
+    Sinkholed,
+    /// Never set up: private, reserved, or a registrar's default
+    Placeholder,
+}
+
+impl AddressVerdict {
+    fn parse(verdict: &str) -> Option<Self> {
+        match verdict {
+            SINKHOLED => Some(AddressVerdict::Sinkholed),
+            PLACEHOLDER => Some(AddressVerdict::Placeholder),
+            _ => None,
+        }
+    }
+}
+
+/// One network and the verdict it implies
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AddressRule {
+    pub network: IpNet,
+    pub verdict: AddressVerdict,
+}
+
+/// An ordered set of rules; the first that contains an address decides
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AddressRules {
+    rules: Vec<AddressRule>,
+}
+
+impl AddressRules {
+    /// The rules shipped in `data/sinkholes.txt`
+    pub fn builtin() -> Self {
+        AddressRules::parse(BUILTIN_RULES).expect("built-in sinkhole rules are valid")
+    }
+
+    /// Parse rules, one per line as `VERDICT NETWORK`: `sinkholed` or
+    /// `placeholder`, and an address or CIDR network. Blank lines and lines
+    /// starting with `#` are ignored.
+    pub fn parse(text: &str) -> Result<Self, DomFuzzError> {
+        let mut rules = Vec::new();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid =
+                |reason: String| DomFuzzError::Parse(format!("line {}: {}", number + 1, reason));
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let [verdict, network] = fields[..] else {
+                return Err(invalid("expected VERDICT NETWORK".to_string()));
+            };
+            let verdict = AddressVerdict::parse(verdict).ok_or_else(|| {
+                invalid(format!(
+                    "expected sinkholed or placeholder, got {:?}",
+                    verdict
+                ))
+            })?;
+            let network = network
+                .parse::<IpNet>()
+                .or_else(|_| network.parse::<IpAddr>().map(IpNet::from))
+                .map_err(|_| invalid(format!("invalid address or network {:?}", network)))?;
+            rules.push(AddressRule { network, verdict });
+        }
+        Ok(AddressRules { rules })
+    }
+
+    /// These rules followed by `other`'s, which therefore have lower precedence
+    pub fn then(mut self, other: AddressRules) -> Self {
+        self.rules.extend(other.rules);
+        self
+    }
+
+    /// The verdict of the first rule containing `address`
+    pub fn verdict(&self, address: IpAddr) -> Option<AddressVerdict> {
+        self.rules
+            .iter()
+            .find(|rule| rule.network.contains(&address))
+            .map(|rule| rule.verdict)
+    }
+
+    /// [`SINKHOLED`] or [`PLACEHOLDER`] for a registered domain resolving to
+    /// `addresses`; `None` if there are none or any is not listed
+    pub fn classify(&self, addresses: &[IpAddr]) -> Option<&'static str> {
+        let verdicts = addresses
+            .iter()
+            .map(|&address| self.verdict(address))
+            .collect::<Option<Vec<_>>>()?;
+        if verdicts.is_empty() {
+            None
+        } else if verdicts.contains(&AddressVerdict::Sinkholed) {
+            Some(SINKHOLED)
+        } else {
+            Some(PLACEHOLDER)
+        }
+    }
+}
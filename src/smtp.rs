@@ -0,0 +1,192 @@
+//! SMTP banner probes of the mail servers behind registered variations.
+//!
+//! Many squats publish MX records that point nowhere useful. Connecting to the
+//! preferred MX on port 25 and recording its greeting and EHLO response tells
+//! real mail infrastructure, which can receive misdirected email, apart from
+//! placeholders. The probe never sends a message: it greets, reads, and quits.
+
+use std::time::Duration;
+
+use hickory_resolver::error::ResolveErrorKind;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::time::timeout;
+
+use crate::checker::{network, DNS_RESOLVER};
+use crate::error::DomFuzzError;
+use crate::{metrics, net};
+
+pub const SMTP_PORT: u16 = 25;
+pub const SMTP_CONNECT_TIMEOUT_SECS: u64 = 5;
+/// Time allowed for each server reply
+pub const SMTP_READ_TIMEOUT_SECS: u64 = 10;
+
+/// Name announced in `EHLO`; `.invalid` makes clear it is not a mail host
+const EHLO_NAME: &str = "domfuzz.invalid";
+/// Longest reply line accepted; RFC 5321 allows 512 octets
+const MAX_LINE_BYTES: u64 = 1024;
+/// Most lines read from one multi-line reply
+const MAX_REPLY_LINES: usize = 50;
+
+/// Mail setup of a domain and what its preferred MX said when probed
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MailProbe {
+    /// MX hosts, most preferred first
+    pub mx: Vec<String>,
+    /// The domain publishes a null MX (RFC 7505): it accepts no mail
+    pub null_mx: bool,
+    /// Host that was probed
+    pub host: Option<String>,
+    /// Greeting line, e.g. `220 mx.example.com ESMTP Postfix`
+    pub banner: Option<String>,
+    /// EHLO response lines, i.e. the advertised extensions
+    pub ehlo: Vec<String>,
+}
+
+impl MailProbe {
+    /// Whether the probed server greeted with `220`, i.e. is ready to accept mail
+    pub fn accepts_connections(&self) -> bool {
+        self.banner.as_deref().is_some_and(|b| b.starts_with("220"))
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        let mut json = serde_json::json!({
+            "mx": self.mx,
+            "null_mx": self.null_mx,
+            "accepts_connections": self.accepts_connections(),
+        });
+        if let Some(host) = &self.host {
+            json["host"] = host.clone().into();
+        }
+        if let Some(banner) = &self.banner {
+            json["banner"] = banner.clone().into();
+        }
+        if !self.ehlo.is_empty() {
+            json["ehlo"] = self.ehlo.clone().into();
+        }
+        json
+    }
+}
+
+/// Look up `domain`'s MX records and probe the most preferred host; `None`
+/// if the domain has no MX records
+pub async fn probe_mail(domain: &str) -> Result<Option<MailProbe>, DomFuzzError> {
+    let mx = lookup_mx(domain).await?;
+    if mx.is_empty() {
+        return Ok(None);
+    }
+    let mut probe = MailProbe {
+        null_mx: mx.iter().all(|host| host.is_empty()),
+        mx,
+        ..MailProbe::default()
+    };
+    let Some(host) = probe.mx.iter().find(|host| !host.is_empty()).cloned() else {
+        return Ok(Some(probe));
+    };
+
+    let (banner, ehlo) = greet(&host).await?;
+    probe.host = Some(host);
+    probe.banner = Some(banner);
+    probe.ehlo = ehlo;
+    Ok(Some(probe))
+}
+
+/// `domain`'s MX hosts, most preferred first; empty if it has no MX records,
+/// and a single empty host for a null MX
+pub async fn lookup_mx(domain: &str) -> Result<Vec<String>, DomFuzzError> {
+    let resolver = &*DNS_RESOLVER;
+    metrics::record_request("dns", metrics::RESOLVER);
+    let lookup = match timeout(network().dns_timeout, resolver.mx_lookup(domain)).await {
+        Ok(Ok(lookup)) => lookup,
+        Ok(Err(e)) if matches!(e.kind(), ResolveErrorKind::NoRecordsFound { .. }) => {
+            return Ok(Vec::new())
+        }
+        Ok(Err(e)) => {
+            return Err(DomFuzzError::Dns {
+                domain: domain.to_string(),
+                message: e.to_string(),
+            })
+        }
+        Err(_) => {
+            return Err(DomFuzzError::Timeout {
+                protocol: "DNS",
+                domain: domain.to_string(),
+            })
+        }
+    };
+    let mut records: Vec<_> = lookup.iter().collect();
+    records.sort_by_key(|mx| mx.preference());
+    Ok(records
+        .iter()
+        .map(|mx| mx.exchange().to_string().trim_end_matches('.').to_string())
+        .collect())
+}
+
+/// Connect to `host`, read its banner, send EHLO, and quit
+async fn greet(host: &str) -> Result<(String, Vec<String>), DomFuzzError> {
+    let timed_out = || DomFuzzError::Timeout {
+        protocol: "SMTP",
+        domain: host.to_string(),
+    };
+    metrics::record_request("smtp", metrics::SITES);
+    let stream = timeout(
+        Duration::from_secs(SMTP_CONNECT_TIMEOUT_SECS),
+        net::connect(host, SMTP_PORT),
+    )
+    .await
+    .map_err(|_| timed_out())??;
+    let (reader, mut writer) = stream.into_split();
+    let mut reader = BufReader::new(reader);
+
+    let banner = read_reply(&mut reader, host).await?;
+    let Some(first) = banner.first().cloned() else {
+        return Ok((String::new(), Vec::new()));
+    };
+    if !first.starts_with("220") {
+        return Ok((first, Vec::new()));
+    }
+
+    timeout(
+        Duration::from_secs(SMTP_READ_TIMEOUT_SECS),
+        writer.write_all(format!("EHLO {}\r\n", EHLO_NAME).as_bytes()),
+    )
+    .await
+    .map_err(|_| timed_out())??;
+    let ehlo = read_reply(&mut reader, host).await?;
+    // Best effort: the server may already have hung up
+    let _ = timeout(
+        Duration::from_secs(SMTP_CONNECT_TIMEOUT_SECS),
+        writer.write_all(b"QUIT\r\n"),
+    )
+    .await;
+    Ok((first, ehlo))
+}
+
+/// Read one (possibly multi-line) reply: lines are `NNN-text` until `NNN text`
+async fn read_reply<R>(reader: &mut R, host: &str) -> Result<Vec<String>, DomFuzzError>
+where
+    R: AsyncBufReadExt + Unpin,
+{
+    let mut lines = Vec::new();
+    while lines.len() < MAX_REPLY_LINES {
+        let mut line = String::new();
+        let read = timeout(
+            Duration::from_secs(SMTP_READ_TIMEOUT_SECS),
+            (&mut *reader).take(MAX_LINE_BYTES).read_line(&mut line),
+        )
+        .await
+        .map_err(|_| DomFuzzError::Timeout {
+            protocol: "SMTP",
+            domain: host.to_string(),
+        })??;
+        if read == 0 {
+            break; // Connection closed
+        }
+        let line = line.trim_end().to_string();
+        let last = line.as_bytes().get(3) != Some(&b'-');
+        lines.push(line);
+        if last {
+            break;
+        }
+    }
+    Ok(lines)
+}
@@ -0,0 +1,233 @@
+//! Findings sent to a syslog collector (`--sink syslog`).
+//!
+//! Each finding becomes one RFC 5424 message whose MSG is the finding's output
+//! line (CSV, JSON Lines, CEF, or LEEF), for environments where a syslog
+//! collector is the only way into monitoring. Collectors are reached over UDP
+//! (RFC 5426, one message per datagram) or over TCP and TLS (RFC 6587 and
+//! RFC 5425, with octet-counting framing).
+
+use std::io;
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{SecondsFormat, Utc};
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+use tokio::net::{lookup_host, TcpStream, UdpSocket};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio::time::timeout;
+
+use crate::error::DomFuzzError;
+
+/// Default collector ports per transport
+pub const SYSLOG_UDP_PORT: u16 = 514;
+pub const SYSLOG_TCP_PORT: u16 = 514;
+pub const SYSLOG_TLS_PORT: u16 = 6514;
+/// Time allowed to connect to a TCP or TLS collector, and for each write
+pub const SYSLOG_TIMEOUT_SECS: u64 = 5;
+
+/// PRI of every message: facility `user` (1), severity `notice` (5)
+const PRIORITY: u8 = 8 + 5;
+const APP_NAME: &str = "domfuzz";
+const MSG_ID: &str = "finding";
+
+enum Transport {
+    Udp(UdpSocket),
+    Tcp(TcpStream),
+    Tls(Box<tokio_native_tls::TlsStream<TcpStream>>),
+}
+
+/// Connection to a syslog collector. Records are queued by [`SyslogSender::send`]
+/// and written by a background task, so callers never block on the network.
+pub struct SyslogSender {
+    records: mpsc::UnboundedSender<String>,
+    writer: JoinHandle<io::Result<()>>,
+    /// Why the writer stopped, once it has
+    failure: Arc<Mutex<Option<io::Error>>>,
+    hostname: String,
+}
+
+impl SyslogSender {
+    /// Connect to `server`, given as `udp://HOST[:PORT]`, `tcp://HOST[:PORT]`,
+    /// or `tls://HOST[:PORT]`. TLS collectors must present a certificate valid
+    /// for HOST.
+    pub async fn connect(server: &str) -> Result<Self, DomFuzzError> {
+        let invalid = |message: &str| {
+            DomFuzzError::Config(format!("invalid syslog server {}: {}", server, message))
+        };
+        let url = url::Url::parse(server).map_err(|e| invalid(&e.to_string()))?;
+        let host = url
+            .host_str()
+            .ok_or_else(|| invalid("missing host"))?
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .to_string();
+        let default_port = match url.scheme() {
+            "udp" => SYSLOG_UDP_PORT,
+            "tcp" => SYSLOG_TCP_PORT,
+            "tls" => SYSLOG_TLS_PORT,
+            _ => return Err(invalid("expected udp://, tcp://, or tls://")),
+        };
+        let port = url.port().unwrap_or(default_port);
+        let unreachable = |e: io::Error| {
+            DomFuzzError::Io(io::Error::new(
+                e.kind(),
+                format!("syslog server {}: {}", server, e),
+            ))
+        };
+
+        let timeout_duration = Duration::from_secs(SYSLOG_TIMEOUT_SECS);
+        let transport = if url.scheme() == "udp" {
+            let address = lookup_host((host.as_str(), port))
+                .await
+                .and_then(|mut addresses| {
+                    addresses
+                        .next()
+                        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no addresses"))
+                })
+                .map_err(unreachable)?;
+            let local: SocketAddr = if address.is_ipv4() {
+                (Ipv4Addr::UNSPECIFIED, 0).into()
+            } else {
+                (Ipv6Addr::UNSPECIFIED, 0).into()
+            };
+            let socket = UdpSocket::bind(local).await.map_err(unreachable)?;
+            socket.connect(address).await.map_err(unreachable)?;
+            Transport::Udp(socket)
+        } else {
+            let stream = connect_tcp(&host, port, timeout_duration)
+                .await
+                .map_err(unreachable)?;
+            if url.scheme() == "tls" {
+                let connector = native_tls::TlsConnector::new()
+                    .map_err(|e| unreachable(io::Error::other(e.to_string())))?;
+                let stream = timeout(
+                    timeout_duration,
+                    tokio_native_tls::TlsConnector::from(connector).connect(&host, stream),
+                )
+                .await
+                .map_err(|_| unreachable(io::ErrorKind::TimedOut.into()))?
+                .map_err(|e| unreachable(io::Error::other(e.to_string())))?;
+                Transport::Tls(Box::new(stream))
+            } else {
+                Transport::Tcp(stream)
+            }
+        };
+
+        let (records, receiver) = mpsc::unbounded_channel();
+        let failure = Arc::new(Mutex::new(None));
+        let writer = tokio::spawn(write_records(
+            transport,
+            receiver,
+            timeout_duration,
+            Arc::clone(&failure),
+        ));
+        Ok(SyslogSender {
+            records,
+            writer,
+            failure,
+            hostname: local_hostname(),
+        })
+    }
+
+    /// Queue `message` as the MSG of one syslog record. Fails once an earlier
+    /// record could not be written.
+    pub fn send(&mut self, message: &str) -> io::Result<()> {
+        let record = format!(
+            "<{}>1 {} {} {} {} {} - {}",
+            PRIORITY,
+            Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+            self.hostname,
+            APP_NAME,
+            std::process::id(),
+            MSG_ID,
+            message
+        );
+        self.records.send(record).map_err(|_| {
+            self.failure
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .take()
+                .unwrap_or_else(|| io::Error::other("syslog writer stopped"))
+        })
+    }
+
+    /// Wait for every queued record to be written, then close the connection
+    pub async fn close(self) -> io::Result<()> {
+        drop(self.records);
+        self.writer.await.map_err(io::Error::other)?
+    }
+}
+
+/// Write each of `records` to `transport` until the sender is dropped or a
+/// write fails, keeping the failure in `failure` for [`SyslogSender::send`]
+async fn write_records(
+    mut transport: Transport,
+    mut records: mpsc::UnboundedReceiver<String>,
+    timeout_duration: Duration,
+    failure: Arc<Mutex<Option<io::Error>>>,
+) -> io::Result<()> {
+    while let Some(record) = records.recv().await {
+        let written = match timeout(timeout_duration, transport.write(&record)).await {
+            Ok(written) => written,
+            Err(_) => Err(io::ErrorKind::TimedOut.into()),
+        };
+        if let Err(e) = written {
+            let message = e.to_string();
+            *failure.lock().unwrap_or_else(|e| e.into_inner()) = Some(e);
+            return Err(io::Error::other(message));
+        }
+    }
+    match &mut transport {
+        Transport::Udp(_) => Ok(()),
+        Transport::Tcp(stream) => stream.shutdown().await,
+        Transport::Tls(stream) => stream.shutdown().await,
+    }
+}
+
+impl Transport {
+    /// Send `record`: alone in a datagram over UDP, octet-counted over TCP and TLS
+    async fn write(&mut self, record: &str) -> io::Result<()> {
+        match self {
+            Transport::Udp(socket) => socket.send(record.as_bytes()).await.map(|_| ()),
+            Transport::Tcp(stream) => write_framed(stream, record).await,
+            Transport::Tls(stream) => write_framed(stream.as_mut(), record).await,
+        }
+    }
+}
+
+async fn write_framed(stream: &mut (impl AsyncWrite + Unpin), record: &str) -> io::Result<()> {
+    let framed = format!("{} {}", record.len(), record);
+    stream.write_all(framed.as_bytes()).await?;
+    stream.flush().await
+}
+
+/// Connect to the first of `host`'s addresses that accepts within `timeout_duration`
+async fn connect_tcp(host: &str, port: u16, timeout_duration: Duration) -> io::Result<TcpStream> {
+    let mut last_error = io::Error::new(io::ErrorKind::NotFound, "no addresses");
+    for address in lookup_host((host, port)).await? {
+        match timeout(timeout_duration, TcpStream::connect(address)).await {
+            Ok(Ok(stream)) => return Ok(stream),
+            Ok(Err(e)) => last_error = e,
+            Err(_) => last_error = io::ErrorKind::TimedOut.into(),
+        }
+    }
+    Err(last_error)
+}
+
+/// HOSTNAME field: this machine's name, or `-` (the RFC 5424 nil value) if it
+/// cannot be determined
+fn local_hostname() -> String {
+    let name = std::env::var("HOSTNAME")
+        .or_else(|_| std::env::var("COMPUTERNAME"))
+        .or_else(|_| std::fs::read_to_string("/etc/hostname"))
+        .unwrap_or_default();
+    let name = name.trim();
+    // PRINTUSASCII without spaces, at most 255 octets
+    if name.is_empty() || name.len() > 255 || !name.bytes().all(|b| b.is_ascii_graphic()) {
+        "-".to_string()
+    } else {
+        name.to_string()
+    }
+}
@@ -0,0 +1,174 @@
+//! Target profiles: everything known about one brand, in one file (`--target`).
+//!
+//! Repeated engagements against the same brand keep passing the same domains,
+//! aliases, and thresholds. A profile records them once, in YAML, where they can
+//! be reviewed alongside the findings; the main command and `monitor` read it,
+//! and options given on the command line take precedence over it.
+//!
+//! ```yaml
+//! # The first domain is the one generated from; the others are seeds, as
+//! # --alias names are
+//! domains: [example.com, example.co.uk]
+//! aliases: [exmpl]
+//! # Defensive registrations the brand already holds, never reported
+//! owned: [examp1e.com, example-login.com]
+//! # Joined to the domains by combosquatting, and looked for in certificates
+//! # and pages
+//! keywords: [pay, wallet]
+//! # Tried by tld-variations on top of --tld-set
+//! tlds: [io, app]
+//! thresholds:
+//!   min_similarity: 60%
+//!   max_similarity: 0.98
+//!   # monitor raises alerts only for variations at least this similar
+//!   alert_similarity: 75%
+//! ```
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use serde::{Deserialize, Deserializer};
+
+use crate::domain::Domain;
+use crate::error::DomFuzzError;
+use crate::generators::GeneratorOptions;
+use crate::similarity::{check_similarity_range, parse_similarity_threshold};
+
+/// A parsed target profile
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TargetProfile {
+    /// Primary domains, the first generated from; never empty
+    pub domains: Vec<String>,
+    /// Other names of the brand, as given to `--alias`
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    /// Defensive registrations, in Unicode form once loaded
+    #[serde(default)]
+    pub owned: Vec<String>,
+    #[serde(default)]
+    pub keywords: Vec<String>,
+    /// Priority TLDs, without the dot
+    #[serde(default)]
+    pub tlds: Vec<String>,
+    #[serde(default)]
+    pub thresholds: Thresholds,
+}
+
+/// Similarity thresholds, each given as a decimal or a percentage
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Thresholds {
+    #[serde(default, deserialize_with = "threshold")]
+    pub min_similarity: Option<f64>,
+    #[serde(default, deserialize_with = "threshold")]
+    pub max_similarity: Option<f64>,
+    /// Least similarity of a variation whose changes raise alerts in `monitor`
+    #[serde(default, deserialize_with = "threshold")]
+    pub alert_similarity: Option<f64>,
+}
+
+impl TargetProfile {
+    /// Read and validate the profile at `path`
+    pub fn load(path: &Path) -> Result<TargetProfile, DomFuzzError> {
+        let contents = std::fs::read_to_string(path).map_err(|e| {
+            DomFuzzError::Config(format!("cannot read target {}: {}", path.display(), e))
+        })?;
+        TargetProfile::parse(&contents).map_err(|e| {
+            let message = match e {
+                DomFuzzError::Parse(message) => message,
+                e => e.to_string(),
+            };
+            DomFuzzError::Config(format!("invalid target {}: {}", path.display(), message))
+        })
+    }
+
+    /// Parse and validate a profile: at least one domain, every domain valid,
+    /// and the thresholds in order
+    pub fn parse(text: &str) -> Result<TargetProfile, DomFuzzError> {
+        let mut profile: TargetProfile =
+            serde_norway::from_str(text).map_err(|e| DomFuzzError::Parse(e.to_string()))?;
+        if profile.domains.is_empty() {
+            return Err(DomFuzzError::Parse(
+                "`domains` must list at least one domain".to_string(),
+            ));
+        }
+        for domain in &profile.domains {
+            Domain::parse(domain)?;
+        }
+        profile.owned = profile
+            .owned
+            .iter()
+            .map(|domain| Domain::parse(domain).map(|domain| domain.to_unicode()))
+            .collect::<Result<_, _>>()?;
+        profile.tlds = profile
+            .tlds
+            .iter()
+            .map(|tld| tld.trim().trim_start_matches('.').to_lowercase())
+            .collect();
+        check_similarity_range(
+            profile.thresholds.min_similarity,
+            profile.thresholds.max_similarity,
+        )?;
+        Ok(profile)
+    }
+
+    /// The domain variations are generated from
+    pub fn domain(&self) -> &str {
+        &self.domains[0]
+    }
+
+    /// The other primary domains, then the aliases: names that variations are
+    /// generated from as well
+    pub fn seeds(&self) -> Vec<String> {
+        self.domains[1..]
+            .iter()
+            .chain(&self.aliases)
+            .cloned()
+            .collect()
+    }
+
+    /// Defensive registrations, to leave out of results
+    pub fn owned_domains(&self) -> HashSet<String> {
+        self.owned.iter().cloned().collect()
+    }
+
+    /// Add the keywords to combosquatting's dictionary and the priority TLDs to
+    /// those tld-variations tries, each once
+    pub fn apply(&self, options: &mut GeneratorOptions) {
+        for keyword in &self.keywords {
+            let keyword = keyword.trim().to_lowercase();
+            if !keyword.is_empty() && !options.dict_words.contains(&keyword) {
+                options.dict_words.push(keyword);
+            }
+        }
+        let mut tlds = self.tlds.clone();
+        tlds.extend(
+            std::mem::take(&mut options.tlds)
+                .into_iter()
+                .filter(|tld| !self.tlds.contains(tld)),
+        );
+        options.tlds = tlds;
+    }
+}
+
+/// A threshold written as a YAML number (`0.6`) or string (`60%`)
+fn threshold<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<f64>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Raw {
+        Number(f64),
+        Text(String),
+    }
+    let text = match Option::<Raw>::deserialize(deserializer)? {
+        None => return Ok(None),
+        Some(Raw::Number(number)) => number.to_string(),
+        Some(Raw::Text(text)) => text,
+    };
+    parse_similarity_threshold(&text)
+        .map(Some)
+        .map_err(|e| match e {
+            DomFuzzError::Parse(message) => serde::de::Error::custom(message),
+            e => serde::de::Error::custom(e),
+        })
+}
@@ -0,0 +1,320 @@
+//! Per-endpoint concurrency limits tuned at run time (AIMD).
+//!
+//! Each RDAP or WHOIS endpoint starts at [`INITIAL_CONCURRENCY`] lookups in
+//! flight. Every successful lookup raises the limit by `1/limit`, roughly one
+//! extra slot per round of successes; a rate limit, timeout, or server error
+//! halves it. Large runs thus find the fastest rate an endpoint tolerates
+//! without tripping its defenses.
+//!
+//! Each endpoint also has a circuit breaker. After [`BREAKER_THRESHOLD`]
+//! timeouts in a row it opens, and lookups on the endpoint are turned away at
+//! once, leaving their domains `unknown`, instead of each waiting out its own
+//! timeout. After [`BREAKER_COOLDOWN`] one lookup is let through as a trial: an
+//! answer closes the breaker, another timeout opens it again.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use tokio::sync::Notify;
+
+use crate::checker::network;
+use crate::public;
+
+/// Lookups allowed in flight per endpoint before any feedback
+pub const INITIAL_CONCURRENCY: usize = 4;
+/// Ceiling for any one endpoint; profiles can lower it
+pub const MAX_CONCURRENCY: usize = 64;
+
+/// Minimum time between two decreases of the same endpoint, so a burst of
+/// failures from requests already in flight counts as one congestion signal
+const DECREASE_COOLDOWN: Duration = Duration::from_secs(1);
+
+/// Timeouts in a row after which an endpoint's circuit breaker opens
+pub const BREAKER_THRESHOLD: u32 = 5;
+/// How long an open breaker turns lookups away before letting a trial through
+pub const BREAKER_COOLDOWN: Duration = Duration::from_secs(30);
+
+/// Whether an endpoint's lookups are let through
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreakerState {
+    Closed,
+    /// Lookups are turned away until the cool-down ends
+    Open,
+    /// The cool-down has ended; the next lookup is a trial
+    HalfOpen,
+}
+
+impl BreakerState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BreakerState::Closed => "closed",
+            BreakerState::Open => "open",
+            BreakerState::HalfOpen => "half-open",
+        }
+    }
+}
+
+/// Current and historical concurrency of one endpoint
+#[derive(Debug, Clone, PartialEq)]
+pub struct EndpointConcurrency {
+    /// e.g. `rdap.verisign.com` or `whois:whois.nic.io:43`
+    pub endpoint: String,
+    pub limit: usize,
+    /// Highest limit reached during the run
+    pub peak: usize,
+    pub requests: u64,
+    /// Times the limit was cut after a failure
+    pub decreases: u64,
+    pub breaker: BreakerState,
+    /// Times the breaker opened
+    pub trips: u64,
+    /// Lookups turned away while the breaker was open
+    pub rejected: u64,
+}
+
+/// How a lookup on an endpoint went
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Outcome {
+    Success,
+    Failure,
+    Timeout,
+    /// Not reported; the slot is freed without feedback
+    Abandoned,
+}
+
+/// Result of asking an endpoint for a slot
+enum Slot {
+    Acquired { trial: bool },
+    Full,
+    Open,
+}
+
+struct State {
+    limit: f64,
+    peak: f64,
+    in_flight: usize,
+    requests: u64,
+    decreases: u64,
+    last_decrease: Option<Instant>,
+    consecutive_timeouts: u32,
+    /// When the breaker last opened; `None` while it is closed
+    opened: Option<Instant>,
+    trial_in_flight: bool,
+    trips: u64,
+    rejected: u64,
+}
+
+impl State {
+    fn breaker(&self) -> BreakerState {
+        match self.opened {
+            None => BreakerState::Closed,
+            Some(opened) if opened.elapsed() < BREAKER_COOLDOWN => BreakerState::Open,
+            Some(_) => BreakerState::HalfOpen,
+        }
+    }
+}
+
+struct Limiter {
+    state: Mutex<State>,
+    released: Notify,
+}
+
+impl Limiter {
+    fn new() -> Self {
+        Limiter {
+            state: Mutex::new(State {
+                limit: INITIAL_CONCURRENCY.min(max_concurrency()) as f64,
+                peak: INITIAL_CONCURRENCY.min(max_concurrency()) as f64,
+                in_flight: 0,
+                requests: 0,
+                decreases: 0,
+                last_decrease: None,
+                consecutive_timeouts: 0,
+                opened: None,
+                trial_in_flight: false,
+                trips: 0,
+                rejected: 0,
+            }),
+            released: Notify::new(),
+        }
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, State> {
+        // Plain counters stay usable after a panic elsewhere
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn try_acquire(&self) -> Slot {
+        let mut state = self.state();
+        let trial = match state.breaker() {
+            BreakerState::Closed => false,
+            BreakerState::HalfOpen if !state.trial_in_flight => true,
+            BreakerState::Open | BreakerState::HalfOpen => {
+                state.rejected += 1;
+                return Slot::Open;
+            }
+        };
+        if state.in_flight < state.limit as usize {
+            state.in_flight += 1;
+            state.requests += 1;
+            state.trial_in_flight |= trial;
+            Slot::Acquired { trial }
+        } else {
+            Slot::Full
+        }
+    }
+
+    fn release(&self, outcome: Outcome, trial: bool) {
+        {
+            let mut state = self.state();
+            state.in_flight -= 1;
+            if trial {
+                state.trial_in_flight = false;
+            }
+            match outcome {
+                Outcome::Success => {
+                    state.limit = (state.limit + 1.0 / state.limit).min(max_concurrency() as f64);
+                    state.peak = state.peak.max(state.limit);
+                }
+                Outcome::Failure | Outcome::Timeout => {
+                    let now = Instant::now();
+                    if state
+                        .last_decrease
+                        .is_none_or(|last| now.duration_since(last) >= DECREASE_COOLDOWN)
+                    {
+                        state.limit = (state.limit / 2.0).max(1.0);
+                        state.decreases += 1;
+                        state.last_decrease = Some(now);
+                    }
+                }
+                Outcome::Abandoned => {}
+            }
+            match outcome {
+                // Any answer shows the endpoint is reachable
+                Outcome::Success | Outcome::Failure => {
+                    state.consecutive_timeouts = 0;
+                    state.opened = None;
+                }
+                Outcome::Timeout => {
+                    state.consecutive_timeouts += 1;
+                    // Lookups already in flight when the breaker opened do not
+                    // extend the cool-down; a failed trial does
+                    let reopen = trial
+                        || (state.opened.is_none()
+                            && state.consecutive_timeouts >= BREAKER_THRESHOLD);
+                    if reopen {
+                        state.opened = Some(Instant::now());
+                        state.trips += 1;
+                    }
+                }
+                Outcome::Abandoned => {}
+            }
+        }
+        self.released.notify_waiters();
+    }
+}
+
+/// The configured per-endpoint ceiling, at most [`MAX_CONCURRENCY`]
+fn max_concurrency() -> usize {
+    network().max_concurrency.min(MAX_CONCURRENCY)
+}
+
+lazy_static::lazy_static! {
+    static ref LIMITERS: Mutex<HashMap<String, Arc<Limiter>>> = Mutex::new(HashMap::new());
+}
+
+/// A lookup slot on one endpoint; report how the lookup went with [`Permit::finish`]
+/// or [`Permit::time_out`]. Dropping it unreported frees the slot without
+/// adjusting the limit.
+pub struct Permit {
+    limiter: Arc<Limiter>,
+    /// Let through a half-open breaker
+    trial: bool,
+    finished: bool,
+}
+
+impl Permit {
+    /// Free the slot, raising the endpoint's limit after a success and cutting it
+    /// after a failure that suggests overload (rate limit, server error)
+    pub fn finish(mut self, success: bool) {
+        self.finished = true;
+        let outcome = if success {
+            Outcome::Success
+        } else {
+            Outcome::Failure
+        };
+        self.limiter.release(outcome, self.trial);
+    }
+
+    /// Free the slot after the lookup timed out, cutting the endpoint's limit and
+    /// counting towards opening its circuit breaker
+    pub fn time_out(mut self) {
+        self.finished = true;
+        self.limiter.release(Outcome::Timeout, self.trial);
+    }
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        if !self.finished {
+            self.limiter.release(Outcome::Abandoned, self.trial);
+        }
+    }
+}
+
+/// Wait for a free slot on `endpoint`, then for its turn in public mode; `None`,
+/// at once, while its circuit breaker is open
+pub async fn acquire(endpoint: &str) -> Option<Permit> {
+    let limiter = {
+        let mut limiters = LIMITERS.lock().unwrap_or_else(|e| e.into_inner());
+        limiters
+            .entry(endpoint.to_string())
+            .or_insert_with(|| Arc::new(Limiter::new()))
+            .clone()
+    };
+    let trial = loop {
+        // Register for wake-ups before checking, so a release in between is not missed
+        let released = limiter.released.notified();
+        tokio::pin!(released);
+        released.as_mut().enable();
+        match limiter.try_acquire() {
+            Slot::Acquired { trial } => break trial,
+            Slot::Open => return None,
+            Slot::Full => released.await,
+        }
+    };
+    public::pace(endpoint).await;
+    Some(Permit {
+        limiter,
+        trial,
+        finished: false,
+    })
+}
+
+/// Concurrency of every endpoint used so far, busiest first
+pub fn snapshot() -> Vec<EndpointConcurrency> {
+    let limiters = LIMITERS.lock().unwrap_or_else(|e| e.into_inner());
+    let mut endpoints: Vec<EndpointConcurrency> = limiters
+        .iter()
+        .map(|(endpoint, limiter)| {
+            let state = limiter.state();
+            EndpointConcurrency {
+                endpoint: endpoint.clone(),
+                limit: state.limit as usize,
+                peak: state.peak as usize,
+                requests: state.requests,
+                decreases: state.decreases,
+                breaker: state.breaker(),
+                trips: state.trips,
+                rejected: state.rejected,
+            }
+        })
+        .collect();
+    endpoints.sort_by(|a, b| {
+        b.requests
+            .cmp(&a.requests)
+            .then_with(|| a.endpoint.cmp(&b.endpoint))
+    });
+    endpoints
+}
@@ -0,0 +1,177 @@
+//! TLS certificates served by registered variations.
+//!
+//! A squat that obtains a certificate is usually about to serve content, so
+//! monitor mode records which certificate each variation presents on port 443.
+//! Certificates are fetched without validation: self-signed and mismatched
+//! certificates are exactly the ones worth seeing. Only the few fields worth
+//! tracking (issuer and DNS subject alternative names) are read, with
+//! `x509-cert`; nothing is verified.
+
+use std::time::Duration;
+
+use ring::digest::{digest, SHA256};
+use tokio::time::timeout;
+use x509_cert::der::oid::db::rfc4519;
+use x509_cert::der::oid::ObjectIdentifier;
+use x509_cert::der::Decode;
+use x509_cert::ext::pkix::name::GeneralName;
+use x509_cert::ext::pkix::SubjectAltName;
+use x509_cert::name::Name;
+use x509_cert::Certificate as X509Certificate;
+
+use crate::error::DomFuzzError;
+use crate::{metrics, net};
+
+pub const HTTPS_PORT: u16 = 443;
+/// Time allowed to connect and complete the handshake
+pub const TLS_TIMEOUT_SECS: u64 = 10;
+
+/// Name attributes rendered in the issuer, in order
+const NAME_ATTRIBUTES: [(ObjectIdentifier, &str); 4] = [
+    (rfc4519::CN, "CN"),
+    (rfc4519::OU, "OU"),
+    (rfc4519::O, "O"),
+    (rfc4519::C, "C"),
+];
+
+/// Leaf certificate presented by a host
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Certificate {
+    /// SHA-256 of the DER encoding, lowercase hex
+    pub sha256: String,
+    /// Issuer's common name, organizational unit, organization, and country,
+    /// as `CN=R11, O=Let's Encrypt, C=US`; `None` if the certificate could not
+    /// be read
+    pub issuer: Option<String>,
+    /// DNS subject alternative names, lowercase and sorted
+    pub sans: Vec<String>,
+}
+
+impl Certificate {
+    pub fn from_der(der: &[u8]) -> Self {
+        let sha256 = digest(&SHA256, der)
+            .as_ref()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+        let (issuer, sans) = parse_tbs(der).unwrap_or_default();
+        Certificate {
+            sha256,
+            issuer,
+            sans,
+        }
+    }
+}
+
+/// The issuer and DNS SANs of a DER certificate, or `None` if it is malformed
+fn parse_tbs(der: &[u8]) -> Option<(Option<String>, Vec<String>)> {
+    let certificate = X509Certificate::from_der(der).ok()?;
+    let tbs = &certificate.tbs_certificate;
+    let issuer = render_name(&tbs.issuer);
+    let mut sans: Vec<String> = match tbs.get::<SubjectAltName>() {
+        Ok(Some((_, names))) => names
+            .0
+            .iter()
+            .filter_map(|name| match name {
+                GeneralName::DnsName(dns_name) => Some(dns_name.as_str().to_lowercase()),
+                _ => None,
+            })
+            .collect(),
+        Ok(None) => Vec::new(),
+        Err(_) => return None,
+    };
+    sans.sort();
+    sans.dedup();
+    Some((issuer, sans))
+}
+
+/// `CN=..., OU=..., O=..., C=...` from the attributes of an X.509 Name
+fn render_name(name: &Name) -> Option<String> {
+    let mut attributes = Vec::new();
+    for attribute in name.0.iter().flat_map(|rdn| rdn.0.iter()) {
+        if let Some((order, (_, label))) = NAME_ATTRIBUTES
+            .iter()
+            .enumerate()
+            .find(|(_, (oid, _))| *oid == attribute.oid)
+        {
+            let value = String::from_utf8_lossy(attribute.value.value()).into_owned();
+            attributes.push((order, *label, value));
+        }
+    }
+    attributes.sort_by_key(|(order, _, _)| *order);
+    let rendered: Vec<String> = attributes
+        .into_iter()
+        .map(|(_, label, value)| format!("{}={}", label, value))
+        .collect();
+    (!rendered.is_empty()).then(|| rendered.join(", "))
+}
+
+/// Complete a TLS handshake with `domain` (as SNI) and return its leaf
+/// certificate; `None` if the handshake succeeded without one
+pub async fn fetch_certificate(domain: &str) -> Result<Option<Certificate>, DomFuzzError> {
+    let failed = |message: String| DomFuzzError::Http {
+        url: format!("https://{}/", domain),
+        message,
+    };
+    let connector = native_tls::TlsConnector::builder()
+        .danger_accept_invalid_certs(true)
+        .danger_accept_invalid_hostnames(true)
+        .build()
+        .map_err(|e| failed(e.to_string()))?;
+    let connector = tokio_native_tls::TlsConnector::from(connector);
+    let handshake = async {
+        metrics::record_request("tls", metrics::SITES);
+        let stream = net::connect(domain, HTTPS_PORT).await?;
+        connector
+            .connect(domain, stream)
+            .await
+            .map_err(|e| failed(e.to_string()))
+    };
+    let stream = timeout(Duration::from_secs(TLS_TIMEOUT_SECS), handshake)
+        .await
+        .map_err(|_| DomFuzzError::Timeout {
+            protocol: "TLS",
+            domain: domain.to_string(),
+        })??;
+    let certificate = stream
+        .get_ref()
+        .peer_certificate()
+        .map_err(|e| failed(e.to_string()))?;
+    certificate
+        .map(|certificate| certificate.to_der().map(|der| Certificate::from_der(&der)))
+        .transpose()
+        .map_err(|e| failed(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Self-signed, issued by `C=US, O=Example Trust, CN=Example Issuing CA`,
+    /// for `WWW.Examp1e.com`, `examp1e.com` twice, and an IP address
+    const CERTIFICATE: &[u8] = include_bytes!("../tests/fixtures/certificate.der");
+
+    #[test]
+    fn reads_issuer_and_dns_sans() {
+        let certificate = Certificate::from_der(CERTIFICATE);
+        assert_eq!(
+            certificate.issuer.as_deref(),
+            Some("CN=Example Issuing CA, O=Example Trust, C=US")
+        );
+        assert_eq!(certificate.sans, ["examp1e.com", "www.examp1e.com"]);
+        assert_eq!(certificate.sha256.len(), 64);
+    }
+
+    #[test]
+    fn malformed_der_keeps_only_the_fingerprint() {
+        let truncated = &CERTIFICATE[..CERTIFICATE.len() / 2];
+        let mut corrupt_length = CERTIFICATE.to_vec();
+        corrupt_length[1] = 0x84;
+        for der in [&[][..], &[0x30, 0x82, 0xff][..], truncated, &corrupt_length] {
+            let certificate = Certificate::from_der(der);
+            assert_eq!(certificate.issuer, None);
+            assert!(certificate.sans.is_empty());
+            assert_eq!(certificate.sha256.len(), 64);
+        }
+    }
+}
@@ -0,0 +1,311 @@
+//! Whole-name transliteration between Latin, Cyrillic, and Greek.
+//!
+//! Homoglyphs swap a letter for one that looks the same; transliteration
+//! respells the whole name as it sounds, the way a reader of another alphabet
+//! would write the brand: `yandex` is `яндекс` in Cyrillic and `γιαντεξ` in
+//! Greek, and `яндекс` is `yandex` in Latin. Spellings are matched longest
+//! first (`sh` before `s`), and where a sound has several usual spellings the
+//! first is taken, with the others giving further variations.
+
+use std::collections::HashSet;
+
+/// Most spellings produced per target script
+pub const MAX_TRANSLITERATIONS: usize = 16;
+
+/// Alphabets a name is transliterated between
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Alphabet {
+    Latin,
+    Cyrillic,
+    Greek,
+}
+
+/// Spellings in one alphabet and how each is written in another, usual first
+type Table = &'static [(&'static str, &'static [&'static str])];
+
+/// Latin spellings and the Cyrillic letters they sound like, usual first
+const LATIN_TO_CYRILLIC: Table = &[
+    ("shch", &["щ"]),
+    ("sch", &["ш", "сх"]),
+    ("sh", &["ш"]),
+    ("ch", &["ч", "х"]),
+    ("zh", &["ж"]),
+    ("kh", &["х"]),
+    ("ts", &["ц", "тс"]),
+    ("ya", &["я"]),
+    ("yu", &["ю"]),
+    ("yo", &["ё", "йо"]),
+    ("ye", &["е", "йе"]),
+    ("ph", &["ф"]),
+    ("th", &["т", "з"]),
+    ("ck", &["к"]),
+    ("qu", &["кв"]),
+    ("ee", &["и"]),
+    ("oo", &["у"]),
+    ("a", &["а"]),
+    ("b", &["б"]),
+    ("c", &["к", "с", "ц"]),
+    ("d", &["д"]),
+    ("e", &["е", "э"]),
+    ("f", &["ф"]),
+    ("g", &["г", "дж"]),
+    ("h", &["х", "г"]),
+    ("i", &["и", "ай"]),
+    ("j", &["дж", "й", "ж"]),
+    ("k", &["к"]),
+    ("l", &["л"]),
+    ("m", &["м"]),
+    ("n", &["н"]),
+    ("o", &["о"]),
+    ("p", &["п"]),
+    ("q", &["к"]),
+    ("r", &["р"]),
+    ("s", &["с", "з"]),
+    ("t", &["т"]),
+    ("u", &["у", "ю"]),
+    ("v", &["в"]),
+    ("w", &["в", "у"]),
+    ("x", &["кс"]),
+    ("y", &["й", "и", "ы"]),
+    ("z", &["з"]),
+];
+
+/// Latin spellings and the Greek letters they sound like, usual first; a final
+/// `σ` is written `ς`
+const LATIN_TO_GREEK: Table = &[
+    ("th", &["θ"]),
+    ("ph", &["φ"]),
+    ("ch", &["χ", "τσ"]),
+    ("ps", &["ψ"]),
+    ("ks", &["ξ"]),
+    ("sh", &["σ"]),
+    ("oo", &["ου"]),
+    ("ou", &["ου"]),
+    ("ee", &["ι"]),
+    ("ya", &["για"]),
+    ("nd", &["ντ"]),
+    ("nt", &["ντ"]),
+    ("mb", &["μπ"]),
+    ("a", &["α"]),
+    ("b", &["μπ", "β"]),
+    ("c", &["κ", "σ"]),
+    ("d", &["ντ", "δ"]),
+    ("e", &["ε", "αι"]),
+    ("f", &["φ"]),
+    ("g", &["γκ", "γ"]),
+    ("h", &["χ"]),
+    ("i", &["ι", "η"]),
+    ("j", &["τζ"]),
+    ("k", &["κ"]),
+    ("l", &["λ"]),
+    ("m", &["μ"]),
+    ("n", &["ν"]),
+    ("o", &["ο", "ω"]),
+    ("p", &["π"]),
+    ("q", &["κ"]),
+    ("r", &["ρ"]),
+    ("s", &["σ"]),
+    ("t", &["τ"]),
+    ("u", &["ου", "υ"]),
+    ("v", &["β"]),
+    ("w", &["ου"]),
+    ("x", &["ξ"]),
+    ("y", &["ι", "υ"]),
+    ("z", &["ζ"]),
+];
+
+/// Cyrillic letters and how they are written in Latin, usual first
+const CYRILLIC_TO_LATIN: Table = &[
+    ("кс", &["x", "ks"]),
+    ("а", &["a"]),
+    ("б", &["b"]),
+    ("в", &["v", "w"]),
+    ("г", &["g"]),
+    ("д", &["d"]),
+    ("е", &["e", "ye"]),
+    ("ё", &["yo", "e"]),
+    ("ж", &["zh", "j"]),
+    ("з", &["z"]),
+    ("и", &["i"]),
+    ("й", &["y", "i"]),
+    ("к", &["k"]),
+    ("л", &["l"]),
+    ("м", &["m"]),
+    ("н", &["n"]),
+    ("о", &["o"]),
+    ("п", &["p"]),
+    ("р", &["r"]),
+    ("с", &["s"]),
+    ("т", &["t"]),
+    ("у", &["u"]),
+    ("ф", &["f"]),
+    ("х", &["kh", "h"]),
+    ("ц", &["ts", "c"]),
+    ("ч", &["ch"]),
+    ("ш", &["sh"]),
+    ("щ", &["shch", "sch"]),
+    ("ъ", &[""]),
+    ("ы", &["y"]),
+    ("ь", &[""]),
+    ("э", &["e"]),
+    ("ю", &["yu", "u"]),
+    ("я", &["ya"]),
+    // Ukrainian, Belarusian, Serbian, and Macedonian letters
+    ("є", &["ye"]),
+    ("і", &["i"]),
+    ("ї", &["yi"]),
+    ("ґ", &["g"]),
+    ("ў", &["u"]),
+    ("ј", &["j"]),
+    ("љ", &["lj"]),
+    ("њ", &["nj"]),
+    ("ћ", &["c"]),
+    ("ђ", &["dj"]),
+    ("џ", &["dz"]),
+];
+
+/// Greek letters and how they are written in Latin, usual first
+const GREEK_TO_LATIN: Table = &[
+    ("ου", &["ou", "u"]),
+    ("μπ", &["b", "mp"]),
+    ("ντ", &["d", "nt"]),
+    ("γκ", &["g", "gk"]),
+    ("α", &["a"]),
+    ("ά", &["a"]),
+    ("β", &["v", "b"]),
+    ("γ", &["g"]),
+    ("δ", &["d"]),
+    ("ε", &["e"]),
+    ("έ", &["e"]),
+    ("ζ", &["z"]),
+    ("η", &["i", "e"]),
+    ("ή", &["i", "e"]),
+    ("θ", &["th"]),
+    ("ι", &["i"]),
+    ("ί", &["i"]),
+    ("ϊ", &["i"]),
+    ("κ", &["k"]),
+    ("λ", &["l"]),
+    ("μ", &["m"]),
+    ("ν", &["n"]),
+    ("ξ", &["x", "ks"]),
+    ("ο", &["o"]),
+    ("ό", &["o"]),
+    ("π", &["p"]),
+    ("ρ", &["r"]),
+    ("σ", &["s"]),
+    ("ς", &["s"]),
+    ("τ", &["t"]),
+    ("υ", &["y", "u"]),
+    ("ύ", &["y", "u"]),
+    ("φ", &["f", "ph"]),
+    ("χ", &["ch", "h"]),
+    ("ψ", &["ps"]),
+    ("ω", &["o"]),
+    ("ώ", &["o"]),
+];
+
+/// The alphabet `name`'s letters are written in; `None` for a name without
+/// letters or with letters of several alphabets or of another
+pub fn alphabet_of(name: &str) -> Option<Alphabet> {
+    let mut alphabet = None;
+    for c in name.chars().filter(|c| c.is_alphabetic()) {
+        let this = match c {
+            'a'..='z' | 'A'..='Z' => Alphabet::Latin,
+            '\u{0400}'..='\u{04FF}' => Alphabet::Cyrillic,
+            '\u{0370}'..='\u{03FF}' | '\u{1F00}'..='\u{1FFF}' => Alphabet::Greek,
+            _ => return None,
+        };
+        if alphabet.is_some_and(|alphabet| alphabet != this) {
+            return None;
+        }
+        alphabet = Some(this);
+    }
+    alphabet
+}
+
+/// `name` respelled in every other alphabet: Latin names in Cyrillic and Greek,
+/// Cyrillic and Greek names in Latin. Characters that are not letters, such as
+/// digits, hyphens, and dots, are kept. The usual spelling comes first, then
+/// spellings differing from it in one choice, at most
+/// [`MAX_TRANSLITERATIONS`] per alphabet.
+pub fn transliterations(name: &str) -> Vec<String> {
+    let name = name.to_lowercase();
+    // Each table, and whether it spells Greek
+    let tables: &[(Table, bool)] = match alphabet_of(&name) {
+        Some(Alphabet::Latin) => &[(LATIN_TO_CYRILLIC, false), (LATIN_TO_GREEK, true)],
+        Some(Alphabet::Cyrillic) => &[(CYRILLIC_TO_LATIN, false)],
+        Some(Alphabet::Greek) => &[(GREEK_TO_LATIN, false)],
+        None => &[],
+    };
+    let mut seen = HashSet::new();
+    let mut results = Vec::new();
+    for &(table, greek) in tables {
+        for spelling in spellings(&name, table) {
+            let spelling = if greek {
+                final_sigma(&spelling)
+            } else {
+                spelling
+            };
+            if spelling != name && seen.insert(spelling.clone()) {
+                results.push(spelling);
+            }
+        }
+    }
+    results
+}
+
+/// The usual spelling of `name` under `table`, then those differing from it in
+/// one choice
+fn spellings(name: &str, table: Table) -> Vec<String> {
+    // Each piece of the name with its spellings, usual first
+    let mut pieces: Vec<Vec<&str>> = Vec::new();
+    let mut rest = name;
+    while let Some(c) = rest.chars().next() {
+        let longest = table
+            .iter()
+            .filter(|(from, _)| rest.starts_with(from))
+            .max_by_key(|(from, _)| from.len());
+        match longest {
+            Some((from, to)) => {
+                pieces.push(to.to_vec());
+                rest = &rest[from.len()..];
+            }
+            None => {
+                pieces.push(vec![&rest[..c.len_utf8()]]);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+
+    let usual: Vec<&str> = pieces.iter().map(|spellings| spellings[0]).collect();
+    let mut spellings = vec![usual.concat()];
+    for (i, alternatives) in pieces.iter().enumerate() {
+        for alternative in &alternatives[1..] {
+            if spellings.len() >= MAX_TRANSLITERATIONS {
+                return spellings;
+            }
+            let mut spelling = usual.clone();
+            spelling[i] = alternative;
+            spellings.push(spelling.concat());
+        }
+    }
+    spellings
+}
+
+/// `σ` at the end of each word written `ς`, as Greek spells it
+fn final_sigma(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    chars
+        .iter()
+        .enumerate()
+        .map(|(i, &c)| {
+            let at_end = chars.get(i + 1).is_none_or(|next| !next.is_alphabetic());
+            if c == 'σ' && at_end {
+                'ς'
+            } else {
+                c
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,222 @@
+//! How likely a person typing a domain is to produce a given variation.
+//!
+//! A noisy-channel model of typing: each key of the intended domain is typed
+//! correctly or slips into one of the errors keystroke studies see most often,
+//! namely hitting a neighbouring key, dropping a key (most often one of a
+//! doubled letter), adding a key (most often a repeat or a neighbour), or
+//! swapping two keys. [`typo_likelihood`] is the probability of the likeliest
+//! run of slips that turns the domain into the variation, so of N visits typed
+//! by hand, about N times that reach the variation.
+//!
+//! The per-keystroke rates are rounded from published statistics rather than
+//! fitted here: about 1% of keystrokes end up as uncorrected errors (Dhakal et
+//! al., "Observations on Typing from 136 Million Keystrokes", CHI 2018), split
+//! between error types in the order reported for spelling-error corpora
+//! (Damerau 1964; Kukich 1992), with most substitutions on adjacent keys and,
+//! among those, slips of the same finger and the same hand the likeliest.
+//! Characters not on a US QWERTY keyboard, such as homoglyphs, are never typed
+//! by mistake and give a likelihood of 0.
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// Probability, per keystroke, of each kind of slip
+const ADJACENT_SUBSTITUTION: f64 = 0.003;
+const OTHER_SUBSTITUTION: f64 = 0.0007;
+const OMISSION: f64 = 0.0025;
+/// Dropping one of a doubled letter (`bokeeper`) is several times likelier
+const DOUBLED_OMISSION: f64 = 0.01;
+pub(crate) const REPEAT_INSERTION: f64 = 0.001;
+const ADJACENT_INSERTION: f64 = 0.001;
+const OTHER_INSERTION: f64 = 0.0002;
+const TRANSPOSITION: f64 = 0.0015;
+/// Probability of typing a key as intended
+const CORRECT: f64 = 1.0
+    - (ADJACENT_SUBSTITUTION
+        + OTHER_SUBSTITUTION
+        + OMISSION
+        + REPEAT_INSERTION
+        + ADJACENT_INSERTION
+        + OTHER_INSERTION
+        + TRANSPOSITION);
+
+/// Unshifted US QWERTY rows used in domains, with each row's offset from the
+/// left in key widths
+const ROWS: [(&str, f64); 4] = [
+    ("1234567890-", 0.0),
+    ("qwertyuiop", 0.5),
+    ("asdfghjkl;", 0.75),
+    ("zxcvbnm,./", 1.25),
+];
+/// Keys whose centres are at most this far apart (in key widths) are
+/// neighbours: the keys beside and diagonally above and below
+const NEIGHBOUR_DISTANCE: f64 = 1.2;
+/// Keys struck by each finger in touch typing, left pinky to right pinky; the
+/// first four are the left hand's
+const FINGERS: [&str; 8] = [
+    "1qaz", "2wsx", "3edc", "45rtfgvb", "67yuhjnm", "8ik,", "9ol.", "0-p;/",
+];
+/// How much likelier a slip onto a neighbour is when the same finger (a row up
+/// or down) or the same hand strikes it than when the other hand does
+const SAME_FINGER_WEIGHT: f64 = 3.0;
+const SAME_HAND_WEIGHT: f64 = 2.0;
+
+struct Key {
+    /// Neighbouring keys with the share of slips onto this key's neighbours
+    /// that land on each; the shares sum to 1
+    neighbours: Vec<(char, f64)>,
+}
+
+/// Index into [`FINGERS`] of the finger that strikes `key`
+fn finger(key: char) -> Option<usize> {
+    FINGERS.iter().position(|keys| keys.contains(key))
+}
+
+/// Relative likelihood of slipping from `key` onto its neighbour `other`
+fn slip_weight(key: char, other: char) -> f64 {
+    match (finger(key), finger(other)) {
+        (Some(a), Some(b)) if a == b => SAME_FINGER_WEIGHT,
+        (Some(a), Some(b)) if (a < 4) == (b < 4) => SAME_HAND_WEIGHT,
+        _ => 1.0,
+    }
+}
+
+fn keyboard() -> &'static HashMap<char, Key> {
+    static KEYBOARD: OnceLock<HashMap<char, Key>> = OnceLock::new();
+    KEYBOARD.get_or_init(|| {
+        let positions: Vec<(char, f64, f64)> = ROWS
+            .iter()
+            .enumerate()
+            .flat_map(|(row, (keys, offset))| {
+                keys.chars()
+                    .enumerate()
+                    .map(move |(column, key)| (key, offset + column as f64, row as f64))
+            })
+            .collect();
+        positions
+            .iter()
+            .map(|&(key, x, y)| {
+                let neighbours: Vec<(char, f64)> = positions
+                    .iter()
+                    .filter(|&&(other, other_x, other_y)| {
+                        other != key && (x - other_x).hypot(y - other_y) <= NEIGHBOUR_DISTANCE
+                    })
+                    .map(|&(other, _, _)| (other, slip_weight(key, other)))
+                    .collect();
+                let total: f64 = neighbours.iter().map(|(_, weight)| weight).sum();
+                let neighbours = neighbours
+                    .into_iter()
+                    .map(|(other, weight)| (other, weight / total))
+                    .collect();
+                (key, Key { neighbours })
+            })
+            .collect()
+    })
+}
+
+/// Keys next to `key`, empty if it is not on the keyboard
+pub(crate) fn neighbours(key: char) -> impl Iterator<Item = char> {
+    keyboard()
+        .get(&key)
+        .into_iter()
+        .flat_map(|key| key.neighbours.iter().map(|&(other, _)| other))
+}
+
+/// Share of slips from `key` onto its neighbours that land on `other`; 0 if
+/// they are not neighbours
+fn neighbour_share(key: char, other: char) -> f64 {
+    keyboard().get(&key).map_or(0.0, |key| {
+        key.neighbours
+            .iter()
+            .find(|&&(neighbour, _)| neighbour == other)
+            .map_or(0.0, |&(_, share)| share)
+    })
+}
+
+/// Probability that intending `intended` types `typed` instead
+pub(crate) fn substitution(intended: char, typed: char) -> f64 {
+    let keyboard = keyboard();
+    let (Some(key), true) = (keyboard.get(&intended), keyboard.contains_key(&typed)) else {
+        return 0.0;
+    };
+    match neighbour_share(intended, typed) {
+        share if share > 0.0 => ADJACENT_SUBSTITUTION * share,
+        _ => OTHER_SUBSTITUTION / (keyboard.len() - 1 - key.neighbours.len()) as f64,
+    }
+}
+
+/// Probability that an extra `typed` slips in between the intended keys
+/// `previous` and `next`
+pub(crate) fn insertion(previous: Option<char>, next: Option<char>, typed: char) -> f64 {
+    let keyboard = keyboard();
+    if !keyboard.contains_key(&typed) {
+        return 0.0;
+    }
+    if previous == Some(typed) {
+        return REPEAT_INSERTION;
+    }
+    let share = [previous, next]
+        .into_iter()
+        .flatten()
+        .map(|key| neighbour_share(key, typed))
+        .fold(0.0, f64::max);
+    if share > 0.0 {
+        ADJACENT_INSERTION * share
+    } else {
+        OTHER_INSERTION / keyboard.len() as f64
+    }
+}
+
+/// Probability that typing `original` produces `variant`, following the
+/// likeliest sequence of slips; both are compared lowercased, whole domains
+/// including the TLD
+pub fn typo_likelihood(original: &str, variant: &str) -> f64 {
+    let normalize = |domain: &str| -> Vec<char> {
+        domain
+            .trim()
+            .trim_end_matches('.')
+            .to_lowercase()
+            .chars()
+            .collect()
+    };
+    let (intended, typed) = (normalize(original), normalize(variant));
+    let (n, m) = (intended.len(), typed.len());
+
+    // best[i][j]: log-probability of typing intended[..i] as typed[..j]
+    let mut best = vec![vec![f64::NEG_INFINITY; m + 1]; n + 1];
+    best[0][0] = 0.0;
+    for i in 0..=n {
+        for j in 0..=m {
+            let mut score = best[i][j];
+            if i > 0 {
+                let key = intended[i - 1];
+                let doubled = (i >= 2 && intended[i - 2] == key) || intended.get(i) == Some(&key);
+                let omission = if doubled { DOUBLED_OMISSION } else { OMISSION };
+                score = score.max(best[i - 1][j] + omission.ln());
+            }
+            if j > 0 {
+                let previous = i.checked_sub(1).map(|k| intended[k]);
+                let extra = insertion(previous, intended.get(i).copied(), typed[j - 1]);
+                score = score.max(best[i][j - 1] + extra.ln());
+            }
+            if i > 0 && j > 0 {
+                let step = if intended[i - 1] == typed[j - 1] {
+                    CORRECT
+                } else {
+                    substitution(intended[i - 1], typed[j - 1])
+                };
+                score = score.max(best[i - 1][j - 1] + step.ln());
+            }
+            if i >= 2
+                && j >= 2
+                && intended[i - 2] != intended[i - 1]
+                && intended[i - 2] == typed[j - 1]
+                && intended[i - 1] == typed[j - 2]
+            {
+                score = score.max(best[i - 2][j - 2] + TRANSPOSITION.ln() + CORRECT.ln());
+            }
+            best[i][j] = score;
+        }
+    }
+    best[n][m].exp()
+}
@@ -0,0 +1,153 @@
+//! Result artifacts uploaded to S3-compatible object storage (`--upload`).
+//!
+//! Scheduled runs in containers often have no shared filesystem, so the result
+//! file and screenshots can be PUT under an `s3://BUCKET/PREFIX/` location when
+//! the run completes. Uploads go through the AWS SDK, which signs them and
+//! finds credentials and the region as the AWS CLI does; `AWS_ENDPOINT_URL`
+//! points them at another S3-compatible store, such as MinIO, with path-style
+//! addressing.
+
+use std::path::Path;
+use std::time::Duration;
+
+use aws_config::meta::region::RegionProviderChain;
+use aws_config::timeout::TimeoutConfig;
+use aws_config::BehaviorVersion;
+use aws_sdk_s3::config::ProvideCredentials;
+use aws_sdk_s3::primitives::ByteStream;
+use aws_sdk_s3::Client;
+
+use crate::error::DomFuzzError;
+use crate::metrics;
+
+/// Time allowed for each object to upload
+pub const UPLOAD_TIMEOUT_SECS: u64 = 300;
+/// Objects uploaded at once
+pub const UPLOAD_CONCURRENCY: usize = 4;
+/// Region used when the environment and AWS config files name none
+pub const DEFAULT_REGION: &str = "us-east-1";
+
+/// Where artifacts go: `s3://BUCKET/PREFIX/`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct S3Location {
+    pub bucket: String,
+    /// Key prefix, empty or ending in `/`
+    pub prefix: String,
+}
+
+impl S3Location {
+    pub fn parse(location: &str) -> Result<Self, DomFuzzError> {
+        let invalid = |message: &str| {
+            DomFuzzError::Config(format!("invalid upload location {}: {}", location, message))
+        };
+        let rest = location
+            .strip_prefix("s3://")
+            .ok_or_else(|| invalid("expected s3://BUCKET/PREFIX/"))?;
+        let (bucket, prefix) = rest.split_once('/').unwrap_or((rest, ""));
+        if bucket.is_empty() {
+            return Err(invalid("missing bucket"));
+        }
+        let prefix = prefix.trim_matches('/');
+        Ok(S3Location {
+            bucket: bucket.to_string(),
+            prefix: if prefix.is_empty() {
+                String::new()
+            } else {
+                format!("{}/", prefix)
+            },
+        })
+    }
+}
+
+/// Sends uploads to one [`S3Location`]
+#[derive(Debug, Clone)]
+pub struct S3Uploader {
+    location: S3Location,
+    client: Client,
+}
+
+impl S3Uploader {
+    /// Uploader with credentials, region, and endpoint found the way the AWS
+    /// SDK finds them: the `AWS_*` environment variables, then the shared
+    /// config and credentials files, then container and instance roles.
+    /// Credentials are resolved here, so that a run without any fails before
+    /// it starts.
+    pub async fn from_env(location: S3Location) -> Result<Self, DomFuzzError> {
+        let config = aws_config::defaults(BehaviorVersion::latest())
+            .region(RegionProviderChain::default_provider().or_else(DEFAULT_REGION))
+            .timeout_config(
+                TimeoutConfig::builder()
+                    .operation_timeout(Duration::from_secs(UPLOAD_TIMEOUT_SECS))
+                    .build(),
+            )
+            .load()
+            .await;
+        let no_credentials = |message: String| {
+            DomFuzzError::Config(format!("--upload found no AWS credentials: {}", message))
+        };
+        config
+            .credentials_provider()
+            .ok_or_else(|| no_credentials("no credentials provider".to_string()))?
+            .provide_credentials()
+            .await
+            .map_err(|e| no_credentials(error_chain(&e)))?;
+        // Other S3-compatible stores rarely support virtual-hosted buckets
+        let path_style = config.endpoint_url().is_some();
+        let client = Client::from_conf(
+            aws_sdk_s3::config::Builder::from(&config)
+                .force_path_style(path_style)
+                .build(),
+        );
+        Ok(S3Uploader { location, client })
+    }
+
+    /// PUT the file at `path` as PREFIX + `name`, returning its `s3://` URL
+    pub async fn put_file(&self, path: &Path, name: &str) -> Result<String, DomFuzzError> {
+        let key = format!("{}{}", self.location.prefix, name);
+        let object = format!("s3://{}/{}", self.location.bucket, key);
+        let body = ByteStream::from_path(path)
+            .await
+            .map_err(|e| DomFuzzError::Io(std::io::Error::other(e)))?;
+        metrics::record_request("http", &self.location.bucket);
+        self.client
+            .put_object()
+            .bucket(&self.location.bucket)
+            .key(key)
+            .content_type(content_type(name))
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| DomFuzzError::Http {
+                url: object.clone(),
+                message: error_chain(&e),
+            })?;
+        Ok(object)
+    }
+}
+
+/// `error` and its sources, `: `-separated; SDK errors alone say little more
+/// than `dispatch failure`
+fn error_chain(error: &dyn std::error::Error) -> String {
+    let mut message = error.to_string();
+    let mut source = error.source();
+    while let Some(error) = source {
+        message.push_str(": ");
+        message.push_str(&error.to_string());
+        source = error.source();
+    }
+    message
+}
+
+/// `Content-Type` of an artifact, from its extension
+fn content_type(name: &str) -> &'static str {
+    match Path::new(name).extension().and_then(|ext| ext.to_str()) {
+        Some("csv") => "text/csv",
+        Some("jsonl") => "application/x-ndjson",
+        Some("json") => "application/json",
+        Some("pdf") => "application/pdf",
+        Some("png") => "image/png",
+        Some("gz") => "application/gzip",
+        Some("zst") => "application/zstd",
+        _ => "application/octet-stream",
+    }
+}
@@ -0,0 +1,104 @@
+//! JavaScript bindings for browser builds.
+//!
+//! Build with `wasm-pack build --target web -- --no-default-features --features wasm`.
+//! No networking is available here; status checking stays in the CLI.
+
+use wasm_bindgen::prelude::*;
+
+use crate::domain::Domain;
+use crate::generators::{
+    generate_variations, parse_transformations, split_transformation_list,
+    transformation_generators, GeneratorOptions,
+};
+use crate::similarity::{calculate_similarity, score_domain};
+
+/// A generated variation with its source transformation and similarity scores
+#[wasm_bindgen]
+pub struct JsVariation {
+    domain: String,
+    transformation: String,
+    score: f64,
+    visual_score: f64,
+    cognitive_score: f64,
+}
+
+#[wasm_bindgen]
+impl JsVariation {
+    #[wasm_bindgen(getter)]
+    pub fn domain(&self) -> String {
+        self.domain.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn transformation(&self) -> String {
+        self.transformation.clone()
+    }
+
+    /// Combined similarity score in 0.0..=1.0
+    #[wasm_bindgen(getter)]
+    pub fn score(&self) -> f64 {
+        self.score
+    }
+
+    #[wasm_bindgen(getter, js_name = visualScore)]
+    pub fn visual_score(&self) -> f64 {
+        self.visual_score
+    }
+
+    #[wasm_bindgen(getter, js_name = cognitiveScore)]
+    pub fn cognitive_score(&self) -> f64 {
+        self.cognitive_score
+    }
+}
+
+/// Generate variations of `domain`, highest similarity first. `transformations`
+/// is a comma-separated list of transformation or bundle names; omit it to use
+/// the default `lookalike` bundle. A domain that is not valid has none.
+#[wasm_bindgen]
+pub fn generate(domain: &str, transformations: Option<String>) -> Vec<JsVariation> {
+    let requested = transformations
+        .as_deref()
+        .map(split_transformation_list)
+        .unwrap_or_default();
+    let enabled = parse_transformations(&requested);
+    let Ok(parsed) = Domain::parse(domain) else {
+        return Vec::new();
+    };
+
+    let mut variations: Vec<JsVariation> =
+        generate_variations(&parsed, &enabled, &GeneratorOptions::default())
+            .into_iter()
+            .map(|variation| {
+                let score =
+                    calculate_similarity(domain, &variation.domain, variation.transformation);
+                JsVariation {
+                    domain: variation.domain,
+                    transformation: variation.transformation.to_string(),
+                    score: score.combined_score,
+                    visual_score: score.visual_score,
+                    cognitive_score: score.cognitive_score,
+                }
+            })
+            .collect();
+    variations.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    variations
+}
+
+/// Combined similarity of `variant` to `original` in 0.0..=1.0
+#[wasm_bindgen]
+pub fn similarity(original: &str, variant: &str) -> f64 {
+    score_domain(original, variant).combined_score
+}
+
+/// Names of all individual transformations
+#[wasm_bindgen]
+pub fn transformations() -> Vec<String> {
+    transformation_generators(&GeneratorOptions::default())
+        .into_iter()
+        .map(|(name, _)| name.to_string())
+        .collect()
+}
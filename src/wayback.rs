@@ -0,0 +1,129 @@
+//! Past web presence of registered variations, from the Internet Archive
+//! (`--wayback`).
+//!
+//! A variation the Wayback Machine has captured serving pages for years is
+//! most likely a long-held defensive or unrelated registration; one never
+//! captured, or only in the last few weeks, is more likely fresh attack
+//! infrastructure. The CDX API is asked for successful captures of the
+//! homepage, one per month, which is enough to tell when a site first and last
+//! served content and for how long. Requests go through [`crate::throttle`],
+//! since the API rate-limits heavy users.
+
+use tokio::time::timeout;
+
+use crate::checker::{network, HTTP_CLIENT};
+use crate::error::DomFuzzError;
+use crate::metrics;
+use crate::throttle;
+
+/// Wayback Machine CDX API
+pub const CDX_ENDPOINT: &str = "https://web.archive.org/cdx/search/cdx";
+
+/// Flag recorded for registered variations the Wayback Machine never captured
+pub const NO_WEB_HISTORY: &str = "no-web-history";
+
+/// What the Wayback Machine holds of a domain's homepage
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WebHistory {
+    /// Months with at least one successful capture
+    pub months_archived: usize,
+    /// First and last successful captures, in RFC 3339 form
+    pub first_capture: Option<String>,
+    pub last_capture: Option<String>,
+}
+
+impl WebHistory {
+    /// Whether the homepage was ever captured serving content
+    pub fn is_archived(&self) -> bool {
+        self.months_archived > 0
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        let mut json = serde_json::json!({ "months_archived": self.months_archived });
+        if let Some(first) = &self.first_capture {
+            json["first_capture"] = first.clone().into();
+        }
+        if let Some(last) = &self.last_capture {
+            json["last_capture"] = last.clone().into();
+        }
+        json
+    }
+}
+
+/// Successful captures of `domain`'s homepage, one per month
+pub async fn web_history(domain: &str) -> Result<WebHistory, DomFuzzError> {
+    let url = format!(
+        "{}?url={}&output=json&fl=timestamp&filter=statuscode:200&collapse=timestamp:6",
+        CDX_ENDPOINT, domain
+    );
+    let http_error = |message: String| DomFuzzError::Http {
+        url: url.clone(),
+        message,
+    };
+
+    let host = metrics::url_host(CDX_ENDPOINT);
+    let Some(permit) = throttle::acquire(host).await else {
+        return Err(DomFuzzError::CircuitOpen {
+            endpoint: host.to_string(),
+            domain: domain.to_string(),
+        });
+    };
+    metrics::record_request("http", host);
+    let response = match timeout(network().http_timeout, HTTP_CLIENT.get(&url).send()).await {
+        Ok(Ok(response)) => response,
+        Ok(Err(e)) => {
+            permit.finish(false);
+            return Err(http_error(e.to_string()));
+        }
+        Err(_) => {
+            permit.time_out();
+            return Err(DomFuzzError::Timeout {
+                protocol: "Wayback",
+                domain: domain.to_string(),
+            });
+        }
+    };
+    let status = response.status();
+    permit.finish(status.is_success());
+    if !status.is_success() {
+        return Err(http_error(format!("HTTP {}", status)));
+    }
+    // A header row, then one row per capture; an empty array when there are none
+    let rows: Vec<Vec<String>> = match timeout(network().http_timeout, response.json()).await {
+        Ok(Ok(rows)) => rows,
+        Ok(Err(e)) => return Err(http_error(e.to_string())),
+        Err(_) => {
+            return Err(DomFuzzError::Timeout {
+                protocol: "Wayback",
+                domain: domain.to_string(),
+            })
+        }
+    };
+    let timestamps: Vec<&str> = rows
+        .iter()
+        .skip(1)
+        .filter_map(|row| row.first().map(String::as_str))
+        .collect();
+    Ok(WebHistory {
+        months_archived: timestamps.len(),
+        first_capture: timestamps.iter().min().and_then(|t| rfc3339(t)),
+        last_capture: timestamps.iter().max().and_then(|t| rfc3339(t)),
+    })
+}
+
+/// A CDX timestamp (`20150302101010`) in RFC 3339 form
+fn rfc3339(timestamp: &str) -> Option<String> {
+    if timestamp.len() != 14 || !timestamp.bytes().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let part = |range: std::ops::Range<usize>| &timestamp[range];
+    Some(format!(
+        "{}-{}-{}T{}:{}:{}Z",
+        part(0..4),
+        part(4..6),
+        part(6..8),
+        part(8..10),
+        part(10..12),
+        part(12..14)
+    ))
+}
@@ -0,0 +1,126 @@
+//! Per-TLD rules for reading WHOIS responses.
+//!
+//! Registries word "this domain is free" very differently: .de answers
+//! `Status: free`, .jp answers in Japanese, and thin registries say little at
+//! all. Rather than one English keyword list, responses are classified by the
+//! rules in `data/whois-rules.txt`, which users can extend with their own file
+//! in the same format (see [`WhoisRules::parse`]).
+
+use crate::error::DomFuzzError;
+
+/// The built-in rules, shipped with DomFuzz
+const BUILTIN_RULES: &str = include_str!("../data/whois-rules.txt");
+
+/// What a matching pattern says about the domain
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WhoisVerdict {
+    Available,
+    Registered,
+    /// Refines a registered verdict
+    Parked,
+}
+
+impl WhoisVerdict {
+    fn parse(verdict: &str) -> Option<Self> {
+        match verdict {
+            "available" => Some(WhoisVerdict::Available),
+            "registered" => Some(WhoisVerdict::Registered),
+            "parked" => Some(WhoisVerdict::Parked),
+            _ => None,
+        }
+    }
+}
+
+/// One pattern and the verdict it implies
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WhoisRule {
+    /// TLD without the dot; `None` applies to every TLD
+    pub tld: Option<String>,
+    pub verdict: WhoisVerdict,
+    /// Lowercase substring searched for in the lowercased response
+    pub pattern: String,
+}
+
+/// An ordered set of rules; earlier rules take precedence within each tier
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WhoisRules {
+    rules: Vec<WhoisRule>,
+}
+
+impl WhoisRules {
+    /// The rules shipped in `data/whois-rules.txt`
+    pub fn builtin() -> Self {
+        WhoisRules::parse(BUILTIN_RULES).expect("built-in WHOIS rules are valid")
+    }
+
+    /// Parse rules, one per line as `TLD VERDICT PATTERN`: a TLD without the
+    /// dot (or `*` for all), `available`, `registered`, or `parked`, and the rest
+    /// of the line as a case-insensitive substring. Blank lines and lines
+    /// starting with `#` are ignored.
+    pub fn parse(text: &str) -> Result<Self, DomFuzzError> {
+        let mut rules = Vec::new();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid =
+                |reason: &str| DomFuzzError::Parse(format!("line {}: {}", number + 1, reason));
+            let mut fields = line.splitn(2, char::is_whitespace);
+            let tld = fields.next().unwrap_or_default();
+            let rest = fields.next().unwrap_or_default().trim_start();
+            let mut fields = rest.splitn(2, char::is_whitespace);
+            let verdict = fields.next().unwrap_or_default();
+            let pattern = fields.next().unwrap_or_default().trim();
+            let verdict = WhoisVerdict::parse(verdict).ok_or_else(|| {
+                invalid(&format!(
+                    "expected available, registered, or parked, got {:?}",
+                    verdict
+                ))
+            })?;
+            if pattern.is_empty() {
+                return Err(invalid("missing pattern"));
+            }
+            rules.push(WhoisRule {
+                tld: (tld != "*").then(|| tld.trim_start_matches('.').to_lowercase()),
+                verdict,
+                pattern: pattern.to_lowercase(),
+            });
+        }
+        Ok(WhoisRules { rules })
+    }
+
+    /// These rules followed by `other`'s, which therefore have lower precedence
+    pub fn then(mut self, other: WhoisRules) -> Self {
+        self.rules.extend(other.rules);
+        self
+    }
+
+    /// Classify a lowercased WHOIS `response` for a domain under `tld` as
+    /// `available`, `registered`, or `parked`; `None` if no rule matched
+    pub fn classify(&self, tld: &str, response: &str) -> Option<&'static str> {
+        let tld = tld.to_lowercase();
+        let matches = |verdict: WhoisVerdict, specific: bool| {
+            self.rules.iter().any(|rule| {
+                rule.verdict == verdict
+                    && match &rule.tld {
+                        Some(rule_tld) => specific && *rule_tld == tld,
+                        None => !specific,
+                    }
+                    && response.contains(&rule.pattern)
+            })
+        };
+        // The TLD's own wording decides before the generic phrases are consulted
+        for specific in [true, false] {
+            if matches(WhoisVerdict::Available, specific) {
+                return Some("available");
+            }
+            if matches(WhoisVerdict::Registered, specific) {
+                let parked =
+                    matches(WhoisVerdict::Parked, true) || matches(WhoisVerdict::Parked, false);
+                return Some(if parked { "parked" } else { "registered" });
+            }
+        }
+        None
+    }
+}